@@ -1,4 +1,3 @@
-use cstr_core::CString;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 use embedded_graphics_simulator::{
@@ -11,9 +10,7 @@ use lvgl::input_device::{
     InputDriver,
 };
 use lvgl::misc::anim::{AnimRepeatCount, Animation};
-use lvgl::style::Style;
-use lvgl::widgets::{Btn, Label};
-use lvgl::{Align, Color, Display, DrawBuffer, LvError, Part, Widget};
+use lvgl::prelude::*;
 use std::thread::sleep;
 use std::time::Duration;
 use std::time::Instant;
@@ -32,7 +29,7 @@ fn main() -> Result<(), LvError> {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display.draw_iter(refresh.as_pixels()).unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     })?;
 
     // Define the initial state of your input
@@ -52,7 +49,7 @@ fn main() -> Result<(), LvError> {
     button.set_align(Align::LeftMid, 30, 0);
     button.set_size(180, 80);
     let mut btn_lbl = Label::create(&mut button)?;
-    btn_lbl.set_text(CString::new("Click me!").unwrap().as_c_str())?;
+    btn_lbl.set_text("Click me!")?;
 
     let mut btn_state = false;
 
@@ -65,11 +62,9 @@ fn main() -> Result<(), LvError> {
         println!("Button received event: {:?}", event);
         if let lvgl::Event::Clicked = event {
             if btn_state {
-                let nt = CString::new("Click me!").unwrap();
-                btn_lbl.set_text(nt.as_c_str()).unwrap();
+                btn_lbl.set_text("Click me!").unwrap();
             } else {
-                let nt = CString::new("Clicked!").unwrap();
-                btn_lbl.set_text(nt.as_c_str()).unwrap();
+                btn_lbl.set_text("Clicked!").unwrap();
             }
             btn_state = !btn_state;
         }
@@ -77,7 +72,7 @@ fn main() -> Result<(), LvError> {
 
     'running: loop {
         let start = Instant::now();
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&sim_display);
 
         let events = window.events().peekable();
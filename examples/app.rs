@@ -21,9 +21,7 @@ fn main() {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        embedded_graphics_display
-            .draw_iter(refresh.as_pixels())
-            .unwrap();
+        refresh.draw(&mut embedded_graphics_display).unwrap();
     })
     .unwrap();
 }
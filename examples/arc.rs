@@ -1,13 +1,10 @@
-use cstr_core::CString;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 use embedded_graphics_simulator::{
     OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
 };
 use lvgl;
-use lvgl::style::Style;
-use lvgl::widgets::{Arc, Label};
-use lvgl::{Align, Color, Display, DrawBuffer, LvError, Part, Widget};
+use lvgl::prelude::*;
 use lvgl_sys;
 use std::thread::sleep;
 use std::time::Duration;
@@ -44,7 +41,7 @@ fn main() -> Result<(), LvError> {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display.draw_iter(refresh.as_pixels()).unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     })?;
 
     let mut screen = display.get_scr_act()?;
@@ -62,7 +59,7 @@ fn main() -> Result<(), LvError> {
     arc.set_end_angle(135);
 
     let mut loading_lbl = Label::create(&mut screen)?;
-    loading_lbl.set_text(CString::new("Loading...").unwrap().as_c_str());
+    loading_lbl.set_text("Loading...")?;
     loading_lbl.set_align(Align::OutTopMid, 0, 0);
     //loading_lbl.set_label_align(LabelAlign::Center)?;
 
@@ -85,7 +82,7 @@ fn main() -> Result<(), LvError> {
         arc.set_end_angle(angle + 135);
         i += 1;
 
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&sim_display);
 
         for event in window.events() {
@@ -1,4 +1,3 @@
-use cstr_core::CString;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 use embedded_graphics_simulator::{
@@ -10,9 +9,7 @@ use lvgl::input_device::{
     pointer::{Pointer, PointerInputData},
     InputDriver,
 };
-use lvgl::style::Style;
-use lvgl::widgets::{Btn, Label};
-use lvgl::{Align, Color, Display, DrawBuffer, LvError, Part, Widget};
+use lvgl::prelude::*;
 use std::thread::sleep;
 use std::time::Duration;
 use std::time::Instant;
@@ -31,7 +28,7 @@ fn main() -> Result<(), LvError> {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display.draw_iter(refresh.as_pixels()).unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     })?;
 
     // Define the initial state of your input
@@ -51,18 +48,16 @@ fn main() -> Result<(), LvError> {
     button.set_align(Align::LeftMid, 30, 0);
     button.set_size(180, 80);
     let mut btn_lbl = Label::create(&mut button)?;
-    btn_lbl.set_text(CString::new("Click me!").unwrap().as_c_str());
+    btn_lbl.set_text("Click me!")?;
 
     let mut btn_state = false;
     button.on_event(|_btn, event| {
         println!("Button received event: {:?}", event);
         if let lvgl::Event::Clicked = event {
             if btn_state {
-                let nt = CString::new("Click me!").unwrap();
-                btn_lbl.set_text(nt.as_c_str());
+                btn_lbl.set_text("Click me!").unwrap();
             } else {
-                let nt = CString::new("Clicked!").unwrap();
-                btn_lbl.set_text(nt.as_c_str());
+                btn_lbl.set_text("Clicked!").unwrap();
             }
             btn_state = !btn_state;
         }
@@ -70,7 +65,7 @@ fn main() -> Result<(), LvError> {
 
     'running: loop {
         let start = Instant::now();
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&sim_display);
 
         let events = window.events().peekable();
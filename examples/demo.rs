@@ -1,4 +1,3 @@
-use cstr_core::CString;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 use embedded_graphics_simulator::{
@@ -6,9 +5,7 @@ use embedded_graphics_simulator::{
 };
 use lvgl;
 use lvgl::font::Font;
-use lvgl::style::Style;
-use lvgl::widgets::Label;
-use lvgl::{Align, Color, Display, DrawBuffer, LvError, Part, TextAlign, Widget};
+use lvgl::prelude::*;
 use lvgl_sys;
 use std::thread::sleep;
 use std::time::Duration;
@@ -30,7 +27,7 @@ fn main() -> Result<(), LvError> {
     // Register your display update callback with LVGL. The closure you pass here will be called
     // whenever LVGL has updates to be painted to the display.
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display.draw_iter(refresh.as_pixels()).unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     });
 
     // Create screen and widgets
@@ -54,19 +51,19 @@ fn main() -> Result<(), LvError> {
 
     time.add_style(Part::Main, &mut style_time);
     time.set_align(Align::Center, 0, 90);
-    time.set_width(240);
-    time.set_height(240);
+    time.set_width(240)?;
+    time.set_height(240)?;
 
     let mut bt = Label::from("#5794f2 \u{F293}#");
-    bt.set_width(50);
-    bt.set_height(80);
+    bt.set_width(50)?;
+    bt.set_height(80)?;
     let _ = bt.set_recolor(true);
     bt.set_align(Align::TopLeft, 0, 0);
 
     let mut power: Label = "#fade2a 20%#".into();
     let _ = power.set_recolor(true);
-    power.set_width(80);
-    power.set_height(20);
+    power.set_width(80)?;
+    power.set_height(20)?;
     power.set_align(Align::TopRight, 40, 0);
 
     let mut i = 0;
@@ -75,11 +72,10 @@ fn main() -> Result<(), LvError> {
         if i > 59 {
             i = 0;
         }
-        let val = CString::new(format!("21:{:02}", i)).unwrap();
-        let _ = time.set_text(&val);
+        let _ = time.set_text_fmt(format_args!("21:{:02}", i));
         i = 1 + i;
 
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&sim_display);
 
         for event in window.events() {
@@ -27,9 +27,7 @@ fn main() -> Result<(), LvError> {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display
-            .draw_iter(refresh.as_pixels())
-            .unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     })?;
 
     let mut screen = display.get_scr_act()?;
@@ -67,7 +65,7 @@ fn main() -> Result<(), LvError> {
     'running: loop {
         gauge.set_value(0, i)?;
 
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&sim_display);
 
         for event in window.events() {
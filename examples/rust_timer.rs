@@ -1,13 +1,10 @@
-use cstr_core::CString;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 use embedded_graphics_simulator::{
     OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
 };
 use lvgl;
-use lvgl::style::Style;
-use lvgl::widgets::{Bar, Label};
-use lvgl::{Align, AnimationState, Color, Display, DrawBuffer, Event, LvError, Part, Widget};
+use lvgl::prelude::*;
 use std::thread::sleep;
 use std::time::Duration;
 use std::time::Instant;
@@ -45,7 +42,7 @@ fn main() -> Result<(), LvError> {
     let buffer = DrawBuffer::<{ (HOR_RES * VER_RES) as usize }>::default();
 
     let display = Display::register(buffer, HOR_RES, VER_RES, |refresh| {
-        sim_display.draw_iter(refresh.as_pixels()).unwrap();
+        refresh.draw(&mut sim_display).unwrap();
     })?;
 
     let mut screen = display.get_scr_act()?;
@@ -70,7 +67,7 @@ fn main() -> Result<(), LvError> {
     bar.add_style(Part::Any, &mut ind_style);
 
     let mut loading_lbl = Label::create(&mut screen)?;
-    loading_lbl.set_text(CString::new("Loading...").unwrap().as_c_str());
+    loading_lbl.set_text("Loading...")?;
     loading_lbl.set_align(Align::OutTopMid, 0, 0);
 
     let mut loading_style = Style::default();
@@ -91,7 +88,7 @@ fn main() -> Result<(), LvError> {
         bar.set_value(i, AnimationState::ON);
         i += 1;
 
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         window.update(&mut sim_display);
 
         for event in window.events() {
@@ -3,14 +3,10 @@
 //#![allow(unused_variables)]
 //#![allow(unreachable_code)]
 
-use cstr_core::CString;
 use lvgl::input_device::InputDriver;
 use lvgl::lv_drv_disp_sdl;
 use lvgl::lv_drv_input_pointer_sdl;
-use lvgl::style::Style;
-use lvgl::widgets::{Btn, Label};
-use lvgl::LvResult;
-use lvgl::{Align, Color, DrawBuffer, Part, Widget};
+use lvgl::prelude::*;
 use std::thread::sleep;
 use std::time::Duration;
 use std::time::Instant;
@@ -34,18 +30,16 @@ fn main() -> LvResult<()> {
     button.set_align(Align::LeftMid, 30, 0);
     button.set_size(180, 80);
     let mut btn_lbl = Label::create(&mut button)?;
-    btn_lbl.set_text(CString::new("Click me!").unwrap().as_c_str());
+    btn_lbl.set_text("Click me!")?;
 
     let mut btn_state = false;
     button.on_event(|_btn, event| {
         println!("Button received event: {:?}", event);
         if let lvgl::Event::Clicked = event {
             if btn_state {
-                let nt = CString::new("Click me!").unwrap();
-                btn_lbl.set_text(nt.as_c_str());
+                btn_lbl.set_text("Click me!").unwrap();
             } else {
-                let nt = CString::new("Clicked!").unwrap();
-                btn_lbl.set_text(nt.as_c_str());
+                btn_lbl.set_text("Clicked!").unwrap();
             }
             btn_state = !btn_state;
         }
@@ -53,7 +47,7 @@ fn main() -> LvResult<()> {
 
     loop {
         let start = Instant::now();
-        lvgl::task_handler();
+        lvgl::task_handler()?;
         sleep(Duration::from_millis(5));
         lvgl::tick_inc(Instant::now().duration_since(start));
     }
@@ -2,7 +2,11 @@
  * Generated by https://littlevgl.com/ttf-font-to-c-array from NotoSans-regular
  */
 
+#ifdef LV_LVGL_H_INCLUDE_SIMPLE
+#include "lvgl.h"
+#else
 #include "lvgl/lvgl.h"
+#endif
 
 /*******************************************************************************
  * Size: 80 px
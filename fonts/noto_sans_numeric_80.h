@@ -9,7 +9,11 @@
 #ifndef _APP_FONTS_NOTO_SANS_NUMERIC_80_H
 #define _APP_FONTS_NOTO_SANS_NUMERIC_80_H
 
+#ifdef LV_LVGL_H_INCLUDE_SIMPLE
+#include "lvgl.h"
+#else
 #include "lvgl/lvgl.h"
+#endif
 
 #ifdef __cplusplus
 extern "C" {
@@ -14,6 +14,29 @@ type CGResult<T> = Result<T, Box<dyn Error>>;
 
 const LIB_PREFIX: &str = "lv_";
 
+/// Names of the common `Builder` methods, which generated builder methods
+/// must not shadow.
+const BUILDER_METHODS: &[&str] = &[
+    "new",
+    "with",
+    "size",
+    "width",
+    "height",
+    "pos",
+    "align",
+    "flag",
+    "style",
+    "on_event",
+    "on_clicked",
+    "on_change",
+    "flex_flow",
+    "build",
+];
+
+/// C functions wrapped by hand in the `lvgl` crate, which must not be
+/// generated as well.
+const HANDWRITTEN: &[&str] = &["lv_dropdown_set_options"];
+
 lazy_static! {
     static ref TYPE_MAPPINGS: HashMap<&'static str, &'static str> = [
         ("u16", "u16"),
@@ -50,6 +73,34 @@ impl LvWidget {
     fn pascal_name(&self) -> String {
         to_pascal_case(&self.name)
     }
+
+    /// Generates `builder()` for widgets with a constructor, along with a
+    /// builder method for each generated setter.
+    pub fn builder_code(&self) -> WrapperResult<TokenStream> {
+        let create = format!("{}{}_create", LIB_PREFIX, self.name);
+        if self.name.as_str().eq("obj") || !self.methods.iter().any(|m| m.name == create) {
+            return Err(WrapperError::Skip);
+        }
+
+        let widget_name = format_ident!("{}", self.pascal_name());
+        let create = format_ident!("{}", create);
+        let methods: Vec<TokenStream> = self
+            .methods
+            .iter()
+            .flat_map(|m| m.builder_code(self))
+            .collect();
+        Ok(quote! {
+            impl<'a> #widget_name<'a> {
+                pub fn builder() -> crate::Builder<Self> {
+                    crate::Builder::new(lvgl_sys::#create)
+                }
+            }
+
+            impl<'a, S: FnOnce(&mut #widget_name<'a>)> crate::Builder<#widget_name<'a>, S> {
+                #(#methods)*
+            }
+        })
+    }
 }
 
 impl Rusty for LvWidget {
@@ -62,6 +113,7 @@ impl Rusty for LvWidget {
         }
 
         let widget_name = format_ident!("{}", self.pascal_name());
+        let class = format_ident!("{}{}_class", LIB_PREFIX, self.name);
         let methods: Vec<TokenStream> = self.methods.iter().flat_map(|m| m.code(self)).collect();
         Ok(quote! {
             define_object!(#widget_name);
@@ -69,6 +121,12 @@ impl Rusty for LvWidget {
             impl<'a> #widget_name<'a> {
                 #(#methods)*
             }
+
+            unsafe impl crate::WidgetClass for #widget_name<'_> {
+                fn class() -> &'static lvgl_sys::lv_obj_class_t {
+                    unsafe { &lvgl_sys::#class }
+                }
+            }
         })
     }
 }
@@ -103,11 +161,16 @@ impl Rusty for LvFunc {
         let func_name = format_ident!("{}", new_name);
         let original_func_name = format_ident!("{}", self.name.as_str());
 
+        if HANDWRITTEN.contains(&self.name.as_str()) {
+            return Err(WrapperError::Skip);
+        }
+
         // generate constructor
         if new_name.as_str().eq("create") {
             return Ok(quote! {
 
-                pub fn create(parent: &mut impl crate::NativeObject) -> crate::LvResult<Self> {
+                pub fn create<'p: 'a>(parent: &mut impl crate::Widget<'p>) -> crate::LvResult<Self> {
+                    crate::check_init()?;
                     unsafe {
                         let ptr = lvgl_sys::#original_func_name(
                             parent.raw().as_mut(),
@@ -209,8 +272,8 @@ impl Rusty for LvFunc {
 
         // Generate the arguments being passed into the FFI interface
         //
-        // - The first argument will be always self.core.raw().as_mut() (see quote! when arg_idx == 0), it's most likely a pointer to lv_obj_t
-        //   TODO: When handling getters this should be self.raw().as_ptr() instead, this also requires updating args_decl
+        // - The first argument will be always self.core.raw(), it's most likely a pointer to lv_obj_t. Getters
+        //   taking a const pointer get it through as_ptr(), so they only need &self
         // - The arguments will be appended to the accumulator (args_accumulator) as they are generated in the closure
         let ffi_args = self
             .args
@@ -218,7 +281,11 @@ impl Rusty for LvFunc {
             .enumerate()
             .fold(quote!(), |args_accumulator, (arg_idx, arg)| {
                 let next_arg = if arg_idx == 0 {
-                    quote!(self.core.raw().as_mut())
+                    if arg.get_type().is_const() {
+                        quote!(self.core.raw().as_ptr())
+                    } else {
+                        quote!(self.core.raw().as_mut())
+                    }
                 } else {
                     let var = arg.get_value_usage();
                     quote!(#var)
@@ -261,6 +328,46 @@ impl Rusty for LvFunc {
     }
 }
 
+impl LvFunc {
+    /// Generates a builder method for a generated `set_*` method, named
+    /// without the `set_` prefix, which calls the setter on the built widget.
+    pub fn builder_code(&self, parent: &LvWidget) -> WrapperResult<TokenStream> {
+        let templ = format!("{}{}_", LIB_PREFIX, parent.name.as_str());
+        let setter_name = self.name.replace(templ.as_str(), "");
+        let name = match setter_name.strip_prefix("set_") {
+            Some(name) if self.ret.is_none() && !BUILDER_METHODS.contains(&name) => name,
+            _ => return Err(WrapperError::Skip),
+        };
+        // Only setters which are generated themselves can be called
+        self.code(parent)?;
+
+        let widget_name = format_ident!("{}", parent.pascal_name());
+        let setter_name = format_ident!("{}", setter_name);
+        let func_name =
+            syn::parse_str::<syn::Ident>(name).unwrap_or_else(|_| format_ident!("r#{}", name));
+        let args = self
+            .args
+            .iter()
+            .skip(1)
+            .map(|arg| {
+                if arg.typ.is_str() {
+                    let ident = arg.get_name_ident();
+                    Ok(quote!(#ident: &'a cstr_core::CStr))
+                } else {
+                    arg.code(self)
+                }
+            })
+            .collect::<WrapperResult<Vec<TokenStream>>>()?;
+        let arg_names = self.args.iter().skip(1).map(LvArg::get_name_ident);
+
+        Ok(quote! {
+            pub fn #func_name(self, #(#args),*) -> crate::Builder<#widget_name<'a>, impl FnOnce(&mut #widget_name<'a>)> {
+                self.with(move |widget| widget.#setter_name(#(#arg_names),*))
+            }
+        })
+    }
+}
+
 impl From<ForeignItemFn> for LvFunc {
     fn from(ffi: ForeignItemFn) -> Self {
         let ret = match ffi.sig.output {
@@ -369,8 +476,10 @@ impl LvType {
         }
     }
 
+    /// Whether the type is const, or a pointer to const, as bindgen writes
+    /// e.g. `const lv_obj_t *` as `* const lv_obj_t`.
     pub fn is_const(&self) -> bool {
-        self.literal_name.starts_with("const ")
+        self.literal_name.starts_with("const ") || self.literal_name.starts_with("* const ")
     }
 
     pub fn is_str(&self) -> bool {
@@ -727,6 +836,104 @@ mod test {
         assert_eq!(code.to_string(), expected_code.to_string());
     }
 
+    #[test]
+    fn generate_getter_taking_const_self() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_bar_get_value(obj: *const lv_obj_t) -> i32;
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+
+        let bar_get_value = cg.get(0).unwrap().clone();
+        let parent_widget = LvWidget {
+            name: "bar".to_string(),
+            methods: vec![],
+        };
+
+        let code = bar_get_value.code(&parent_widget).unwrap();
+        let expected_code = quote! {
+            pub fn get_value(&self) -> i32 {
+                unsafe {
+                    lvgl_sys::lv_bar_get_value(
+                        self.core.raw().as_ptr()
+                    )
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn generate_builder_method_for_setter() {
+        // pub fn lv_bar_set_range(obj: *mut lv_obj_t, min: i32, max: i32);
+        let bar_set_range = LvFunc::new(
+            "lv_bar_set_range".to_string(),
+            vec![
+                LvArg::new("obj".to_string(), LvType::new("*mut lv_obj_t".to_string())),
+                LvArg::new("min".to_string(), LvType::new("i32".to_string())),
+                LvArg::new("max".to_string(), LvType::new("i32".to_string())),
+            ],
+            None,
+        );
+        let bar_widget = LvWidget {
+            name: "bar".to_string(),
+            methods: vec![],
+        };
+
+        let code = bar_set_range.builder_code(&bar_widget).unwrap();
+        let expected_code = quote! {
+            pub fn range(self, min: i32, max: i32) -> crate::Builder<Bar<'a>, impl FnOnce(&mut Bar<'a>)> {
+                self.with(move |widget| widget.set_range(min, max))
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn skip_builder_method_shadowing_common_methods() {
+        // pub fn lv_spinbox_set_pos(obj: *mut lv_obj_t, pos: u8);
+        let spinbox_set_pos = LvFunc::new(
+            "lv_spinbox_set_pos".to_string(),
+            vec![
+                LvArg::new("obj".to_string(), LvType::new("*mut lv_obj_t".to_string())),
+                LvArg::new("pos".to_string(), LvType::new("u8".to_string())),
+            ],
+            None,
+        );
+        let spinbox_widget = LvWidget {
+            name: "spinbox".to_string(),
+            methods: vec![],
+        };
+
+        assert!(spinbox_set_pos.builder_code(&spinbox_widget).is_err());
+    }
+
+    #[test]
+    fn skip_handwritten_methods() {
+        // pub fn lv_dropdown_set_options(obj: *mut lv_obj_t, options: *const cty::c_char);
+        let dropdown_set_options = LvFunc::new(
+            "lv_dropdown_set_options".to_string(),
+            vec![
+                LvArg::new("obj".to_string(), LvType::new("*mut lv_obj_t".to_string())),
+                LvArg::new(
+                    "options".to_string(),
+                    LvType::new("* const cty :: c_char".to_string()),
+                ),
+            ],
+            None,
+        );
+        let dropdown_widget = LvWidget {
+            name: "dropdown".to_string(),
+            methods: vec![],
+        };
+
+        assert!(dropdown_set_options.code(&dropdown_widget).is_err());
+        assert!(dropdown_set_options.builder_code(&dropdown_widget).is_err());
+    }
+
     #[test]
     fn generate_basic_widget_code() {
         let arc_widget = LvWidget {
@@ -741,6 +948,12 @@ mod test {
             impl<'a> Arc<'a> {
 
             }
+
+            unsafe impl crate::WidgetClass for Arc<'_> {
+                fn class() -> &'static lvgl_sys::lv_obj_class_t {
+                    unsafe { &lvgl_sys::lv_arc_class }
+                }
+            }
         };
 
         assert_eq!(code.to_string(), expected_code.to_string());
@@ -771,7 +984,8 @@ mod test {
             define_object!(Arc);
 
             impl<'a> Arc<'a> {
-                pub fn create(parent: &mut impl crate::NativeObject) -> crate::LvResult<Self> {
+                pub fn create<'p: 'a>(parent: &mut impl crate::Widget<'p>) -> crate::LvResult<Self> {
+                    crate::check_init()?;
                     unsafe {
                         let ptr = lvgl_sys::lv_arc_create(
                             parent.raw().as_mut(),
@@ -790,6 +1004,12 @@ mod test {
                     Self::create(&mut parent)
                 }
             }
+
+            unsafe impl crate::WidgetClass for Arc<'_> {
+                fn class() -> &'static lvgl_sys::lv_obj_class_t {
+                    unsafe { &lvgl_sys::lv_arc_class }
+                }
+            }
         };
 
         assert_eq!(code.to_string(), expected_code.to_string());
@@ -0,0 +1,246 @@
+//! Procedural macros for the `lvgl` crate
+//!
+//! These are re-exported by `lvgl` under its `macros` feature, and expand to
+//! code referring to it as `::lvgl`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Ident};
+
+/// Derives a constructor creating a screen's widgets from a struct whose
+/// fields are widgets, along with typed accessors for them.
+///
+/// The generated `create(parent)` creates each field's widget in field order,
+/// using its type's `builder()`, and `field()`/`field_mut()` return them.
+/// Fields can be configured with `#[lvgl(...)]` attributes:
+///
+/// - `parent = field`: creates the widget inside an earlier field's widget
+///   instead of inside the parent.
+/// - `builder = expr`: uses the given `Builder` instead of the type's
+///   default one, e.g. `builder = Label::builder().text("Hi")`.
+/// - `setup = expr`: calls a closure on the widget once it is created.
+/// - `skip`: not a widget; the field is initialized with `Default::default()`
+///   and has no accessors.
+///
+/// ```ignore
+/// use lvgl::widgets::{Btn, Label};
+///
+/// #[derive(lvgl::Screen)]
+/// struct Dialog<'a> {
+///     #[lvgl(builder = Label::builder().text("Delete file?"))]
+///     message: Label<'a>,
+///     #[lvgl(setup = |btn| btn.set_align(Align::BottomMid, 0, -10))]
+///     confirm: Btn<'a>,
+///     #[lvgl(parent = confirm, builder = Label::builder().text("OK"))]
+///     confirm_label: Label<'a>,
+/// }
+///
+/// let dialog = Dialog::create(&mut screen)?;
+/// dialog.confirm_mut().on_event(|_, _| { /* ... */ })?;
+/// ```
+#[proc_macro_derive(Screen, attributes(lvgl))]
+pub fn derive_screen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_screen(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    parent: Option<Ident>,
+    builder: Option<Expr>,
+    setup: Option<Expr>,
+    skip: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("lvgl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("parent") {
+                    attrs.parent = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("builder") {
+                    attrs.builder = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("setup") {
+                    attrs.setup = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else {
+                    return Err(meta.error("expected `parent`, `builder`, `setup` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+fn expand_screen(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`Screen` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`Screen` can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut created: Vec<&Ident> = Vec::new();
+    let mut creation = Vec::new();
+    let mut accessors = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.skip {
+            creation.push(quote! {
+                let #name = ::core::default::Default::default();
+            });
+            continue;
+        }
+
+        let parent = match &attrs.parent {
+            Some(parent) if created.contains(&parent) => quote!(&mut #parent),
+            Some(parent) => {
+                return Err(syn::Error::new_spanned(
+                    parent,
+                    "the parent must be an earlier widget field",
+                ))
+            }
+            None => quote!(&mut *parent),
+        };
+        let builder = match &attrs.builder {
+            Some(builder) => quote!(#builder),
+            None => quote!(<#ty>::builder()),
+        };
+        let setup = attrs.setup.as_ref().map(|setup| quote!(.with(#setup)));
+        creation.push(quote! {
+            #[allow(unused_mut)]
+            let mut #name: #ty = #builder #setup .build(#parent)?;
+        });
+        created.push(name);
+
+        let name_mut = format_ident!("{}_mut", name);
+        accessors.push(quote! {
+            pub fn #name(&self) -> &#ty {
+                &self.#name
+            }
+
+            pub fn #name_mut(&mut self) -> &mut #ty {
+                &mut self.#name
+            }
+        });
+    }
+
+    // The widgets cannot outlive the parent they are created on
+    let lifetimes: Vec<_> = input.generics.lifetimes().map(|l| &l.lifetime).collect();
+    let parent_bounds = if lifetimes.is_empty() {
+        quote!('__parent: 'static)
+    } else {
+        quote!(#('__parent: #lifetimes),*)
+    };
+
+    let ident = &input.ident;
+    let field_names = fields.iter().map(|f| f.ident.as_ref().unwrap());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn create<'__parent>(
+                parent: &mut impl ::lvgl::Widget<'__parent>,
+            ) -> ::lvgl::LvResult<Self>
+            where
+                #parent_bounds,
+            {
+                #(#creation)*
+                Ok(Self { #(#field_names),* })
+            }
+
+            #(#accessors)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::expand_screen;
+    use quote::quote;
+
+    #[test]
+    fn generate_screen_constructor_and_accessors() {
+        let input = syn::parse2(quote! {
+            struct Dialog<'a> {
+                btn: Btn<'a>,
+                #[lvgl(parent = btn, setup = |l| l.set_text(text))]
+                label: Label<'a>,
+                #[lvgl(skip)]
+                count: u32,
+            }
+        })
+        .unwrap();
+
+        let code = expand_screen(input).unwrap();
+        let expected_code = quote! {
+            impl<'a> Dialog<'a> {
+                pub fn create<'__parent>(
+                    parent: &mut impl ::lvgl::Widget<'__parent>,
+                ) -> ::lvgl::LvResult<Self>
+                where
+                    '__parent: 'a,
+                {
+                    #[allow(unused_mut)]
+                    let mut btn: Btn<'a> = <Btn<'a> >::builder().build(&mut *parent)?;
+                    #[allow(unused_mut)]
+                    let mut label: Label<'a> = <Label<'a> >::builder()
+                        .with(|l| l.set_text(text))
+                        .build(&mut btn)?;
+                    let count = ::core::default::Default::default();
+                    Ok(Self { btn, label, count })
+                }
+
+                pub fn btn(&self) -> &Btn<'a> {
+                    &self.btn
+                }
+
+                pub fn btn_mut(&mut self) -> &mut Btn<'a> {
+                    &mut self.btn
+                }
+
+                pub fn label(&self) -> &Label<'a> {
+                    &self.label
+                }
+
+                pub fn label_mut(&mut self) -> &mut Label<'a> {
+                    &mut self.label
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn reject_parent_declared_later() {
+        let input = syn::parse2(quote! {
+            struct Dialog<'a> {
+                #[lvgl(parent = btn)]
+                label: Label<'a>,
+                btn: Btn<'a>,
+            }
+        })
+        .unwrap();
+
+        assert!(expand_screen(input).is_err());
+    }
+}
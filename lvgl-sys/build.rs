@@ -8,6 +8,9 @@ use std::{
 };
 
 static CONFIG_NAME: &str = "DEP_LV_CONFIG_PATH";
+static CONFIG_NAME_ALIAS: &str = "LVGL_CONFIG_PATH";
+static CONFIG_OVERRIDE_PREFIX: &str = "DEP_LV_CONFIG_";
+static LVGL_PATH_NAME: &str = "LVGL_PATH";
 
 // See https://github.com/rust-lang/rust-bindgen/issues/687#issuecomment-450750547
 #[cfg(feature = "drivers")]
@@ -38,18 +41,25 @@ fn main() {
     let shims_dir = project_dir.join("shims");
     let vendor = project_dir.join("vendor");
     println!("cargo:rerun-if-env-changed={}", CONFIG_NAME);
+    println!("cargo:rerun-if-env-changed={}", CONFIG_NAME_ALIAS);
     let lv_config_dir = get_conf_path(&vendor);
+    let lvgl_dir = get_lvgl_path(&vendor);
+    let lv_config_dir = override_conf(&lv_config_dir).unwrap_or(lv_config_dir);
+    emit_version(&lvgl_dir);
     let font_extra_src: Option<PathBuf> = get_font_extra_dir();
     if let Some(p) = &font_extra_src {
         println!("cargo:rerun-if-changed={}", p.to_str().unwrap())
     }
+    let ui_src: Option<PathBuf> = get_ui_dir();
 
     //p!("----- Create BuildConf -----");
     let conf = BuildConf {
         lv_config_dir: lv_config_dir.as_path(),
         vendor: vendor.as_path(),
+        lvgl_dir: lvgl_dir.as_path(),
         shims_dir: &shims_dir,
         font_extra_src: font_extra_src.as_ref().map(PathBuf::as_path),
+        ui_src: ui_src.as_ref().map(PathBuf::as_path),
     };
 
     #[cfg(feature = "library")]
@@ -73,20 +83,36 @@ fn get_font_extra_dir() -> Option<PathBuf> {
     }
 }
 
+/// The directory of C sources exported from a UI designer such as SquareLine
+/// Studio, compiled along with LVGL if `LVGL_UI_DIR` is set.
+fn get_ui_dir() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=LVGL_UI_DIR");
+    let path = canonicalize(PathBuf::from(env::var("LVGL_UI_DIR").ok()?));
+    println!("cargo:rerun-if-changed={}", path.display());
+    Some(path)
+}
+
 struct BuildConf<'a> {
     lv_config_dir: &'a Path,
     vendor: &'a Path,
+    lvgl_dir: &'a Path,
     shims_dir: &'a Path,
     font_extra_src: Option<&'a Path>,
+    #[cfg_attr(not(feature = "library"), allow(dead_code))]
+    ui_src: Option<&'a Path>,
 }
 
 #[cfg(feature = "library")]
 fn compile_library(conf: &BuildConf) {
     let vendor = conf.vendor;
 
-    let lvgl_src = vendor.join("lvgl").join("src");
+    let lvgl_src = conf.lvgl_dir.join("src");
     #[cfg(feature = "rust_timer")]
     let timer_shim = vendor.join("include").join("timer");
+    #[cfg(feature = "rust_alloc")]
+    let alloc_shim = vendor.join("include").join("alloc");
+    #[cfg(feature = "rust_assert")]
+    let assert_shim = vendor.join("include").join("assert");
 
     // Some basic defaults; SDL2 is the only driver enabled in the provided
     // driver config by default
@@ -115,11 +141,21 @@ fn compile_library(conf: &BuildConf) {
     }
     add_c_files(&mut cfg, &lvgl_src);
     add_c_files(&mut cfg, conf.shims_dir);
+    if let Some(p) = conf.ui_src {
+        add_c_files(&mut cfg, p);
+        cfg.include(p);
+    }
     #[cfg(feature = "drivers")]
     add_c_files(&mut cfg, &drivers);
+    #[cfg(feature = "demo_music")]
+    add_c_files(&mut cfg, conf.lvgl_dir.join("demos").join("music"));
+    #[cfg(feature = "demo_widgets")]
+    add_c_files(&mut cfg, conf.lvgl_dir.join("demos").join("widgets"));
 
     cfg.define("LV_CONF_INCLUDE_SIMPLE", Some("1"))
+        .define("LV_LVGL_H_INCLUDE_SIMPLE", Some("1"))
         .include(&lvgl_src)
+        .include(conf.lvgl_dir)
         .include(&vendor)
         .warnings(false)
         .include(conf.lv_config_dir);
@@ -128,11 +164,21 @@ fn compile_library(conf: &BuildConf) {
     }
     #[cfg(feature = "rust_timer")]
     cfg.include(&timer_shim);
+    #[cfg(feature = "rust_alloc")]
+    cfg.include(&alloc_shim);
+    #[cfg(feature = "rust_assert")]
+    cfg.include(&assert_shim);
     #[cfg(feature = "drivers")]
     cfg.include(&drivers);
     #[cfg(feature = "drivers")]
     cfg.includes(incl_extra.split(','));
 
+    #[cfg(feature = "sdl")]
+    cfg.define("USE_SDL", Some("1"));
+
+    #[cfg(any(feature = "gpu_stm32_dma2d", feature = "gpu_nxp_pxp"))]
+    cfg.includes(gpu_includes());
+
     cflags_extra.clone().for_each(|e| {
         let mut it = e.split('=');
         cfg.define(it.next().unwrap(), it.next().unwrap_or_default());
@@ -152,14 +198,19 @@ fn generate_bindings(conf: &BuildConf) {
         env::var("TARGET_C_INCLUDE_PATH").unwrap_or("/usr/include,/usr/local/include".to_string()); // Added
     let mut cc_args = vec![
         "-DLV_CONF_INCLUDE_SIMPLE=1",
+        "-DLV_LVGL_H_INCLUDE_SIMPLE=1",
         "-I",
         conf.lv_config_dir.to_str().unwrap(),
         "-I",
+        conf.lvgl_dir.to_str().unwrap(),
+        "-I",
         conf.vendor.to_str().unwrap(),
         "-I",                   // Added
         &target_c_include_path, // Added
         "-fvisibility=default",
     ];
+    #[cfg(feature = "sdl")]
+    cc_args.push("-DUSE_SDL=1");
 
     // Set correct target triple for bindgen when cross-compiling
     let target = env::var("CROSS_COMPILE").map_or_else(
@@ -201,6 +252,12 @@ fn generate_bindings(conf: &BuildConf) {
         }
     }
 
+    #[cfg(any(feature = "gpu_stm32_dma2d", feature = "gpu_nxp_pxp"))]
+    for dir in gpu_includes() {
+        additional_args.push("-I".to_string());
+        additional_args.push(dir);
+    }
+
     #[cfg(feature = "drivers")]
     let ignored_macros = IgnoreMacros(
         vec![
@@ -241,8 +298,22 @@ fn generate_bindings(conf: &BuildConf) {
         .expect("Can't write bindings!");
 }
 
+/// The directories of the vendor SDK headers LVGL's GPU backends include,
+/// e.g. CMSIS for the STM32 DMA2D or the MCUXpresso SDK drivers for the PXP.
+#[cfg(any(feature = "gpu_stm32_dma2d", feature = "gpu_nxp_pxp"))]
+fn gpu_includes() -> Vec<String> {
+    println!("cargo:rerun-if-env-changed=LVGL_GPU_INCLUDE");
+    env::var("LVGL_GPU_INCLUDE")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 fn get_conf_path(vendor: &PathBuf) -> PathBuf {
     let conf_path = env::var(CONFIG_NAME)
+        .or_else(|_| env::var(CONFIG_NAME_ALIAS))
         .map(PathBuf::from)
         .unwrap_or_else(|_| {
             match std::env::var("DOCS_RS") {
@@ -254,8 +325,8 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
                 Err(_) => {
                     #[cfg(not(feature = "use-vendored-config"))]
                     panic!(
-                        "The environment variable {} is required to be defined",
-                        CONFIG_NAME
+                        "The environment variable {} (or {}) is required to be defined",
+                        CONFIG_NAME, CONFIG_NAME_ALIAS
                     );
 
                     #[cfg(feature = "use-vendored-config")]
@@ -302,6 +373,185 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
     conf_path
 }
 
+/// Returns the LVGL source tree to build: the checkout referenced by
+/// `LVGL_PATH` if set, or the vendored submodule otherwise.
+fn get_lvgl_path(vendor: &Path) -> PathBuf {
+    println!("cargo:rerun-if-env-changed={}", LVGL_PATH_NAME);
+    let Ok(path) = env::var(LVGL_PATH_NAME) else {
+        return vendor.join("lvgl");
+    };
+    let path = PathBuf::from(path);
+    if !path.is_dir() {
+        panic!(
+            "Directory {} referenced by {} needs to exist",
+            path.to_string_lossy(),
+            LVGL_PATH_NAME
+        );
+    }
+    if !path.join("lvgl.h").exists() || !path.join("src").is_dir() {
+        panic!(
+            "Directory {} referenced by {} needs to be an LVGL source tree, containing lvgl.h and src/",
+            path.to_string_lossy(),
+            LVGL_PATH_NAME
+        );
+    }
+    let path = canonicalize(path);
+    println!("cargo:rerun-if-changed={}", path.join("src").display());
+    path
+}
+
+/// Reads the LVGL version from its headers, exposing it to dependent crates
+/// as `DEP_LVGL_VERSION_{MAJOR,MINOR,PATCH}`.
+fn emit_version(lvgl_dir: &Path) {
+    let read_define = |name: &str| {
+        ["lvgl.h", "lv_version.h"].iter().find_map(|header| {
+            let src = std::fs::read_to_string(lvgl_dir.join(header)).ok()?;
+            src.lines().find_map(|line| {
+                let mut it = line.split_whitespace();
+                (it.next() == Some("#define") && it.next() == Some(name))
+                    .then(|| it.next()?.parse::<u32>().ok())
+                    .flatten()
+            })
+        })
+    };
+    let (Some(major), Some(minor), Some(patch)) = (
+        read_define("LVGL_VERSION_MAJOR"),
+        read_define("LVGL_VERSION_MINOR"),
+        read_define("LVGL_VERSION_PATCH"),
+    ) else {
+        println!(
+            "cargo:warning=Could not read the LVGL version from {}",
+            lvgl_dir.display()
+        );
+        return;
+    };
+
+    println!("cargo:version_major={major}");
+    println!("cargo:version_minor={minor}");
+    println!("cargo:version_patch={patch}");
+}
+
+/// Collects `lv_conf.h` settings implied by enabled features, followed by
+/// those set through `DEP_LV_CONFIG_<NAME>=<value>` environment variables,
+/// which set `LV_<NAME>`.
+fn conf_overrides() -> Vec<(String, String)> {
+    #[allow(unused_mut)]
+    let mut overrides: Vec<(String, String)> = Vec::new();
+    #[allow(unused_macros)]
+    macro_rules! set {
+        ($name:literal, $value:literal) => {
+            overrides.push(($name.to_string(), $value.to_string()))
+        };
+    }
+
+    #[cfg(feature = "rust_timer")]
+    {
+        set!("LV_TICK_CUSTOM", "1");
+        set!("LV_TICK_CUSTOM_INCLUDE", "<rs_timer.h>");
+        set!("LV_TICK_CUSTOM_SYS_TIME_EXPR", "(rs_lv_timer())");
+    }
+    #[cfg(feature = "rust_alloc")]
+    {
+        set!("LV_MEM_CUSTOM", "1");
+        set!("LV_MEM_CUSTOM_INCLUDE", "<rs_alloc.h>");
+        set!("LV_MEM_CUSTOM_ALLOC", "rs_lv_malloc");
+        set!("LV_MEM_CUSTOM_FREE", "rs_lv_free");
+        set!("LV_MEM_CUSTOM_REALLOC", "rs_lv_realloc");
+    }
+    #[cfg(feature = "rust_assert")]
+    {
+        set!("LV_ASSERT_HANDLER_INCLUDE", "<rs_assert.h>");
+        set!(
+            "LV_ASSERT_HANDLER",
+            "rs_lv_assert_handler(__FILE__, __LINE__);"
+        );
+    }
+    #[cfg(feature = "log")]
+    {
+        set!("LV_USE_LOG", "1");
+        set!("LV_LOG_PRINTF", "0");
+    }
+    #[cfg(feature = "demo_music")]
+    {
+        set!("LV_USE_DEMO_MUSIC", "1");
+        set!("LV_FONT_MONTSERRAT_12", "1");
+        set!("LV_FONT_MONTSERRAT_16", "1");
+    }
+    #[cfg(feature = "demo_widgets")]
+    set!("LV_USE_DEMO_WIDGETS", "1");
+    #[cfg(feature = "tiny_ttf")]
+    set!("LV_USE_TINY_TTF", "1");
+    #[cfg(feature = "ime_pinyin")]
+    {
+        set!("LV_USE_IME_PINYIN", "1");
+        set!("LV_FONT_SIMSUN_16_CJK", "1");
+    }
+    #[cfg(feature = "snapshot")]
+    set!("LV_USE_SNAPSHOT", "1");
+    #[cfg(feature = "gpu_stm32_dma2d")]
+    {
+        if env::var(format!("{CONFIG_OVERRIDE_PREFIX}GPU_DMA2D_CMSIS_INCLUDE")).is_err() {
+            panic!(
+                "The gpu_stm32_dma2d feature needs the CMSIS header of the chip, e.g. \
+                 {CONFIG_OVERRIDE_PREFIX}GPU_DMA2D_CMSIS_INCLUDE=\"<stm32f7xx.h>\""
+            );
+        }
+        set!("LV_USE_GPU_STM32_DMA2D", "1");
+    }
+    #[cfg(feature = "gpu_nxp_pxp")]
+    {
+        set!("LV_USE_GPU_NXP_PXP", "1");
+        // Initializes the PXP in `lv_init()`
+        set!("LV_USE_GPU_NXP_PXP_AUTO_INIT", "1");
+    }
+
+    let mut from_env: Vec<(String, String)> = env::vars()
+        .filter_map(|(k, v)| {
+            let name = k.strip_prefix(CONFIG_OVERRIDE_PREFIX)?;
+            (name != "PATH").then(|| {
+                println!("cargo:rerun-if-env-changed={k}");
+                (format!("LV_{name}"), v)
+            })
+        })
+        .collect();
+    from_env.sort();
+    overrides.extend(from_env);
+    overrides
+}
+
+/// Generates an `lv_conf.h` which includes the user's configuration and then
+/// applies `conf_overrides()`. Returns the directory containing it, or `None`
+/// if there is nothing to override.
+fn override_conf(conf_dir: &Path) -> Option<PathBuf> {
+    let overrides = conf_overrides();
+    if overrides.is_empty() {
+        return None;
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("lv_conf");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let mut conf = String::from(
+        "/* Generated by the lvgl-sys build script. Do not edit. */\n\
+         #ifndef LV_CONF_GENERATED_H\n\
+         #define LV_CONF_GENERATED_H\n\n",
+    );
+    conf += &format!("#include \"{}\"\n\n", conf_dir.join("lv_conf.h").display());
+    for (name, value) in &overrides {
+        conf += &format!("#undef {name}\n#define {name} {value}\n");
+    }
+    conf += "\n#endif /*LV_CONF_GENERATED_H*/\n";
+    std::fs::write(out_dir.join("lv_conf.h"), conf).unwrap();
+
+    let drv_conf = format!(
+        "#include \"{}\"\n",
+        conf_dir.join("lv_drv_conf.h").display()
+    );
+    std::fs::write(out_dir.join("lv_drv_conf.h"), drv_conf).unwrap();
+
+    Some(out_dir)
+}
+
 fn add_font_headers(bindings: bindgen::Builder, dir: Option<&Path>) -> bindgen::Builder {
     if let Some(p) = dir {
         let mut temp = bindings;
@@ -24,3 +24,18 @@ uint16_t _LV_COLOR_GET_A(lv_color_t color)
 {
     return LV_COLOR_GET_A(color);
 }
+
+uint32_t _LV_COLOR_TO32(lv_color_t color)
+{
+    return lv_color_to32(color);
+}
+
+lv_color_t _LV_COLOR_MIX(lv_color_t c1, lv_color_t c2, uint8_t mix)
+{
+    return lv_color_mix(c1, c2, mix);
+}
+
+uint8_t _LV_COLOR_BRIGHTNESS(lv_color_t color)
+{
+    return lv_color_brightness(color);
+}
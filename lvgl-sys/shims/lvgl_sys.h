@@ -5,13 +5,23 @@
 extern "C" {
 #endif
 
-#include "lvgl/lvgl.h"
+#include "lvgl.h"
+
+/* LV_MEM_SIZE is only defined when LVGL's own allocator is used */
+#if LV_MEM_CUSTOM == 0
+#define _LV_CONF_MEM_SIZE LV_MEM_SIZE
+#else
+#define _LV_CONF_MEM_SIZE 0
+#endif
 
 lv_color_t _LV_COLOR_MAKE(uint8_t r, uint8_t g, uint8_t b);
 uint16_t _LV_COLOR_GET_R(lv_color_t color);
 uint16_t _LV_COLOR_GET_G(lv_color_t color);
 uint16_t _LV_COLOR_GET_B(lv_color_t color);
 uint16_t _LV_COLOR_GET_A(lv_color_t color);
+uint32_t _LV_COLOR_TO32(lv_color_t color);
+lv_color_t _LV_COLOR_MIX(lv_color_t c1, lv_color_t c2, uint8_t mix);
+uint8_t _LV_COLOR_BRIGHTNESS(lv_color_t color);
 
 
 #ifdef __cplusplus
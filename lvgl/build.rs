@@ -7,16 +7,21 @@ use std::io::prelude::*;
 use std::path::PathBuf;
 
 fn main() {
+    emit_version_cfgs();
+
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let rs = out_path.join("generated.rs");
 
     let widgets_impl = lvgl_sys::_bindgen_raw_src();
+    emit_deinit_cfg(widgets_impl);
+    emit_style_sentinel_cfg(widgets_impl);
+    emit_theme_mono_cfg(widgets_impl);
 
     let codegen = CodeGen::from(widgets_impl).unwrap();
     let widgets_impl: Vec<TokenStream> = codegen
         .get_widgets()
         .iter()
-        .flat_map(|w| w.code(&()))
+        .flat_map(|w| w.code(&()).into_iter().chain(w.builder_code()))
         .collect();
 
     let code = quote! {
@@ -31,3 +36,51 @@ fn main() {
     )
     .unwrap();
 }
+
+/// Emits `lvgl_v<major>` and `lvgl_v<major>_<minor>` cfgs for the LVGL version
+/// `lvgl-sys` was built against, so version-specific APIs can be gated. The
+/// minor cfgs are cumulative: LVGL 8.3 sets `lvgl_v8_0` up to `lvgl_v8_3`.
+fn emit_version_cfgs() {
+    for major in [8, 9] {
+        println!("cargo:rustc-check-cfg=cfg(lvgl_v{major})");
+        for minor in 0..=4 {
+            println!("cargo:rustc-check-cfg=cfg(lvgl_v{major}_{minor})");
+        }
+    }
+
+    let major = env::var("DEP_LVGL_VERSION_MAJOR");
+    let minor = env::var("DEP_LVGL_VERSION_MINOR").map(|minor| minor.parse::<u32>());
+    if let (Ok(major), Ok(Ok(minor))) = (major, minor) {
+        println!("cargo:rustc-cfg=lvgl_v{major}");
+        for minor in 0..=minor {
+            println!("cargo:rustc-cfg=lvgl_v{major}_{minor}");
+        }
+    }
+}
+
+/// Emits the `lvgl_deinit` cfg if the bindings include `lv_deinit()`, which
+/// LVGL 8 only provides with its own allocator or `LV_ENABLE_GC`.
+fn emit_deinit_cfg(bindings: &str) {
+    println!("cargo:rustc-check-cfg=cfg(lvgl_deinit)");
+    if bindings.contains("pub fn lv_deinit()") {
+        println!("cargo:rustc-cfg=lvgl_deinit");
+    }
+}
+
+/// Emits the `lvgl_style_sentinel` cfg if `lv_style_t` starts with a sentinel,
+/// which LVGL adds with `LV_USE_ASSERT_STYLE`. No other LVGL 8 type has one.
+fn emit_style_sentinel_cfg(bindings: &str) {
+    println!("cargo:rustc-check-cfg=cfg(lvgl_style_sentinel)");
+    if bindings.contains("pub sentinel: u32") {
+        println!("cargo:rustc-cfg=lvgl_style_sentinel");
+    }
+}
+
+/// Emits the `lvgl_theme_mono` cfg if the bindings include LVGL's monochrome
+/// theme, which is only built with `LV_USE_THEME_MONO`.
+fn emit_theme_mono_cfg(bindings: &str) {
+    println!("cargo:rustc-check-cfg=cfg(lvgl_theme_mono)");
+    if bindings.contains("pub fn lv_theme_mono_init(") {
+        println!("cargo:rustc-cfg=lvgl_theme_mono");
+    }
+}
@@ -12,13 +12,13 @@ pub struct LvglAlloc;
 
 unsafe impl GlobalAlloc for LvglAlloc {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        // Make sure LVGL is initialized!
-        crate::init();
+        // Make sure LVGL is initialized! This is an error if it already is.
+        let _ = crate::init();
         lvgl_sys::lv_mem_alloc(layout.size() as c_size_t) as *mut u8
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
-        crate::init();
+        let _ = crate::init();
         lvgl_sys::lv_mem_free(ptr as *mut c_void)
     }
 }
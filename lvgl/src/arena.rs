@@ -0,0 +1,378 @@
+//! Caller-provided memory for the crate's own bookkeeping
+//!
+//! Next to LVGL's objects, the safe layer keeps some state of its own: the
+//! closures of event handlers and animations, styles, driver registrations,
+//! and copies of text and arrays LVGL keeps pointers to. By default these
+//! are placed in LVGL's memory, i.e. its pool or, with `rust_alloc`, Rust's
+//! heap. With the `static_arena` feature, they are placed in an [`Arena`]
+//! instead, a static buffer sized by the application:
+//!
+//! ```ignore
+//! use lvgl::arena::{self, Arena};
+//!
+//! static ARENA: Arena<{ 16 * 1024 }> = Arena::new();
+//!
+//! fn main() -> LvResult<()> {
+//!     arena::register(&ARENA)?;
+//!     // Create displays and widgets...
+//! }
+//! ```
+//!
+//! Together with LVGL's own static pool, this needs neither a heap nor the
+//! `alloc` crate, and running out of bookkeeping memory cannot starve LVGL
+//! of memory for its objects, or the other way around. [`usage()`] reports
+//! how much of the arena is used, to size it.
+//!
+//! Allocations made before an arena is registered are placed in LVGL's
+//! memory, and are returned there when freed. Once registered, the arena
+//! is used until the program ends. Running out of it fails like running out
+//! of LVGL's memory: with `LvError::LvOOMemory`, or with a panic where the
+//! crate cannot recover.
+//!
+//! # Building
+//!
+//! Enable the `static_arena` feature. It cannot be combined with
+//! `lvgl_alloc`, which places the crate's bookkeeping on Rust's heap.
+
+use crate::{LvError, LvResult};
+use core::cell::UnsafeCell;
+use core::ffi::c_void;
+use core::hint;
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Alignment of every block handed out, enough for any primitive type.
+const ALIGN: usize = 16;
+
+/// Every block is prefixed by a header storing its total size, padded to
+/// `ALIGN` so the returned pointer keeps the block's alignment.
+const HEADER: usize = ALIGN;
+
+/// Static memory for the crate's bookkeeping, of `N` bytes. See the module
+/// documentation.
+#[repr(C, align(16))]
+pub struct Arena<const N: usize> {
+    memory: UnsafeCell<MaybeUninit<[u8; N]>>,
+}
+
+// Safety: the memory is only accessed by the allocator, behind its lock
+unsafe impl<const N: usize> Sync for Arena<N> {}
+
+impl<const N: usize> Arena<N> {
+    /// Creates an arena, to be placed in a `static` and registered.
+    pub const fn new() -> Self {
+        const {
+            assert!(N >= 4 * ALIGN, "an arena must have at least 64 bytes");
+        }
+        Self {
+            memory: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// How much of the registered arena is used, in bytes, including the
+/// header of each block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaUsage {
+    /// The size of the arena.
+    pub total: usize,
+    /// The memory allocated now.
+    pub used: usize,
+    /// The most memory allocated at once since the arena was registered.
+    pub max_used: usize,
+}
+
+/// A free block, linked in order of addresses.
+#[repr(C)]
+struct Free {
+    size: usize,
+    next: *mut Free,
+}
+
+/// A first-fit allocator over a buffer, merging neighbouring free blocks.
+struct Heap {
+    start: usize,
+    end: usize,
+    free: *mut Free,
+    used: usize,
+    max_used: usize,
+}
+
+impl Heap {
+    /// Manages the `size` bytes at `start`, which must be aligned to `ALIGN`.
+    unsafe fn new(start: *mut u8, size: usize) -> Self {
+        let size = size - size % ALIGN;
+        let free = start as *mut Free;
+        free.write(Free {
+            size,
+            next: ptr::null_mut(),
+        });
+        Self {
+            start: start as usize,
+            end: start as usize + size,
+            free,
+            used: 0,
+            max_used: 0,
+        }
+    }
+
+    fn contains(&self, ptr: *mut u8) -> bool {
+        (self.start..self.end).contains(&(ptr as usize))
+    }
+
+    fn alloc(&mut self, size: usize) -> *mut u8 {
+        let Some(needed) = size
+            .checked_add(HEADER + ALIGN - 1)
+            .map(|size| size & !(ALIGN - 1))
+        else {
+            return ptr::null_mut();
+        };
+        let mut link: *mut *mut Free = &mut self.free;
+        unsafe {
+            while !(*link).is_null() {
+                let block = *link;
+                if (*block).size >= needed {
+                    let rest = (*block).size - needed;
+                    let taken = if rest >= ALIGN {
+                        let split = (block as *mut u8).add(needed) as *mut Free;
+                        split.write(Free {
+                            size: rest,
+                            next: (*block).next,
+                        });
+                        *link = split;
+                        needed
+                    } else {
+                        *link = (*block).next;
+                        (*block).size
+                    };
+                    (block as *mut usize).write(taken);
+                    self.used += taken;
+                    self.max_used = self.max_used.max(self.used);
+                    return (block as *mut u8).add(HEADER);
+                }
+                link = &mut (*block).next;
+            }
+        }
+        ptr::null_mut()
+    }
+
+    /// Frees a block returned by `alloc()` or `realloc()`.
+    unsafe fn free(&mut self, ptr: *mut u8) {
+        let block = ptr.sub(HEADER) as *mut Free;
+        let size = *(block as *const usize);
+        self.used -= size;
+
+        let mut prev: *mut Free = ptr::null_mut();
+        let mut next = self.free;
+        while !next.is_null() && next < block {
+            prev = next;
+            next = (*next).next;
+        }
+        block.write(Free { size, next });
+        if !next.is_null() && block as usize + size == next as usize {
+            (*block).size += (*next).size;
+            (*block).next = (*next).next;
+        }
+        if prev.is_null() {
+            self.free = block;
+        } else if prev as usize + (*prev).size == block as usize {
+            (*prev).size += (*block).size;
+            (*prev).next = (*block).next;
+        } else {
+            (*prev).next = block;
+        }
+    }
+
+    /// Grows or shrinks a block returned by `alloc()` or `realloc()`, moving
+    /// it if needed. Returns null, leaving the block as it is, if the arena
+    /// is full.
+    unsafe fn realloc(&mut self, ptr: *mut u8, size: usize) -> *mut u8 {
+        let capacity = *(ptr.sub(HEADER) as *const usize) - HEADER;
+        if size <= capacity {
+            return ptr;
+        }
+        let moved = self.alloc(size);
+        if !moved.is_null() {
+            ptr::copy_nonoverlapping(ptr, moved, capacity);
+            self.free(ptr);
+        }
+        moved
+    }
+
+    fn usage(&self) -> ArenaUsage {
+        ArenaUsage {
+            total: self.end - self.start,
+            used: self.used,
+            max_used: self.max_used,
+        }
+    }
+}
+
+/// The registered arena, behind a spin lock, as blocks may be freed on any
+/// thread.
+struct Registered {
+    locked: AtomicBool,
+    heap: UnsafeCell<Option<Heap>>,
+}
+
+// Safety: the heap is only accessed with the lock held
+unsafe impl Sync for Registered {}
+
+static REGISTERED: Registered = Registered {
+    locked: AtomicBool::new(false),
+    heap: UnsafeCell::new(None),
+};
+
+fn with_heap<R>(f: impl FnOnce(&mut Option<Heap>) -> R) -> R {
+    while REGISTERED
+        .locked
+        .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        hint::spin_loop();
+    }
+    let result = f(unsafe { &mut *REGISTERED.heap.get() });
+    REGISTERED.locked.store(false, Ordering::Release);
+    result
+}
+
+/// Places the crate's bookkeeping allocations made from now on in `arena`.
+/// Fails with `LvError::AlreadyInUse` if an arena is already registered.
+pub fn register<const N: usize>(arena: &'static Arena<N>) -> LvResult<()> {
+    with_heap(|heap| {
+        if heap.is_some() {
+            return Err(LvError::AlreadyInUse);
+        }
+        // Safety: the arena is only ever registered once, and is aligned
+        *heap = Some(unsafe { Heap::new(arena.memory.get() as *mut u8, N) });
+        Ok(())
+    })
+}
+
+/// Unregisters the arena, so that tests leave later ones with LVGL's memory.
+/// All its blocks must have been freed.
+#[cfg(test)]
+fn unregister() {
+    with_heap(|heap| {
+        assert_eq!(heap.take().map(|heap| heap.used), Some(0));
+    })
+}
+
+/// Returns how much of the registered arena is used, or `None` if no arena
+/// is registered.
+pub fn usage() -> Option<ArenaUsage> {
+    with_heap(|heap| heap.as_ref().map(Heap::usage))
+}
+
+/// Allocates `size` bytes in the registered arena, or in LVGL's memory if
+/// none is registered. Returns null if the memory is full.
+pub(crate) unsafe fn alloc(size: usize) -> *mut c_void {
+    match with_heap(|heap| heap.as_mut().map(|heap| heap.alloc(size))) {
+        Some(ptr) => ptr as *mut c_void,
+        None => lvgl_sys::lv_mem_alloc(size as _),
+    }
+}
+
+/// Resizes a block returned by `alloc()` or `realloc()`, in the memory it was
+/// allocated in. Returns null, leaving the block as it is, if the memory is
+/// full.
+pub(crate) unsafe fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return alloc(size);
+    }
+    let resized = with_heap(|heap| match heap {
+        Some(heap) if heap.contains(ptr as *mut u8) => Some(heap.realloc(ptr as *mut u8, size)),
+        _ => None,
+    });
+    match resized {
+        Some(ptr) => ptr as *mut c_void,
+        None => lvgl_sys::lv_mem_realloc(ptr, size as _),
+    }
+}
+
+/// Frees a block returned by `alloc()` or `realloc()`, in the memory it was
+/// allocated in.
+pub(crate) unsafe fn free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let freed = with_heap(|heap| match heap {
+        Some(heap) if heap.contains(ptr as *mut u8) => {
+            heap.free(ptr as *mut u8);
+            true
+        }
+        _ => false,
+    });
+    if !freed {
+        lvgl_sys::lv_mem_free(ptr)
+    }
+}
+
+// The header must hold a block's size, and a free block its link
+const _: () = assert!(mem::size_of::<Free>() <= HEADER);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::TextBuf;
+    use crate::Box;
+
+    #[test]
+    fn allocates_first_fit_and_merges() {
+        let mut memory = [0u128; 16];
+        let mut heap = unsafe { Heap::new(memory.as_mut_ptr() as *mut u8, 256) };
+
+        let a = heap.alloc(10);
+        let b = heap.alloc(40);
+        let c = heap.alloc(0);
+        assert_eq!(a as usize % ALIGN, 0);
+        assert_eq!(b as usize - a as usize, 32);
+        assert_eq!(c as usize - b as usize, 64);
+        assert_eq!(heap.usage().used, 112);
+        assert!(heap.alloc(200).is_null());
+
+        unsafe {
+            heap.free(a);
+            heap.free(b);
+            // Fits in the merged blocks of `a` and `b`
+            assert_eq!(heap.alloc(80), a);
+            let a = heap.realloc(a, 90);
+            assert_eq!(heap.realloc(a, 8), a);
+            heap.free(c);
+            heap.free(a);
+        }
+        let usage = heap.usage();
+        assert_eq!(usage.used, 0);
+        assert_eq!(usage.max_used, 224);
+        assert!(!heap.alloc(240).is_null());
+    }
+
+    static ARENA: Arena<{ 64 * 1024 }> = Arena::new();
+
+    #[test]
+    fn bookkeeping_in_arena() {
+        crate::tests::initialize_test(false);
+        let outside = Box::new(1u32);
+        register(&ARENA).unwrap();
+        assert_eq!(register(&ARENA), Err(LvError::AlreadyInUse));
+
+        let boxed = Box::new([7u8; 100]);
+        let raw = Box::into_raw(boxed);
+        assert!(with_heap(|heap| heap
+            .as_ref()
+            .unwrap()
+            .contains(raw as *mut u8)));
+        assert!(usage().unwrap().used >= 100 + HEADER);
+        drop(unsafe { Box::from_raw(raw) });
+        drop(outside);
+
+        let text = "long enough to be copied into memory, rather than the stack";
+        let mut buf = TextBuf::with_capacity(4).unwrap();
+        buf.push_str(text).unwrap();
+        buf.push_str(text).unwrap();
+        assert_eq!(buf.as_c_str().to_bytes().len(), 2 * text.len());
+        drop(buf);
+        unregister();
+    }
+}
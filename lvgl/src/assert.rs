@@ -0,0 +1,105 @@
+//! Rust handling of LVGL's internal assertions
+//!
+//! When one of LVGL's internal checks fails (e.g. a NULL parameter or a failed
+//! allocation), it runs `LV_ASSERT_HANDLER`, which by default halts in an
+//! endless loop. This feature instead calls a Rust handler with the location
+//! of the failed assertion, so firmware can log it and reset or panic.
+//!
+//! # Building
+//!
+//! Enable the `rust_assert` feature on the `lvgl` crate. The build script
+//! applies the following on top of your `lv_conf.h`:
+//!
+//! ```c
+//! #define LV_ASSERT_HANDLER_INCLUDE <rs_assert.h>
+//! #define LV_ASSERT_HANDLER rs_lv_assert_handler(__FILE__, __LINE__);
+//! ```
+//!
+//! # Usage
+//!
+//! By default a failed assertion is reported through the enabled logging
+//! backends and then panics. A custom handler can be installed at any time:
+//!
+//! ```ignore
+//! fn on_lvgl_assert(file: &str, line: u32) -> ! {
+//!     defmt::error!("LVGL assertion failed at {}:{}", file, line);
+//!     cortex_m::peripheral::SCB::sys_reset()
+//! }
+//!
+//! fn main() {
+//!     lvgl::assert::set_handler(on_lvgl_assert);
+//!     // Initialize displays, etc.
+//! }
+//! ```
+//!
+//! As the handler is called from C code, a panic raised in it cannot unwind
+//! back into LVGL and aborts the program after the panic handler runs.
+
+use core::ffi::{c_char, c_int};
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use cstr_core::CStr;
+
+/// A handler for failed LVGL assertions, receiving the C source file and line
+/// of the assertion. LVGL's state is not consistent after an assertion fails,
+/// so the handler must not return.
+pub type AssertHandler = fn(file: &str, line: u32) -> !;
+
+/// The installed handler, or null for `default_handler()`.
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs the handler called when an LVGL assertion fails, replacing the
+/// previous one.
+pub fn set_handler(handler: AssertHandler) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+fn default_handler(file: &str, line: u32) -> ! {
+    diag!(error, "LVGL assertion failed at {}:{}", file, line);
+    panic!("LVGL assertion failed at {}:{}", file, line);
+}
+
+fn handler() -> AssertHandler {
+    let handler = HANDLER.load(Ordering::Acquire);
+    if handler.is_null() {
+        default_handler
+    } else {
+        // Safety: only `set_handler()` stores pointers, from an `AssertHandler`
+        unsafe { mem::transmute::<*mut (), AssertHandler>(handler) }
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_assert_handler(file: *const c_char, line: c_int) {
+    handle(file, line)
+}
+
+unsafe fn handle(file: *const c_char, line: c_int) -> ! {
+    let file = if file.is_null() {
+        "<unknown>"
+    } else {
+        CStr::from_ptr(file).to_str().unwrap_or("<invalid UTF-8>")
+    };
+    handler()(file, line as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn panicking_handler(file: &str, line: u32) -> ! {
+        panic!("{}:{}", file, line);
+    }
+
+    #[test]
+    fn calls_installed_handler() {
+        set_handler(panicking_handler);
+        let err = std::panic::catch_unwind(|| unsafe {
+            handle(b"lv_obj.c\0".as_ptr() as *const c_char, 42)
+        })
+        .unwrap_err();
+        assert_eq!(err.downcast_ref::<String>().unwrap(), "lv_obj.c:42");
+        set_handler(default_handler);
+    }
+}
@@ -0,0 +1,106 @@
+//! The effective LVGL configuration
+//!
+//! Values of the `lv_conf.h` the bindings were compiled with, after LVGL's
+//! defaults and any overrides applied by the build. They can be used to check
+//! at compile time that the configuration matches what the application or
+//! hardware expects:
+//!
+//! ```ignore
+//! const _: () = assert!(lvgl::config::COLOR_DEPTH == 16);
+//! const _: () = assert!(lvgl::config::USE_CHART, "the dashboard needs charts");
+//! ```
+//!
+//! LVGL 8 has no default resolution in its configuration; the resolution of
+//! each display is given when registering it.
+
+/// Color depth in bits per pixel (`LV_COLOR_DEPTH`).
+pub const COLOR_DEPTH: u32 = lvgl_sys::LV_COLOR_DEPTH;
+
+/// Whether the bytes of 16-bit colors are swapped (`LV_COLOR_16_SWAP`).
+pub const COLOR_16_SWAP: bool = lvgl_sys::LV_COLOR_16_SWAP != 0;
+
+/// Default DPI of displays, used to scale the default styles (`LV_DPI_DEF`).
+pub const DPI: u32 = lvgl_sys::LV_DPI_DEF;
+
+/// Default display refresh period in milliseconds (`LV_DISP_DEF_REFR_PERIOD`).
+pub const REFR_PERIOD_MS: u32 = lvgl_sys::LV_DISP_DEF_REFR_PERIOD;
+
+/// Default input device read period in milliseconds
+/// (`LV_INDEV_DEF_READ_PERIOD`).
+pub const INDEV_READ_PERIOD_MS: u32 = lvgl_sys::LV_INDEV_DEF_READ_PERIOD;
+
+/// Whether LVGL uses a custom allocator instead of its own memory pool
+/// (`LV_MEM_CUSTOM`).
+pub const MEM_CUSTOM: bool = lvgl_sys::LV_MEM_CUSTOM != 0;
+
+/// Size in bytes of LVGL's memory pool (`LV_MEM_SIZE`), or `None` if a
+/// custom allocator is used.
+pub const MEM_SIZE: Option<usize> = match lvgl_sys::_LV_CONF_MEM_SIZE {
+    0 => None,
+    size => Some(size as usize),
+};
+
+macro_rules! enabled {
+    ($($name:ident => $define:ident,)*) => {
+        $(
+            #[doc = concat!("Whether `", stringify!($define), "` is enabled.")]
+            pub const $name: bool = lvgl_sys::$define != 0;
+        )*
+    };
+}
+
+enabled! {
+    USE_LOG => LV_USE_LOG,
+    USE_FLEX => LV_USE_FLEX,
+    USE_GRID => LV_USE_GRID,
+    USE_ARC => LV_USE_ARC,
+    USE_BAR => LV_USE_BAR,
+    USE_BTN => LV_USE_BTN,
+    USE_BTNMATRIX => LV_USE_BTNMATRIX,
+    USE_CANVAS => LV_USE_CANVAS,
+    USE_CHECKBOX => LV_USE_CHECKBOX,
+    USE_DROPDOWN => LV_USE_DROPDOWN,
+    USE_IMG => LV_USE_IMG,
+    USE_LABEL => LV_USE_LABEL,
+    USE_LINE => LV_USE_LINE,
+    USE_ROLLER => LV_USE_ROLLER,
+    USE_SLIDER => LV_USE_SLIDER,
+    USE_SWITCH => LV_USE_SWITCH,
+    USE_TEXTAREA => LV_USE_TEXTAREA,
+    USE_TABLE => LV_USE_TABLE,
+    USE_ANIMIMG => LV_USE_ANIMIMG,
+    USE_CALENDAR => LV_USE_CALENDAR,
+    USE_CHART => LV_USE_CHART,
+    USE_COLORWHEEL => LV_USE_COLORWHEEL,
+    USE_IMGBTN => LV_USE_IMGBTN,
+    USE_KEYBOARD => LV_USE_KEYBOARD,
+    USE_LED => LV_USE_LED,
+    USE_LIST => LV_USE_LIST,
+    USE_MENU => LV_USE_MENU,
+    USE_METER => LV_USE_METER,
+    USE_MSGBOX => LV_USE_MSGBOX,
+    USE_SPAN => LV_USE_SPAN,
+    USE_SPINBOX => LV_USE_SPINBOX,
+    USE_SPINNER => LV_USE_SPINNER,
+    USE_TABVIEW => LV_USE_TABVIEW,
+    USE_TILEVIEW => LV_USE_TILEVIEW,
+    USE_WIN => LV_USE_WIN,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn color_depth_matches_color_type() {
+        assert_eq!(
+            COLOR_DEPTH.max(8) as usize,
+            core::mem::size_of::<lvgl_sys::lv_color_t>() * 8
+        );
+    }
+
+    #[test]
+    fn mem_size_matches_allocator() {
+        assert_eq!(MEM_SIZE.is_none(), MEM_CUSTOM);
+    }
+}
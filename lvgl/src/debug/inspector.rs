@@ -0,0 +1,427 @@
+use super::info;
+use crate::lv_core::widget_id;
+use crate::style::{ConstStyle, ConstStyleProp, Opacity, StyleProp};
+use crate::Color;
+use core::fmt::{self, Write};
+use core::ptr::{self, NonNull};
+use core::str;
+
+/// The longest command line an `Inspector` accepts, in bytes.
+pub const INSPECTOR_LINE_LEN: usize = 80;
+
+/// The outline drawn around the highlighted object.
+static HIGHLIGHT: ConstStyle = ConstStyle::new(&[
+    ConstStyleProp::color(StyleProp::OUTLINE_COLOR, Color::from_hex(0xFF00FF)),
+    ConstStyleProp::num(StyleProp::OUTLINE_WIDTH, 2),
+    ConstStyleProp::opa(StyleProp::OUTLINE_OPA, Opacity::OPA_COVER),
+    ConstStyleProp::num(StyleProp::OUTLINE_PAD, 1),
+    ConstStyleProp::END,
+]);
+
+/// The style properties `set` changes, and whether they are colors.
+const PROPS: &[(&str, StyleProp, bool)] = &[
+    ("x", StyleProp::X, false),
+    ("y", StyleProp::Y, false),
+    ("width", StyleProp::WIDTH, false),
+    ("height", StyleProp::HEIGHT, false),
+    ("pad_top", StyleProp::PAD_TOP, false),
+    ("pad_bottom", StyleProp::PAD_BOTTOM, false),
+    ("pad_left", StyleProp::PAD_LEFT, false),
+    ("pad_right", StyleProp::PAD_RIGHT, false),
+    ("pad_row", StyleProp::PAD_ROW, false),
+    ("pad_column", StyleProp::PAD_COLUMN, false),
+    ("radius", StyleProp::RADIUS, false),
+    ("opa", StyleProp::OPA, false),
+    ("bg_color", StyleProp::BG_COLOR, true),
+    ("bg_opa", StyleProp::BG_OPA, false),
+    ("border_color", StyleProp::BORDER_COLOR, true),
+    ("border_width", StyleProp::BORDER_WIDTH, false),
+    ("text_color", StyleProp::TEXT_COLOR, true),
+    ("text_letter_space", StyleProp::TEXT_LETTER_SPACE, false),
+    ("text_line_space", StyleProp::TEXT_LINE_SPACE, false),
+];
+
+/// Answers commands about the widgets of the default display's active
+/// screen, read line by line from a serial port or an RTT channel. See the
+/// module documentation.
+pub struct Inspector {
+    line: [u8; INSPECTOR_LINE_LEN],
+    len: usize,
+    overflow: bool,
+    highlighted: Option<NonNull<lvgl_sys::lv_obj_t>>,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inspector {
+    /// Creates an inspector waiting for its first command.
+    pub const fn new() -> Self {
+        Self {
+            line: [0; INSPECTOR_LINE_LEN],
+            len: 0,
+            overflow: false,
+            highlighted: None,
+        }
+    }
+
+    /// Adds a received byte to the current command, and runs the command at
+    /// the end of the line, writing the answer to `out`.
+    pub fn feed<W: Write + ?Sized>(&mut self, byte: u8, out: &mut W) -> fmt::Result {
+        match byte {
+            b'\n' | b'\r' => {
+                let (len, overflow) = (self.len, self.overflow);
+                self.len = 0;
+                self.overflow = false;
+                if overflow {
+                    return writeln!(out, "error: line too long");
+                }
+                let line = self.line;
+                match str::from_utf8(&line[..len]) {
+                    Ok(line) if line.trim().is_empty() => Ok(()),
+                    Ok(line) => self.execute(line, out),
+                    Err(_) => writeln!(out, "error: not UTF-8"),
+                }
+            }
+            _ if self.len == INSPECTOR_LINE_LEN => {
+                self.overflow = true;
+                Ok(())
+            }
+            _ => {
+                self.line[self.len] = byte;
+                self.len += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs a command, writing its answer to `out`. Answers end with a line
+    /// of `ok`, or are a single line starting with `error:`.
+    pub fn execute<W: Write + ?Sized>(&mut self, line: &str, out: &mut W) -> fmt::Result {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let result = match command {
+            "tree" => self.tree(words.next(), out),
+            "info" => self.info(words.next(), out),
+            "highlight" => self.highlight(words.next()),
+            "set" => self.set(words.next(), words.next(), words.next()),
+            "help" => help(out),
+            _ => Err(Error::Command),
+        };
+        match result {
+            Ok(Ok(())) => writeln!(out, "ok"),
+            Ok(Err(e)) => Err(e),
+            Err(e) => writeln!(out, "error: {}", e),
+        }
+    }
+
+    fn tree<W: Write + ?Sized>(&mut self, path: Option<&str>, out: &mut W) -> Answer {
+        let obj = match path {
+            Some(path) => resolve(path)?,
+            None => screen()?,
+        };
+        unsafe {
+            lvgl_sys::lv_obj_update_layout(obj);
+            Ok(dump(obj, &mut Path::of(obj), out))
+        }
+    }
+
+    fn info<W: Write + ?Sized>(&mut self, path: Option<&str>, out: &mut W) -> Answer {
+        let obj = resolve(path.ok_or(Error::Arguments)?)?;
+        unsafe {
+            lvgl_sys::lv_obj_update_layout(obj);
+            Ok(writeln!(out, "{}", info(obj)))
+        }
+    }
+
+    fn highlight(&mut self, path: Option<&str>) -> Answer {
+        let obj = path.map(resolve).transpose()?;
+        unsafe {
+            if let Some(previous) = self.highlighted.take() {
+                if lvgl_sys::lv_obj_is_valid(previous.as_ptr()) {
+                    lvgl_sys::lv_obj_remove_style(
+                        previous.as_ptr(),
+                        HIGHLIGHT.raw(),
+                        lvgl_sys::LV_PART_MAIN,
+                    );
+                }
+            }
+            if let Some(obj) = obj {
+                lvgl_sys::lv_obj_add_style(obj, HIGHLIGHT.raw(), lvgl_sys::LV_PART_MAIN);
+                self.highlighted = NonNull::new(obj);
+            }
+        }
+        Ok(Ok(()))
+    }
+
+    fn set(&mut self, path: Option<&str>, prop: Option<&str>, value: Option<&str>) -> Answer {
+        let (path, prop, value) = match (path, prop, value) {
+            (Some(path), Some(prop), Some(value)) => (path, prop, value),
+            _ => return Err(Error::Arguments),
+        };
+        let obj = resolve(path)?;
+        let &(_, prop, is_color) = PROPS
+            .iter()
+            .find(|(name, _, _)| *name == prop)
+            .ok_or(Error::Property)?;
+        let value = if is_color {
+            let hex = value.strip_prefix('#').ok_or(Error::Value)?;
+            let hex = u32::from_str_radix(hex, 16).map_err(|_| Error::Value)?;
+            lvgl_sys::lv_style_value_t {
+                color: Color::from_hex(hex).raw,
+            }
+        } else {
+            lvgl_sys::lv_style_value_t {
+                num: value.parse().map_err(|_| Error::Value)?,
+            }
+        };
+        unsafe {
+            lvgl_sys::lv_obj_set_local_style_prop(
+                obj,
+                prop.bits() as _,
+                value,
+                lvgl_sys::LV_PART_MAIN,
+            );
+        }
+        Ok(Ok(()))
+    }
+}
+
+/// The result of a command: an error to answer with, or the result of
+/// writing the answer.
+type Answer = Result<fmt::Result, Error>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Error {
+    Command,
+    Arguments,
+    NoScreen,
+    NotFound,
+    Property,
+    Value,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::Command => "unknown command, try help",
+            Error::Arguments => "missing arguments, try help",
+            Error::NoScreen => "no active screen",
+            Error::NotFound => "no such object",
+            Error::Property => "unknown property, try help",
+            Error::Value => "invalid value",
+        })
+    }
+}
+
+fn help<W: Write + ?Sized>(out: &mut W) -> Answer {
+    let mut write = || {
+        writeln!(out, "tree [PATH]          list objects with their paths")?;
+        writeln!(out, "info PATH            show an object")?;
+        writeln!(
+            out,
+            "highlight [PATH]     outline an object, or clear the outline"
+        )?;
+        writeln!(
+            out,
+            "set PATH PROP VALUE  set a style property of an object"
+        )?;
+        writeln!(
+            out,
+            "PATH is child indices from the screen, e.g. 0.2, `.` for the screen, or #id"
+        )?;
+        write!(out, "PROP is one of")?;
+        for (name, _, is_color) in PROPS {
+            write!(out, " {}{}", name, if *is_color { "=#RRGGBB" } else { "" })?;
+        }
+        writeln!(out)
+    };
+    Ok(write())
+}
+
+fn screen() -> Result<*mut lvgl_sys::lv_obj_t, Error> {
+    let screen = unsafe { lvgl_sys::lv_disp_get_scr_act(ptr::null_mut()) };
+    if screen.is_null() {
+        Err(Error::NoScreen)
+    } else {
+        Ok(screen)
+    }
+}
+
+/// Finds the object at `path`. See `help()`.
+fn resolve(path: &str) -> Result<*mut lvgl_sys::lv_obj_t, Error> {
+    let screen = screen()?;
+    let obj = if let Some(id) = path.strip_prefix('#') {
+        unsafe { widget_id::find(screen, id) }
+    } else if path == "." {
+        screen
+    } else {
+        let mut obj = screen;
+        for index in path.split('.') {
+            let index: i32 = index.parse().map_err(|_| Error::NotFound)?;
+            if index < 0 {
+                return Err(Error::NotFound);
+            }
+            obj = unsafe { lvgl_sys::lv_obj_get_child(obj, index) };
+            if obj.is_null() {
+                break;
+            }
+        }
+        obj
+    };
+    if obj.is_null() {
+        Err(Error::NotFound)
+    } else {
+        Ok(obj)
+    }
+}
+
+/// The child indices leading to an object, for the tree listing.
+#[derive(Default)]
+struct Path {
+    indices: [u32; 16],
+    len: usize,
+}
+
+impl Path {
+    /// Returns the path of `obj` from its screen, e.g. of an object found by
+    /// its ID, so that the listing can be resolved again.
+    unsafe fn of(obj: *mut lvgl_sys::lv_obj_t) -> Self {
+        let mut path = Self::default();
+        let mut parent = lvgl_sys::lv_obj_get_parent(obj);
+        while !parent.is_null() {
+            path.len += 1;
+            parent = lvgl_sys::lv_obj_get_parent(parent);
+        }
+        let mut child = obj;
+        for level in (0..path.len).rev() {
+            if level < path.indices.len() {
+                path.indices[level] = lvgl_sys::lv_obj_get_index(child);
+            }
+            child = lvgl_sys::lv_obj_get_parent(child);
+        }
+        path
+    }
+
+    fn push(&mut self, index: u32) {
+        if self.len < self.indices.len() {
+            self.indices[self.len] = index;
+        }
+        self.len += 1;
+    }
+
+    fn pop(&mut self) {
+        self.len -= 1;
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.len == 0 {
+            return f.write_str(".");
+        }
+        for (i, index) in self.indices[..self.len.min(self.indices.len())]
+            .iter()
+            .enumerate()
+        {
+            if i > 0 {
+                f.write_char('.')?;
+            }
+            write!(f, "{}", index)?;
+        }
+        if self.len > self.indices.len() {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+unsafe fn dump<W: Write + ?Sized>(
+    obj: *mut lvgl_sys::lv_obj_t,
+    path: &mut Path,
+    out: &mut W,
+) -> fmt::Result {
+    let info = info(obj);
+    writeln!(out, "{} {}", path, info)?;
+    for i in 0..info.children {
+        path.push(i);
+        dump(lvgl_sys::lv_obj_get_child(obj, i as i32), path, out)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Btn, Label};
+    use crate::{Display, NativeObject, Widget};
+    use std::string::String;
+
+    fn run(inspector: &mut Inspector, line: &str) -> String {
+        let mut out = String::new();
+        for byte in line.bytes().chain([b'\n']) {
+            inspector.feed(byte, &mut out).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn inspect_remotely() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        screen.clean();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.set_id("ok");
+        let _label = Label::create(&mut btn).unwrap();
+        let mut inspector = Inspector::new();
+
+        let tree = run(&mut inspector, "tree");
+        let lines: std::vec::Vec<_> = tree.lines().collect();
+        assert!(lines[0].starts_with(". Obj ("));
+        assert!(lines[1].starts_with("0 Btn #ok ("));
+        assert!(lines[2].starts_with("0.0 Label ("));
+        assert_eq!(lines.last(), Some(&"ok"));
+        assert!(run(&mut inspector, "info 0.0").starts_with("Label ("));
+        // Paths are from the screen, whichever path the listing starts at
+        let tree = run(&mut inspector, "tree #ok");
+        let lines: std::vec::Vec<_> = tree.lines().collect();
+        assert!(lines[0].starts_with("0 Btn #ok ("));
+        assert!(lines[1].starts_with("0.0 Label ("));
+
+        assert_eq!(run(&mut inspector, "highlight #ok"), "ok\n");
+        let outline = unsafe {
+            lvgl_sys::lv_obj_get_style_prop(
+                btn.raw().as_ptr(),
+                lvgl_sys::LV_PART_MAIN,
+                StyleProp::OUTLINE_WIDTH.bits() as _,
+            )
+            .num
+        };
+        assert_eq!(outline, 2);
+        assert_eq!(run(&mut inspector, "highlight"), "ok\n");
+
+        assert_eq!(run(&mut inspector, "set 0 width 77"), "ok\n");
+        assert_eq!(run(&mut inspector, "set #ok bg_color #00FF00"), "ok\n");
+        assert_eq!(btn.get_style_width(crate::Part::Main), 77);
+
+        assert_eq!(
+            run(&mut inspector, "set 3 width 1"),
+            "error: no such object\n"
+        );
+        assert_eq!(
+            run(&mut inspector, "set 0 shadow 1"),
+            "error: unknown property, try help\n"
+        );
+        assert_eq!(
+            run(&mut inspector, "frobnicate"),
+            "error: unknown command, try help\n"
+        );
+        let long = "x".repeat(INSPECTOR_LINE_LEN + 1);
+        assert_eq!(run(&mut inspector, &long), "error: line too long\n");
+    }
+}
@@ -0,0 +1,249 @@
+//! Listings of the live widget tree, for diagnosing layouts on a device
+//!
+//! [`dump_tree()`] writes one line per object below a widget, indented by
+//! depth, with its type, ID, coordinates, flags, states and number of
+//! styles. It writes to anything implementing `core::fmt::Write`, such as an
+//! RTT channel or a serial port wrapper, so no debugger GUI is needed:
+//!
+//! ```ignore
+//! let screen = display.get_scr_act()?;
+//! lvgl::debug::dump_tree(&screen, &mut rtt)?;
+//! ```
+//!
+//! ```text
+//! Obj (0, 0)-(239, 239) flags=CLICKABLE | ... styles=1
+//!   Btn #save (10, 10)-(109, 49) flags=CLICKABLE | ... state=PRESSED styles=2
+//!     Label (28, 21)-(91, 38) flags=... styles=1
+//! ```
+//!
+//! [`inspect()`] returns the same information for a single object.
+//!
+//! # Remote inspector
+//!
+//! With the `inspector` feature, an [`Inspector`] answers text commands
+//! sent from the host, e.g. from a serial terminal or `probe-rs`'s RTT
+//! console, to list the active screen's objects, outline one of them, and
+//! change basic style properties while the application runs:
+//!
+//! ```ignore
+//! let mut inspector = Inspector::new();
+//! loop {
+//!     while let Some(byte) = serial.read() {
+//!         inspector.feed(byte, &mut serial)?;
+//!     }
+//!     lvgl::task_handler()?;
+//! }
+//! ```
+//!
+//! ```text
+//! > tree
+//! . Obj (0, 0)-(239, 239) flags=CLICKABLE | ... styles=1
+//! 0 Btn #save (10, 10)-(109, 49) flags=CLICKABLE | ... styles=2
+//! 0.0 Label (28, 21)-(91, 38) flags=... styles=1
+//! ok
+//! > highlight #save
+//! ok
+//! > set 0 bg_color #FF8800
+//! ok
+//! ```
+//!
+//! `help` lists the commands and properties. Every answer ends with a line
+//! of `ok`, or is a single line starting with `error:`, so host tools can
+//! script it.
+
+#[cfg(feature = "inspector")]
+mod inspector;
+#[cfg(feature = "inspector")]
+pub use inspector::*;
+
+use crate::lv_core::widget_id;
+use crate::{Area, NativeObject, ObjFlag, State};
+use core::fmt;
+use core::ptr;
+
+/// What `inspect()` reports about an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjInfo {
+    /// The name of the most derived widget type the object is, e.g. `"Btn"`,
+    /// or `"Obj"` for plain objects and types without a name here.
+    pub type_name: &'static str,
+    /// The ID assigned with `Widget::set_id()`, if any.
+    pub id: Option<&'static str>,
+    /// The coordinates of the object on the screen.
+    pub coords: Area,
+    /// The flags set on the object.
+    pub flags: ObjFlag,
+    /// The states the object is in, empty in its default state.
+    pub state: State,
+    /// The number of styles applied to the object, counting its local style
+    /// properties as one.
+    pub styles: u32,
+    /// The number of direct children of the object.
+    pub children: u32,
+}
+
+impl fmt::Display for ObjInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.type_name)?;
+        if let Some(id) = self.id {
+            write!(f, " #{}", id)?;
+        }
+        let Area { x1, y1, x2, y2 } = self.coords;
+        write!(f, " ({}, {})-({}, {}) flags=", x1, y1, x2, y2)?;
+        bitflags::parser::to_writer(&self.flags, &mut *f)?;
+        if !self.state.is_empty() {
+            f.write_str(" state=")?;
+            bitflags::parser::to_writer(&self.state, &mut *f)?;
+        }
+        write!(f, " styles={}", self.styles)
+    }
+}
+
+/// Returns the type, coordinates, flags, states and styles of `obj`, with
+/// its layout up to date.
+pub fn inspect(obj: &impl NativeObject) -> ObjInfo {
+    let obj = obj.raw().as_ptr();
+    unsafe {
+        lvgl_sys::lv_obj_update_layout(obj);
+        info(obj)
+    }
+}
+
+/// Writes `root` and every object below it to `out`, one line per object,
+/// indented by two spaces per level. See the module documentation.
+pub fn dump_tree<W: fmt::Write + ?Sized>(root: &impl NativeObject, out: &mut W) -> fmt::Result {
+    let root = root.raw().as_ptr();
+    unsafe {
+        lvgl_sys::lv_obj_update_layout(root);
+        dump(root, 0, out)
+    }
+}
+
+unsafe fn dump<W: fmt::Write + ?Sized>(
+    obj: *mut lvgl_sys::lv_obj_t,
+    depth: usize,
+    out: &mut W,
+) -> fmt::Result {
+    let info = info(obj);
+    writeln!(out, "{:indent$}{}", "", info, indent = depth * 2)?;
+    for i in 0..info.children {
+        dump(lvgl_sys::lv_obj_get_child(obj, i as i32), depth + 1, out)?;
+    }
+    Ok(())
+}
+
+unsafe fn info(obj: *mut lvgl_sys::lv_obj_t) -> ObjInfo {
+    let raw = &*obj;
+    ObjInfo {
+        type_name: type_name(lvgl_sys::lv_obj_get_class(obj)),
+        id: widget_id::get(obj),
+        coords: raw.coords.into(),
+        flags: ObjFlag::from_bits_retain(raw.flags as u32),
+        state: State::from_bits_retain(raw.state as u16),
+        styles: raw.style_cnt() as u32,
+        children: lvgl_sys::lv_obj_get_child_cnt(obj),
+    }
+}
+
+/// Returns the name of the first widget type in the chain of base classes
+/// of `class`, e.g. `"Spinner"` rather than `"Arc"` for a spinner.
+fn type_name(mut class: *const lvgl_sys::lv_obj_class_t) -> &'static str {
+    let types: [(&lvgl_sys::lv_obj_class_t, &'static str); 35] = unsafe {
+        [
+            (&lvgl_sys::lv_animimg_class, "Animimg"),
+            (&lvgl_sys::lv_arc_class, "Arc"),
+            (&lvgl_sys::lv_bar_class, "Bar"),
+            (&lvgl_sys::lv_btn_class, "Btn"),
+            (&lvgl_sys::lv_btnmatrix_class, "Btnmatrix"),
+            (&lvgl_sys::lv_calendar_class, "Calendar"),
+            (&lvgl_sys::lv_canvas_class, "Canvas"),
+            (&lvgl_sys::lv_chart_class, "Chart"),
+            (&lvgl_sys::lv_checkbox_class, "Checkbox"),
+            (&lvgl_sys::lv_colorwheel_class, "Colorwheel"),
+            (&lvgl_sys::lv_dropdown_class, "Dropdown"),
+            (&lvgl_sys::lv_img_class, "Img"),
+            (&lvgl_sys::lv_imgbtn_class, "Imgbtn"),
+            (&lvgl_sys::lv_keyboard_class, "Keyboard"),
+            (&lvgl_sys::lv_label_class, "Label"),
+            (&lvgl_sys::lv_led_class, "Led"),
+            (&lvgl_sys::lv_line_class, "Line"),
+            (&lvgl_sys::lv_list_class, "List"),
+            (&lvgl_sys::lv_list_btn_class, "ListBtn"),
+            (&lvgl_sys::lv_list_text_class, "ListText"),
+            (&lvgl_sys::lv_menu_class, "Menu"),
+            (&lvgl_sys::lv_meter_class, "Meter"),
+            (&lvgl_sys::lv_msgbox_class, "Msgbox"),
+            (&lvgl_sys::lv_roller_class, "Roller"),
+            (&lvgl_sys::lv_slider_class, "Slider"),
+            (&lvgl_sys::lv_spangroup_class, "Spangroup"),
+            (&lvgl_sys::lv_spinbox_class, "Spinbox"),
+            (&lvgl_sys::lv_spinner_class, "Spinner"),
+            (&lvgl_sys::lv_switch_class, "Switch"),
+            (&lvgl_sys::lv_table_class, "Table"),
+            (&lvgl_sys::lv_tabview_class, "Tabview"),
+            (&lvgl_sys::lv_textarea_class, "Textarea"),
+            (&lvgl_sys::lv_tileview_class, "Tileview"),
+            (&lvgl_sys::lv_tileview_tile_class, "TileviewTile"),
+            (&lvgl_sys::lv_win_class, "Win"),
+        ]
+    };
+    while let Some(c) = unsafe { class.as_ref() } {
+        if let Some((_, name)) = types.iter().find(|(of, _)| ptr::eq(c, *of)) {
+            return name;
+        }
+        class = c.base_class;
+    }
+    "Obj"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Btn, Label};
+    use crate::{Display, Widget};
+    use std::string::String;
+
+    #[test]
+    fn dump_widget_tree() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.set_pos(10, 10);
+        btn.set_size(100, 40);
+        btn.set_id("save");
+        btn.add_state(State::CHECKED);
+        let _label = Label::create(&mut btn).unwrap();
+
+        let info = inspect(&btn);
+        assert_eq!(info.type_name, "Btn");
+        assert_eq!(info.id, Some("save"));
+        assert_eq!(
+            info.coords,
+            Area {
+                x1: 10,
+                y1: 10,
+                x2: 109,
+                y2: 49
+            }
+        );
+        assert!(info.flags.contains(ObjFlag::CLICKABLE));
+        assert_eq!(info.state, State::CHECKED);
+        assert_eq!(info.children, 1);
+
+        let mut out = String::new();
+        dump_tree(&btn, &mut out).unwrap();
+        let lines: std::vec::Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Btn #save (10, 10)-(109, 49) flags="));
+        assert!(lines[0].contains(" state=CHECKED styles="));
+        assert!(lines[1].starts_with("  Label ("));
+
+        // Named after the derived class, not its base class `Arc`
+        let spinner = unsafe { lvgl_sys::lv_spinner_create(screen.raw().as_ptr(), 1000, 60) };
+        assert_eq!(
+            type_name(unsafe { lvgl_sys::lv_obj_get_class(spinner) }),
+            "Spinner"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Changes to the UI applied at a safe point
+//!
+//! Some code must not change widgets right away: an event handler running
+//! while LVGL draws or walks the widget tree must not delete the widget it
+//! was called for, and another thread or task must not call into LVGL at
+//! all. [`defer()`] queues a closure instead, which `task_handler()` runs at
+//! its start, before LVGL's timers and redraws:
+//!
+//! ```ignore
+//! use lvgl::defer::{defer, defer_on};
+//!
+//! // Sensor thread
+//! let celsius = read_sensor();
+//! defer(move || TEMPERATURE.set(celsius))?;
+//!
+//! // Event handler of a dialog's close button, deleting the dialog
+//! close.on_event(move |_, event| {
+//!     if let Event::Clicked = event {
+//!         let _ = defer_on(&dialog, |dialog| unsafe {
+//!             lvgl_sys::lv_obj_del(dialog.raw().as_ptr())
+//!         });
+//!     }
+//! })?;
+//! ```
+//!
+//! The queue holds up to [`DEFER_CAPACITY`] closures, without allocating:
+//! each closure is stored in the queue itself and may capture up to
+//! [`DEFER_CLOSURE_SIZE`] bytes, which is checked at compile time. Larger
+//! state can be captured in a `Box` with the `alloc` feature. Closures
+//! deferred by a deferred closure run during the next `task_handler()` call.
+//!
+//! # Building
+//!
+//! Enable the `defer` feature.
+
+use crate::guard::guard;
+use crate::sync::{Command, UiChannel};
+use crate::{LvError, LvResult, NativeObject, Widget};
+use core::ffi::c_void;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of closures that can be queued at once.
+pub const DEFER_CAPACITY: usize = 16;
+
+/// The largest size of a deferred closure, in bytes: four pointers.
+pub const DEFER_CLOSURE_SIZE: usize = mem::size_of::<Storage>();
+
+type Storage = [MaybeUninit<usize>; 4];
+
+static QUEUE: UiChannel<Deferred, DEFER_CAPACITY> = UiChannel::new();
+
+/// A closure stored inline, with the functions calling and dropping it.
+struct Deferred {
+    closure: Storage,
+    call: unsafe fn(*mut Storage),
+    drop: unsafe fn(*mut Storage),
+}
+
+// Safety: `Deferred::new()` only takes `Send` closures
+unsafe impl Send for Deferred {}
+
+impl Deferred {
+    fn new<F: FnOnce() + Send + 'static>(f: F) -> Self {
+        const {
+            assert!(
+                mem::size_of::<F>() <= DEFER_CLOSURE_SIZE,
+                "deferred closure captures more than DEFER_CLOSURE_SIZE bytes"
+            );
+            assert!(mem::align_of::<F>() <= mem::align_of::<Storage>());
+        }
+        unsafe fn call_closure<F: FnOnce()>(closure: *mut Storage) {
+            ptr::read(closure as *mut F)()
+        }
+        unsafe fn drop_closure<F>(closure: *mut Storage) {
+            ptr::drop_in_place(closure as *mut F)
+        }
+
+        let mut closure = [MaybeUninit::uninit(); 4];
+        unsafe { ptr::write(closure.as_mut_ptr() as *mut F, f) };
+        Self {
+            closure,
+            call: call_closure::<F>,
+            drop: drop_closure::<F>,
+        }
+    }
+}
+
+impl Command for Deferred {
+    fn execute(self) {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { (this.call)(&mut this.closure) }
+    }
+}
+
+impl Drop for Deferred {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(&mut self.closure) }
+    }
+}
+
+/// Queues `f` to run at the start of the next `task_handler()` call, on the
+/// thread running it. Fails with `LvError::LvOOMemory` if `DEFER_CAPACITY`
+/// closures are already queued.
+pub fn defer(f: impl FnOnce() + Send + 'static) -> LvResult<()> {
+    QUEUE
+        .send(Deferred::new(f))
+        .map_err(|_| LvError::LvOOMemory)
+}
+
+/// Queues `f` to run with `widget` at the start of the next `task_handler()`
+/// call, unless the widget is deleted by then. See `defer()`.
+///
+/// The widget is marked with an event callback identifying the deferred
+/// call, which LVGL removes with the widget. A widget created at the same
+/// address in the meantime does not carry it, so `f` is never called with
+/// another widget.
+pub fn defer_on<'a, W: Widget<'a>>(widget: &W, f: impl FnOnce(W) + Send + 'static) -> LvResult<()> {
+    struct Raw(NonNull<lvgl_sys::lv_obj_t>, usize);
+    // Safety: the pointer is only used by the closure, on the UI thread
+    unsafe impl Send for Raw {}
+
+    unsafe extern "C" fn marker(_event: *mut lvgl_sys::lv_event_t) {}
+
+    // Tickets start at 1, so a marker never has null user data
+    static NEXT_TICKET: AtomicUsize = AtomicUsize::new(1);
+    let ticket = NEXT_TICKET.fetch_add(1, Ordering::Relaxed);
+    let obj = widget.raw().as_ptr();
+    unsafe {
+        lvgl_sys::lv_obj_add_event_cb(
+            obj,
+            Some(marker),
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+            ticket as *mut c_void,
+        );
+    }
+    let raw = Raw(widget.raw(), ticket);
+    let queued = defer(move || {
+        // Captures all of `raw`, which is `Send`, rather than its fields
+        let raw = raw;
+        // `lv_obj_is_valid()` only compares addresses, so the marker is only
+        // looked up on a live widget
+        let alive = unsafe {
+            lvgl_sys::lv_obj_is_valid(raw.0.as_ptr())
+                && lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    raw.0.as_ptr(),
+                    Some(marker),
+                    raw.1 as *const c_void,
+                )
+        };
+        if alive {
+            if let Some(widget) = unsafe { W::from_raw(raw.0) } {
+                f(widget)
+            }
+        }
+    });
+    if queued.is_err() {
+        unsafe {
+            lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                obj,
+                Some(marker),
+                ticket as *const c_void,
+            );
+        }
+    }
+    queued
+}
+
+/// Tests if closures are queued, which `run_loop()` does not wait for.
+pub(crate) fn is_pending() -> bool {
+    !QUEUE.is_empty()
+}
+
+/// Runs the closures queued before the call. Called by `task_handler()`.
+pub(crate) fn run_pending() {
+    for _ in 0..QUEUE.len() {
+        match QUEUE.recv() {
+            Some(deferred) => guard("deferred", || deferred.execute()),
+            None => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Label;
+    use crate::Display;
+    use core::sync::atomic::AtomicU32;
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+
+    #[test]
+    fn deferred_until_task_handler() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+
+        defer(|| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            defer(|| {
+                CALLS.fetch_add(10, Ordering::Relaxed);
+            })
+            .unwrap();
+        })
+        .unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 0);
+        crate::task_handler().unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        crate::task_handler().unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 11);
+
+        let label = Label::create(&mut screen).unwrap();
+        let deleted = Label::create(&mut screen).unwrap();
+        defer_on(&label, |_| {
+            CALLS.fetch_add(100, Ordering::Relaxed);
+        })
+        .unwrap();
+        defer_on(&deleted, |_| {
+            CALLS.fetch_add(1000, Ordering::Relaxed);
+        })
+        .unwrap();
+        unsafe { lvgl_sys::lv_obj_del(deleted.raw().as_ptr()) };
+        crate::task_handler().unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 111);
+
+        // A widget created in place of a deleted one is not passed to `f`,
+        // whether or not it reuses the address
+        let deleted = Label::create(&mut screen).unwrap();
+        defer_on(&deleted, |_| {
+            CALLS.fetch_add(1000, Ordering::Relaxed);
+        })
+        .unwrap();
+        unsafe { lvgl_sys::lv_obj_del(deleted.raw().as_ptr()) };
+        let _replacement = Label::create(&mut screen).unwrap();
+        crate::task_handler().unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 111);
+
+        for _ in 0..DEFER_CAPACITY {
+            defer(|| {}).unwrap();
+        }
+        assert_eq!(defer(|| {}), Err(LvError::LvOOMemory));
+        crate::task_handler().unwrap();
+        assert!(QUEUE.is_empty());
+    }
+}
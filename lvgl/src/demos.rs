@@ -0,0 +1,65 @@
+//! LVGL's demo applications
+//!
+//! The demos are full user interfaces shipped with LVGL. Running one is a
+//! quick way to check that a display and its input devices are brought up
+//! correctly before writing screens of your own:
+//!
+//! ```ignore
+//! let display = Display::register(buffer, HOR_RES, VER_RES, flush)?;
+//! lvgl::demos::widgets()?;
+//! loop {
+//!     lvgl::task_handler()?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Each demo is compiled into LVGL only with its feature, e.g.
+//! `demo_widgets`, which also enables it in the generated `lv_conf.h`. The
+//! demos create their widgets on the active screen of the default display,
+//! and expect the widgets and fonts that LVGL enables by default.
+
+use crate::{LvError, LvResult};
+
+extern "C" {
+    #[cfg(feature = "demo_music")]
+    fn lv_demo_music();
+    #[cfg(feature = "demo_widgets")]
+    fn lv_demo_widgets();
+}
+
+/// Shows LVGL's music player demo, which animates images, a spectrum and
+/// large fonts throughout. It is the usual performance check for a new board:
+/// set `LV_USE_PERF_MONITOR` to show its frame rate and CPU load. Its
+/// variants are chosen by `LV_DEMO_MUSIC_*` in `lv_conf.h`, e.g.
+/// `DEP_LV_CONFIG_DEMO_MUSIC_AUTO_PLAY=1` to play through the demo unattended.
+///
+/// Fails with `LvError::Uninitialized` before `init()`, and with
+/// `LvError::InvalidReference` if no display is registered.
+#[cfg(feature = "demo_music")]
+pub fn music() -> LvResult<()> {
+    check_display()?;
+    unsafe { lv_demo_music() }
+    Ok(())
+}
+
+/// Shows LVGL's widget demo: a profile, analytics and shop page in a tab
+/// view, exercising most widgets, styles and input. It adapts its layout to
+/// the size of the default display, and needs `LV_MEM_SIZE` of at least 38
+/// KB when LVGL's own allocator is used.
+///
+/// Fails with `LvError::Uninitialized` before `init()`, and with
+/// `LvError::InvalidReference` if no display is registered.
+#[cfg(feature = "demo_widgets")]
+pub fn widgets() -> LvResult<()> {
+    check_display()?;
+    unsafe { lv_demo_widgets() }
+    Ok(())
+}
+
+fn check_display() -> LvResult<()> {
+    crate::check_init()?;
+    if unsafe { lvgl_sys::lv_disp_get_default() }.is_null() {
+        return Err(LvError::InvalidReference);
+    }
+    Ok(())
+}
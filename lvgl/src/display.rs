@@ -1,12 +1,14 @@
+use crate::draw::DrawAccel;
 use crate::functions::CoreError;
+use crate::guard::guard;
 use crate::{disp_drv_register, disp_get_default, get_str_act, NativeObject};
 use crate::{Box, Color};
-use crate::{Obj, Screen, Widget};
+use crate::{LvError, LvResult, Obj, Screen, Widget};
 use core::convert::TryInto;
 #[cfg(feature = "nightly")]
 use core::error::Error;
 use core::fmt;
-use core::mem::{ManuallyDrop, MaybeUninit};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::pin::Pin;
 use core::ptr::NonNull;
 use core::{ptr, result};
@@ -53,7 +55,8 @@ impl<'a> Display {
     }
 
     /// Registers a given `DrawBuffer` with an associated update function to
-    /// LVGL. `display_update` takes a `&DisplayRefresh`.
+    /// LVGL. `display_update` takes a `&DisplayRefresh`. Fails with
+    /// `DisplayError::NotAvailable` if LVGL is not initialized.
     pub fn register<F, const N: usize>(
         draw_buffer: DrawBuffer<N>,
         hor_res: u32,
@@ -63,6 +66,7 @@ impl<'a> Display {
     where
         F: FnMut(&DisplayRefresh<N>) + 'a,
     {
+        crate::check_init()?;
         let mut display_diver = DisplayDriver::new(draw_buffer, display_update)?;
         let disp_p = &mut display_diver.disp_drv;
         disp_p.hor_res = hor_res.try_into().unwrap_or(240);
@@ -71,12 +75,104 @@ impl<'a> Display {
         //display_diver.disp_drv.leak();
     }
 
+    /// Registers a `DynDrawBuffer`, whose size was chosen at runtime, with an
+    /// associated update function to LVGL. `display_update` takes a
+    /// `&DynDisplayRefresh`. Otherwise the same as `register()`.
+    pub fn register_dyn<F>(
+        draw_buffer: DynDrawBuffer,
+        hor_res: u32,
+        ver_res: u32,
+        display_update: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&DynDisplayRefresh) + 'a,
+    {
+        crate::check_init()?;
+        let mut display_driver = DisplayDriver::new_dyn(draw_buffer, display_update);
+        let disp_p = &mut display_driver.disp_drv;
+        disp_p.hor_res = hor_res.try_into().unwrap_or(240);
+        disp_p.ver_res = ver_res.try_into().unwrap_or(240);
+        Ok(disp_drv_register(&mut display_driver, None)?)
+    }
+
+    /// Registers a 1-bit display, such as an OLED or a memory LCD, and
+    /// switches it to LVGL's monochrome theme if `LV_USE_THEME_MONO` is
+    /// enabled (see `theme::use_mono_theme()`). LVGL packs the pixels it renders into `draw_buffer` to one bit each.
+    /// `display_update` takes a `&MonoRefresh`. Otherwise the same as
+    /// `register()`.
+    pub fn register_mono<F, const N: usize>(
+        draw_buffer: DrawBuffer<N>,
+        hor_res: u32,
+        ver_res: u32,
+        display_update: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&MonoRefresh) + 'a,
+    {
+        crate::check_init()?;
+        let mut display_driver = DisplayDriver::new_mono(draw_buffer, display_update);
+        let disp_p = &mut display_driver.disp_drv;
+        disp_p.hor_res = hor_res.try_into().unwrap_or(240);
+        disp_p.ver_res = ver_res.try_into().unwrap_or(240);
+        let display = disp_drv_register(&mut display_driver, None)?;
+        #[cfg(lvgl_theme_mono)]
+        crate::theme::use_mono_theme(&display, false);
+        Ok(display)
+    }
+
+    /// Registers a display whose draw buffer is laid out by `set_px`, e.g.
+    /// with vertical bytes or bit planes, so that it can be sent to the
+    /// display as is. LVGL calls `set_px` with the whole buffer for every
+    /// pixel it draws, instead of writing `lv_color_t`s:
+    ///
+    /// ```ignore
+    /// // Each byte holds 8 vertical 1-bit pixels, as on SSD1306 pages
+    /// let display = Display::register_set_px(
+    ///     DrawBuffer::<{ 128 * 64 }>::default(),
+    ///     128,
+    ///     64,
+    ///     |buf, px| {
+    ///         let i = (px.y as usize / 8) * px.buf_width as usize + px.x as usize;
+    ///         let bit = 1 << (px.y % 8);
+    ///         if px.color.brightness() >= 128 {
+    ///             buf[i] |= bit;
+    ///         } else {
+    ///             buf[i] &= !bit;
+    ///         }
+    ///     },
+    ///     |refresh| panel.write_pages(&refresh.area, refresh.bytes),
+    /// )?;
+    /// ```
+    ///
+    /// `display_update` takes a `&RawDisplayRefresh`. Otherwise the same as
+    /// `register()`.
+    pub fn register_set_px<P, F, const N: usize>(
+        draw_buffer: DrawBuffer<N>,
+        hor_res: u32,
+        ver_res: u32,
+        set_px: P,
+        display_update: F,
+    ) -> Result<Self>
+    where
+        P: FnMut(&mut [u8], PixelWrite) + 'a,
+        F: FnMut(&RawDisplayRefresh) + 'a,
+    {
+        crate::check_init()?;
+        let mut display_driver = DisplayDriver::new_set_px(draw_buffer, set_px, display_update);
+        let disp_p = &mut display_driver.disp_drv;
+        disp_p.hor_res = hor_res.try_into().unwrap_or(240);
+        disp_p.ver_res = ver_res.try_into().unwrap_or(240);
+        Ok(disp_drv_register(&mut display_driver, None)?)
+    }
+
     /// Returns the currently-active screen.
     pub fn get_scr_act(&'a self) -> Result<Screen<'a>> {
         Ok(get_str_act(Some(self))?.try_into()?)
     }
 
-    /// Sets a `Screen` as currently active.
+    /// Sets a `Screen` as currently active. The previous screen is not
+    /// deleted, so handles to its widgets stay valid and it can be loaded
+    /// again.
     pub fn set_scr_act(&'a self, screen: &'a mut Screen) {
         let scr_ptr = unsafe { screen.raw().as_mut() };
         unsafe { lvgl_sys::lv_disp_load_scr(scr_ptr) }
@@ -110,6 +206,86 @@ impl<'a> Display {
         }
     }
 
+    /// Changes the resolution of the display, e.g. after a window has been
+    /// resized. LVGL redraws the active screen at the new size.
+    pub fn set_resolution(&self, hor_res: u32, ver_res: u32) {
+        unsafe {
+            let driver = (*self.disp.as_ptr()).driver;
+            (*driver).hor_res = hor_res.try_into().unwrap_or(lvgl_sys::lv_coord_t::MAX);
+            (*driver).ver_res = ver_res.try_into().unwrap_or(lvgl_sys::lv_coord_t::MAX);
+            lvgl_sys::lv_disp_drv_update(self.disp.as_ptr(), driver);
+        }
+    }
+
+    /// Rounds every area LVGL redraws with `R`, so that flushed areas match
+    /// what the display controller can write, e.g. `AlignArea<1, 8>` for the
+    /// 8-pixel pages of an SSD1306. Areas are kept within the screen after
+    /// rounding.
+    pub fn set_rounder<R: Rounder>(&self) {
+        unsafe {
+            let driver = (*self.disp.as_ptr()).driver;
+            (*driver).rounder_cb = Some(rounder_trampoline::<R>);
+        }
+    }
+
+    /// Hands the fills and copies of LVGL's software renderer to `A`, e.g.
+    /// to run them on a 2D engine. See the `draw` module. Fails with
+    /// `DisplayError::NotAvailable` if LVGL is out of memory.
+    pub fn set_draw_accel<A: DrawAccel>(&self) -> Result<()> {
+        unsafe {
+            self.set_draw_ctx(
+                crate::draw::ACCEL_CTX_SIZE,
+                Some(crate::draw::init_trampoline::<A>),
+                Some(lvgl_sys::lv_draw_sw_deinit_ctx),
+            )
+        }
+    }
+
+    /// Replaces the draw context of the display with one of `size` bytes set
+    /// up by `init` and torn down by `deinit`, such as one of the GPU draw
+    /// contexts of LVGL, e.g. `lv_draw_stm32_dma2d_ctx_init`. The previous
+    /// context is torn down and freed. Fails with
+    /// `DisplayError::NotAvailable` if LVGL is out of memory, keeping the
+    /// previous context.
+    ///
+    /// # Safety
+    ///
+    /// `size` must be the size of the context `init` sets up, and `init` and
+    /// `deinit` must uphold LVGL's requirements for draw contexts. It must
+    /// not be called while the display is being drawn.
+    pub unsafe fn set_draw_ctx(
+        &self,
+        size: usize,
+        init: Option<
+            unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t, *mut lvgl_sys::lv_draw_ctx_t),
+        >,
+        deinit: Option<
+            unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t, *mut lvgl_sys::lv_draw_ctx_t),
+        >,
+    ) -> Result<()> {
+        let driver = (*self.disp.as_ptr()).driver;
+        let draw_ctx = lvgl_sys::lv_mem_alloc(size as _) as *mut lvgl_sys::lv_draw_ctx_t;
+        if draw_ctx.is_null() {
+            return Err(DisplayError::NotAvailable);
+        }
+        ptr::write_bytes(draw_ctx as *mut u8, 0, size);
+        let old = (*driver).draw_ctx;
+        if !old.is_null() {
+            if let Some(old_deinit) = (*driver).draw_ctx_deinit {
+                old_deinit(driver, old);
+            }
+            lvgl_sys::lv_mem_free(old as *mut _);
+        }
+        (*driver).draw_ctx_init = init;
+        (*driver).draw_ctx_deinit = deinit;
+        (*driver).draw_ctx_size = size as _;
+        if let Some(init) = init {
+            init(driver, draw_ctx);
+        }
+        (*driver).draw_ctx = draw_ctx;
+        Ok(())
+    }
+
     /// Registers a display from raw functions and values.
     ///
     /// # Safety
@@ -150,6 +326,7 @@ impl<'a> Display {
         render_start_cb: Option<unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t)>,
         drop: Option<unsafe extern "C" fn()>,
     ) -> Result<Self> {
+        crate::check_init()?;
         let mut display_driver = DisplayDriver::new_raw(
             draw_buffer,
             flush_cb,
@@ -222,13 +399,92 @@ impl<const N: usize> DrawBuffer<N> {
     }
 }
 
+/// A buffer whose size is chosen at runtime, e.g. from the resolution of the
+/// detected display or the memory available. Like `DrawBuffer`, it can be
+/// smaller than the screen. It is allocated in LVGL's memory.
+pub struct DynDrawBuffer {
+    draw_buf: Pin<Box<lvgl_sys::lv_disp_draw_buf_t>>,
+    refresh_buffer: NonNull<lvgl_sys::lv_color_t>,
+    size: usize,
+}
+
+impl DynDrawBuffer {
+    /// Allocates a buffer of `pixels` pixels. Returns
+    /// `LvError::InvalidReference` if `pixels` is 0 or more than LVGL can
+    /// address, and `LvError::LvOOMemory` if LVGL cannot allocate it.
+    pub fn pixels(pixels: usize) -> LvResult<Self> {
+        if pixels == 0 || u32::try_from(pixels).is_err() {
+            return Err(LvError::InvalidReference);
+        }
+        let bytes = pixels
+            .checked_mul(mem::size_of::<lvgl_sys::lv_color_t>())
+            .ok_or(LvError::LvOOMemory)?;
+        let buf = unsafe { lvgl_sys::lv_mem_alloc(bytes as _) } as *mut lvgl_sys::lv_color_t;
+        let refresh_buffer = NonNull::new(buf).ok_or(LvError::LvOOMemory)?;
+        let draw_buf = Box::pin(unsafe {
+            let mut inner: MaybeUninit<lvgl_sys::lv_disp_draw_buf_t> = MaybeUninit::uninit();
+            lvgl_sys::lv_disp_draw_buf_init(
+                inner.as_mut_ptr(),
+                buf as *mut _,
+                ptr::null_mut(),
+                pixels as u32,
+            );
+            inner.assume_init()
+        });
+        Ok(Self {
+            draw_buf,
+            refresh_buffer,
+            size: pixels,
+        })
+    }
+
+    /// Allocates a buffer of `lines` full lines of a display `hor_res` pixels
+    /// wide, e.g. a tenth of the display's lines.
+    pub fn lines(hor_res: u32, lines: u32) -> LvResult<Self> {
+        Self::pixels(hor_res as usize * lines as usize)
+    }
+
+    /// Returns the size of the buffer in pixels.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Drop for DynDrawBuffer {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_mem_free(self.refresh_buffer.as_ptr() as *mut _) }
+    }
+}
+
 #[repr(C)]
-pub(crate) struct DisplayDriver<const N: usize> {
+pub(crate) struct DisplayDriver<B> {
     pub(crate) disp_drv: Pin<Box<lvgl_sys::lv_disp_drv_t>>,
-    _buffer: DrawBuffer<N>,
+    _buffer: B,
 }
 
-impl<'a, const N: usize> DisplayDriver<N> {
+impl<'a> DisplayDriver<DynDrawBuffer> {
+    fn new_dyn<F>(mut draw_buffer: DynDrawBuffer, display_update_callback: F) -> ManuallyDrop<Self>
+    where
+        F: FnMut(&DynDisplayRefresh) + 'a,
+    {
+        let mut disp_drv = Box::pin(unsafe {
+            let mut inner = MaybeUninit::uninit();
+            lvgl_sys::lv_disp_drv_init(inner.as_mut_ptr());
+            inner.assume_init()
+        });
+        disp_drv.draw_buf = &mut *draw_buffer.draw_buf as *mut _;
+        disp_drv.user_data = Box::<F>::into_raw(Box::new(display_update_callback)) as *mut _;
+        disp_drv.flush_cb = Some(disp_flush_dyn_trampoline::<F>);
+
+        // The buffer is leaked along with the driver, as LVGL keeps using it
+        ManuallyDrop::new(Self {
+            disp_drv,
+            _buffer: draw_buffer,
+        })
+    }
+}
+
+impl<'a, const N: usize> DisplayDriver<DrawBuffer<N>> {
     pub fn new<F>(
         mut draw_buffer: DrawBuffer<N>,
         display_update_callback: F,
@@ -258,6 +514,56 @@ impl<'a, const N: usize> DisplayDriver<N> {
         }))
     }
 
+    fn new_mono<F>(draw_buffer: DrawBuffer<N>, mut display_update_callback: F) -> ManuallyDrop<Self>
+    where
+        F: FnMut(&MonoRefresh) + 'a,
+    {
+        Self::new_set_px(
+            draw_buffer,
+            mono_set_px,
+            move |refresh: &RawDisplayRefresh| {
+                let area = refresh.area;
+                let stride = ((area.x2 - area.x1 + 1) as usize).div_ceil(8);
+                let len = stride * (area.y2 - area.y1 + 1) as usize;
+                // `mono_set_px()` packed the area at the start of the buffer
+                display_update_callback(&MonoRefresh {
+                    area,
+                    bits: &refresh.bytes[..len],
+                    stride,
+                });
+            },
+        )
+    }
+
+    fn new_set_px<P, F>(
+        mut draw_buffer: DrawBuffer<N>,
+        set_px: P,
+        display_update_callback: F,
+    ) -> ManuallyDrop<Self>
+    where
+        P: FnMut(&mut [u8], PixelWrite) + 'a,
+        F: FnMut(&RawDisplayRefresh) + 'a,
+    {
+        let mut disp_drv = Box::pin(unsafe {
+            let mut inner = MaybeUninit::uninit();
+            lvgl_sys::lv_disp_drv_init(inner.as_mut_ptr());
+            inner.assume_init()
+        });
+        disp_drv.draw_buf = draw_buffer.get_ptr() as *mut _;
+        let callbacks = SetPxCallbacks {
+            set_px,
+            flush: display_update_callback,
+        };
+        disp_drv.user_data = Box::into_raw(Box::new(callbacks)) as *mut _;
+        disp_drv.flush_cb = Some(disp_flush_set_px_trampoline::<P, F, N>);
+        disp_drv.set_px_cb = Some(set_px_trampoline::<P, F, N>);
+
+        ManuallyDrop::new(Self {
+            disp_drv,
+            _buffer: draw_buffer,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub unsafe fn new_raw(
         mut draw_buffer: DrawBuffer<N>,
@@ -317,6 +623,7 @@ impl<'a, const N: usize> DisplayDriver<N> {
 }
 
 /// Represents a sub-area of the display that is being updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Area {
     pub x1: i16,
     pub x2: i16,
@@ -324,6 +631,52 @@ pub struct Area {
     pub y2: i16,
 }
 
+impl From<lvgl_sys::lv_area_t> for Area {
+    fn from(area: lvgl_sys::lv_area_t) -> Self {
+        Self {
+            x1: area.x1,
+            x2: area.x2,
+            y1: area.y1,
+            y2: area.y2,
+        }
+    }
+}
+
+impl From<Area> for lvgl_sys::lv_area_t {
+    fn from(area: Area) -> Self {
+        Self {
+            x1: area.x1,
+            y1: area.y1,
+            x2: area.x2,
+            y2: area.y2,
+        }
+    }
+}
+
+/// Rounds the areas LVGL redraws, to constraints of a display controller.
+/// See `Display::set_rounder()`.
+pub trait Rounder {
+    /// Grows `area` to the nearest area the controller can write.
+    fn round(area: &mut Area);
+}
+
+/// Aligns areas to tiles of `X` by `Y` pixels from the top left corner of
+/// the screen, e.g. `AlignArea<8, 1>` for controllers taking whole bytes of a
+/// 1-bit row, such as the SSD1680.
+pub struct AlignArea<const X: u16, const Y: u16>;
+
+impl<const X: u16, const Y: u16> Rounder for AlignArea<X, Y> {
+    fn round(area: &mut Area) {
+        fn align(start: &mut i16, end: &mut i16, size: u16) {
+            let size = size.clamp(1, i16::MAX as u16) as i16;
+            *start -= start.rem_euclid(size);
+            *end += size - 1 - end.rem_euclid(size);
+        }
+        align(&mut area.x1, &mut area.x2, X);
+        align(&mut area.y1, &mut area.y2, Y);
+    }
+}
+
 /// An update to the display information, contains the area that is being
 /// updated and the color of the pixels that need to be updated. The colors
 /// are represented in a contiguous array.
@@ -332,38 +685,177 @@ pub struct DisplayRefresh<'a, const N: usize> {
     pub colors: &'a [Color; N],
 }
 
+/// An update to a display registered with a `DynDrawBuffer`. Unlike in
+/// `DisplayRefresh`, `colors` holds exactly the pixels of the area, row by row.
+pub struct DynDisplayRefresh<'a> {
+    pub area: Area,
+    pub colors: &'a [Color],
+}
+
+/// An update to a display registered with `Display::register_mono()`. `bits`
+/// holds the pixels of the area row by row, eight per byte with the leftmost
+/// in the most significant bit, and each row starting on a new byte. A set
+/// bit is a light pixel, e.g. a lit pixel of an OLED.
+pub struct MonoRefresh<'a> {
+    pub area: Area,
+    pub bits: &'a [u8],
+    /// The number of bytes per row.
+    pub stride: usize,
+}
+
+impl MonoRefresh<'_> {
+    /// Returns whether the pixel at `x`, `y`, relative to the top left corner
+    /// of the area, is light.
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        self.bits
+            .get(y * self.stride + x / 8)
+            .map_or(false, |byte| byte & (0x80 >> (x % 8)) != 0)
+    }
+}
+
+/// A pixel LVGL draws on a display registered with
+/// `Display::register_set_px()`.
+#[derive(Clone, Copy)]
+pub struct PixelWrite {
+    /// The width of the area being drawn, in pixels.
+    pub buf_width: i16,
+    /// The column of the pixel, from the left of the area being drawn.
+    pub x: i16,
+    /// The row of the pixel, from the top of the area being drawn.
+    pub y: i16,
+    pub color: Color,
+    /// The opacity (0-255) to blend `color` with the pixel with.
+    pub opa: u8,
+}
+
+/// An update to a display registered with `Display::register_set_px()`.
+/// `bytes` is the whole draw buffer, laid out by the `set_px` callback.
+pub struct RawDisplayRefresh<'a> {
+    pub area: Area,
+    pub bytes: &'a [u8],
+}
+
+/// The callbacks of a display registered with `Display::register_set_px()`,
+/// kept together in the driver's user data.
+struct SetPxCallbacks<P, F> {
+    set_px: P,
+    flush: F,
+}
+
 #[cfg(feature = "embedded_graphics")]
 mod embedded_graphics_impl {
-    use crate::{Color, DisplayRefresh};
+    use crate::display::Area;
+    use crate::{Color, DisplayRefresh, DynDisplayRefresh, MonoRefresh};
+    use embedded_graphics::pixelcolor::BinaryColor;
     use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::Rectangle;
     use embedded_graphics::Pixel;
 
-    impl<const N: usize> DisplayRefresh<N> {
+    impl<const N: usize> DisplayRefresh<'_, N> {
         pub fn as_pixels<C>(&self) -> impl IntoIterator<Item = Pixel<C>> + '_
         where
             C: PixelColor + From<Color>,
         {
-            let area = &self.area;
-            let x1 = area.x1;
-            let x2 = area.x2;
-            let y1 = area.y1;
-            let y2 = area.y2;
-
-            let ys = y1..=y2;
-            let xs = (x1..=x2).enumerate();
-            let x_len = (x2 - x1 + 1) as usize;
-
-            // We use iterators here to ensure that the Rust compiler can apply all possible
-            // optimizations at compile time.
-            ys.enumerate().flat_map(move |(iy, y)| {
-                xs.clone().map(move |(ix, x)| {
-                    let color_len = x_len * iy + ix;
-                    let raw_color = self.colors[color_len];
-                    Pixel(Point::new(x as i32, y as i32), raw_color.into())
-                })
-            })
+            pixels(&self.area, self.colors)
+        }
+
+        /// Draws the updated area to `target`, passing its colors as one
+        /// contiguous run with `DrawTarget::fill_contiguous()`. Displays that
+        /// implement it with a single window write, such as most SPI
+        /// displays, are much faster this way than with `as_pixels()`.
+        pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+        where
+            D: DrawTarget,
+            D::Color: From<Color>,
+        {
+            draw(&self.area, self.colors, target)
         }
     }
+
+    impl DynDisplayRefresh<'_> {
+        pub fn as_pixels<C>(&self) -> impl IntoIterator<Item = Pixel<C>> + '_
+        where
+            C: PixelColor + From<Color>,
+        {
+            pixels(&self.area, self.colors)
+        }
+
+        /// Draws the updated area to `target`. See `DisplayRefresh::draw()`.
+        pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+        where
+            D: DrawTarget,
+            D::Color: From<Color>,
+        {
+            draw(&self.area, self.colors, target)
+        }
+    }
+
+    impl MonoRefresh<'_> {
+        /// Draws the updated area to a 1-bit `target`, light pixels being
+        /// `BinaryColor::On`.
+        pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = BinaryColor>,
+        {
+            if self.area.x2 < self.area.x1 || self.area.y2 < self.area.y1 {
+                return Ok(());
+            }
+            let (w, h) = (
+                (self.area.x2 - self.area.x1 + 1) as usize,
+                (self.area.y2 - self.area.y1 + 1) as usize,
+            );
+            let rect = Rectangle::new(
+                Point::new(self.area.x1 as i32, self.area.y1 as i32),
+                Size::new(w as u32, h as u32),
+            );
+            let colors = (0..h).flat_map(|y| (0..w).map(move |x| self.pixel(x, y).into()));
+            target.fill_contiguous(&rect, colors)
+        }
+    }
+
+    fn draw<D>(area: &Area, colors: &[Color], target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: From<Color>,
+    {
+        if area.x2 < area.x1 || area.y2 < area.y1 {
+            return Ok(());
+        }
+        let size = Size::new(
+            (area.x2 - area.x1 + 1) as u32,
+            (area.y2 - area.y1 + 1) as u32,
+        );
+        let rect = Rectangle::new(Point::new(area.x1 as i32, area.y1 as i32), size);
+        match colors.get(..(size.width * size.height) as usize) {
+            Some(colors) => target.fill_contiguous(&rect, colors.iter().map(|&c| c.into())),
+            // The buffer does not cover the area, so only its pixels are drawn
+            None => target.draw_iter(pixels(area, colors).take(colors.len())),
+        }
+    }
+
+    fn pixels<'a, C>(area: &Area, colors: &'a [Color]) -> impl Iterator<Item = Pixel<C>> + 'a
+    where
+        C: PixelColor + From<Color>,
+    {
+        let x1 = area.x1;
+        let x2 = area.x2;
+        let y1 = area.y1;
+        let y2 = area.y2;
+
+        let ys = y1..=y2;
+        let xs = (x1..=x2).enumerate();
+        let x_len = (x2 - x1 + 1) as usize;
+
+        // We use iterators here to ensure that the Rust compiler can apply all possible
+        // optimizations at compile time.
+        ys.enumerate().flat_map(move |(iy, y)| {
+            xs.clone().map(move |(ix, x)| {
+                let color_len = x_len * iy + ix;
+                let raw_color = colors[color_len];
+                Pixel(Point::new(x as i32, y as i32), raw_color.into())
+            })
+        })
+    }
 }
 
 unsafe extern "C" fn disp_flush_trampoline<'a, F, const N: usize>(
@@ -373,42 +865,156 @@ unsafe extern "C" fn disp_flush_trampoline<'a, F, const N: usize>(
 ) where
     F: FnMut(&DisplayRefresh<N>) + 'a,
 {
-    let display_driver = *disp_drv;
-    if !display_driver.user_data.is_null() {
-        let callback = &mut *(display_driver.user_data as *mut F);
-
-        // Build a slice from the pixel data, without copying the data itself.
-        //
-        // Safety
-        // - color_p points to the buffer(s) in the statically-allocated
-        //   DrawBuffer. When double-buffering, it will alternate between both.
-        // - The Color type is a `#[repr(transparent)]`, single-field struct
-        //   wrapper around lv_color_t. It is safe across the ffi and allows
-        //   the lv_color_t *mut raw pointer to be safely mutated into a *mut
-        //   Color raw pointer.
-        // <https://doc.rust-lang.org/nomicon/other-reprs.html#reprtransparent>
-        // <https://doc.rust-lang.org/core/slice/fn.from_raw_parts.html>
-        let colors: &[Color; N] = unsafe {
-            core::slice::from_raw_parts(color_p as *mut _ as *mut Color, N)
-                .try_into()
-                .unwrap()
-        };
+    guard("display flush", || {
+        let display_driver = *disp_drv;
+        if !display_driver.user_data.is_null() {
+            let callback = &mut *(display_driver.user_data as *mut F);
 
-        let update = DisplayRefresh {
-            area: Area {
-                x1: (*area).x1,
-                x2: (*area).x2,
-                y1: (*area).y1,
-                y2: (*area).y2,
-            },
-            colors,
-        };
-        callback(&update);
+            // Build a slice from the pixel data, without copying the data itself.
+            //
+            // Safety
+            // - color_p points to the buffer(s) in the statically-allocated
+            //   DrawBuffer. When double-buffering, it will alternate between both.
+            // - The Color type is a `#[repr(transparent)]`, single-field struct
+            //   wrapper around lv_color_t. It is safe across the ffi and allows
+            //   the lv_color_t *mut raw pointer to be safely mutated into a *mut
+            //   Color raw pointer.
+            // <https://doc.rust-lang.org/nomicon/other-reprs.html#reprtransparent>
+            // <https://doc.rust-lang.org/core/slice/fn.from_raw_parts.html>
+            let colors: &[Color; N] = unsafe {
+                core::slice::from_raw_parts(color_p as *mut _ as *mut Color, N)
+                    .try_into()
+                    .unwrap()
+            };
+
+            let update = DisplayRefresh {
+                area: Area::from(*area),
+                colors,
+            };
+            callback(&update);
+        }
+        // Not doing this causes a segfault in rust >= 1.69.0
+        *disp_drv = display_driver;
+        // Indicate to LVGL that we are ready with the flushing
+        lvgl_sys::lv_disp_flush_ready(disp_drv);
+    })
+}
+
+unsafe extern "C" fn disp_flush_dyn_trampoline<'a, F>(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    area: *const lvgl_sys::lv_area_t,
+    color_p: *mut lvgl_sys::lv_color_t,
+) where
+    F: FnMut(&DynDisplayRefresh) + 'a,
+{
+    guard("display flush", || {
+        let display_driver = *disp_drv;
+        if !display_driver.user_data.is_null() {
+            let callback = &mut *(display_driver.user_data as *mut F);
+            let area = Area::from(*area);
+            let len = (area.x2 - area.x1 + 1) as usize * (area.y2 - area.y1 + 1) as usize;
+            // Safety: LVGL renders the area contiguously at the start of the
+            // buffer, and `Color` is a transparent wrapper of `lv_color_t`.
+            let colors = core::slice::from_raw_parts(color_p as *const Color, len);
+            callback(&DynDisplayRefresh { area, colors });
+        }
+        // As in `disp_flush_trampoline()`
+        *disp_drv = display_driver;
+        lvgl_sys::lv_disp_flush_ready(disp_drv);
+    })
+}
+
+unsafe extern "C" fn disp_flush_set_px_trampoline<'a, P, F, const N: usize>(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    area: *const lvgl_sys::lv_area_t,
+    color_p: *mut lvgl_sys::lv_color_t,
+) where
+    P: FnMut(&mut [u8], PixelWrite) + 'a,
+    F: FnMut(&RawDisplayRefresh) + 'a,
+{
+    guard("display flush", || {
+        let display_driver = *disp_drv;
+        if !display_driver.user_data.is_null() {
+            let callbacks = &mut *(display_driver.user_data as *mut SetPxCallbacks<P, F>);
+            let bytes = core::slice::from_raw_parts(
+                color_p as *const u8,
+                N * mem::size_of::<lvgl_sys::lv_color_t>(),
+            );
+            (callbacks.flush)(&RawDisplayRefresh {
+                area: Area::from(*area),
+                bytes,
+            });
+        }
+        // As in `disp_flush_trampoline()`
+        *disp_drv = display_driver;
+        lvgl_sys::lv_disp_flush_ready(disp_drv);
+    })
+}
+
+unsafe extern "C" fn set_px_trampoline<'a, P, F, const N: usize>(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    buf: *mut u8,
+    buf_w: lvgl_sys::lv_coord_t,
+    x: lvgl_sys::lv_coord_t,
+    y: lvgl_sys::lv_coord_t,
+    color: lvgl_sys::lv_color_t,
+    opa: lvgl_sys::lv_opa_t,
+) where
+    P: FnMut(&mut [u8], PixelWrite) + 'a,
+    F: FnMut(&RawDisplayRefresh) + 'a,
+{
+    guard("display set_px", || {
+        let user_data = (*disp_drv).user_data;
+        if !user_data.is_null() {
+            let callbacks = &mut *(user_data as *mut SetPxCallbacks<P, F>);
+            // Safety: LVGL draws into the draw buffer, of `N` colors
+            let buf =
+                core::slice::from_raw_parts_mut(buf, N * mem::size_of::<lvgl_sys::lv_color_t>());
+            (callbacks.set_px)(
+                buf,
+                PixelWrite {
+                    buf_width: buf_w,
+                    x,
+                    y,
+                    color: Color::from_raw(color),
+                    opa,
+                },
+            );
+        }
+    })
+}
+
+/// Writes a pixel into a 1-bit buffer as laid out in `MonoRefresh`. LVGL
+/// blends through this instead of writing `lv_color_t`s, so pixels mostly
+/// covered and light enough are set.
+fn mono_set_px(buf: &mut [u8], px: PixelWrite) {
+    if px.opa < lvgl_sys::LV_OPA_50 as lvgl_sys::lv_opa_t {
+        return;
     }
-    // Not doing this causes a segfault in rust >= 1.69.0
-    *disp_drv = display_driver;
-    // Indicate to LVGL that we are ready with the flushing
-    lvgl_sys::lv_disp_flush_ready(disp_drv);
+    let stride = (px.buf_width as usize).div_ceil(8);
+    let byte = &mut buf[px.y as usize * stride + px.x as usize / 8];
+    let mask = 0x80 >> (px.x % 8);
+    if px.color.brightness() >= 128 {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+}
+
+unsafe extern "C" fn rounder_trampoline<R: Rounder>(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    area: *mut lvgl_sys::lv_area_t,
+) {
+    guard("display rounder", || {
+        let mut rounded = Area::from(*area);
+        R::round(&mut rounded);
+        let (hor_res, ver_res) = ((*disp_drv).hor_res, (*disp_drv).ver_res);
+        rounded.x1 = rounded.x1.max(0);
+        rounded.y1 = rounded.y1.max(0);
+        rounded.x2 = rounded.x2.min(hor_res - 1);
+        rounded.y2 = rounded.y2.min(ver_res - 1);
+        *area = rounded.into();
+    })
 }
 
 impl From<CoreError> for DisplayError {
@@ -451,4 +1057,198 @@ mod tests {
             .expect("Return screen directly from the display instance");
         Ok(())
     }
+
+    #[test]
+    fn register_runtime_sized_buffer() {
+        tests::initialize_test(false);
+        let buffer = DynDrawBuffer::lines(240, 24).unwrap();
+        assert_eq!(buffer.size(), 240 * 24);
+        assert_eq!(
+            DynDrawBuffer::pixels(0).err(),
+            Some(LvError::InvalidReference)
+        );
+        assert_eq!(
+            DynDrawBuffer::lines(u32::MAX, 2).err(),
+            Some(LvError::InvalidReference)
+        );
+        let flushed = std::rc::Rc::new(core::cell::Cell::new(0));
+        let counter = flushed.clone();
+        let display = Display::register_dyn(buffer, 240, 240, move |refresh| {
+            let area = &refresh.area;
+            let (w, h) = (area.x2 - area.x1 + 1, area.y2 - area.y1 + 1);
+            assert_eq!(refresh.colors.len(), w as usize * h as usize);
+            counter.set(counter.get() + refresh.colors.len());
+        })
+        .unwrap();
+        unsafe { lvgl_sys::lv_refr_now(display.disp.as_ptr()) };
+        assert_eq!(flushed.get(), 240 * 240);
+    }
+
+    #[test]
+    fn round_flushed_areas() {
+        tests::initialize_test(false);
+        let areas = std::rc::Rc::new(core::cell::RefCell::new(std::vec::Vec::new()));
+        let flushed = areas.clone();
+        let display = Display::register(
+            DrawBuffer::<{ 100 * 10 }>::default(),
+            100,
+            60,
+            move |refresh| flushed.borrow_mut().push(refresh.area),
+        )
+        .unwrap();
+        display.set_rounder::<AlignArea<8, 16>>();
+        unsafe { lvgl_sys::lv_refr_now(display.disp.as_ptr()) };
+        areas.borrow_mut().clear();
+
+        let mut area = lvgl_sys::lv_area_t {
+            x1: 3,
+            y1: 5,
+            x2: 6,
+            y2: 8,
+        };
+        unsafe {
+            lvgl_sys::_lv_inv_area(display.disp.as_ptr(), &mut area);
+            lvgl_sys::lv_refr_now(display.disp.as_ptr());
+        }
+        let areas = areas.borrow();
+        assert_eq!(areas.first().map(|a| (a.x1, a.x2, a.y1)), Some((0, 7, 0)));
+        assert_eq!(areas.last().map(|a| a.y2), Some(15));
+
+        let mut edge = Area {
+            x1: 90,
+            x2: 99,
+            y1: 50,
+            y2: 59,
+        };
+        AlignArea::<8, 16>::round(&mut edge);
+        assert_eq!((edge.x1, edge.x2, edge.y1, edge.y2), (88, 103, 48, 63));
+    }
+
+    #[test]
+    fn register_custom_pixel_layout() {
+        tests::initialize_test(false);
+        let written = std::rc::Rc::new(core::cell::Cell::new(0));
+        let counter = written.clone();
+        let flushed = std::rc::Rc::new(core::cell::Cell::new(false));
+        let done = flushed.clone();
+        // Vertical bytes of 1-bit pixels
+        let display = Display::register_set_px(
+            DrawBuffer::<{ 16 * 16 }>::default(),
+            16,
+            16,
+            move |buf, px| {
+                let i = (px.y as usize / 8) * px.buf_width as usize + px.x as usize;
+                let bit = 1 << (px.y % 8);
+                if px.color.brightness() >= 128 {
+                    buf[i] |= bit;
+                } else {
+                    buf[i] &= !bit;
+                }
+                counter.set(counter.get() + 1);
+            },
+            move |refresh| {
+                assert_eq!(refresh.area.x2 - refresh.area.x1 + 1, 16);
+                // The light theme's background
+                assert!(refresh.bytes[..32].iter().all(|&b| b == 0xFF));
+                done.set(true);
+            },
+        )
+        .unwrap();
+        unsafe { lvgl_sys::lv_refr_now(display.disp.as_ptr()) };
+        assert!(flushed.get());
+        assert!(written.get() >= 16 * 16);
+    }
+
+    #[test]
+    fn register_monochrome_display() {
+        tests::initialize_test(false);
+        let flushed = std::rc::Rc::new(core::cell::Cell::new(0));
+        let counter = flushed.clone();
+        let display = Display::register_mono(
+            DrawBuffer::<{ 128 * 16 }>::default(),
+            128,
+            64,
+            move |refresh| {
+                let area = &refresh.area;
+                let h = (area.y2 - area.y1 + 1) as usize;
+                assert_eq!(refresh.stride, 16);
+                assert_eq!(refresh.bits.len(), 16 * h);
+                // The light theme's background
+                assert!(refresh.pixel(0, 0));
+                counter.set(counter.get() + 128 * h);
+            },
+        )
+        .unwrap();
+        unsafe { lvgl_sys::lv_refr_now(display.disp.as_ptr()) };
+        assert_eq!(flushed.get(), 128 * 64);
+    }
+
+    #[cfg(feature = "embedded_graphics")]
+    #[test]
+    fn draw_refresh_contiguously() {
+        use embedded_graphics::pixelcolor::Rgb565;
+        use embedded_graphics::prelude::*;
+        use embedded_graphics::primitives::Rectangle;
+
+        #[derive(Default)]
+        struct Target {
+            fills: Vec<(Rectangle, usize)>,
+        }
+
+        impl OriginDimensions for Target {
+            fn size(&self) -> Size {
+                Size::new(240, 240)
+            }
+        }
+
+        impl DrawTarget for Target {
+            type Color = Rgb565;
+            type Error = core::convert::Infallible;
+
+            fn draw_iter<I>(&mut self, _: I) -> result::Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = Pixel<Self::Color>>,
+            {
+                unreachable!()
+            }
+
+            fn fill_contiguous<I>(
+                &mut self,
+                area: &Rectangle,
+                colors: I,
+            ) -> result::Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = Self::Color>,
+            {
+                self.fills.push((*area, colors.into_iter().count()));
+                Ok(())
+            }
+        }
+
+        let colors = [Color::RED; 16];
+        let refresh = DisplayRefresh {
+            area: Area {
+                x1: 2,
+                x2: 4,
+                y1: 10,
+                y2: 13,
+            },
+            colors: &colors,
+        };
+        let mut target = Target::default();
+        refresh.draw(&mut target).unwrap();
+        let rect = Rectangle::new(Point::new(2, 10), Size::new(3, 4));
+        assert_eq!(target.fills, [(rect, 12)]);
+    }
+
+    #[test]
+    fn change_resolution() {
+        tests::initialize_test(true);
+        let display = Display::default();
+        display.set_resolution(320, 200);
+        unsafe {
+            assert_eq!(lvgl_sys::lv_disp_get_hor_res(display.disp.as_ptr()), 320);
+            assert_eq!(lvgl_sys::lv_disp_get_ver_res(display.disp.as_ptr()), 200);
+        }
+    }
 }
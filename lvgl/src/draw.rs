@@ -0,0 +1,293 @@
+//! Hooks for accelerating LVGL's rendering
+//!
+//! LVGL draws through a display's draw context, whose software renderer
+//! ends up filling and copying rectangles of pixels in the draw buffer. A
+//! [`DrawAccel`] takes over those rectangles, e.g. to run them on a vendor's
+//! 2D engine, and leaves anything it declines to the software renderer:
+//!
+//! ```ignore
+//! use lvgl::draw::{Blend, BlendSource, DrawAccel};
+//!
+//! struct Dma2d;
+//!
+//! impl DrawAccel for Dma2d {
+//!     fn blend(blend: &mut Blend) -> bool {
+//!         match blend.src {
+//!             BlendSource::Color(color) if blend.opa == 255 => {
+//!                 dma2d::fill(blend.dest, blend.dest_stride, blend.width, blend.height, color);
+//!                 true
+//!             }
+//!             _ => false,
+//!         }
+//!     }
+//!
+//!     fn wait_for_finish() {
+//!         dma2d::wait();
+//!     }
+//! }
+//!
+//! display.set_draw_accel::<Dma2d>()?;
+//! ```
+//!
+//! Draw contexts written in C are installed with `Display::set_draw_ctx()`
+//! instead.
+//!
+//! # GPU backends
+//!
+//! LVGL's own backends for the STM32 DMA2D and the NXP PXP are enabled with
+//! the `gpu_stm32_dma2d` and `gpu_nxp_pxp` features. LVGL then initializes
+//! the GPU in `lvgl::init()` and gives every display registered afterwards
+//! its GPU draw context, so no further setup is needed; a `DrawAccel` would
+//! replace it. The C sources include the vendor's SDK headers, whose
+//! directories are passed with the comma-separated `LVGL_GPU_INCLUDE`
+//! variable:
+//!
+//! ```shell
+//! $ DEP_LV_CONFIG_GPU_DMA2D_CMSIS_INCLUDE="<stm32f7xx.h>" \
+//!   LVGL_GPU_INCLUDE=/path/to/CMSIS/Include,/path/to/CMSIS/Device/ST/STM32F7xx/Include \
+//!   LVGL_CFLAGS=STM32F746xx cargo build --features gpu_stm32_dma2d
+//! ```
+
+use crate::guard::guard;
+use crate::Color;
+use core::mem;
+
+/// A rectangle LVGL's software renderer is about to fill with a color or
+/// copy from an image, clipped to the area being drawn. Only rectangles
+/// without masks or special blend modes are passed to `DrawAccel::blend()`.
+pub struct Blend<'a> {
+    /// The draw buffer, from the first pixel of the rectangle.
+    pub dest: &'a mut [Color],
+    /// The number of pixels from one row of `dest` to the next.
+    pub dest_stride: usize,
+    /// The width of the rectangle, in pixels.
+    pub width: usize,
+    /// The height of the rectangle, in pixels.
+    pub height: usize,
+    /// What to draw.
+    pub src: BlendSource<'a>,
+    /// The opacity (0-255) to blend the source with the buffer with.
+    pub opa: u8,
+}
+
+/// The pixels drawn by a [`Blend`].
+pub enum BlendSource<'a> {
+    /// A fill of a single color.
+    Color(Color),
+    /// A copy of an image, from the pixel for the first of the rectangle.
+    Image {
+        pixels: &'a [Color],
+        /// The number of pixels from one row of `pixels` to the next.
+        stride: usize,
+    },
+}
+
+/// Accelerated drawing routines for a display. See the module
+/// documentation.
+pub trait DrawAccel {
+    /// Draws `blend` into the draw buffer. Returns `false` to leave it to
+    /// LVGL's software renderer.
+    fn blend(blend: &mut Blend) -> bool;
+
+    /// Waits until the drawing started by `blend()` is done. LVGL calls it
+    /// before flushing the draw buffer; a `blend()` running in the
+    /// background must also wait before returning `false`, as LVGL then
+    /// draws into the buffer in software.
+    fn wait_for_finish() {}
+
+    /// Changes the draw context further once LVGL's software renderer has
+    /// set it up, e.g. replacing `draw_img_decoded` with an accelerated
+    /// routine.
+    ///
+    /// # Safety
+    ///
+    /// Any replaced function must uphold LVGL's requirements for it.
+    unsafe fn init(_draw_ctx: &mut lvgl_sys::lv_draw_ctx_t) {}
+}
+
+/// LVGL's software draw context, with the software blend routine replaced
+/// by `blend_trampoline()`.
+#[repr(C)]
+struct AccelCtx {
+    sw: lvgl_sys::lv_draw_sw_ctx_t,
+    sw_blend: Option<
+        unsafe extern "C" fn(*mut lvgl_sys::lv_draw_ctx_t, *const lvgl_sys::lv_draw_sw_blend_dsc_t),
+    >,
+    sw_wait_for_finish: Option<unsafe extern "C" fn(*mut lvgl_sys::lv_draw_ctx_t)>,
+}
+
+/// The size of the draw context `init_trampoline::<A>()` sets up.
+pub(crate) const ACCEL_CTX_SIZE: usize = mem::size_of::<AccelCtx>();
+
+pub(crate) unsafe extern "C" fn init_trampoline<A: DrawAccel>(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    draw_ctx: *mut lvgl_sys::lv_draw_ctx_t,
+) {
+    guard("draw context init", || {
+        lvgl_sys::lv_draw_sw_init_ctx(disp_drv, draw_ctx);
+        let ctx = &mut *(draw_ctx as *mut AccelCtx);
+        ctx.sw_blend = ctx.sw.blend;
+        ctx.sw.blend = Some(blend_trampoline::<A>);
+        ctx.sw_wait_for_finish = ctx.sw.base_draw.wait_for_finish;
+        ctx.sw.base_draw.wait_for_finish = Some(wait_for_finish_trampoline::<A>);
+        A::init(&mut ctx.sw.base_draw);
+    })
+}
+
+unsafe extern "C" fn blend_trampoline<A: DrawAccel>(
+    draw_ctx: *mut lvgl_sys::lv_draw_ctx_t,
+    dsc: *const lvgl_sys::lv_draw_sw_blend_dsc_t,
+) {
+    guard("draw blend", || {
+        let ctx = &*(draw_ctx as *mut AccelCtx);
+        let done = match blend_of(&*draw_ctx, &*dsc) {
+            Some(mut blend) => A::blend(&mut blend),
+            None => false,
+        };
+        if !done {
+            if let Some(sw_blend) = ctx.sw_blend {
+                sw_blend(draw_ctx, dsc);
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn wait_for_finish_trampoline<A: DrawAccel>(
+    draw_ctx: *mut lvgl_sys::lv_draw_ctx_t,
+) {
+    guard("draw wait_for_finish", || {
+        A::wait_for_finish();
+        let ctx = &*(draw_ctx as *mut AccelCtx);
+        if let Some(sw_wait_for_finish) = ctx.sw_wait_for_finish {
+            sw_wait_for_finish(draw_ctx);
+        }
+    })
+}
+
+/// Describes `dsc` as a `Blend`, or returns `None` if it is masked, uses a
+/// special blend mode, draws nothing or goes through the display's
+/// `set_px_cb`.
+unsafe fn blend_of<'a>(
+    draw_ctx: &'a lvgl_sys::lv_draw_ctx_t,
+    dsc: &'a lvgl_sys::lv_draw_sw_blend_dsc_t,
+) -> Option<Blend<'a>> {
+    let masked = !dsc.mask_buf.is_null()
+        && dsc.mask_res != lvgl_sys::LV_DRAW_MASK_RES_FULL_COVER as lvgl_sys::lv_draw_mask_res_t;
+    if masked
+        || dsc.mask_res == lvgl_sys::LV_DRAW_MASK_RES_TRANSP as lvgl_sys::lv_draw_mask_res_t
+        || dsc.blend_mode != lvgl_sys::LV_BLEND_MODE_NORMAL as lvgl_sys::lv_blend_mode_t
+        || dsc.opa <= lvgl_sys::LV_OPA_MIN as lvgl_sys::lv_opa_t
+    {
+        return None;
+    }
+    let disp = lvgl_sys::_lv_refr_get_disp_refreshing();
+    if disp.is_null() || (*(*disp).driver).set_px_cb.is_some() {
+        return None;
+    }
+
+    let mut area: lvgl_sys::lv_area_t = mem::zeroed();
+    if !lvgl_sys::_lv_area_intersect(&mut area, dsc.blend_area, draw_ctx.clip_area) {
+        return None;
+    }
+    let width = (area.x2 - area.x1 + 1) as usize;
+    let height = (area.y2 - area.y1 + 1) as usize;
+    let len = |stride: usize| stride * (height - 1) + width;
+
+    let buf_area = &*draw_ctx.buf_area;
+    let dest_stride = (buf_area.x2 - buf_area.x1 + 1) as usize;
+    let dest_start =
+        (area.y1 - buf_area.y1) as usize * dest_stride + (area.x1 - buf_area.x1) as usize;
+    let dest = core::slice::from_raw_parts_mut(
+        (draw_ctx.buf as *mut Color).add(dest_start),
+        len(dest_stride),
+    );
+
+    let src = if dsc.src_buf.is_null() {
+        BlendSource::Color(Color::from_raw(dsc.color))
+    } else {
+        let blend_area = &*dsc.blend_area;
+        let stride = (blend_area.x2 - blend_area.x1 + 1) as usize;
+        let start =
+            (area.y1 - blend_area.y1) as usize * stride + (area.x1 - blend_area.x1) as usize;
+        BlendSource::Image {
+            pixels: core::slice::from_raw_parts(
+                (dsc.src_buf as *const Color).add(start),
+                len(stride),
+            ),
+            stride,
+        }
+    };
+
+    Some(Blend {
+        dest,
+        dest_stride,
+        width,
+        height,
+        src,
+        opa: dsc.opa,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, DrawBuffer};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Fills opaque rectangles itself, in software.
+    struct CountingFill;
+
+    impl DrawAccel for CountingFill {
+        fn blend(blend: &mut Blend) -> bool {
+            match blend.src {
+                BlendSource::Color(color) if blend.opa == 255 => {
+                    for y in 0..blend.height {
+                        let row = y * blend.dest_stride;
+                        blend.dest[row..row + blend.width].fill(color);
+                    }
+                    FILLS.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    #[test]
+    fn accelerated_fills() {
+        crate::tests::initialize_test(false);
+        let frame = std::rc::Rc::new(core::cell::Cell::new(0));
+        let flushed = frame.clone();
+        let display = Display::register(
+            DrawBuffer::<{ 32 * 32 }>::default(),
+            32,
+            32,
+            move |refresh| {
+                flushed.set(refresh.colors[0].to_u32());
+            },
+        )
+        .unwrap();
+        display.set_draw_accel::<CountingFill>().unwrap();
+        unsafe {
+            let screen = lvgl_sys::lv_disp_get_scr_act(display.disp.as_ptr());
+            lvgl_sys::lv_obj_invalidate(screen);
+            lvgl_sys::lv_refr_now(display.disp.as_ptr());
+        }
+        assert!(FILLS.load(Ordering::Relaxed) > 0);
+        // The screen's background, filled by `CountingFill`
+        let bg = unsafe {
+            let screen = lvgl_sys::lv_disp_get_scr_act(display.disp.as_ptr());
+            crate::Color::from_raw(
+                lvgl_sys::lv_obj_get_style_prop(
+                    screen,
+                    lvgl_sys::LV_PART_MAIN,
+                    lvgl_sys::lv_style_prop_t_LV_STYLE_BG_COLOR,
+                )
+                .color,
+            )
+        };
+        assert_eq!(frame.get(), bg.to_u32());
+    }
+}
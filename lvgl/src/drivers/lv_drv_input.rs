@@ -38,6 +38,19 @@ macro_rules! lv_drv_input_pointer_sdl {
     };
 }
 
+#[macro_export]
+macro_rules! lv_drv_input_keyboard_sdl {
+    ($disp:ident) => {
+        unsafe {
+            $crate::input_device::keypad::Keypad::new_raw(
+                Some(lvgl_sys::sdl_keyboard_read),
+                None,
+                &$disp,
+            )
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! lv_drv_input_ad_touch {
     ($disp:ident) => {
@@ -5,7 +5,8 @@
 //! `lv_drv_conf.h`, or compilation will error.
 //!
 //! The `sdl` example shows how to use both input and display drivers to port
-//! the `button_click` example.
+//! the `button_click` example. For desktop prototyping, the `sdl` feature
+//! also provides a ready-made simulator window in the [`sdl`] submodule.
 //!
 //! # Building
 //! To compile in support for drivers, ensure the `drivers` feature is enabled
@@ -66,3 +67,5 @@
 
 mod lv_drv_display;
 mod lv_drv_input;
+#[cfg(feature = "sdl")]
+pub mod sdl;
@@ -0,0 +1,69 @@
+//! A desktop simulator window backed by SDL2
+//!
+//! [`Simulator`] opens a window through the SDL2 driver of [`lv_drivers`] and
+//! registers it as a display, along with the mouse as a pointer and the
+//! keyboard as a keypad. This is the quickest way to prototype a UI on a
+//! desktop:
+//!
+//! ```no_run
+//! use lvgl::drivers::sdl::Simulator;
+//! use lvgl::DrawBuffer;
+//! use std::time::Duration;
+//!
+//! let buffer = DrawBuffer::<{ Simulator::BUFFER_SIZE }>::default();
+//! let sim = Simulator::new(buffer).unwrap();
+//! let mut screen = sim.display.get_scr_act().unwrap();
+//! // Create widgets on `screen`...
+//! loop {
+//!     lvgl::task_handler()?;
+//!     std::thread::sleep(Duration::from_millis(5));
+//!     lvgl::tick_inc(Duration::from_millis(5));
+//! }
+//! ```
+//!
+//! # Building
+//!
+//! Enable the `sdl` feature, which also enables the SDL2 driver in
+//! `lv_drv_conf.h`. The window size is set by `SDL_HOR_RES` and `SDL_VER_RES`
+//! in `lv_drv_conf.h`, and enlarged by `SDL_ZOOM`.
+//!
+//! [`lv_drivers`]: https://github.com/lvgl/lv_drivers
+
+use crate::input_device::keypad::Keypad;
+use crate::input_device::pointer::Pointer;
+use crate::input_device::InputDriver;
+use crate::{Display, DrawBuffer, LvResult};
+
+/// An SDL2 window with mouse and keyboard input, registered with LVGL.
+pub struct Simulator {
+    /// The display drawing to the window.
+    pub display: Display,
+    /// The mouse, as a pointer device.
+    pub mouse: Pointer,
+    /// The keyboard, as a keypad device. Assign it to a group to control
+    /// widgets with it.
+    pub keyboard: Keypad,
+}
+
+impl Simulator {
+    /// Horizontal resolution of the window, set by `SDL_HOR_RES`.
+    pub const HOR_RES: u32 = lvgl_sys::SDL_HOR_RES;
+    /// Vertical resolution of the window, set by `SDL_VER_RES`.
+    pub const VER_RES: u32 = lvgl_sys::SDL_VER_RES;
+    /// A draw buffer size covering the whole window.
+    pub const BUFFER_SIZE: usize = (Self::HOR_RES * Self::VER_RES) as usize;
+
+    /// Opens the window and registers it with its input devices. This can
+    /// only be done once.
+    pub fn new<const N: usize>(draw_buffer: DrawBuffer<N>) -> LvResult<Self> {
+        let (hor_res, ver_res) = (Self::HOR_RES, Self::VER_RES);
+        let display = crate::lv_drv_disp_sdl!(draw_buffer, hor_res, ver_res)?;
+        let mouse = crate::lv_drv_input_pointer_sdl!(display)?;
+        let keyboard = crate::lv_drv_input_keyboard_sdl!(display)?;
+        Ok(Self {
+            display,
+            mouse,
+            keyboard,
+        })
+    }
+}
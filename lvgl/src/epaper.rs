@@ -0,0 +1,204 @@
+//! E-paper displays, redrawn on demand
+//!
+//! E-paper panels take a long time to update, and partial updates leave
+//! ghosts of the previous content that only a full update, flashing the
+//! whole panel, clears. [`Epaper`] registers a display that LVGL only redraws
+//! when `refresh()` is called, telling the flush callback which kind of
+//! update to run, so that the application decides how often to pay for a
+//! full one:
+//!
+//! ```ignore
+//! use lvgl::epaper::{Epaper, RefreshMode};
+//!
+//! let mut epd = Epaper::register(
+//!     DrawBuffer::<{ 200 * 20 }>::default(),
+//!     200,
+//!     200,
+//!     |refresh| {
+//!         panel.write_area(&refresh.area, refresh.colors);
+//!         if refresh.last {
+//!             panel.update(refresh.mode == RefreshMode::Full);
+//!         }
+//!     },
+//!     || while busy_pin.is_high() {},
+//! )?;
+//! let mut partial_updates = 0;
+//! loop {
+//!     lvgl::task_handler()?;
+//!     if epd.needs_refresh() {
+//!         partial_updates = (partial_updates + 1) % 10;
+//!         epd.refresh(if partial_updates == 0 {
+//!             RefreshMode::Full
+//!         } else {
+//!             RefreshMode::Partial
+//!         });
+//!     }
+//! }
+//! ```
+//!
+//! Timers, animations and input devices keep running in `task_handler()`;
+//! only the drawing waits for `refresh()`.
+
+use crate::display::Area;
+use crate::{Box, Color, Display, DrawBuffer, LvError, LvResult};
+use core::cell::Cell;
+use core::ptr;
+
+/// The kind of update an e-paper panel runs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RefreshMode {
+    /// Updates the changed areas only, quickly but leaving some ghosting.
+    #[default]
+    Partial,
+    /// Redraws the whole screen, for the panel to run its full, flashing
+    /// update that clears ghosting.
+    Full,
+}
+
+/// An update to an e-paper display. `colors` holds exactly the pixels of
+/// the area, row by row.
+pub struct EpaperRefresh<'a> {
+    pub area: Area,
+    pub colors: &'a [Color],
+    /// The kind of update `refresh()` was called for.
+    pub mode: RefreshMode,
+    /// Whether this is the last area of the refresh, after which the panel
+    /// can start updating.
+    pub last: bool,
+}
+
+/// A display drawn to an e-paper panel when `refresh()` is called.
+pub struct Epaper<W> {
+    /// The registered display.
+    pub display: Display,
+    mode: &'static Cell<RefreshMode>,
+    wait: W,
+}
+
+impl<W: FnMut()> Epaper<W> {
+    /// Registers a `width` by `height` display that is only redrawn by
+    /// `refresh()`. `flush` takes an `&EpaperRefresh`, and `wait` blocks
+    /// until the panel is done with its previous update, e.g. by polling its
+    /// BUSY line.
+    pub fn register<F, const N: usize>(
+        draw_buffer: DrawBuffer<N>,
+        width: u32,
+        height: u32,
+        mut flush: F,
+        wait: W,
+    ) -> LvResult<Self>
+    where
+        F: FnMut(&EpaperRefresh),
+    {
+        // Leaked along with the display driver, as LVGL keeps the display
+        let mode: &'static Cell<RefreshMode> =
+            unsafe { &*Box::into_raw(Box::new(Cell::new(RefreshMode::Partial))) };
+        let display = Display::register(draw_buffer, width, height, move |refresh| {
+            let area = &refresh.area;
+            let len = (area.x2 - area.x1 + 1) as usize * (area.y2 - area.y1 + 1) as usize;
+            let last = unsafe {
+                let disp = lvgl_sys::_lv_refr_get_disp_refreshing();
+                disp.is_null() || lvgl_sys::lv_disp_flush_is_last((*disp).driver)
+            };
+            flush(&EpaperRefresh {
+                area: *area,
+                colors: &refresh.colors[..len],
+                mode: mode.get(),
+                last,
+            });
+        })
+        .map_err(LvError::from)?;
+        // Without its refresh timer, LVGL only redraws the display when asked
+        unsafe {
+            let disp = display.disp.as_ptr();
+            lvgl_sys::lv_timer_del((*disp).refr_timer);
+            (*disp).refr_timer = ptr::null_mut();
+        }
+        Ok(Self {
+            display,
+            mode,
+            wait,
+        })
+    }
+
+    /// Returns whether parts of the screen changed since the last refresh.
+    pub fn needs_refresh(&self) -> bool {
+        unsafe { (*self.display.disp.as_ptr()).inv_p > 0 }
+    }
+
+    /// Waits for the panel, then redraws and flushes the changed areas, or
+    /// the whole screen with `RefreshMode::Full`. Returns `false` without
+    /// waiting if a partial refresh has nothing to redraw.
+    pub fn refresh(&mut self, mode: RefreshMode) -> bool {
+        if mode == RefreshMode::Partial && !self.needs_refresh() {
+            return false;
+        }
+        (self.wait)();
+        unsafe {
+            let disp = self.display.disp.as_ptr();
+            if mode == RefreshMode::Full {
+                lvgl_sys::lv_obj_invalidate(lvgl_sys::lv_disp_get_scr_act(disp));
+            }
+            // Without a timer, LVGL refreshes the default display
+            let default = lvgl_sys::lv_disp_get_default();
+            lvgl_sys::lv_disp_set_default(disp);
+            self.mode.set(mode);
+            lvgl_sys::_lv_disp_refr_timer(ptr::null_mut());
+            self.mode.set(RefreshMode::Partial);
+            lvgl_sys::lv_disp_set_default(default);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[test]
+    fn refresh_on_demand() {
+        crate::tests::initialize_test(false);
+        let flushes = Rc::new(RefCell::new(Vec::new()));
+        let seen = flushes.clone();
+        let waits = Rc::new(Cell::new(0));
+        let waited = waits.clone();
+        let mut epd = Epaper::register(
+            DrawBuffer::<{ 64 * 8 }>::default(),
+            64,
+            32,
+            move |refresh| {
+                let area = refresh.area;
+                let rows = (area.y2 - area.y1 + 1) as usize;
+                seen.borrow_mut().push((refresh.mode, rows, refresh.last));
+            },
+            move || waited.set(waited.get() + 1),
+        )
+        .unwrap();
+
+        for _ in 0..5 {
+            crate::tick_inc(core::time::Duration::from_millis(50));
+            crate::task_handler().unwrap();
+        }
+        assert!(flushes.borrow().is_empty());
+        assert!(epd.needs_refresh());
+
+        assert!(epd.refresh(RefreshMode::Partial));
+        assert!(!epd.needs_refresh());
+        assert!(!epd.refresh(RefreshMode::Partial));
+        assert_eq!(waits.get(), 1);
+        let partial: Vec<_> = flushes.borrow_mut().drain(..).collect();
+        assert_eq!(partial.iter().map(|f| f.1).sum::<usize>(), 32);
+        assert!(partial.iter().all(|f| f.0 == RefreshMode::Partial));
+        assert_eq!(partial.last().map(|f| f.2), Some(true));
+
+        assert!(epd.refresh(RefreshMode::Full));
+        assert_eq!(waits.get(), 2);
+        let full = flushes.borrow();
+        assert_eq!(full.iter().map(|f| f.1).sum::<usize>(), 32);
+        assert!(full.iter().all(|f| f.0 == RefreshMode::Full));
+        assert_eq!(full.iter().filter(|f| f.2).count(), 1);
+    }
+}
@@ -21,4 +21,19 @@ impl Font {
             inner: Box::new(raw),
         }
     }
+
+    /// Keeps the font in LVGL memory until the program ends, for APIs which
+    /// keep pointing to it, e.g. `Span::font()`.
+    pub fn leak(self) -> &'static Font {
+        unsafe { &*Box::into_raw(Box::new(self)) }
+    }
+
+    pub(crate) fn raw(&self) -> *const lvgl_sys::lv_font_t {
+        &*self.inner
+    }
+
+    /// Returns the height of a line of text in the font, in pixels.
+    pub fn line_height(&self) -> i16 {
+        self.inner.line_height as i16
+    }
 }
@@ -44,6 +44,11 @@
 //! This operation is inherently unsafe as it instantiates and uses arbitrary
 //! data structures that the Rust compiler can't check.
 //!
+//! # TrueType fonts
+//! With the `tiny_ttf` feature, TTF files can be embedded with
+//! `include_bytes!` and rendered at any size by LVGL's Tiny TTF decoder; see
+//! [`TinyTtfFont`].
+//!
 //! [official online converter]: https://lvgl.io/tools/fontconverter
 
 mod generic;
@@ -51,3 +56,7 @@ pub use generic::*;
 
 #[cfg(feature = "nightly")]
 mod builtin;
+#[cfg(feature = "tiny_ttf")]
+mod tiny_ttf;
+#[cfg(feature = "tiny_ttf")]
+pub use tiny_ttf::*;
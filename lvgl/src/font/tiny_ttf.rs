@@ -0,0 +1,60 @@
+use crate::font::Font;
+use crate::{LvError, LvResult};
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// A TrueType font rendered by LVGL's Tiny TTF decoder, without FreeType.
+/// Requires the `tiny_ttf` feature.
+///
+/// ```ignore
+/// use lvgl::font::TinyTtfFont;
+///
+/// static INTER: &[u8] = include_bytes!("../fonts/Inter-Regular.ttf");
+///
+/// // Safety: INTER is a valid TrueType font
+/// let inter_20 = unsafe { TinyTtfFont::from_bytes(INTER, 20) }?;
+/// style.set_text_font(inter_20.into());
+/// ```
+///
+/// Glyphs are rendered from `data` as they are drawn, so it must be
+/// `'static`. Dropping a `TinyTtfFont` frees the decoder's state and glyph
+/// cache. Converting it into a `Font` instead leaks them, as LVGL keeps
+/// pointing to them from the styles the font is set on.
+pub struct TinyTtfFont {
+    raw: NonNull<lvgl_sys::lv_font_t>,
+}
+
+impl TinyTtfFont {
+    /// Loads the TTF font in `data`, for text `size` pixels high. Each size
+    /// is a separate font. Fails with `LvError::InvalidReference` if `size`
+    /// is out of range, LVGL is out of memory or the decoder rejects the font.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be a valid TrueType font. The decoder trusts the offsets
+    /// in its tables, and reads out of bounds on malformed data.
+    pub unsafe fn from_bytes(data: &'static [u8], size: u16) -> LvResult<Self> {
+        let size = size.try_into().map_err(|_| LvError::InvalidReference)?;
+        let raw = unsafe {
+            lvgl_sys::lv_tiny_ttf_create_data(data.as_ptr() as *const _, data.len() as _, size)
+        };
+        NonNull::new(raw)
+            .map(|raw| Self { raw })
+            .ok_or(LvError::InvalidReference)
+    }
+}
+
+impl Drop for TinyTtfFont {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_tiny_ttf_destroy(self.raw.as_ptr()) }
+    }
+}
+
+impl From<TinyTtfFont> for Font {
+    fn from(value: TinyTtfFont) -> Self {
+        let value = ManuallyDrop::new(value);
+        // Safety: the font was set up by the decoder, whose state it points
+        // to is leaked above
+        unsafe { Font::new_raw(*value.raw.as_ptr()) }
+    }
+}
@@ -0,0 +1,283 @@
+//! A Linux framebuffer (`/dev/fb*`) display backend
+//!
+//! [`Framebuffer`] maps a framebuffer device into memory and registers it as
+//! an LVGL display, without needing lv_drivers. The pixel format is read
+//! from the device, so any packed RGB format of 16, 24 or 32 bits per pixel
+//! (such as RGB565, RGB888 or XRGB8888) is supported.
+//!
+//! Combined with the `lv_drv_input_pointer_evdev!` input driver, this makes
+//! a complete stack for headless embedded Linux devices:
+//!
+//! ```ignore
+//! use lvgl::framebuffer::Framebuffer;
+//! use lvgl::input_device::InputDriver;
+//! use lvgl::DrawBuffer;
+//!
+//! let fb = Framebuffer::open("/dev/fb0").unwrap();
+//! let buffer = DrawBuffer::<{ 800 * 48 }>::default();
+//! let display = fb.register(buffer).unwrap();
+//! let _touch = lvgl::lv_drv_input_pointer_evdev!(display).unwrap();
+//! ```
+
+use crate::display::DisplayError;
+use crate::{Color, Display, DrawBuffer};
+use core::ptr;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+const FBIOGET_VSCREENINFO: libc::c_ulong = 0x4600;
+const FBIOGET_FSCREENINFO: libc::c_ulong = 0x4602;
+
+/// `struct fb_bitfield` from `linux/fb.h`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+struct Bitfield {
+    offset: u32,
+    length: u32,
+    msb_right: u32,
+}
+
+/// `struct fb_var_screeninfo` from `linux/fb.h`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+struct VarScreenInfo {
+    xres: u32,
+    yres: u32,
+    xres_virtual: u32,
+    yres_virtual: u32,
+    xoffset: u32,
+    yoffset: u32,
+    bits_per_pixel: u32,
+    grayscale: u32,
+    red: Bitfield,
+    green: Bitfield,
+    blue: Bitfield,
+    transp: Bitfield,
+    nonstd: u32,
+    activate: u32,
+    height: u32,
+    width: u32,
+    accel_flags: u32,
+    pixclock: u32,
+    left_margin: u32,
+    right_margin: u32,
+    upper_margin: u32,
+    lower_margin: u32,
+    hsync_len: u32,
+    vsync_len: u32,
+    sync: u32,
+    vmode: u32,
+    rotate: u32,
+    colorspace: u32,
+    reserved: [u32; 4],
+}
+
+/// `struct fb_fix_screeninfo` from `linux/fb.h`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+struct FixScreenInfo {
+    id: [u8; 16],
+    smem_start: libc::c_ulong,
+    smem_len: u32,
+    type_: u32,
+    type_aux: u32,
+    visual: u32,
+    xpanstep: u16,
+    ypanstep: u16,
+    ywrapstep: u16,
+    line_length: u32,
+    mmio_start: libc::c_ulong,
+    mmio_len: u32,
+    accel: u32,
+    capabilities: u16,
+    reserved: [u16; 2],
+}
+
+/// The layout of a pixel in framebuffer memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PixelFormat {
+    /// Size of a pixel in bytes.
+    pub bytes_per_pixel: usize,
+    red: Bitfield,
+    green: Bitfield,
+    blue: Bitfield,
+    transp: Bitfield,
+}
+
+impl PixelFormat {
+    fn from_var(var: &VarScreenInfo) -> io::Result<Self> {
+        let bytes_per_pixel = match var.bits_per_pixel {
+            16 | 24 | 32 => var.bits_per_pixel as usize / 8,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "unsupported framebuffer pixel format",
+                ))
+            }
+        };
+        Ok(Self {
+            bytes_per_pixel,
+            red: var.red,
+            green: var.green,
+            blue: var.blue,
+            transp: var.transp,
+        })
+    }
+
+    /// Packs an `0xAARRGGBB` color into a pixel value of this format.
+    fn encode(&self, argb: u32) -> u32 {
+        fn channel(value: u32, field: &Bitfield) -> u32 {
+            if field.length == 0 {
+                return 0;
+            }
+            ((value & 0xFF) >> 8u32.saturating_sub(field.length)) << field.offset
+        }
+        channel(argb >> 16, &self.red)
+            | channel(argb >> 8, &self.green)
+            | channel(argb, &self.blue)
+            | channel(argb >> 24, &self.transp)
+    }
+}
+
+/// A memory-mapped Linux framebuffer device.
+pub struct Framebuffer {
+    _file: File,
+    mem: *mut u8,
+    len: usize,
+    width: u32,
+    height: u32,
+    line_length: usize,
+    format: PixelFormat,
+}
+
+impl Framebuffer {
+    /// Opens and maps the framebuffer device at `path`, e.g. `/dev/fb0`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let fd = file.as_raw_fd();
+
+        let mut var = VarScreenInfo::default();
+        let mut fix = FixScreenInfo::default();
+        unsafe {
+            if libc::ioctl(fd, FBIOGET_VSCREENINFO as _, &mut var) < 0
+                || libc::ioctl(fd, FBIOGET_FSCREENINFO as _, &mut fix) < 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        let format = PixelFormat::from_var(&var)?;
+
+        let len = fix.smem_len as usize;
+        let mem = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if mem == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            _file: file,
+            mem: mem as *mut u8,
+            len,
+            width: var.xres,
+            height: var.yres,
+            line_length: fix.line_length as usize,
+            format,
+        })
+    }
+
+    /// Returns the visible resolution as `(width, height)`.
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the pixel format of the device.
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Writes one pixel. Pixels outside the visible area are ignored.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let bpp = self.format.bytes_per_pixel;
+        let offset = y as usize * self.line_length + x as usize * bpp;
+        if offset + bpp > self.len {
+            return;
+        }
+        let value = self.format.encode(color.to_u32()).to_le_bytes();
+        // Safety: the offset was checked against the mapping's length.
+        unsafe { ptr::copy_nonoverlapping(value.as_ptr(), self.mem.add(offset), bpp) }
+    }
+
+    /// Registers the framebuffer as an LVGL display at its full resolution.
+    pub fn register<const N: usize>(
+        mut self,
+        draw_buffer: DrawBuffer<N>,
+    ) -> Result<Display, DisplayError> {
+        let (width, height) = self.resolution();
+        Display::register(draw_buffer, width, height, move |refresh| {
+            let area = &refresh.area;
+            let row_len = (area.x2 - area.x1 + 1) as usize;
+            for (iy, y) in (area.y1..=area.y2).enumerate() {
+                for (ix, x) in (area.x1..=area.x2).enumerate() {
+                    self.set_pixel(x as u32, y as u32, refresh.colors[iy * row_len + ix]);
+                }
+            }
+        })
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mem as *mut _, self.len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn field(offset: u32, length: u32) -> Bitfield {
+        Bitfield {
+            offset,
+            length,
+            msb_right: 0,
+        }
+    }
+
+    #[test]
+    fn encode_common_formats() {
+        let rgb565 = PixelFormat {
+            bytes_per_pixel: 2,
+            red: field(11, 5),
+            green: field(5, 6),
+            blue: field(0, 5),
+            transp: field(0, 0),
+        };
+        assert_eq!(rgb565.encode(0xFFFF_0000), 0xF800);
+        assert_eq!(rgb565.encode(0xFF00_FF00), 0x07E0);
+        assert_eq!(rgb565.encode(0xFF00_00FF), 0x001F);
+
+        let xrgb8888 = PixelFormat {
+            bytes_per_pixel: 4,
+            red: field(16, 8),
+            green: field(8, 8),
+            blue: field(0, 8),
+            transp: field(0, 0),
+        };
+        assert_eq!(xrgb8888.encode(0xFF12_3456), 0x0012_3456);
+    }
+}
@@ -2,6 +2,7 @@ use crate::display::{Display, DisplayDriver};
 use crate::input_device::InputDriver;
 use crate::{Event, LvError, LvResult, Obj, Widget};
 use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 #[cfg(not(feature = "rust_timer"))]
 use core::time::Duration;
 use core::{ptr, result};
@@ -16,8 +17,8 @@ pub enum CoreError {
 type Result<T> = result::Result<T, CoreError>;
 
 /// Register own buffer
-pub(crate) fn disp_drv_register<const N: usize>(
-    disp_drv: &mut DisplayDriver<N>,
+pub(crate) fn disp_drv_register<B>(
+    disp_drv: &mut DisplayDriver<B>,
     drop: Option<unsafe extern "C" fn()>,
 ) -> Result<Display> {
     let disp_ptr = unsafe {
@@ -53,18 +54,116 @@ pub(crate) fn get_str_act(disp: Option<&Display>) -> Result<Obj<'_>> {
 
 /// Runs an LVGL tick lasting a given `core::time::Duration`. This function
 /// should be called periodically.
+///
+/// This is safe to call from an interrupt handler: it neither allocates nor
+/// takes any lock, including that of `sync::LvglMutex`.
 #[inline]
 #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
 pub fn tick_inc(tick_period: Duration) {
+    tick_inc_ms(tick_period.as_millis() as u32);
+}
+
+/// Runs an LVGL tick lasting `ms` milliseconds. Like `tick_inc()`, this is
+/// safe to call from an interrupt handler, and avoids the `Duration`
+/// conversion there.
+#[inline]
+#[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+pub fn tick_inc_ms(ms: u32) {
     unsafe {
-        lvgl_sys::lv_tick_inc(tick_period.as_millis() as u32);
+        lvgl_sys::lv_tick_inc(ms);
     }
 }
 
+static IN_TASK_HANDLER: AtomicBool = AtomicBool::new(false);
+
 /// Calls the LVGL timer handler. This function should be called periodically.
-#[inline]
-pub fn task_handler() {
-    unsafe { lvgl_sys::lv_timer_handler() };
+/// With the `defer` feature, the closures queued with `defer()` run first.
+///
+/// Returns `LvError::Uninitialized` before `init()`, and
+/// `LvError::AlreadyInUse` when called from code the timer handler is
+/// running, such as an event handler.
+pub fn task_handler() -> LvResult<()> {
+    timer_handler().map(|_| ())
+}
+
+/// Calls the LVGL timer handler like `task_handler()`, and returns the time
+/// until its next timer is due, i.e. how long the caller can sleep. This is
+/// `Duration::MAX` if no timer is running. See the `pacing` module.
+pub fn timer_handler() -> LvResult<Duration> {
+    crate::check_init()?;
+    if IN_TASK_HANDLER.swap(true, Ordering::Acquire) {
+        return Err(LvError::AlreadyInUse);
+    }
+    #[cfg(feature = "defer")]
+    crate::defer::run_pending();
+    let next = unsafe { lvgl_sys::lv_timer_handler() };
+    IN_TASK_HANDLER.store(false, Ordering::Release);
+    Ok(if next == lvgl_sys::LV_NO_TIMER_READY {
+        Duration::MAX
+    } else {
+        Duration::from_millis(next.into())
+    })
+}
+
+/// Tests if `task_handler()` is running, e.g. when called from an event
+/// handler.
+pub(crate) fn in_task_handler() -> bool {
+    IN_TASK_HANDLER.load(Ordering::Acquire)
+}
+
+static UPDATE_DEPTH: AtomicU32 = AtomicU32::new(0);
+
+/// Runs `f` with LVGL's style refreshes and screen invalidation paused, then
+/// refreshes the screens of every display once. This avoids recalculating
+/// styles and redrawing areas after each change when creating or updating
+/// many widgets, e.g. when populating a large list or table.
+///
+/// Layouts are already only updated when the screen is refreshed, unless the
+/// size or position of a widget is read within `f`. Calls can be nested, in
+/// which case the screens are refreshed when the outermost one returns.
+pub fn with_updates<R>(f: impl FnOnce() -> R) -> R {
+    struct Resume;
+
+    impl Drop for Resume {
+        fn drop(&mut self) {
+            if UPDATE_DEPTH.fetch_sub(1, Ordering::AcqRel) == 1 {
+                unsafe { set_updates_enabled(true) };
+            }
+        }
+    }
+
+    if UPDATE_DEPTH.fetch_add(1, Ordering::AcqRel) == 0 {
+        unsafe { set_updates_enabled(false) };
+    }
+    let _resume = Resume;
+    f()
+}
+
+/// Pauses or resumes style refreshes and invalidation. Resuming refreshes the
+/// styles and layout of every display's screens and layers, and invalidates
+/// them, as the changes made while paused were not.
+unsafe fn set_updates_enabled(enabled: bool) {
+    lvgl_sys::lv_obj_enable_style_refresh(enabled);
+    let mut disp = lvgl_sys::lv_disp_get_next(ptr::null_mut());
+    while !disp.is_null() {
+        lvgl_sys::lv_disp_enable_invalidation(disp, enabled);
+        if enabled {
+            for screen in [
+                lvgl_sys::lv_disp_get_scr_act(disp),
+                lvgl_sys::lv_disp_get_layer_top(disp),
+                lvgl_sys::lv_disp_get_layer_sys(disp),
+            ] {
+                lvgl_sys::lv_obj_refresh_style(
+                    screen,
+                    lvgl_sys::LV_PART_ANY,
+                    lvgl_sys::lv_style_prop_t_LV_STYLE_PROP_ANY,
+                );
+                lvgl_sys::lv_obj_update_layout(screen);
+                lvgl_sys::lv_obj_invalidate(screen);
+            }
+        }
+        disp = lvgl_sys::lv_disp_get_next(disp);
+    }
 }
 
 /// Directly send an event to a specific widget.
@@ -80,6 +179,7 @@ pub fn event_send<W: for<'a> Widget<'a>>(
 
 /// Register an input device driver to LVGL.
 pub(crate) fn indev_drv_register<D>(input_device: &mut impl InputDriver<D>) -> LvResult<()> {
+    crate::check_init()?;
     unsafe {
         let descr = lvgl_sys::lv_indev_drv_register(input_device.get_driver() as *mut _);
         if descr.is_null() {
@@ -89,3 +189,98 @@ pub(crate) fn indev_drv_register<D>(input_device: &mut impl InputDriver<D>) -> L
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{tests, NativeObject};
+
+    #[test]
+    fn init_guards() {
+        tests::initialize_test(false);
+        assert!(crate::is_initialized());
+        assert_eq!(crate::init(), Err(LvError::AlreadyInUse));
+
+        #[cfg(lvgl_deinit)]
+        {
+            unsafe { crate::deinit().unwrap() };
+            assert!(!crate::is_initialized());
+            assert_eq!(task_handler(), Err(LvError::Uninitialized));
+            assert_eq!(Obj::blank().unwrap_err(), LvError::Uninitialized);
+            crate::init().unwrap();
+        }
+    }
+
+    #[cfg(lvgl_deinit)]
+    #[test]
+    fn deinit_drops_widgets() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut obj = Obj::create(&mut screen).unwrap();
+        let state = std::rc::Rc::new(());
+        let captured = state.clone();
+        obj.on_event(move |_, _| drop(captured.clone())).unwrap();
+
+        unsafe { crate::deinit().unwrap() };
+        assert_eq!(std::rc::Rc::strong_count(&state), 1);
+        assert_eq!(unsafe { crate::deinit() }, Err(LvError::Uninitialized));
+
+        tests::initialize_test(true);
+        assert!(crate::display::get_scr_act().is_ok());
+    }
+
+    #[test]
+    fn batched_updates() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let disp = unsafe { lvgl_sys::lv_disp_get_default() };
+        let invalidation = || unsafe { lvgl_sys::lv_disp_is_invalidation_enabled(disp) };
+
+        let obj = with_updates(|| {
+            let mut obj = Obj::create(&mut screen).unwrap();
+            with_updates(|| obj.set_pos(30, 0));
+            assert!(!invalidation());
+            obj
+        });
+        assert!(invalidation());
+        assert_eq!(unsafe { lvgl_sys::lv_obj_get_x(obj.raw().as_ptr()) }, 30);
+    }
+
+    #[test]
+    fn batched_updates_are_drawn() {
+        tests::initialize_test(false);
+        let flushes = std::rc::Rc::new(core::cell::Cell::new(0));
+        let counter = flushes.clone();
+        let display = crate::Display::register(
+            crate::DrawBuffer::<{ 100 * 10 }>::default(),
+            100,
+            60,
+            move |_| counter.set(counter.get() + 1),
+        )
+        .unwrap();
+        let disp = display.disp.as_ptr();
+        unsafe { lvgl_sys::lv_refr_now(disp) };
+        flushes.set(0);
+
+        let mut screen = display.get_scr_act().unwrap();
+        with_updates(|| Obj::create(&mut screen).unwrap());
+        unsafe { lvgl_sys::lv_refr_now(disp) };
+        assert!(flushes.get() > 0);
+    }
+
+    #[test]
+    fn task_handler_is_not_reentrant() {
+        unsafe extern "C" fn nested(timer: *mut lvgl_sys::lv_timer_t) {
+            *((*timer).user_data as *mut Option<LvResult<()>>) = Some(task_handler());
+            lvgl_sys::lv_timer_del(timer);
+        }
+
+        tests::initialize_test(false);
+        let mut result: Option<LvResult<()>> = None;
+        unsafe {
+            lvgl_sys::lv_timer_create(Some(nested), 0, &mut result as *mut _ as *mut _);
+        }
+        task_handler().unwrap();
+        assert_eq!(result, Some(Err(LvError::AlreadyInUse)));
+    }
+}
@@ -0,0 +1,77 @@
+//! Containment of panics in Rust code called from LVGL
+//!
+//! Event handlers, flush and input callbacks, animations and timers are run
+//! by LVGL through `extern "C"` functions, which a panic must not unwind out
+//! of. Each of them runs its Rust code through [`guard()`], which with the
+//! `std` feature catches a panic, reports which callback raised it and aborts.
+//! The report goes to the `log` or `defmt` logger if either feature is
+//! enabled, and to standard error otherwise.
+//! Without `std`, panics cannot be caught; targets without it normally abort
+//! on panic already, which the guard leaves as is.
+
+/// Runs the Rust code of the LVGL callback named `callback`, aborting the
+/// program if it panics.
+#[inline]
+pub(crate) fn guard<R>(callback: &str, f: impl FnOnce() -> R) -> R {
+    #[cfg(any(feature = "std", test))]
+    {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+            Ok(value) => value,
+            Err(_) => {
+                #[cfg(any(feature = "log", feature = "defmt"))]
+                diag!(error, "Panic in LVGL {} callback, aborting", callback);
+                #[cfg(not(any(feature = "log", feature = "defmt")))]
+                std::eprintln!("Panic in LVGL {} callback, aborting", callback);
+                std::process::abort()
+            }
+        }
+    }
+    #[cfg(not(any(feature = "std", test)))]
+    {
+        let _ = callback;
+        f()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn passes_through_result() {
+        let mut calls = 0;
+        assert_eq!(
+            guard("test", || {
+                calls += 1;
+                42
+            }),
+            42
+        );
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn aborts_on_panic() {
+        // Run in a child process of the test binary, which the abort ends
+        if std::env::var_os("LVGL_GUARD_PANIC").is_some() {
+            guard("test", || panic!("raised in a callback"));
+            return;
+        }
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "guard::test::aborts_on_panic", "--nocapture"])
+            .env("LVGL_GUARD_PANIC", "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            assert_eq!(output.status.signal(), Some(6));
+        }
+        #[cfg(not(any(feature = "log", feature = "defmt")))]
+        {
+            let stderr = std::string::String::from_utf8_lossy(&output.stderr);
+            assert_eq!(stderr.matches("Panic in LVGL test callback").count(), 1);
+        }
+    }
+}
@@ -0,0 +1,428 @@
+//! Translations of UI text
+//!
+//! A [`Catalog`] holds the translations of one language, keyed by strings
+//! that are shown as they are when there is no translation. Catalogs are
+//! tables in flash, or loaded from files with the `alloc` feature. The
+//! [`tr!`](crate::tr!) macro makes a [`Text`] that is formatted in the
+//! current language, and labels bound to a text are updated when the
+//! language changes:
+//!
+//! ```ignore
+//! use lvgl::i18n::{self, plural, Catalog};
+//!
+//! static DE: Catalog = Catalog::new("de", plural::english, &[
+//!     ("Settings", &["Einstellungen"]),
+//!     ("{} new messages", &["{} neue Nachricht", "{} neue Nachrichten"]),
+//! ]);
+//!
+//! let mut title = Label::create(&mut screen)?;
+//! i18n::bind(&mut title, lvgl::tr!("Settings"));
+//! let mut inbox = Label::create(&mut screen)?;
+//! i18n::bind(&mut inbox, lvgl::tr!("{} new messages", unread));
+//!
+//! // Updates both labels
+//! i18n::set_locale(&DE);
+//! ```
+//!
+//! Plural texts have several forms, of which the catalog's [`PluralRule`]
+//! picks one for the count, and `{}` in them is replaced by the count.
+
+use crate::guard::guard;
+use crate::widgets::Label;
+use crate::{Box, NativeObject, Widget};
+#[cfg(feature = "alloc")]
+use crate::{LvError, LvResult};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(feature = "alloc")]
+use cstr_core::CStr;
+
+/// Picks which form of a plural text to use for a count.
+pub type PluralRule = fn(u32) -> usize;
+
+/// Plural rules for common languages. Each returns the index of a form in
+/// the order the forms are listed.
+pub mod plural {
+    /// A single form, for e.g. Chinese, Japanese and Korean.
+    pub fn none(_: u32) -> usize {
+        0
+    }
+
+    /// A singular form for 1, and a plural form otherwise, for e.g. English,
+    /// German, Dutch, Spanish and Italian.
+    pub fn english(n: u32) -> usize {
+        usize::from(n != 1)
+    }
+
+    /// A singular form for 0 and 1, and a plural form otherwise, for e.g.
+    /// French and Brazilian Portuguese.
+    pub fn french(n: u32) -> usize {
+        usize::from(n > 1)
+    }
+
+    /// Forms for 1, 21, 31, ...; for 2 to 4, 22 to 24, ...; and for the rest,
+    /// for e.g. Russian and Ukrainian.
+    pub fn east_slavic(n: u32) -> usize {
+        if n % 10 == 1 && n % 100 != 11 {
+            0
+        } else if (2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100)) {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+enum Entries {
+    Static(&'static [(&'static str, &'static [&'static str])]),
+    #[cfg(feature = "alloc")]
+    Loaded(Vec<(String, Vec<String>)>),
+}
+
+/// The translations of one language.
+pub struct Catalog {
+    locale: &'static str,
+    plural: PluralRule,
+    entries: Entries,
+}
+
+impl Catalog {
+    /// Creates a catalog from a table of keys and their translations, which
+    /// are a single form, or one form per plural form of the language.
+    pub const fn new(
+        locale: &'static str,
+        plural: PluralRule,
+        entries: &'static [(&'static str, &'static [&'static str])],
+    ) -> Self {
+        Self {
+            locale,
+            plural,
+            entries: Entries::Static(entries),
+        }
+    }
+
+    /// Parses a catalog from lines of `key = translation`, where the plural
+    /// forms of a translation are separated by `|`. Blank lines and lines
+    /// starting with `#` are skipped:
+    ///
+    /// ```text
+    /// # German
+    /// Settings = Einstellungen
+    /// {} new messages = {} neue Nachricht|{} neue Nachrichten
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn parse(locale: &'static str, plural: PluralRule, text: &str) -> Self {
+        let entries = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, forms)| {
+                let forms = forms.split('|').map(|form| form.trim().into()).collect();
+                (key.trim().into(), forms)
+            })
+            .collect();
+        Self {
+            locale,
+            plural,
+            entries: Entries::Loaded(entries),
+        }
+    }
+
+    /// Loads a catalog in the format of `parse()` from a file, through
+    /// LVGL's file system drivers, e.g. `S:/lang/de.txt`. Fails if the file
+    /// cannot be read or is not UTF-8.
+    ///
+    /// `set_locale()` takes a catalog that lives forever; keep a loaded one
+    /// in a static, or leak it with `Box::leak()`.
+    #[cfg(feature = "alloc")]
+    pub fn load(locale: &'static str, plural: PluralRule, path: &CStr) -> LvResult<Self> {
+        let mut text = Vec::new();
+        unsafe {
+            let mut file = core::mem::MaybeUninit::<lvgl_sys::lv_fs_file_t>::uninit();
+            let res = lvgl_sys::lv_fs_open(
+                file.as_mut_ptr(),
+                path.as_ptr(),
+                lvgl_sys::LV_FS_MODE_RD as _,
+            );
+            if u32::from(res) != lvgl_sys::LV_FS_RES_OK {
+                return Err(LvError::InvalidReference);
+            }
+            let mut file = file.assume_init();
+            let mut buf = [0u8; 256];
+            let res = loop {
+                let mut read = 0;
+                let res = lvgl_sys::lv_fs_read(
+                    &mut file,
+                    buf.as_mut_ptr() as *mut _,
+                    buf.len() as u32,
+                    &mut read,
+                );
+                if u32::from(res) != lvgl_sys::LV_FS_RES_OK || read == 0 {
+                    break res;
+                }
+                text.extend_from_slice(&buf[..read as usize]);
+            };
+            lvgl_sys::lv_fs_close(&mut file);
+            if u32::from(res) != lvgl_sys::LV_FS_RES_OK {
+                return Err(LvError::InvalidReference);
+            }
+        }
+        let text = core::str::from_utf8(&text).map_err(|_| LvError::InvalidReference)?;
+        Ok(Self::parse(locale, plural, text))
+    }
+
+    /// Returns the name of the catalog's language, e.g. `"de"`.
+    pub fn locale(&self) -> &'static str {
+        self.locale
+    }
+
+    /// Returns the translation of `key`, in the plural form for `count` if
+    /// one is given, or `None` if the catalog does not translate it.
+    pub fn get(&self, key: &str, count: Option<u32>) -> Option<&str> {
+        match &self.entries {
+            Entries::Static(entries) => {
+                let (_, forms) = entries.iter().find(|(k, _)| *k == key)?;
+                self.pick(*forms, count)
+            }
+            #[cfg(feature = "alloc")]
+            Entries::Loaded(entries) => {
+                let (_, forms) = entries.iter().find(|(k, _)| k == key)?;
+                self.pick(forms.as_slice(), count)
+            }
+        }
+    }
+
+    fn pick<'s, S: AsRef<str>>(&self, forms: &'s [S], count: Option<u32>) -> Option<&'s str> {
+        let index = count.map_or(0, self.plural);
+        // Catalogs with fewer forms than the rule expects use the last one
+        let form = forms.get(index).or_else(|| forms.last())?;
+        Some(form.as_ref())
+    }
+}
+
+static CURRENT: AtomicPtr<Catalog> = AtomicPtr::new(ptr::null_mut());
+
+/// Switches to the language of `catalog`, updating every bound label.
+pub fn set_locale(catalog: &'static Catalog) {
+    CURRENT.store(catalog as *const _ as *mut _, Ordering::Relaxed);
+    let mut binding = BINDINGS.load(Ordering::Relaxed);
+    while let Some(node) = unsafe { binding.as_ref() } {
+        if let Some(mut label) = unsafe { Label::from_raw(NonNull::new_unchecked(node.label)) } {
+            render(&mut label, node.text);
+        }
+        binding = node.next;
+    }
+}
+
+/// Returns the catalog of the current language, if `set_locale()` was
+/// called.
+pub fn locale() -> Option<&'static Catalog> {
+    unsafe { CURRENT.load(Ordering::Relaxed).as_ref() }
+}
+
+/// A text to translate, formatted in the current language. Created with
+/// [`tr!`](crate::tr!).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text {
+    key: &'static str,
+    count: Option<u32>,
+}
+
+impl Text {
+    /// A text translated as a whole.
+    pub const fn new(key: &'static str) -> Self {
+        Self { key, count: None }
+    }
+
+    /// A text with plural forms, where `{}` is replaced by `count`.
+    pub const fn plural(key: &'static str, count: u32) -> Self {
+        Self {
+            key,
+            count: Some(count),
+        }
+    }
+}
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = locale()
+            .and_then(|catalog| catalog.get(self.key, self.count))
+            .unwrap_or(self.key);
+        let count = match self.count {
+            Some(count) => count,
+            None => return f.write_str(text),
+        };
+        let mut parts = text.split("{}");
+        f.write_str(parts.next().unwrap_or_default())?;
+        for part in parts {
+            write!(f, "{}{}", count, part)?;
+        }
+        Ok(())
+    }
+}
+
+/// Makes a [`Text`] to translate: `tr!("key")`, or `tr!("key", count)` for a
+/// text with plural forms. See the [`i18n`](crate::i18n) module.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::Text::new($key)
+    };
+    ($key:expr, $count:expr) => {
+        $crate::i18n::Text::plural($key, $count)
+    };
+}
+
+/// A bound label, in a list in LVGL memory.
+struct Binding {
+    label: *mut lvgl_sys::lv_obj_t,
+    text: Text,
+    next: *mut Binding,
+}
+
+static BINDINGS: AtomicPtr<Binding> = AtomicPtr::new(ptr::null_mut());
+
+/// Shows `text` on `label` in the current language, and again whenever the
+/// language changes, until the label is deleted or unbound. Binding a label
+/// again replaces its text.
+pub fn bind(label: &mut Label<'_>, text: Text) {
+    unsafe {
+        let raw = label.raw().as_ptr();
+        let binding = lvgl_sys::lv_obj_get_event_user_data(raw, Some(binding_callback));
+        if let Some(binding) = (binding as *mut Binding).as_mut() {
+            binding.text = text;
+        } else {
+            let binding = Box::into_raw(Box::new(Binding {
+                label: raw,
+                text,
+                next: BINDINGS.load(Ordering::Relaxed),
+            }));
+            BINDINGS.store(binding, Ordering::Relaxed);
+            lvgl_sys::lv_obj_add_event_cb(
+                raw,
+                Some(binding_callback),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                binding as *mut _,
+            );
+        }
+    }
+    render(label, text);
+}
+
+/// Stops updating `label` when the language changes. Its text is kept.
+pub fn unbind(label: &mut Label<'_>) {
+    unsafe {
+        let raw = label.raw().as_ptr();
+        let binding = lvgl_sys::lv_obj_get_event_user_data(raw, Some(binding_callback));
+        if !binding.is_null() {
+            lvgl_sys::lv_obj_remove_event_cb(raw, Some(binding_callback));
+            unlink(binding as *mut Binding);
+        }
+    }
+}
+
+fn render(label: &mut Label<'_>, text: Text) {
+    // Overly long translations are truncated
+    let _ = label.set_text_fmt(format_args!("{}", text));
+}
+
+/// Removes a binding from the list and frees it.
+unsafe fn unlink(binding: *mut Binding) {
+    let mut node = BINDINGS.load(Ordering::Relaxed);
+    if node == binding {
+        BINDINGS.store((*binding).next, Ordering::Relaxed);
+    }
+    while !node.is_null() {
+        if (*node).next == binding {
+            (*node).next = (*binding).next;
+            break;
+        }
+        node = (*node).next;
+    }
+    drop(Box::from_raw(binding));
+}
+
+unsafe extern "C" fn binding_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("i18n label", || unlink((*event).user_data as *mut Binding))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Label;
+
+    static EN: Catalog = Catalog::new(
+        "en",
+        plural::english,
+        &[("{} files", &["{} file", "{} files"])],
+    );
+
+    static DE: Catalog = Catalog::new(
+        "de",
+        plural::english,
+        &[
+            ("Settings", &["Einstellungen"]),
+            ("{} files", &["{} Datei", "{} Dateien"]),
+        ],
+    );
+
+    fn text(label: &Label) -> &str {
+        unsafe {
+            let text = lvgl_sys::lv_label_get_text(label.raw().as_ptr());
+            cstr_core::CStr::from_ptr(text).to_str().unwrap()
+        }
+    }
+
+    #[test]
+    fn plural_rules() {
+        let forms: std::vec::Vec<_> = [0, 1, 2, 5, 11, 21, 22, 112]
+            .into_iter()
+            .map(plural::east_slavic)
+            .collect();
+        assert_eq!(forms, [2, 0, 1, 2, 2, 0, 1, 2]);
+        assert_eq!((plural::french(0), plural::english(0)), (0, 1));
+    }
+
+    #[test]
+    fn switch_locale() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut title = Label::create(&mut screen).unwrap();
+        let mut count = Label::create(&mut screen).unwrap();
+        let mut deleted = Label::create(&mut screen).unwrap();
+
+        set_locale(&EN);
+        assert_eq!(locale().unwrap().locale(), "en");
+        bind(&mut title, tr!("Settings"));
+        bind(&mut count, tr!("{} files", 1));
+        bind(&mut deleted, tr!("Settings"));
+        assert_eq!((text(&title), text(&count)), ("Settings", "1 file"));
+        deleted.delete();
+
+        set_locale(&DE);
+        assert_eq!((text(&title), text(&count)), ("Einstellungen", "1 Datei"));
+        bind(&mut count, tr!("{} files", 3));
+        assert_eq!(text(&count), "3 Dateien");
+
+        unbind(&mut title);
+        set_locale(&EN);
+        assert_eq!((text(&title), text(&count)), ("Einstellungen", "3 files"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn parse_catalog() {
+        let catalog = Catalog::parse(
+            "ru",
+            plural::east_slavic,
+            "# Russian\n\nSettings = Настройки\n{} files = {} файл|{} файла|{} файлов\n",
+        );
+        assert_eq!(catalog.get("Settings", None), Some("Настройки"));
+        assert_eq!(catalog.get("{} files", Some(3)), Some("{} файла"));
+        assert_eq!(catalog.get("Help", None), None);
+    }
+}
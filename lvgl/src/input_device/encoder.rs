@@ -1,4 +1,5 @@
 use super::{BufferStatus, Data, InputDriver, InputState};
+use crate::guard::guard;
 use crate::Box;
 use crate::{LvError, LvResult};
 use core::mem::MaybeUninit;
@@ -110,62 +111,64 @@ unsafe extern "C" fn read_input<F>(
 ) where
     F: Fn() -> BufferStatus,
 {
-    // convert user data to function
-    let user_closure = &mut *((*indev_drv).user_data as *mut F);
-    // call user data
-    let info = user_closure();
-    unsafe {
-        (*data).continue_reading = match info {
-            BufferStatus::Once(b) => {
-                (*data).state = match b {
-                    InputState::Pressed(Data::Encoder(d)) => {
-                        (*data).key = match d {
-                            EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
-                            EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
-                        };
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
-                    }
-                    InputState::Released(Data::Encoder(d)) => {
-                        (*data).key = match d {
-                            EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
-                            EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
-                        };
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
-                    }
-                    _ => panic!("Non-encoder data returned from encoder device!"),
-                };
-                false
-            }
-            BufferStatus::Buffered(b) => {
-                (*data).state = match b {
-                    InputState::Pressed(Data::Encoder(d)) => {
-                        (*data).key = match d {
-                            EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
-                            EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
-                        };
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
-                    }
-                    InputState::Released(Data::Encoder(d)) => {
-                        (*data).key = match d {
-                            EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
-                            EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
-                            EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
-                        };
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
-                    }
-                    _ => panic!("Non-encoder data returned from encoder device!"),
-                };
-                true
+    guard("input device read", || {
+        // convert user data to function
+        let user_closure = &mut *((*indev_drv).user_data as *mut F);
+        // call user data
+        let info = user_closure();
+        unsafe {
+            (*data).continue_reading = match info {
+                BufferStatus::Once(b) => {
+                    (*data).state = match b {
+                        InputState::Pressed(Data::Encoder(d)) => {
+                            (*data).key = match d {
+                                EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
+                                EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
+                            };
+                            lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
+                        }
+                        InputState::Released(Data::Encoder(d)) => {
+                            (*data).key = match d {
+                                EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
+                                EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
+                            };
+                            lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
+                        }
+                        _ => panic!("Non-encoder data returned from encoder device!"),
+                    };
+                    false
+                }
+                BufferStatus::Buffered(b) => {
+                    (*data).state = match b {
+                        InputState::Pressed(Data::Encoder(d)) => {
+                            (*data).key = match d {
+                                EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
+                                EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
+                            };
+                            lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
+                        }
+                        InputState::Released(Data::Encoder(d)) => {
+                            (*data).key = match d {
+                                EncoderInputData::Press => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::LongPress => lvgl_sys::LV_KEY_ENTER,
+                                EncoderInputData::TurnLeft => lvgl_sys::LV_KEY_LEFT,
+                                EncoderInputData::TurnRight => lvgl_sys::LV_KEY_RIGHT,
+                            };
+                            lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
+                        }
+                        _ => panic!("Non-encoder data returned from encoder device!"),
+                    };
+                    true
+                }
             }
         }
-    }
+    })
 }
 
 unsafe extern "C" fn feedback(_indev_drv: *mut lvgl_sys::lv_indev_drv_t, _code: u8) {}
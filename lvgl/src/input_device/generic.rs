@@ -1,4 +1,5 @@
 use super::encoder::*;
+use super::keypad::*;
 use super::pointer::*;
 use crate::LvResult;
 
@@ -10,6 +11,8 @@ pub enum Data {
     Pointer(PointerInputData),
     /// Encoder-specific data.
     Encoder(EncoderInputData),
+    /// Keypad-specific data.
+    Keypad(KeypadInputData),
 }
 
 /// Boolean states for an input.
@@ -0,0 +1,154 @@
+use super::{BufferStatus, Data, InputDriver, InputState};
+use crate::guard::guard;
+use crate::Box;
+use crate::{LvError, LvResult};
+use core::mem::MaybeUninit;
+
+/// Keypad-specific input data. Contains the key, which is either an ASCII
+/// character or one of LVGL's control keys (`lvgl_sys::LV_KEY_*`).
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct KeypadInputData(pub u32);
+
+impl KeypadInputData {
+    pub fn pressed(self) -> InputState {
+        InputState::Pressed(Data::Keypad(self))
+    }
+
+    pub fn released(self) -> InputState {
+        InputState::Released(Data::Keypad(self))
+    }
+}
+
+/// Represents a keypad-type input driver, such as a keyboard.
+pub struct Keypad {
+    pub(crate) driver: Box<lvgl_sys::lv_indev_drv_t>,
+    pub(crate) descriptor: Option<*mut lvgl_sys::lv_indev_t>,
+}
+
+impl InputDriver<Keypad> for Keypad {
+    fn register<F>(handler: F, _: &crate::Display) -> LvResult<Keypad>
+    where
+        F: Fn() -> BufferStatus,
+    {
+        let driver = unsafe {
+            let mut indev_drv = MaybeUninit::uninit();
+            lvgl_sys::lv_indev_drv_init(indev_drv.as_mut_ptr());
+            let mut indev_drv = Box::new(indev_drv.assume_init());
+            indev_drv.type_ = lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_KEYPAD;
+            indev_drv.read_cb = Some(read_input::<F>);
+            indev_drv.feedback_cb = Some(feedback);
+            indev_drv.user_data = Box::into_raw(Box::new(handler)) as *mut _;
+            indev_drv
+        };
+
+        let mut dev = Self {
+            driver,
+            descriptor: None,
+        };
+
+        match crate::indev_drv_register(&mut dev) {
+            Ok(()) => Ok(dev),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn get_driver(&mut self) -> &mut lvgl_sys::lv_indev_drv_t {
+        self.driver.as_mut()
+    }
+
+    fn get_descriptor(&mut self) -> Option<&mut lvgl_sys::lv_indev_t> {
+        match self.descriptor {
+            Some(d) => unsafe { d.as_mut() },
+            None => None,
+        }
+    }
+
+    unsafe fn new_raw(
+        read_cb: Option<
+            unsafe extern "C" fn(*mut lvgl_sys::lv_indev_drv_t, *mut lvgl_sys::lv_indev_data_t),
+        >,
+        feedback_cb: Option<unsafe extern "C" fn(*mut lvgl_sys::lv_indev_drv_t, u8)>,
+        _: &crate::Display,
+    ) -> LvResult<Keypad> {
+        let driver = unsafe {
+            let mut indev_drv = MaybeUninit::uninit();
+            lvgl_sys::lv_indev_drv_init(indev_drv.as_mut_ptr());
+            let mut indev_drv = Box::new(indev_drv.assume_init());
+            indev_drv.type_ = lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_KEYPAD;
+            indev_drv.read_cb = read_cb;
+            indev_drv.feedback_cb = feedback_cb;
+            indev_drv
+        };
+
+        let mut dev = Self {
+            driver,
+            descriptor: None,
+        };
+
+        match crate::indev_drv_register(&mut dev) {
+            Ok(()) => Ok(dev),
+            Err(e) => Err(e),
+        }
+    }
+
+    unsafe fn set_descriptor(&mut self, descriptor: *mut lvgl_sys::lv_indev_t) -> LvResult<()> {
+        if self.descriptor.is_none() {
+            self.descriptor = Some(descriptor);
+        } else {
+            return Err(LvError::AlreadyInUse);
+        }
+        Ok(())
+    }
+}
+
+unsafe extern "C" fn read_input<F>(
+    indev_drv: *mut lvgl_sys::lv_indev_drv_t,
+    data: *mut lvgl_sys::lv_indev_data_t,
+) where
+    F: Fn() -> BufferStatus,
+{
+    guard("input device read", || {
+        // convert user data to function
+        let user_closure = &mut *((*indev_drv).user_data as *mut F);
+        // call user data
+        let info = user_closure();
+        let (state, continue_reading) = match info {
+            BufferStatus::Once(s) => (s, false),
+            BufferStatus::Buffered(s) => (s, true),
+        };
+        unsafe {
+            (*data).state = match state {
+                InputState::Pressed(Data::Keypad(KeypadInputData(key))) => {
+                    (*data).key = key;
+                    lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
+                }
+                InputState::Released(Data::Keypad(KeypadInputData(key))) => {
+                    (*data).key = key;
+                    lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
+                }
+                _ => panic!("Non-keypad data returned from keypad device!"),
+            };
+            (*data).continue_reading = continue_reading;
+        }
+    })
+}
+
+unsafe extern "C" fn feedback(_indev_drv: *mut lvgl_sys::lv_indev_drv_t, _code: u8) {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn keypad_input_device() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+
+        fn read_keypad_device() -> BufferStatus {
+            KeypadInputData(lvgl_sys::LV_KEY_ENTER).pressed().once()
+        }
+
+        let _keypad = Keypad::register(read_keypad_device, &display).unwrap();
+    }
+}
@@ -3,7 +3,7 @@
 //! LVGL supports 4 types of input device. The current status as to support in
 //! this library is:
 //! - Pointer: Fully supported
-//! - Keyboard: Unsupported
+//! - Keyboard: Supported as a keypad
 //! - Button: Unsupported
 //! - Encoder: Unsupported
 //!
@@ -20,7 +20,7 @@
 //! fn main() {
 //!     // IMPORTANT: Initialize a display driver first!
 //!     // ...
-//!     // Define the initial state of your input//! 
+//!     // Define the initial state of your input//!
 //!     let mut latest_touch_status = PointerInputData::Touch(Point::new(0, 0)).released().once();
 //!     // Register a new input device that's capable of reading the current state of the input
 //!     let pointer = Pointer::register(|| latest_touch_status, &display).unwrap();
@@ -33,4 +33,7 @@ mod generic;
 pub use generic::*;
 
 pub mod encoder;
+pub mod keypad;
 pub mod pointer;
+#[cfg(feature = "terminal")]
+pub mod terminal;
@@ -1,13 +1,22 @@
 use super::{BufferStatus, Data, InputDriver, InputState};
+use crate::guard::guard;
 use crate::Box;
 use crate::Point;
 use crate::{LvError, LvResult};
+use core::cell::Cell;
 use core::mem::MaybeUninit;
 
+/// The most touch points `TouchPoints` holds.
+pub const MAX_TOUCH_POINTS: usize = 5;
+
 /// Pointer-specific input data. Contains the point clicked and the key.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum PointerInputData {
     Touch(Point),
+    /// Every point touched on a multi-touch screen. LVGL itself only follows
+    /// the first; all of them can be read back with `Pointer::touches()` or
+    /// `active_touches()`, e.g. to pinch-to-zoom.
+    MultiTouch(TouchPoints),
     Key(u32),
 }
 
@@ -21,12 +30,189 @@ impl PointerInputData {
     }
 }
 
+/// The points touched at once on a multi-touch screen, up to
+/// `MAX_TOUCH_POINTS`.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TouchPoints {
+    points: [Point; MAX_TOUCH_POINTS],
+    len: u8,
+}
+
+impl Default for TouchPoints {
+    fn default() -> Self {
+        Self {
+            points: [Point::new(0, 0); MAX_TOUCH_POINTS],
+            len: 0,
+        }
+    }
+}
+
+impl TouchPoints {
+    /// Collects the first `MAX_TOUCH_POINTS` of `points`.
+    pub fn new(points: &[Point]) -> Self {
+        let mut touches = Self::default();
+        for (slot, point) in touches.points.iter_mut().zip(points) {
+            *slot = *point;
+            touches.len += 1;
+        }
+        touches
+    }
+
+    /// Returns the touched points.
+    pub fn as_slice(&self) -> &[Point] {
+        &self.points[..self.len as usize]
+    }
+
+    /// Returns the distance in pixels between the first two points, or `None`
+    /// with fewer than two. Comparing it between reads gives the zoom of a
+    /// pinch.
+    pub fn spread(&self) -> Option<u32> {
+        match self.as_slice() {
+            [a, b, ..] => {
+                let (dx, dy) = (a.x.abs_diff(b.x) as u64, a.y.abs_diff(b.y) as u64);
+                let square = (dx * dx).saturating_add(dy * dy);
+                Some(isqrt(square).try_into().unwrap_or(u32::MAX))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the square root of `n`, rounded down.
+fn isqrt(n: u64) -> u64 {
+    let mut rest = n;
+    let mut root = 0;
+    let mut bit = 1 << (u64::BITS - 2);
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rest >= root + bit {
+            rest -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+impl core::ops::Deref for TouchPoints {
+    type Target = [Point];
+
+    fn deref(&self) -> &[Point] {
+        self.as_slice()
+    }
+}
+
+/// The read handler of a `Pointer`, after the points it last reported as
+/// touched, which are first so they can be read without knowing `F`.
+#[repr(C)]
+struct Handler<F> {
+    touches: Cell<TouchPoints>,
+    read: F,
+}
+
+/// Returns the points touched on the pointer LVGL is processing, e.g. from an
+/// event handler. LVGL only follows the first point; the others are reported
+/// by `PointerInputData::MultiTouch`. Pointers not registered with
+/// `Pointer::register()`, e.g. with `InputDriver::new_raw()`, report none.
+pub fn active_touches() -> TouchPoints {
+    unsafe { indev_touches(lvgl_sys::lv_indev_get_act()) }
+}
+
+unsafe fn indev_touches(indev: *mut lvgl_sys::lv_indev_t) -> TouchPoints {
+    let Some(indev) = indev.as_ref() else {
+        return TouchPoints::default();
+    };
+    let driver = &*indev.driver;
+    // Only drivers registered by `register()` have a `Handler` as user data,
+    // and they are told apart by their feedback callback, which unlike the
+    // read callback does not depend on the handler's type
+    let registered = matches!(driver.feedback_cb, Some(cb) if cb as usize == feedback as usize);
+    if !registered || driver.type_ != lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_POINTER {
+        return TouchPoints::default();
+    }
+    let touches = driver.user_data as *const Cell<TouchPoints>;
+    touches.as_ref().map(Cell::get).unwrap_or_default()
+}
+
 /// Represents a pointer-type input driver.
 pub struct Pointer {
     pub(crate) driver: Box<lvgl_sys::lv_indev_drv_t>,
     pub(crate) descriptor: Option<*mut lvgl_sys::lv_indev_t>,
 }
 
+impl Pointer {
+    /// Returns the points last read as touched, or none once released.
+    pub fn touches(&self) -> TouchPoints {
+        match self.descriptor {
+            Some(indev) => unsafe { indev_touches(indev) },
+            None => TouchPoints::default(),
+        }
+    }
+
+    /// Scrolls the widget under the pointer by `delta` pixels, as a mouse
+    /// wheel or touchpad would. A positive `y` moves the content down,
+    /// revealing what is above it, as turning a wheel away from the user does,
+    /// and a positive `x` moves it right.
+    ///
+    /// The innermost scrollable widget that can still scroll in that direction
+    /// is scrolled, without going past its content. Returns whether a widget
+    /// was scrolled. LVGL's pointers have no wheel of their own, so desktop
+    /// backends call this on wheel events.
+    pub fn scroll_wheel(&mut self, delta: Point) -> bool {
+        let indev = match self.descriptor {
+            Some(indev) => indev,
+            None => return false,
+        };
+        unsafe {
+            let mut point = lvgl_sys::lv_point_t { x: 0, y: 0 };
+            lvgl_sys::lv_indev_get_point(indev, &mut point);
+            let screen = lvgl_sys::lv_disp_get_scr_act((*(*indev).driver).disp);
+            let mut obj = lvgl_sys::lv_indev_search_obj(screen, &mut point);
+            while !obj.is_null() {
+                if can_scroll(obj, delta) {
+                    lvgl_sys::lv_obj_scroll_by_bounded(
+                        obj,
+                        delta.x as lvgl_sys::lv_coord_t,
+                        delta.y as lvgl_sys::lv_coord_t,
+                        lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+                    );
+                    return true;
+                }
+                obj = lvgl_sys::lv_obj_get_parent(obj);
+            }
+        }
+        false
+    }
+}
+
+/// Tests if `obj` is scrollable and has content left to scroll to in the
+/// direction of `delta`.
+unsafe fn can_scroll(obj: *mut lvgl_sys::lv_obj_t, delta: Point) -> bool {
+    if !lvgl_sys::lv_obj_has_flag(obj, lvgl_sys::LV_OBJ_FLAG_SCROLLABLE as _) {
+        return false;
+    }
+    let dir = lvgl_sys::lv_obj_get_scroll_dir(obj) as u32;
+    let vertical = match delta.y {
+        y if y > 0 => dir & lvgl_sys::LV_DIR_TOP != 0 && lvgl_sys::lv_obj_get_scroll_top(obj) > 0,
+        y if y < 0 => {
+            dir & lvgl_sys::LV_DIR_BOTTOM != 0 && lvgl_sys::lv_obj_get_scroll_bottom(obj) > 0
+        }
+        _ => false,
+    };
+    let horizontal = match delta.x {
+        x if x > 0 => dir & lvgl_sys::LV_DIR_LEFT != 0 && lvgl_sys::lv_obj_get_scroll_left(obj) > 0,
+        x if x < 0 => {
+            dir & lvgl_sys::LV_DIR_RIGHT != 0 && lvgl_sys::lv_obj_get_scroll_right(obj) > 0
+        }
+        _ => false,
+    };
+    vertical || horizontal
+}
+
 impl InputDriver<Pointer> for Pointer {
     fn register<F>(handler: F, _: &crate::Display) -> LvResult<Self>
     where
@@ -39,7 +225,10 @@ impl InputDriver<Pointer> for Pointer {
             indev_drv.type_ = lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_POINTER;
             indev_drv.read_cb = Some(read_input::<F>);
             indev_drv.feedback_cb = Some(feedback);
-            indev_drv.user_data = Box::into_raw(Box::new(handler)) as *mut _;
+            indev_drv.user_data = Box::into_raw(Box::new(Handler {
+                touches: Cell::new(TouchPoints::default()),
+                read: handler,
+            })) as *mut _;
             indev_drv
         };
 
@@ -109,70 +298,57 @@ unsafe extern "C" fn read_input<F>(
 ) where
     F: Fn() -> BufferStatus,
 {
-    // convert user data to function
-    let user_closure = &mut *((*indev_drv).user_data as *mut F);
-    // call user data
-    let info = user_closure();
-    unsafe {
-        (*data).continue_reading = match info {
-            BufferStatus::Once(b) => {
-                (*data).state = match b {
-                    InputState::Pressed(d) => {
-                        match d {
-                            Data::Pointer(PointerInputData::Touch(point)) => {
-                                (*data).point.x = point.x as lvgl_sys::lv_coord_t;
-                                (*data).point.y = point.y as lvgl_sys::lv_coord_t;
-                            }
-                            Data::Pointer(PointerInputData::Key(_)) => {}
-                            _ => panic!("Non-pointer data returned from pointer device!"),
-                        }
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
-                    }
-                    InputState::Released(d) => {
-                        match d {
-                            Data::Pointer(PointerInputData::Touch(point)) => {
-                                (*data).point.x = point.x as lvgl_sys::lv_coord_t;
-                                (*data).point.y = point.y as lvgl_sys::lv_coord_t;
-                            }
-                            Data::Pointer(PointerInputData::Key(_)) => {}
-                            _ => panic!("Non-pointer data returned from pointer device!"),
-                        }
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
-                    }
-                };
-                false
+    guard("input device read", || {
+        // convert user data to function
+        let handler = &*((*indev_drv).user_data as *const Handler<F>);
+        // call user data
+        let info = (handler.read)();
+        let (state, continue_reading) = match info {
+            BufferStatus::Once(s) => (s, false),
+            BufferStatus::Buffered(s) => (s, true),
+        };
+        let (pressed, input) = match state {
+            InputState::Pressed(Data::Pointer(input)) => (true, input),
+            InputState::Released(Data::Pointer(input)) => (false, input),
+            _ => panic!("Non-pointer data returned from pointer device!"),
+        };
+        let primary = match input {
+            PointerInputData::Touch(point) => {
+                handler.touches.set(if pressed {
+                    TouchPoints::new(&[point])
+                } else {
+                    TouchPoints::default()
+                });
+                Some(point)
+            }
+            PointerInputData::MultiTouch(points) => {
+                handler.touches.set(if pressed {
+                    points
+                } else {
+                    TouchPoints::default()
+                });
+                points.first().copied()
             }
-            BufferStatus::Buffered(b) => {
-                (*data).state = match b {
-                    InputState::Pressed(d) => {
-                        match d {
-                            Data::Pointer(PointerInputData::Touch(point)) => {
-                                (*data).point.x = point.x as lvgl_sys::lv_coord_t;
-                                (*data).point.y = point.y as lvgl_sys::lv_coord_t;
-                            }
-                            Data::Pointer(PointerInputData::Key(_)) => {}
-                            _ => panic!("Non-pointer data returned from pointer device!"),
-                        }
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
-                    }
-                    InputState::Released(d) => {
-                        match d {
-                            Data::Pointer(PointerInputData::Touch(point)) => {
-                                (*data).point.x = point.x as lvgl_sys::lv_coord_t;
-                                (*data).point.y = point.y as lvgl_sys::lv_coord_t;
-                            }
-                            Data::Pointer(PointerInputData::Key(_)) => {}
-                            _ => panic!("Non-pointer data returned from pointer device!"),
-                        }
-                        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
-                    }
-                };
-                true
+            PointerInputData::Key(_) => None,
+        };
+        unsafe {
+            // Without a point, LVGL keeps the last one
+            if let Some(point) = primary {
+                (*data).point.x = point.x as lvgl_sys::lv_coord_t;
+                (*data).point.y = point.y as lvgl_sys::lv_coord_t;
             }
+            (*data).state = if pressed {
+                lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
+            } else {
+                lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
+            };
+            (*data).continue_reading = continue_reading;
         }
-    }
+    })
 }
 
+/// The feedback callback of pointers registered with `register()`, which
+/// `indev_touches()` identifies them by.
 unsafe extern "C" fn feedback(_indev_drv: *mut lvgl_sys::lv_indev_drv_t, _code: u8) {}
 
 #[cfg(test)]
@@ -230,4 +406,80 @@ mod test {
 
         let _touch_screen = Pointer::register(read_touchpad_device, &display).unwrap();
     }
+
+    #[test]
+    fn read_back_touch_points() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+
+        fn read_pinch() -> BufferStatus {
+            let points = [Point::new(10, 10), Point::new(40, 50)];
+            PointerInputData::MultiTouch(TouchPoints::new(&points))
+                .pressed()
+                .once()
+        }
+        let touch_screen = Pointer::register(read_pinch, &display).unwrap();
+        assert!(touch_screen.touches().is_empty());
+        crate::tick_inc(core::time::Duration::from_millis(50));
+        crate::task_handler().unwrap();
+        assert_eq!(
+            touch_screen.touches().as_slice(),
+            [Point::new(10, 10), Point::new(40, 50)]
+        );
+        assert_eq!(touch_screen.touches().spread(), Some(50));
+        let wide = [Point::new(0, 0), Point::new(30_000, 40_000)];
+        assert_eq!(TouchPoints::new(&wide).spread(), Some(50_000));
+        let widest = [
+            Point::new(i32::MIN, i32::MIN),
+            Point::new(i32::MAX, i32::MAX),
+        ];
+        assert_eq!(TouchPoints::new(&widest).spread(), Some(u32::MAX));
+
+        let many = [Point::new(1, 1); MAX_TOUCH_POINTS + 2];
+        assert_eq!(TouchPoints::new(&many).len(), MAX_TOUCH_POINTS);
+    }
+
+    #[test]
+    fn raw_pointers_report_no_touches() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut raw = unsafe { Pointer::new_raw(None, None, &display) }.unwrap();
+        // Not a `Handler`, so it must not be read
+        let mut other = 0xFFu8;
+        raw.get_driver().user_data = &mut other as *mut u8 as *mut _;
+        assert!(raw.touches().is_empty());
+    }
+
+    #[test]
+    fn scroll_wheel_under_pointer() {
+        use crate::widgets::{Label, List};
+        use crate::{NativeObject, Widget};
+
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut list = List::create(&mut screen).unwrap();
+        list.set_size(100, 100);
+        for _ in 0..20 {
+            let mut item = Label::create(&mut list).unwrap();
+            item.set_text("Item").unwrap();
+        }
+
+        fn read_mouse() -> BufferStatus {
+            PointerInputData::Touch(Point::new(50, 50))
+                .released()
+                .once()
+        }
+        let mut mouse = Pointer::register(read_mouse, &display).unwrap();
+        crate::tick_inc(core::time::Duration::from_millis(50));
+        crate::task_handler().unwrap();
+
+        let scroll_y = || unsafe { lvgl_sys::lv_obj_get_scroll_y(list.raw().as_ptr()) };
+        // Already at the top
+        assert!(!mouse.scroll_wheel(Point::new(0, 20)));
+        assert!(mouse.scroll_wheel(Point::new(0, -20)));
+        assert_eq!(scroll_y(), 20);
+        assert!(mouse.scroll_wheel(Point::new(0, 5)));
+        assert_eq!(scroll_y(), 15);
+    }
 }
@@ -0,0 +1,240 @@
+//! Keyboard input from a terminal, for development without a display
+//!
+//! [`TerminalInput`] reads keys typed in the terminal the program runs in,
+//! through `crossterm`, and feeds them to LVGL as a keypad or an encoder.
+//! Together with the headless display of the `testing` module, this allows
+//! exercising group navigation and key handling in a text-only session, such
+//! as over SSH or in CI:
+//!
+//! ```ignore
+//! use lvgl::input_device::terminal::TerminalInput;
+//!
+//! let mut ui = TestDisplay::new(DrawBuffer::<{ 240 * 24 }>::default(), 240, 240)?;
+//! let mut input = TerminalInput::keypad(&ui.display)?;
+//! group.set_indev(&mut input.device)?;
+//! while input.poll()? {
+//!     lvgl::task_handler()?;
+//!     // ...
+//! }
+//! ```
+//!
+//! The terminal is put in raw mode while a `TerminalInput` exists, so keys
+//! are read as they are typed. `poll()` returns `false` once Ctrl+C is
+//! pressed, as raw mode does not interrupt the program.
+//!
+//! # Building
+//!
+//! Enable the `terminal` feature. It requires `std`.
+
+use super::encoder::{Encoder, EncoderInputData};
+use super::keypad::{Keypad, KeypadInputData};
+use super::{Data, InputDriver, InputState};
+use crate::{Display, LvError};
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::time::Duration;
+use crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::error::Error;
+use std::io;
+
+/// An error that stopped setting up terminal input.
+#[derive(Debug)]
+pub enum TerminalError {
+    /// The terminal could not be put in raw mode.
+    Io(io::Error),
+    /// Registering the input device failed.
+    Lvgl(LvError),
+}
+
+impl fmt::Display for TerminalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerminalError::Io(e) => write!(f, "Terminal error: {}", e),
+            TerminalError::Lvgl(e) => write!(f, "LVGL error: {:?}", e),
+        }
+    }
+}
+
+impl Error for TerminalError {}
+
+impl From<io::Error> for TerminalError {
+    fn from(err: io::Error) -> Self {
+        TerminalError::Io(err)
+    }
+}
+
+impl From<LvError> for TerminalError {
+    fn from(err: LvError) -> Self {
+        TerminalError::Lvgl(err)
+    }
+}
+
+/// An input device fed by the keys typed in the terminal.
+pub struct TerminalInput<D> {
+    /// The registered keypad or encoder. Assign it to a group to control
+    /// widgets with it.
+    pub device: D,
+    map: fn(&KeyEvent) -> Option<Data>,
+    keys: Rc<RefCell<VecDeque<InputState>>>,
+}
+
+impl TerminalInput<Keypad> {
+    /// Registers a keypad typing the characters and control keys pressed in
+    /// the terminal: arrows, Enter, Esc, Backspace, Delete, Home and End, and
+    /// Tab and Shift+Tab to move to the next and previous widget.
+    pub fn keypad(display: &Display) -> Result<Self, TerminalError> {
+        Self::register(display, keypad_key)
+    }
+}
+
+impl TerminalInput<Encoder> {
+    /// Registers an encoder turned by the arrow keys, left and up turning it
+    /// left, and pressed by Enter or Space.
+    pub fn encoder(display: &Display) -> Result<Self, TerminalError> {
+        Self::register(display, encoder_key)
+    }
+}
+
+impl<D: InputDriver<D>> TerminalInput<D> {
+    fn register(
+        display: &Display,
+        map: fn(&KeyEvent) -> Option<Data>,
+    ) -> Result<Self, TerminalError> {
+        let keys = Rc::new(RefCell::new(VecDeque::new()));
+        let read_keys = keys.clone();
+        let last_key = Cell::new(None);
+        let device = D::register(
+            move || {
+                let mut keys = read_keys.borrow_mut();
+                if let Some(key) = keys.pop_front() {
+                    last_key.set(Some(key));
+                }
+                let state = last_key.get().unwrap_or_else(|| released(map));
+                if keys.is_empty() {
+                    state.once()
+                } else {
+                    state.and_continued()
+                }
+            },
+            display,
+        )?;
+        terminal::enable_raw_mode()?;
+        Ok(Self { device, map, keys })
+    }
+}
+
+impl<D> TerminalInput<D> {
+    /// Queues the keys typed since the last call for LVGL to read, without
+    /// waiting for any. Returns `false` once Ctrl+C is pressed.
+    pub fn poll(&mut self) -> io::Result<bool> {
+        while event::poll(Duration::ZERO)? {
+            let key = match event::read()? {
+                event::Event::Key(key) => key,
+                _ => continue,
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(false);
+            }
+            if let Some(data) = (self.map)(&key) {
+                // Terminals only report presses, so each is followed by a release
+                let mut keys = self.keys.borrow_mut();
+                keys.push_back(InputState::Pressed(data));
+                keys.push_back(InputState::Released(data));
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<D> Drop for TerminalInput<D> {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// The state reported before any key is typed: a released key of the
+/// device's kind.
+fn released(map: fn(&KeyEvent) -> Option<Data>) -> InputState {
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    InputState::Released(map(&enter).unwrap_or(Data::Keypad(KeypadInputData(0))))
+}
+
+fn keypad_key(key: &KeyEvent) -> Option<Data> {
+    let key = match key.code {
+        KeyCode::Char(c) => utf8_key(c),
+        KeyCode::Enter => lvgl_sys::LV_KEY_ENTER,
+        KeyCode::Esc => lvgl_sys::LV_KEY_ESC,
+        KeyCode::Backspace => lvgl_sys::LV_KEY_BACKSPACE,
+        KeyCode::Delete => lvgl_sys::LV_KEY_DEL,
+        KeyCode::Up => lvgl_sys::LV_KEY_UP,
+        KeyCode::Down => lvgl_sys::LV_KEY_DOWN,
+        KeyCode::Left => lvgl_sys::LV_KEY_LEFT,
+        KeyCode::Right => lvgl_sys::LV_KEY_RIGHT,
+        KeyCode::Home => lvgl_sys::LV_KEY_HOME,
+        KeyCode::End => lvgl_sys::LV_KEY_END,
+        KeyCode::Tab => lvgl_sys::LV_KEY_NEXT,
+        KeyCode::BackTab => lvgl_sys::LV_KEY_PREV,
+        _ => return None,
+    };
+    Some(Data::Keypad(KeypadInputData(key)))
+}
+
+fn encoder_key(key: &KeyEvent) -> Option<Data> {
+    let data = match key.code {
+        KeyCode::Left | KeyCode::Up => EncoderInputData::TurnLeft,
+        KeyCode::Right | KeyCode::Down => EncoderInputData::TurnRight,
+        KeyCode::Enter | KeyCode::Char(' ') => EncoderInputData::Press,
+        _ => return None,
+    };
+    Some(Data::Encoder(data))
+}
+
+/// Packs a character's UTF-8 bytes into a key, first byte lowest, as LVGL's
+/// `_lv_txt_encoded_conv_wc()` does.
+fn utf8_key(c: char) -> u32 {
+    let mut bytes = [0; 4];
+    c.encode_utf8(&mut bytes);
+    u32::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keypad(code: KeyCode) -> Option<Data> {
+        keypad_key(&KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn map_terminal_keys() {
+        assert_eq!(
+            keypad(KeyCode::Char('a')),
+            Some(Data::Keypad(KeypadInputData('a' as u32)))
+        );
+        assert_eq!(
+            keypad(KeyCode::Char('é')),
+            Some(Data::Keypad(KeypadInputData(0xA9C3)))
+        );
+        assert_eq!(
+            keypad(KeyCode::BackTab),
+            Some(Data::Keypad(KeypadInputData(lvgl_sys::LV_KEY_PREV)))
+        );
+        assert_eq!(keypad(KeyCode::F(1)), None);
+
+        let up = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(
+            encoder_key(&up),
+            Some(Data::Encoder(EncoderInputData::TurnLeft))
+        );
+        assert!(matches!(
+            released(encoder_key),
+            InputState::Released(Data::Encoder(EncoderInputData::Press))
+        ));
+    }
+}
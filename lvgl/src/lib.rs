@@ -24,9 +24,15 @@ extern crate bitflags;
 #[macro_use]
 mod lv_core;
 
+#[macro_use]
+pub mod logging;
+
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
 // We can ONLY use `alloc::boxed::Box` if `lvgl_alloc` is enabled.
 // That is because we use `Box` to send memory references to LVGL. Since the global allocator, when
 // `lvgl_alloc` feature is enabled, is the LVGL memory manager then everything is in LVGL
@@ -58,53 +64,154 @@ use embedded_graphics::geometry::Point;
 pub use crate::lv_core::*;
 pub use display::*;
 pub use functions::*;
+#[cfg(feature = "macros")]
+pub use lvgl_macros::Screen;
 pub use support::*;
+pub use text::*;
 
 mod display;
 mod functions;
+mod guard;
+mod storage;
 mod support;
-
+mod text;
+
+#[cfg(feature = "static_arena")]
+pub mod arena;
+pub mod config;
+pub mod debug;
+#[cfg(feature = "defer")]
+pub mod defer;
+#[cfg(any(feature = "demo_music", feature = "demo_widgets"))]
+pub mod demos;
+pub mod draw;
 #[cfg(feature = "drivers")]
 pub mod drivers;
+pub mod epaper;
 pub mod font;
+#[cfg(feature = "fbdev")]
+pub mod framebuffer;
+pub mod i18n;
 pub mod input_device;
+#[cfg(feature = "loader")]
+pub mod loader;
 pub mod misc;
+pub mod navigator;
+pub mod observer;
+pub mod pacing;
+pub mod prelude;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod squareline;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+pub mod tick;
+pub mod ui;
+pub mod view;
+#[cfg(feature = "wasm")]
+pub mod web;
 pub mod widgets;
+#[cfg(feature = "winit")]
+pub mod window;
 
 #[cfg(feature = "rust_timer")]
 pub mod timer;
 
+#[cfg(feature = "rust_alloc")]
+pub mod rust_alloc;
+
+#[cfg(feature = "rust_assert")]
+pub mod assert;
+
+#[cfg(all(feature = "rust_alloc", feature = "lvgl_alloc"))]
+compile_error!("The `rust_alloc` and `lvgl_alloc` features are mutually exclusive");
+
+#[cfg(all(feature = "static_arena", feature = "lvgl_alloc"))]
+compile_error!("The `static_arena` and `lvgl_alloc` features are mutually exclusive");
+
+/// The version of LVGL these bindings were built against, as
+/// `(major, minor, patch)`. The same information is available at compile time
+/// through the `lvgl_v<major>` and `lvgl_v<major>_<minor>` cfgs, which gate
+/// APIs LVGL added in a minor release, e.g. the `transform_pivot` style
+/// properties of LVGL 8.3.
+pub const LVGL_VERSION: (u32, u32, u32) = (
+    lvgl_sys::LVGL_VERSION_MAJOR,
+    lvgl_sys::LVGL_VERSION_MINOR,
+    lvgl_sys::LVGL_VERSION_PATCH,
+);
+
 #[cfg(feature = "unsafe_no_autoinit")]
 static mut IS_INIT: bool = false;
 #[cfg(not(feature = "unsafe_no_autoinit"))]
 static mut IS_INIT: bool = true;
 
-/// Initializes LVGL. Call at the start of the program, or after safely
-/// deinitializing with `deinit()`.
-pub fn init() {
+/// Initializes LVGL. Call at the start of the program when the
+/// `unsafe_no_autoinit` feature is enabled, or after safely deinitializing
+/// with `deinit()`. Returns `LvError::AlreadyInUse` if LVGL is already
+/// initialized, which it is at startup without `unsafe_no_autoinit`.
+pub fn init() -> LvResult<()> {
+    if is_initialized() {
+        return Err(LvError::AlreadyInUse);
+    }
     unsafe {
-        if !IS_INIT {
-            lvgl_sys::lv_init();
-            IS_INIT = true;
-        }
+        lvgl_sys::lv_init();
+        IS_INIT = true;
+    }
+    #[cfg(any(feature = "log", feature = "defmt"))]
+    logging::register();
+    Ok(())
+}
+
+/// Tests if LVGL is initialized, i.e. widgets can be created and displays
+/// registered.
+pub fn is_initialized() -> bool {
+    unsafe { IS_INIT }
+}
+
+/// Returns `LvError::Uninitialized` if LVGL is not initialized. Called by
+/// the entry points that would otherwise use LVGL before `init()`.
+pub(crate) fn check_init() -> LvResult<()> {
+    if is_initialized() {
+        Ok(())
+    } else {
+        Err(LvError::Uninitialized)
     }
 }
 
 /// Uninitializes LVGL. Make sure to reinitialize LVGL with `init()` before
 /// accessing its functionality
 ///
+/// All displays are removed first, deleting their screens and widgets so that
+/// the event handlers and user data attached to them are dropped. This allows
+/// tests and simulators to restart LVGL within one process.
+///
+/// Returns `LvError::Uninitialized` if LVGL is not initialized, and
+/// `LvError::AlreadyInUse` when called from code `task_handler()` is running.
+/// Only available if the bound LVGL provides `lv_deinit()`, which LVGL 8 does
+/// when using its own allocator.
+///
 /// # Safety
 ///
 /// After calling, ensure existing LVGL-related values are not accessed even if
 /// LVGL is reinitialized.
-#[cfg(not(feature = "custom_allocator"))]
-pub unsafe fn deinit() {
-    unsafe {
-        if IS_INIT {
-            lvgl_sys::lv_deinit();
-            IS_INIT = false;
+#[cfg(lvgl_deinit)]
+pub unsafe fn deinit() -> LvResult<()> {
+    check_init()?;
+    if functions::in_task_handler() {
+        return Err(LvError::AlreadyInUse);
+    }
+    loop {
+        let disp = lvgl_sys::lv_disp_get_next(core::ptr::null_mut());
+        if disp.is_null() {
+            break;
         }
+        lvgl_sys::lv_disp_remove(disp);
     }
+    lvgl_sys::lv_deinit();
+    IS_INIT = false;
+    Ok(())
 }
 
 #[cfg(not(feature = "unsafe_no_autoinit"))]
@@ -113,6 +220,8 @@ fn once_init() {
     unsafe {
         lvgl_sys::lv_init();
     }
+    #[cfg(any(feature = "log", feature = "defmt"))]
+    logging::register();
 }
 
 #[cfg(test)]
@@ -120,8 +229,9 @@ pub(crate) mod tests {
     use crate::display::{Display, DrawBuffer};
 
     pub(crate) fn initialize_test(buf: bool) {
-        unsafe { crate::deinit() };
-        crate::init();
+        #[cfg(lvgl_deinit)]
+        let _ = unsafe { crate::deinit() };
+        let _ = crate::init();
         if buf {
             const REFRESH_BUFFER_SIZE: usize = 240 * 240 / 10;
             let buffer = DrawBuffer::<REFRESH_BUFFER_SIZE>::default();
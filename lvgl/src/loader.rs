@@ -0,0 +1,678 @@
+//! Screens described in data files
+//!
+//! A [`Loader`] creates widgets from a [`Description`], a tree of widgets
+//! with their types, properties, style references and IDs, which is
+//! deserialized with `serde` from JSON, TOML or any other format. Screens can
+//! thus be changed without rebuilding, or generated by design tools:
+//!
+//! ```json
+//! { "widgets": [
+//!     { "type": "obj", "width": "100%", "height": "content", "flex": "column",
+//!       "children": [
+//!         { "type": "label", "id": "title", "text": "Settings" },
+//!         { "type": "slider", "id": "volume", "min": 0, "max": 11, "value": 5 },
+//!         { "type": "btn", "id": "save", "text": "Save", "styles": ["primary"],
+//!           "style": { "radius": 8, "bg_color": "#2196F3" } }
+//!     ] }
+//! ] }
+//! ```
+//!
+//! ```ignore
+//! use lvgl::loader::Loader;
+//!
+//! let ui = Loader::new()
+//!     .style("primary", &PRIMARY)
+//!     .load_json(&mut screen, include_str!("settings.json"))?;
+//! let volume: Slider = ui.get_as("volume").unwrap();
+//! ```
+//!
+//! Every widget has a `type`, and optionally an `id` to find it in the
+//! returned [`Ui`], `styles` naming the `ConstStyle`s registered with
+//! `Loader::style()`, local `style` properties named like `StyleProp`s in
+//! lowercase, and `children`. Its other fields are properties:
+//!
+//! - `x`, `y`: pixels, or a percentage such as `"50%"`.
+//! - `width`, `height`: pixels, a percentage, or `"content"`.
+//! - `align`: the alignment in the parent, e.g. `"center"` or `"top_mid"`.
+//! - `flex`: the flow of the flex layout to arrange the children with, named
+//!   like a `FlexFlow` in lowercase, e.g. `"column"` or `"row_wrap"`.
+//! - `hidden`, `clickable`, `scrollable`: flags, `true` or `false`.
+//! - `checked`, `disabled`: states, `true` or `false`.
+//! - `text`: the text of a `label`, `checkbox` or `textarea`, or a label
+//!   centered on a `btn`.
+//! - `placeholder`: the placeholder text of a `textarea`.
+//! - `min`, `max`, `value`: the range and value of a `slider`, `bar` or
+//!   `arc`.
+//! - `options`: the options of a `dropdown` or `roller`, separated by `\n`.
+//!
+//! The types are `obj`, `btn`, `label`, `slider`, `bar`, `arc`, `switch`,
+//! `checkbox`, `textarea`, `dropdown` and `roller`. Colors are written as
+//! `"#RRGGBB"`.
+//!
+//! # Building
+//!
+//! Enable the `loader` feature, which requires `alloc`, and `loader_json` or
+//! `loader_toml` for `Loader::load_json()` and `Loader::load_toml()`. TOML
+//! requires `std`.
+
+use crate::misc::area::{Coord, Size, COORD_MAX};
+use crate::style::{ConstStyle, FlexFlow, StyleProp};
+use crate::{
+    Align, AsCStr, Color, LvError, NativeObject, Obj, ObjFlag, State, Widget, WidgetClass,
+};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "nightly")]
+use core::error::Error;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use serde::Deserialize;
+
+/// A tree of widgets to create. See the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Description {
+    /// The widgets created on the parent.
+    #[serde(default)]
+    pub widgets: Vec<Node>,
+}
+
+/// A widget in a `Description`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Node {
+    /// The type of widget, e.g. `"btn"`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The name to find the widget by in the `Ui`.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// The names of registered styles to add to the widget.
+    #[serde(default)]
+    pub styles: Vec<String>,
+    /// Local style properties, keyed by the lowercase name of their
+    /// `StyleProp`, e.g. `bg_color`.
+    #[serde(default)]
+    pub style: BTreeMap<String, Value>,
+    /// The widgets created on this one.
+    #[serde(default)]
+    pub children: Vec<Node>,
+    /// The other properties, e.g. `width` or `text`.
+    #[serde(flatten)]
+    pub props: BTreeMap<String, Value>,
+}
+
+/// The value of a property.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+/// An error that stopped loading a description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The description could not be deserialized.
+    Parse(String),
+    /// A widget has a type the loader does not know.
+    UnknownType(String),
+    /// A widget has a property or style property its type does not have.
+    UnknownProperty(String),
+    /// A property has a value of the wrong kind or out of range.
+    InvalidValue(String),
+    /// A widget refers to a style that was not registered.
+    UnknownStyle(String),
+    /// Creating a widget failed.
+    Lvgl(LvError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Parse(e) => write!(f, "Parse error: {}", e),
+            LoadError::UnknownType(kind) => write!(f, "Unknown widget type: {}", kind),
+            LoadError::UnknownProperty(name) => write!(f, "Unknown property: {}", name),
+            LoadError::InvalidValue(name) => write!(f, "Invalid value of {}", name),
+            LoadError::UnknownStyle(name) => write!(f, "Unknown style: {}", name),
+            LoadError::Lvgl(e) => write!(f, "LVGL error: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl Error for LoadError {}
+
+impl From<LvError> for LoadError {
+    fn from(err: LvError) -> Self {
+        LoadError::Lvgl(err)
+    }
+}
+
+type CreateFn = unsafe extern "C" fn(*mut lvgl_sys::lv_obj_t) -> *mut lvgl_sys::lv_obj_t;
+
+const KINDS: &[(&str, CreateFn)] = &[
+    ("obj", lvgl_sys::lv_obj_create),
+    ("btn", lvgl_sys::lv_btn_create),
+    ("label", lvgl_sys::lv_label_create),
+    ("slider", lvgl_sys::lv_slider_create),
+    ("bar", lvgl_sys::lv_bar_create),
+    ("arc", lvgl_sys::lv_arc_create),
+    ("switch", lvgl_sys::lv_switch_create),
+    ("checkbox", lvgl_sys::lv_checkbox_create),
+    ("textarea", lvgl_sys::lv_textarea_create),
+    ("dropdown", lvgl_sys::lv_dropdown_create),
+    ("roller", lvgl_sys::lv_roller_create),
+];
+
+const ALIGNS: &[(&str, Align)] = &[
+    ("center", Align::Center),
+    ("top_left", Align::TopLeft),
+    ("top_mid", Align::TopMid),
+    ("top_right", Align::TopRight),
+    ("bottom_left", Align::BottomLeft),
+    ("bottom_mid", Align::BottomMid),
+    ("bottom_right", Align::BottomRight),
+    ("left_mid", Align::LeftMid),
+    ("right_mid", Align::RightMid),
+];
+
+/// Style properties holding pointers, which cannot be described as data.
+const POINTER_PROPS: &[StyleProp] = &[
+    StyleProp::BG_IMG_SRC,
+    StyleProp::ARC_IMG_SRC,
+    StyleProp::TEXT_FONT,
+    StyleProp::COLOR_FILTER_DSC,
+    StyleProp::TRANSITION,
+];
+
+/// Creates widgets from descriptions, with the styles they may refer to.
+#[derive(Default)]
+pub struct Loader<'s> {
+    styles: BTreeMap<&'s str, &'static ConstStyle>,
+}
+
+impl<'s> Loader<'s> {
+    /// Creates a loader without styles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `style` for widgets to refer to as `name`.
+    pub fn style(mut self, name: &'s str, style: &'static ConstStyle) -> Self {
+        self.styles.insert(name, style);
+        self
+    }
+
+    /// Creates the widgets of `description` on `parent`. If any widget
+    /// cannot be created, the ones already created are deleted.
+    pub fn load<'a>(
+        &self,
+        parent: &mut impl Widget<'a>,
+        description: &Description,
+    ) -> Result<Ui<'a>, LoadError> {
+        crate::check_init()?;
+        let parent = parent.raw().as_ptr();
+        let mut ui = Ui {
+            ids: BTreeMap::new(),
+            _parent: PhantomData,
+        };
+        let mut created = Vec::new();
+        for node in &description.widgets {
+            match self.create(parent, node, &mut ui) {
+                Ok(obj) => created.push(obj),
+                Err(e) => {
+                    for obj in created {
+                        unsafe { lvgl_sys::lv_obj_del(obj) };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(ui)
+    }
+
+    /// Parses a JSON description and creates its widgets on `parent`.
+    #[cfg(feature = "loader_json")]
+    pub fn load_json<'a>(
+        &self,
+        parent: &mut impl Widget<'a>,
+        json: &str,
+    ) -> Result<Ui<'a>, LoadError> {
+        let description =
+            serde_json::from_str(json).map_err(|e| LoadError::Parse(e.to_string()))?;
+        self.load(parent, &description)
+    }
+
+    /// Parses a TOML description, with the widgets as an array of
+    /// `[[widgets]]` tables, and creates them on `parent`.
+    #[cfg(feature = "loader_toml")]
+    pub fn load_toml<'a>(
+        &self,
+        parent: &mut impl Widget<'a>,
+        toml: &str,
+    ) -> Result<Ui<'a>, LoadError> {
+        let description = toml::from_str(toml).map_err(|e| LoadError::Parse(e.to_string()))?;
+        self.load(parent, &description)
+    }
+
+    /// Creates `node` and its children, deleting them again on failure.
+    fn create(
+        &self,
+        parent: *mut lvgl_sys::lv_obj_t,
+        node: &Node,
+        ui: &mut Ui,
+    ) -> Result<*mut lvgl_sys::lv_obj_t, LoadError> {
+        let &(kind, create) = KINDS
+            .iter()
+            .find(|(kind, _)| *kind == node.kind)
+            .ok_or_else(|| LoadError::UnknownType(node.kind.clone()))?;
+        let obj = NonNull::new(unsafe { create(parent) }).ok_or(LvError::LvOOMemory)?;
+        let result = self.configure(obj.as_ptr(), kind, node).and_then(|()| {
+            for child in &node.children {
+                self.create(obj.as_ptr(), child, ui)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            unsafe { lvgl_sys::lv_obj_del(obj.as_ptr()) };
+            return Err(e);
+        }
+        if let Some(id) = &node.id {
+            ui.ids.insert(id.clone(), obj);
+        }
+        Ok(obj.as_ptr())
+    }
+
+    fn configure(
+        &self,
+        obj: *mut lvgl_sys::lv_obj_t,
+        kind: &str,
+        node: &Node,
+    ) -> Result<(), LoadError> {
+        for name in &node.styles {
+            let style = self
+                .styles
+                .get(name.as_str())
+                .ok_or_else(|| LoadError::UnknownStyle(name.clone()))?;
+            unsafe { lvgl_sys::lv_obj_add_style(obj, style.raw(), lvgl_sys::LV_PART_MAIN) };
+        }
+        for (name, value) in &node.style {
+            set_style_prop(obj, name, value)?;
+        }
+
+        let int = |name: &str| match node.props.get(name) {
+            None => Ok(None),
+            Some(Value::Int(value)) => i32::try_from(*value)
+                .map(Some)
+                .map_err(|_| LoadError::InvalidValue(name.to_string())),
+            Some(_) => Err(LoadError::InvalidValue(name.to_string())),
+        };
+        let (min, max) = (int("min")?, int("max")?);
+        if min.is_some() || max.is_some() {
+            let (min, max) = (min.unwrap_or(0), max.unwrap_or(100));
+            unsafe {
+                match kind {
+                    "slider" => lvgl_sys::lv_slider_set_range(obj, min, max),
+                    "bar" => lvgl_sys::lv_bar_set_range(obj, min, max),
+                    "arc" => lvgl_sys::lv_arc_set_range(
+                        obj,
+                        min.try_into()
+                            .map_err(|_| LoadError::InvalidValue("min".to_string()))?,
+                        max.try_into()
+                            .map_err(|_| LoadError::InvalidValue("max".to_string()))?,
+                    ),
+                    _ => {
+                        let name = if node.props.contains_key("min") {
+                            "min"
+                        } else {
+                            "max"
+                        };
+                        return Err(LoadError::UnknownProperty(name.to_string()));
+                    }
+                }
+            }
+        }
+
+        for (name, value) in &node.props {
+            let invalid = || LoadError::InvalidValue(name.clone());
+            unsafe {
+                match (name.as_str(), value) {
+                    ("min" | "max", _) => {}
+                    ("x", value) => lvgl_sys::lv_obj_set_x(obj, coord(value).ok_or_else(invalid)?),
+                    ("y", value) => lvgl_sys::lv_obj_set_y(obj, coord(value).ok_or_else(invalid)?),
+                    ("width", value) => {
+                        lvgl_sys::lv_obj_set_width(obj, size(value).ok_or_else(invalid)?)
+                    }
+                    ("height", value) => {
+                        lvgl_sys::lv_obj_set_height(obj, size(value).ok_or_else(invalid)?)
+                    }
+                    ("align", Value::Text(align)) => {
+                        let &(_, align) = ALIGNS
+                            .iter()
+                            .find(|(name, _)| name == align)
+                            .ok_or_else(invalid)?;
+                        lvgl_sys::lv_obj_set_align(obj, align.into());
+                    }
+                    ("flex", Value::Text(flow)) => {
+                        let flow =
+                            FlexFlow::from_name(&flow.to_ascii_uppercase()).ok_or_else(invalid)?;
+                        lvgl_sys::lv_obj_set_flex_flow(obj, flow.into());
+                    }
+                    ("hidden", &Value::Bool(set)) => set_flag(obj, ObjFlag::HIDDEN, set),
+                    ("clickable", &Value::Bool(set)) => set_flag(obj, ObjFlag::CLICKABLE, set),
+                    ("scrollable", &Value::Bool(set)) => set_flag(obj, ObjFlag::SCROLLABLE, set),
+                    ("checked", &Value::Bool(set)) => set_state(obj, State::CHECKED, set),
+                    ("disabled", &Value::Bool(set)) => set_state(obj, State::DISABLED, set),
+                    ("text", Value::Text(text)) => set_text(obj, kind, text)?,
+                    ("placeholder", Value::Text(text)) if kind == "textarea" => {
+                        text.as_str().with_c_str(|text| {
+                            lvgl_sys::lv_textarea_set_placeholder_text(obj, text.as_ptr())
+                        })?
+                    }
+                    ("value", Value::Int(_)) => {
+                        let value = int("value")?.unwrap_or(0);
+                        match kind {
+                            "slider" => lvgl_sys::lv_slider_set_value(
+                                obj,
+                                value,
+                                lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+                            ),
+                            "bar" => lvgl_sys::lv_bar_set_value(
+                                obj,
+                                value,
+                                lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+                            ),
+                            "arc" => lvgl_sys::lv_arc_set_value(
+                                obj,
+                                value.try_into().map_err(|_| invalid())?,
+                            ),
+                            _ => return Err(LoadError::UnknownProperty(name.clone())),
+                        }
+                    }
+                    ("options", Value::Text(options)) => {
+                        options.as_str().with_c_str(|options| match kind {
+                            "dropdown" => {
+                                lvgl_sys::lv_dropdown_set_options(obj, options.as_ptr());
+                                Ok(())
+                            }
+                            "roller" => {
+                                lvgl_sys::lv_roller_set_options(
+                                    obj,
+                                    options.as_ptr(),
+                                    lvgl_sys::lv_roller_mode_t_LV_ROLLER_MODE_NORMAL,
+                                );
+                                Ok(())
+                            }
+                            _ => Err(LoadError::UnknownProperty(name.clone())),
+                        })??
+                    }
+                    (
+                        "x" | "y" | "width" | "height" | "align" | "flex" | "hidden" | "clickable"
+                        | "scrollable" | "checked" | "disabled" | "text" | "value" | "options",
+                        _,
+                    ) => return Err(invalid()),
+                    _ => return Err(LoadError::UnknownProperty(name.clone())),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sets the text of a label, checkbox or text area, or adds a label with it
+/// to a button.
+unsafe fn set_text(obj: *mut lvgl_sys::lv_obj_t, kind: &str, text: &str) -> Result<(), LoadError> {
+    text.with_c_str(|text| match kind {
+        "label" => {
+            lvgl_sys::lv_label_set_text(obj, text.as_ptr());
+            Ok(())
+        }
+        "checkbox" => {
+            lvgl_sys::lv_checkbox_set_text(obj, text.as_ptr());
+            Ok(())
+        }
+        "textarea" => {
+            lvgl_sys::lv_textarea_set_text(obj, text.as_ptr());
+            Ok(())
+        }
+        "btn" => {
+            let label = lvgl_sys::lv_label_create(obj);
+            if label.is_null() {
+                return Err(LoadError::Lvgl(LvError::LvOOMemory));
+            }
+            lvgl_sys::lv_label_set_text(label, text.as_ptr());
+            lvgl_sys::lv_obj_center(label);
+            Ok(())
+        }
+        _ => Err(LoadError::UnknownProperty("text".to_string())),
+    })?
+}
+
+unsafe fn set_flag(obj: *mut lvgl_sys::lv_obj_t, flag: ObjFlag, set: bool) {
+    if set {
+        lvgl_sys::lv_obj_add_flag(obj, flag.into());
+    } else {
+        lvgl_sys::lv_obj_clear_flag(obj, flag.into());
+    }
+}
+
+unsafe fn set_state(obj: *mut lvgl_sys::lv_obj_t, state: State, set: bool) {
+    if set {
+        lvgl_sys::lv_obj_add_state(obj, state.into());
+    } else {
+        lvgl_sys::lv_obj_clear_state(obj, state.into());
+    }
+}
+
+fn set_style_prop(
+    obj: *mut lvgl_sys::lv_obj_t,
+    name: &str,
+    value: &Value,
+) -> Result<(), LoadError> {
+    let unknown = || LoadError::UnknownProperty(name.to_string());
+    let prop = StyleProp::from_name(&name.to_ascii_uppercase()).ok_or_else(unknown)?;
+    if POINTER_PROPS.contains(&prop) {
+        return Err(unknown());
+    }
+    let value = match value {
+        Value::Text(text) => {
+            let hex = text
+                .strip_prefix('#')
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| LoadError::InvalidValue(name.to_string()))?;
+            lvgl_sys::lv_style_value_t {
+                color: Color::from_hex(hex).raw,
+            }
+        }
+        Value::Int(num) => lvgl_sys::lv_style_value_t {
+            num: i32::try_from(*num).map_err(|_| LoadError::InvalidValue(name.to_string()))?,
+        },
+        &Value::Bool(set) => lvgl_sys::lv_style_value_t { num: set as i32 },
+    };
+    unsafe {
+        lvgl_sys::lv_obj_set_local_style_prop(obj, prop.bits() as _, value, lvgl_sys::LV_PART_MAIN)
+    };
+    Ok(())
+}
+
+/// A length given in pixels or as a percentage.
+enum Length {
+    Px(i16),
+    Pct(u8),
+}
+
+/// Parses pixels or a percentage, e.g. `"50%"`.
+fn percent_or_px(value: &Value) -> Option<Length> {
+    match value {
+        Value::Int(px) => i16::try_from(*px)
+            .ok()
+            .filter(|px| px.unsigned_abs() <= COORD_MAX as u16)
+            .map(Length::Px),
+        Value::Text(text) => text.strip_suffix('%')?.parse().ok().map(Length::Pct),
+        Value::Bool(_) => None,
+    }
+}
+
+fn coord(value: &Value) -> Option<lvgl_sys::lv_coord_t> {
+    Some(match percent_or_px(value)? {
+        Length::Px(px) => Coord::px(px).raw(),
+        Length::Pct(pct) => Coord::pct(pct).raw(),
+    })
+}
+
+fn size(value: &Value) -> Option<lvgl_sys::lv_coord_t> {
+    if *value == Value::Text("content".to_string()) {
+        return Some(Size::content().raw());
+    }
+    Some(match percent_or_px(value)? {
+        Length::Px(px) => Size::px(px).raw(),
+        Length::Pct(pct) => Size::pct(pct).raw(),
+    })
+}
+
+/// The widgets created by a `Loader` that have an ID.
+pub struct Ui<'a> {
+    ids: BTreeMap<String, NonNull<lvgl_sys::lv_obj_t>>,
+    _parent: PhantomData<Obj<'a>>,
+}
+
+impl<'a> Ui<'a> {
+    /// Returns the widget with the ID `id`.
+    pub fn get(&self, id: &str) -> Option<Obj<'a>> {
+        let raw = *self.ids.get(id)?;
+        unsafe { Obj::from_raw(raw) }
+    }
+
+    /// Returns the widget with the ID `id` as a `W`, if it is one.
+    pub fn get_as<W: Widget<'a> + WidgetClass>(&self, id: &str) -> Option<W> {
+        self.get(id)?.try_into_widget().ok()
+    }
+
+    /// Returns the IDs of the widgets, in alphabetical order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.ids.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::{ConstStyleProp, Opacity};
+    use crate::widgets::{Label, Slider};
+    use crate::Display;
+    use core::ffi::CStr;
+
+    static PRIMARY: ConstStyle = ConstStyle::new(&[
+        ConstStyleProp::opa(StyleProp::BG_OPA, Opacity::OPA_50),
+        ConstStyleProp::END,
+    ]);
+
+    fn node(kind: &str, id: Option<&str>, props: &[(&str, Value)]) -> Node {
+        Node {
+            kind: kind.to_string(),
+            id: id.map(ToString::to_string),
+            styles: Vec::new(),
+            style: BTreeMap::new(),
+            children: Vec::new(),
+            props: props
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn load_description() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+
+        let mut column = node(
+            "obj",
+            None,
+            &[
+                ("flex", Value::Text("column".to_string())),
+                ("width", Value::Text("100%".to_string())),
+                ("height", Value::Text("content".to_string())),
+            ],
+        );
+        let mut title = node(
+            "label",
+            Some("title"),
+            &[("text", Value::Text("Hi".into()))],
+        );
+        title.style.insert("radius".to_string(), Value::Int(2));
+        let mut volume = node(
+            "slider",
+            Some("volume"),
+            &[
+                ("min", Value::Int(0)),
+                ("max", Value::Int(11)),
+                ("value", Value::Int(5)),
+            ],
+        );
+        volume.styles.push("primary".to_string());
+        column.children = alloc::vec![title, volume];
+        let description = Description {
+            widgets: alloc::vec![column],
+        };
+
+        let loader = Loader::new().style("primary", &PRIMARY);
+        let ui = loader.load(&mut screen, &description).unwrap();
+        assert_eq!(ui.ids().collect::<Vec<_>>(), ["title", "volume"]);
+        let title: Label = ui.get_as("title").unwrap();
+        let text = unsafe { CStr::from_ptr(lvgl_sys::lv_label_get_text(title.raw().as_ptr())) };
+        assert_eq!(text.to_bytes(), b"Hi");
+        assert_eq!(title.get_style_radius(crate::Part::Main), 2);
+        let volume: Slider = ui.get_as("volume").unwrap();
+        assert_eq!(volume.get_value(), 5);
+        assert_eq!(volume.get_max_value(), 11);
+        assert!(ui.get_as::<Label>("volume").is_none());
+
+        let children = screen.get_child_count();
+        let broken = Description {
+            widgets: alloc::vec![
+                node("label", None, &[]),
+                node("btn", None, &[("value", Value::Int(1))]),
+            ],
+        };
+        assert_eq!(
+            loader.load(&mut screen, &broken).err(),
+            Some(LoadError::UnknownProperty("value".to_string()))
+        );
+        assert_eq!(screen.get_child_count(), children);
+        let unknown = Description {
+            widgets: alloc::vec![node("spaceship", None, &[])],
+        };
+        assert_eq!(
+            loader.load(&mut screen, &unknown).err(),
+            Some(LoadError::UnknownType("spaceship".to_string()))
+        );
+        let wide_arc = Description {
+            widgets: alloc::vec![node("arc", None, &[("max", Value::Int(40_000))])],
+        };
+        assert_eq!(
+            loader.load(&mut screen, &wide_arc).err(),
+            Some(LoadError::InvalidValue("max".to_string()))
+        );
+    }
+
+    #[cfg(feature = "loader_json")]
+    #[test]
+    fn load_json() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let json = r##"{ "widgets": [
+            { "type": "btn", "id": "save", "text": "Save", "style": { "bg_color": "#FF0000" } }
+        ] }"##;
+        let ui = Loader::new().load_json(&mut screen, json).unwrap();
+        assert!(ui.get("save").is_some());
+        assert!(matches!(
+            Loader::new().load_json(&mut screen, "{"),
+            Err(LoadError::Parse(_))
+        ));
+    }
+}
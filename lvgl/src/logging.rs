@@ -0,0 +1,141 @@
+//! Forwarding of LVGL's internal log output
+//!
+//! LVGL reports internal problems (such as an undersized draw buffer or
+//! invalid parameters) through its own logging module. Two backends are
+//! available to forward those messages, along with this crate's own
+//! diagnostics:
+//! - The `log` feature forwards them to the [`log`] crate, under the `lvgl`
+//!   target.
+//! - The `defmt` feature forwards them to [`defmt`], for RTT-based debugging
+//!   on `no_std` targets without the overhead of `core::fmt`.
+//!
+//! If both features are enabled, messages are sent to both backends.
+//!
+//! # Building
+//!
+//! Enable the `log` or `defmt` feature on the `lvgl` crate. The build script
+//! sets `LV_USE_LOG` to `1` and `LV_LOG_PRINTF` to `0` on top of your
+//! `lv_conf.h`. The minimum level can be chosen with `LV_LOG_LEVEL`, e.g. by
+//! setting `DEP_LV_CONFIG_LOG_LEVEL=LV_LOG_LEVEL_INFO`.
+//!
+//! # Usage
+//!
+//! The forwarder is registered automatically when LVGL is initialized. Any
+//! `log` implementation can be used to display the output:
+//!
+//! ```ignore
+//! fn main() {
+//!     env_logger::init();
+//!     // Initialize displays, etc. LVGL warnings now appear as e.g.
+//!     // `WARN lvgl: lv_obj_set_style: invalid parameter`
+//! }
+//! ```
+//!
+//! With `defmt`, a global logger such as `defmt-rtt` must be linked in.
+//!
+//! [`log`]: https://docs.rs/log
+//! [`defmt`]: https://docs.rs/defmt
+
+#[cfg(any(feature = "log", feature = "defmt"))]
+use core::ffi::c_char;
+#[cfg(any(feature = "log", feature = "defmt"))]
+use cstr_core::CStr;
+
+/// Emits a diagnostic from this crate through the enabled logging backends.
+/// Expands to nothing if no backend is enabled. Format strings must be
+/// compatible with both `core::fmt` and `defmt`, i.e. only plain `{}`
+/// placeholders.
+#[allow(unused_macros)]
+macro_rules! diag {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::$level!(target: "lvgl", $($arg)+);
+        #[cfg(feature = "defmt")]
+        defmt::$level!($($arg)+);
+    }};
+}
+
+/// Registers the log forwarder with LVGL. This is done automatically on
+/// initialization, but can be called again to restore the forwarder if the
+/// print callback was replaced through the raw bindings.
+#[cfg(any(feature = "log", feature = "defmt"))]
+pub fn register() {
+    unsafe { lvgl_sys::lv_log_register_print_cb(Some(log_print)) }
+}
+
+#[cfg(any(feature = "log", feature = "defmt"))]
+unsafe extern "C" fn log_print(buf: *const c_char) {
+    if buf.is_null() {
+        return;
+    }
+    let msg = CStr::from_ptr(buf)
+        .to_str()
+        .unwrap_or("<invalid UTF-8 in LVGL log message>");
+    let (level, msg) = split_level(msg);
+
+    #[cfg(feature = "log")]
+    log::log!(target: "lvgl", log::Level::from(level), "{}", msg);
+
+    #[cfg(feature = "defmt")]
+    match level {
+        Level::Trace => defmt::trace!("{=str}", msg),
+        Level::Info => defmt::info!("{=str}", msg),
+        Level::Warn => defmt::warn!("{=str}", msg),
+        Level::Error => defmt::error!("{=str}", msg),
+    }
+}
+
+/// Log levels used by LVGL. `User` messages are reported as `Info`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Level {
+    Trace,
+    Info,
+    Warn,
+    Error,
+}
+
+#[cfg(feature = "log")]
+impl From<Level> for log::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Trace => log::Level::Trace,
+            Level::Info => log::Level::Info,
+            Level::Warn => log::Level::Warn,
+            Level::Error => log::Level::Error,
+        }
+    }
+}
+
+/// Splits the `[Level]` prefix LVGL adds to each message from its content.
+#[allow(dead_code)]
+fn split_level(msg: &str) -> (Level, &str) {
+    let (prefix, rest) = match msg.strip_prefix('[').and_then(|m| m.split_once(']')) {
+        Some(parts) => parts,
+        None => return (Level::Info, msg.trim_end()),
+    };
+    let level = match prefix {
+        "Trace" => Level::Trace,
+        "Warn" => Level::Warn,
+        "Error" => Level::Error,
+        _ => Level::Info,
+    };
+    (level, rest.trim())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_lvgl_log_levels() {
+        let (level, msg) =
+            split_level("[Warn]\t(0.010, +10)\t lv_init: test \t(in lv_obj.c line #1)\n");
+        assert_eq!(level, Level::Warn);
+        assert_eq!(msg, "(0.010, +10)\t lv_init: test \t(in lv_obj.c line #1)");
+
+        assert_eq!(split_level("[Trace] x").0, Level::Trace);
+        assert_eq!(split_level("[Error] x").0, Level::Error);
+        assert_eq!(split_level("[User] x").0, Level::Info);
+        assert_eq!(split_level("no prefix\n"), (Level::Info, "no prefix"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Builders for creating and configuring widgets in one expression
+//!
+//! Every generated widget has a `builder()`, which collects its properties,
+//! event handlers and widget-specific settings, and applies them in the order
+//! they were given once the widget is created with [`Builder::build()`]:
+//!
+//! ```ignore
+//! let mut button = Btn::builder()
+//!     .size(120, 40)
+//!     .align(Align::Center, 0, 0)
+//!     .on_clicked(|_| println!("Clicked!"))
+//!     .build(&mut screen)?;
+//! ```
+//!
+//! Besides the common settings below, builders have a method for each
+//! generated `set_*` method of their widget, without the `set_` prefix (e.g.
+//! `Bar::builder().range(0, 100)`).
+
+use crate::lv_core::style::{FlexFlow, Style};
+use crate::misc::area::{Coord, Size};
+use crate::{
+    event_callback, Align, Box, Dir, Event, LvError, LvResult, ObjFlag, ScrollbarMode, State,
+    Widget,
+};
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// The LVGL function creating a widget on a parent.
+pub type CreateFn = unsafe extern "C" fn(*mut lvgl_sys::lv_obj_t) -> *mut lvgl_sys::lv_obj_t;
+
+/// Lets a returned `impl Trait` borrow for `'a`, which the edition's
+/// capture rules only allow for lifetimes named in its bounds.
+#[doc(hidden)]
+pub trait Captures<'a> {}
+
+impl<T: ?Sized> Captures<'_> for T {}
+
+/// Creates a widget of type `W` with the settings applied by `S`.
+#[must_use = "builders do nothing until `build()` is called"]
+pub struct Builder<W, S = fn(&mut W)> {
+    create: CreateFn,
+    setup: S,
+    _widget: PhantomData<fn() -> W>,
+}
+
+impl<W> Builder<W> {
+    /// Starts a builder creating its widget with `create`, e.g.
+    /// `lvgl_sys::lv_btn_create`.
+    pub fn new(create: CreateFn) -> Self {
+        Self {
+            create,
+            setup: |_| {},
+            _widget: PhantomData,
+        }
+    }
+}
+
+impl<'a, W, S> Builder<W, S>
+where
+    W: Widget<'a>,
+    S: FnOnce(&mut W),
+{
+    /// Runs `f` on the widget after the settings given so far. This can be
+    /// used for settings without a dedicated builder method.
+    pub fn with<F>(self, f: F) -> Builder<W, impl FnOnce(&mut W)>
+    where
+        F: FnOnce(&mut W),
+    {
+        let setup = self.setup;
+        Builder {
+            create: self.create,
+            setup: move |widget: &mut W| {
+                setup(widget);
+                f(widget)
+            },
+            _widget: PhantomData,
+        }
+    }
+
+    /// Sets the widget's size. See `Widget::set_size()`.
+    pub fn size(self, w: impl Into<Size>, h: impl Into<Size>) -> Builder<W, impl FnOnce(&mut W)> {
+        let (w, h) = (w.into(), h.into());
+        self.with(move |widget| widget.set_size(w, h))
+    }
+
+    /// Sets the widget's width. See `Widget::set_width()`.
+    pub fn width(self, w: impl Into<Size>) -> Builder<W, impl FnOnce(&mut W)> {
+        let w = w.into();
+        // Cannot fail, as `w` is already a `Size`
+        self.with(move |widget| {
+            let _ = widget.set_width(w);
+        })
+    }
+
+    /// Sets the widget's height. See `Widget::set_height()`.
+    pub fn height(self, h: impl Into<Size>) -> Builder<W, impl FnOnce(&mut W)> {
+        let h = h.into();
+        // Cannot fail, as `h` is already a `Size`
+        self.with(move |widget| {
+            let _ = widget.set_height(h);
+        })
+    }
+
+    /// Sets the widget's position. See `Widget::set_pos()`.
+    pub fn pos(self, x: impl Into<Coord>, y: impl Into<Coord>) -> Builder<W, impl FnOnce(&mut W)> {
+        let (x, y) = (x.into(), y.into());
+        self.with(move |widget| widget.set_pos(x, y))
+    }
+
+    /// Aligns the widget to its parent. See `Widget::set_align()`.
+    pub fn align(self, align: Align, x_mod: i32, y_mod: i32) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.set_align(align, x_mod, y_mod))
+    }
+
+    /// Adds object flags to the widget. See `Widget::add_flag()`.
+    pub fn flag(self, flag: ObjFlag) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.add_flag(flag))
+    }
+
+    /// Adds states to the widget. See `Widget::add_state()`.
+    pub fn state(self, state: State) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.add_state(state))
+    }
+
+    /// Sets when the widget's scrollbars are shown. See
+    /// `Widget::set_scrollbar_mode()`.
+    pub fn scrollbar_mode(self, mode: ScrollbarMode) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.set_scrollbar_mode(mode))
+    }
+
+    /// Sets the directions the widget can be scrolled in. See
+    /// `Widget::set_scroll_dir()`.
+    pub fn scroll_dir(self, dir: Dir) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.set_scroll_dir(dir))
+    }
+
+    /// Extends the area where the widget can be pressed. See
+    /// `Widget::set_ext_click_area()`.
+    pub fn ext_click_area(self, pad: i16) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| widget.set_ext_click_area(pad))
+    }
+
+    /// Adds a style to a part of the widget. See `Widget::add_style()`.
+    pub fn style(
+        self,
+        part: W::Part,
+        style: &'a mut Style,
+    ) -> Builder<W, impl FnOnce(&mut W) + Captures<'a>> {
+        self.with(move |widget| widget.add_style(part, style))
+    }
+
+    /// Arranges the widget's children with the flex layout, in the given flow.
+    pub fn flex_flow(self, flow: FlexFlow) -> Builder<W, impl FnOnce(&mut W)> {
+        self.with(move |widget| unsafe {
+            lvgl_sys::lv_obj_set_flex_flow(widget.raw().as_mut(), flow.into())
+        })
+    }
+
+    /// Adds an event handler to the widget, as the widget's `on_event()` does.
+    pub fn on_event<F>(self, f: F) -> Builder<W, impl FnOnce(&mut W)>
+    where
+        F: FnMut(W, Event<W::SpecialEvent>),
+    {
+        self.with(move |widget| unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                widget.raw().as_mut(),
+                lvgl_sys::lv_event_cb_t::Some(event_callback::<'a, W, F>),
+                lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                Box::into_raw(Box::new(f)) as *mut _,
+            );
+        })
+    }
+
+    /// Adds an event handler calling `f` when the widget is clicked.
+    pub fn on_clicked<F>(self, mut f: F) -> Builder<W, impl FnOnce(&mut W)>
+    where
+        F: FnMut(W),
+    {
+        self.on_event(move |widget, event| {
+            if matches!(event, Event::Clicked) {
+                f(widget)
+            }
+        })
+    }
+
+    /// Adds an event handler calling `f` when the widget's value changes.
+    pub fn on_change<F>(self, mut f: F) -> Builder<W, impl FnOnce(&mut W)>
+    where
+        F: FnMut(W),
+    {
+        self.on_event(move |widget, event| {
+            if matches!(event, Event::ValueChanged) {
+                f(widget)
+            }
+        })
+    }
+
+    /// Creates the widget on `parent` and applies the settings in the order
+    /// they were given. The widget cannot outlive its parent's lifetime.
+    pub fn build<'p: 'a>(self, parent: &mut impl Widget<'p>) -> LvResult<W> {
+        crate::check_init()?;
+        let raw = unsafe { (self.create)(parent.raw().as_mut()) };
+        let raw = NonNull::new(raw).ok_or(LvError::InvalidReference)?;
+        let mut widget = unsafe { W::from_raw(raw) }.ok_or(LvError::InvalidReference)?;
+        (self.setup)(&mut widget);
+        Ok(widget)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::Bar;
+    use crate::{tests, Align, AnimationState};
+
+    #[test]
+    fn applies_settings_in_order() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut bar = Bar::builder()
+            .size(100, 10)
+            .align(Align::Center, 0, 0)
+            .range(0, 10)
+            .with(|bar| bar.set_value(20, AnimationState::OFF))
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(bar.get_value(), 10);
+    }
+}
@@ -1,12 +1,35 @@
 use crate::input_device::InputDriver;
 use crate::{LvError, LvResult, NativeObject};
-use core::ptr::NonNull;
+use core::ptr::{self, NonNull};
 
 /// A group of objects, for use with `Encoder` and `Keypad`-type input devices.
+///
+/// With an encoder, the group is either navigating, where turning the knob
+/// moves the focus between its objects, or editing, where turning it changes
+/// the focused object's value, e.g. a slider's. Pressing the knob on an
+/// editable widget enters edit mode, and pressing it again leaves it.
 pub struct Group {
     raw: *mut lvgl_sys::lv_group_t,
 }
 
+/// Which object a group focuses when the focused object is removed from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefocusPolicy {
+    /// Focus the next object.
+    Next,
+    /// Focus the previous object.
+    Prev,
+}
+
+impl From<RefocusPolicy> for lvgl_sys::lv_group_refocus_policy_t {
+    fn from(policy: RefocusPolicy) -> Self {
+        match policy {
+            RefocusPolicy::Next => lvgl_sys::lv_group_refocus_policy_t_LV_GROUP_REFOCUS_POLICY_NEXT,
+            RefocusPolicy::Prev => lvgl_sys::lv_group_refocus_policy_t_LV_GROUP_REFOCUS_POLICY_PREV,
+        }
+    }
+}
+
 impl Default for Group {
     fn default() -> Self {
         Group {
@@ -16,6 +39,55 @@ impl Default for Group {
 }
 
 impl Group {
+    /// Creates a group for keyboard navigation with minimal setup: it becomes
+    /// the default group, so focusable widgets created afterwards are added
+    /// to it, and every keypad and encoder registered so far controls it.
+    /// Tab and Shift+Tab then move between buttons, sliders and the like,
+    /// as on a desktop:
+    ///
+    /// ```ignore
+    /// let _keypad = Keypad::register(read_keys, &display)?;
+    /// let _group = Group::create_default()?;
+    /// let ok = Btn::create(&mut screen)?;
+    /// let cancel = Btn::create(&mut screen)?;
+    /// ```
+    ///
+    /// Dropping the group deletes it, which stops adding widgets to it.
+    pub fn create_default() -> LvResult<Self> {
+        let mut group = Group::default();
+        group.set_default()?;
+        group.set_all_indevs()?;
+        Ok(group)
+    }
+
+    /// Makes this the default group. Widgets that LVGL considers focusable,
+    /// e.g. buttons, sliders and text areas, are added to the default group
+    /// when they are created.
+    pub fn set_default(&mut self) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_set_default(self.raw()?.as_mut()) }
+        Ok(())
+    }
+
+    /// Associates every keypad and encoder input device registered so far
+    /// to the group.
+    pub fn set_all_indevs(&mut self) -> LvResult<()> {
+        let group = self.raw()?.as_ptr();
+        unsafe {
+            let mut indev = lvgl_sys::lv_indev_get_next(ptr::null_mut());
+            while !indev.is_null() {
+                match lvgl_sys::lv_indev_get_type(indev) {
+                    lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_KEYPAD
+                    | lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_ENCODER => {
+                        lvgl_sys::lv_indev_set_group(indev, group)
+                    }
+                    _ => {}
+                }
+                indev = lvgl_sys::lv_indev_get_next(indev);
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a pointer to the underlying `lv_group_t`.
     pub fn raw(&self) -> LvResult<NonNull<lvgl_sys::lv_group_t>> {
         if let Some(non_null_ptr) = NonNull::new(self.raw) {
@@ -42,6 +114,52 @@ impl Group {
         }
         Ok(())
     }
+
+    /// Switches between edit mode, where an encoder changes the focused
+    /// object's value, and navigate mode, where it moves the focus. Use this
+    /// to enter edit mode without a press, e.g. when a knob-only device opens
+    /// a screen with a single slider.
+    pub fn set_editing(&mut self, editing: bool) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_set_editing(self.raw()?.as_mut(), editing) }
+        Ok(())
+    }
+
+    /// Tests if the group is in edit mode.
+    pub fn get_editing(&self) -> LvResult<bool> {
+        Ok(unsafe { lvgl_sys::lv_group_get_editing(self.raw()?.as_ptr()) })
+    }
+
+    /// Sets whether moving the focus past the last object wraps around to the
+    /// first, and the other way around. Groups wrap by default.
+    pub fn set_wrap(&mut self, wrap: bool) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_set_wrap(self.raw()?.as_mut(), wrap) }
+        Ok(())
+    }
+
+    /// Tests if moving the focus wraps around.
+    pub fn get_wrap(&self) -> LvResult<bool> {
+        Ok(unsafe { lvgl_sys::lv_group_get_wrap(self.raw()?.as_ptr()) })
+    }
+
+    /// Sets which object is focused when the focused object is removed from
+    /// the group. The default is `RefocusPolicy::Next`.
+    pub fn set_refocus_policy(&mut self, policy: RefocusPolicy) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_set_refocus_policy(self.raw()?.as_mut(), policy.into()) }
+        Ok(())
+    }
+
+    /// Moves the focus to the next object, as turning an encoder does in
+    /// navigate mode.
+    pub fn focus_next(&mut self) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_focus_next(self.raw()?.as_mut()) }
+        Ok(())
+    }
+
+    /// Moves the focus to the previous object.
+    pub fn focus_prev(&mut self) -> LvResult<()> {
+        unsafe { lvgl_sys::lv_group_focus_prev(self.raw()?.as_mut()) }
+        Ok(())
+    }
 }
 
 impl Drop for Group {
@@ -57,8 +175,10 @@ impl Drop for Group {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::widgets::Btn;
-    use crate::{Display, DrawBuffer};
+    use crate::input_device::keypad::{Keypad, KeypadInputData};
+    use crate::input_device::BufferStatus;
+    use crate::widgets::{Btn, Label, Slider};
+    use crate::{Display, DrawBuffer, State, Widget};
 
     #[test]
     fn group_test() {
@@ -72,4 +192,59 @@ mod test {
         let btn = Btn::create(&mut screen).unwrap();
         group.add_obj(&btn).unwrap();
     }
+
+    #[test]
+    fn knob_edits_slider() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut group = Group::default();
+        let btn = Btn::create(&mut screen).unwrap();
+        let slider = Slider::create(&mut screen).unwrap();
+        group.add_obj(&btn).unwrap();
+        group.add_obj(&slider).unwrap();
+        group.set_wrap(false).unwrap();
+        assert!(!group.get_wrap().unwrap());
+
+        group.focus_next().unwrap();
+        assert!(slider.has_state(State::FOCUSED));
+        // Without wrapping, the focus stays on the last object
+        group.focus_next().unwrap();
+        assert!(slider.has_state(State::FOCUSED));
+
+        assert!(!group.get_editing().unwrap());
+        group.set_editing(true).unwrap();
+        assert!(group.get_editing().unwrap());
+        assert!(slider.has_state(State::EDITED));
+        group.set_editing(false).unwrap();
+        assert!(!slider.has_state(State::EDITED));
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+
+        fn read_keypad_device() -> BufferStatus {
+            KeypadInputData(lvgl_sys::LV_KEY_NEXT).released().once()
+        }
+
+        let mut keypad = Keypad::register(read_keypad_device, &display).unwrap();
+        let group = Group::create_default().unwrap();
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let btn = Btn::create(&mut screen).unwrap();
+        let label = Label::create(&mut screen).unwrap();
+        let group_raw = group.raw().unwrap().as_ptr();
+        unsafe {
+            assert_eq!(
+                lvgl_sys::lv_obj_get_group(btn.raw().as_ptr()),
+                group_raw as *mut _
+            );
+            // Labels are not focusable
+            assert!(lvgl_sys::lv_obj_get_group(label.raw().as_ptr()).is_null());
+            assert_eq!(keypad.get_descriptor().unwrap().group, group_raw);
+        }
+
+        drop(group);
+        assert!(unsafe { lvgl_sys::lv_group_get_default() }.is_null());
+    }
 }
@@ -1,8 +1,15 @@
 #[macro_use]
 pub mod obj;
+pub mod builder;
 pub mod group;
 pub mod screen;
 pub mod style;
+pub mod theme;
+mod user_data;
+pub mod value;
+pub(crate) mod widget_id;
 
+pub use builder::*;
 pub use obj::*;
 pub use screen::*;
+pub use value::*;
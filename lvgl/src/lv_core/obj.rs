@@ -4,14 +4,40 @@
 //! Specifically, an object can either be a widget or a screen. Screen objects
 //! are special in that they do not have a parent object but do still implement
 //! `NativeObject`.
+//!
+//! # Lifetimes
+//!
+//! Widget handles do not own their LVGL object: LVGL keeps each object alive
+//! until it or one of its ancestors is deleted. The lifetime of a `Widget<'a>`
+//! bounds how long its handle can be used. A screen returned by
+//! `Display::get_scr_act()` borrows the display, and a widget created on a
+//! parent cannot outlive the parent's lifetime, so no handle outlives the
+//! display its widget is drawn on.
+//!
+//! Deletion is not tracked by lifetimes. Deleting a widget, one of its
+//! ancestors or its screen leaves every other handle to the deleted objects
+//! dangling; `Widget::is_valid()` can check for this while debugging. Loading
+//! another screen does not delete the previous one, whose widgets stay valid.
 
-use crate::lv_core::style::Style;
-use crate::{event_callback, Align, Box, Event, LvError, LvResult, ObjFlag};
+use crate::font::Font;
+use crate::lv_core::style::{
+    ConstStyle, FlexAlign, FlexFlow, FromStyleValue, GridAlign, Layout, Opacity, Style, StyleProp,
+    StyleTransition,
+};
+use crate::lv_core::{user_data, widget_id};
+use crate::misc::anim::{self, AnimPath};
+use crate::misc::area::{Coord, Size};
+use crate::{
+    event_callback, Align, Area, Box, Color, Dir, Event, LvError, LvResult, ObjFlag, Point,
+    ScrollbarMode, State, TextAlign,
+};
 use core::{
     fmt::{self, Debug},
     marker::PhantomData,
     ptr::{self, NonNull},
+    time::Duration,
 };
+use paste::paste;
 
 /// Represents a native LVGL object.
 pub trait NativeObject {
@@ -42,7 +68,8 @@ impl Debug for Obj<'_> {
 // We need to manually impl methods on Obj since widget codegen is defined in
 // terms of Obj
 impl<'a> Obj<'a> {
-    pub fn create(parent: &'a mut impl NativeObject) -> LvResult<Self> {
+    pub fn create<'p: 'a>(parent: &mut impl Widget<'p>) -> LvResult<Self> {
+        crate::check_init()?;
         unsafe {
             let ptr = lvgl_sys::lv_obj_create(parent.raw().as_mut());
             if let Some(nn_ptr) = ptr::NonNull::new(ptr) {
@@ -59,10 +86,16 @@ impl<'a> Obj<'a> {
 
     pub fn new() -> crate::LvResult<Self> {
         let mut parent = crate::display::get_scr_act()?;
-        Self::create(unsafe { &mut *(&mut parent as *mut _) })
+        Self::create(&mut parent)
+    }
+
+    /// Starts building a generic object, e.g. a container for other widgets.
+    pub fn builder() -> crate::Builder<Self> {
+        crate::Builder::new(lvgl_sys::lv_obj_create)
     }
 
     pub fn blank() -> LvResult<Self> {
+        crate::check_init()?;
         match NonNull::new(unsafe { lvgl_sys::lv_obj_create(ptr::null_mut()) }) {
             Some(raw) => Ok(Self {
                 raw,
@@ -78,13 +111,11 @@ impl<'a> Obj<'a> {
     {
         use NativeObject;
         unsafe {
-            let obj = self.raw().as_mut();
-            obj.user_data = Box::into_raw(Box::new(f)) as *mut _;
             lvgl_sys::lv_obj_add_event_cb(
-                obj,
+                self.raw().as_mut(),
                 lvgl_sys::lv_event_cb_t::Some(event_callback::<'a, Self, F>),
                 lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
-                obj.user_data,
+                Box::into_raw(Box::new(f)) as *mut _,
             );
         }
         Ok(())
@@ -97,6 +128,45 @@ impl NativeObject for Obj<'_> {
     }
 }
 
+macro_rules! gen_lv_obj_style {
+    ($prop:ident, $vty:ty) => {
+        paste! {
+            #[doc = "Sets the `" $prop "` style property of a part of the widget"]
+            #[doc = "locally, overriding the widget's added styles."]
+            #[inline]
+            fn [<set_style_ $prop>](&mut self, part: Self::Part, value: $vty) {
+                unsafe {
+                    lvgl_sys::[<lv_obj_set_style_ $prop>](
+                        self.raw().as_mut(),
+                        value.into(),
+                        part.into(),
+                    );
+                }
+            }
+        }
+    };
+}
+
+macro_rules! gen_lv_obj_style_get {
+    ($prop:ident, $vty:ty) => {
+        paste! {
+            #[doc = "Returns the `" $prop "` style property of a part of the widget"]
+            #[doc = "in its current state, as resolved from its local styles, added"]
+            #[doc = "styles and the theme."]
+            #[inline]
+            fn [<get_style_ $prop>](&self, part: Self::Part) -> $vty {
+                <$vty>::from_style_value(unsafe {
+                    lvgl_sys::lv_obj_get_style_prop(
+                        self.raw().as_ptr(),
+                        part.into(),
+                        lvgl_sys::[<lv_style_prop_t_LV_STYLE_ $prop:upper>],
+                    )
+                })
+            }
+        }
+    };
+}
+
 /// A wrapper for all LVGL common operations on generic objects.
 pub trait Widget<'a>: NativeObject + Sized + 'a {
     type SpecialEvent;
@@ -122,40 +192,70 @@ pub trait Widget<'a>: NativeObject + Sized + 'a {
         };
     }
 
-    /// Sets a widget's position relative to its parent.
-    fn set_pos(&mut self, x: i16, y: i16) {
+    /// Adds a `ConstStyle` to a given widget.
+    fn add_const_style(&mut self, part: Self::Part, style: &'a ConstStyle) {
         unsafe {
-            lvgl_sys::lv_obj_set_pos(
-                self.raw().as_mut(),
-                x as lvgl_sys::lv_coord_t,
-                y as lvgl_sys::lv_coord_t,
-            );
+            lvgl_sys::lv_obj_add_style(self.raw().as_mut(), style.raw(), part.into());
         }
     }
 
-    /// Sets a widget's size. Alternatively, use `set_width()` and `set_height()`.
-    fn set_size(&mut self, w: i16, h: i16) {
+    /// Adds several `ConstStyle`s to the widget's parts, recalculating its
+    /// styles once at the end instead of after each style as
+    /// `add_const_style()` does.
+    fn apply_styles(&mut self, styles: &[(&'a ConstStyle, Self::Part)])
+    where
+        Self::Part: Copy,
+    {
         unsafe {
-            lvgl_sys::lv_obj_set_size(
+            lvgl_sys::lv_obj_enable_style_refresh(false);
+            for &(style, part) in styles {
+                lvgl_sys::lv_obj_add_style(self.raw().as_mut(), style.raw(), part.into());
+            }
+            lvgl_sys::lv_obj_enable_style_refresh(true);
+            lvgl_sys::lv_obj_refresh_style(
                 self.raw().as_mut(),
-                w as lvgl_sys::lv_coord_t,
-                h as lvgl_sys::lv_coord_t,
+                lvgl_sys::LV_PART_ANY,
+                lvgl_sys::lv_style_prop_t_LV_STYLE_PROP_ANY,
             );
         }
     }
 
-    /// Sets a widget's width. Alternatively, use `set_size()`.
-    fn set_width(&mut self, w: u32) {
+    /// Sets a widget's position relative to its parent, in pixels (e.g. `10`)
+    /// or as a percentage of the parent's size (`Coord::pct(10)`).
+    fn set_pos(&mut self, x: impl Into<Coord>, y: impl Into<Coord>) {
+        unsafe {
+            lvgl_sys::lv_obj_set_pos(self.raw().as_mut(), x.into().raw(), y.into().raw());
+        }
+    }
+
+    /// Sets a widget's size, in pixels (e.g. `100`), as a percentage of the
+    /// parent's size (`Size::pct(50)`) or to fit its content
+    /// (`Size::content()`). Alternatively, use `set_width()` and `set_height()`.
+    fn set_size(&mut self, w: impl Into<Size>, h: impl Into<Size>) {
+        unsafe {
+            lvgl_sys::lv_obj_set_size(self.raw().as_mut(), w.into().raw(), h.into().raw());
+        }
+    }
+
+    /// Sets a widget's width, in pixels (e.g. `100`, as an `i16`, `i32` or
+    /// `u32`), as a percentage of the parent's size or to fit its content, as
+    /// in `set_size()`. Fails with `LvError::OutOfRange` if a pixel value is
+    /// beyond `COORD_MAX`.
+    fn set_width(&mut self, w: impl TryInto<Size>) -> LvResult<()> {
+        let w = w.try_into().map_err(|_| LvError::OutOfRange)?;
         unsafe {
-            lvgl_sys::lv_obj_set_width(self.raw().as_mut(), w as lvgl_sys::lv_coord_t);
+            lvgl_sys::lv_obj_set_width(self.raw().as_mut(), w.raw());
         }
+        Ok(())
     }
 
-    /// Sets a widget's height. Alternatively, use `set_size()`.
-    fn set_height(&mut self, h: u32) {
+    /// Sets a widget's height, as `set_width()` sets its width.
+    fn set_height(&mut self, h: impl TryInto<Size>) -> LvResult<()> {
+        let h = h.try_into().map_err(|_| LvError::OutOfRange)?;
         unsafe {
-            lvgl_sys::lv_obj_set_height(self.raw().as_mut(), h as lvgl_sys::lv_coord_t);
+            lvgl_sys::lv_obj_set_height(self.raw().as_mut(), h.raw());
         }
+        Ok(())
     }
 
     /// Sets a widget's align relative to its parent along with an offset.
@@ -170,19 +270,35 @@ pub trait Widget<'a>: NativeObject + Sized + 'a {
         }
     }
 
-    /// Tests if a widget has the specified object flag set.
+    /// Tests if a widget has all of the specified object flags set.
     fn has_flag(&self, flag: ObjFlag) -> bool {
         unsafe { lvgl_sys::lv_obj_has_flag(self.raw().as_ref(), flag.into()) }
     }
 
-    /// Add the specified object flag to the widget's existing flags.
+    /// Tests if a widget has any of the specified object flags set.
+    fn has_flag_any(&self, flag: ObjFlag) -> bool {
+        unsafe { lvgl_sys::lv_obj_has_flag_any(self.raw().as_ref(), flag.into()) }
+    }
+
+    /// Add the specified object flags to the widget's existing flags.
     fn add_flag(&mut self, flag: ObjFlag) {
         unsafe {
             lvgl_sys::lv_obj_add_flag(self.raw().as_mut() as *mut lvgl_sys::lv_obj_t, flag.into());
         }
     }
 
-    /// Remove the specified object flag from the widget's existing flags.
+    /// Clear the specified object flags from the widget's existing flags.
+    fn clear_flag(&mut self, flag: ObjFlag) {
+        unsafe {
+            lvgl_sys::lv_obj_clear_flag(
+                self.raw().as_mut() as *mut lvgl_sys::lv_obj_t,
+                flag.into(),
+            );
+        }
+    }
+
+    /// Remove the specified object flag from the widget's existing flags. The
+    /// same as `clear_flag()`.
     fn remove_flag(&mut self, flag: ObjFlag) {
         unsafe {
             lvgl_sys::lv_obj_clear_flag(
@@ -191,6 +307,542 @@ pub trait Widget<'a>: NativeObject + Sized + 'a {
             );
         }
     }
+
+    /// Adds states to the widget, e.g. `State::DISABLED` to keep a Save
+    /// button from being pressed until a form is valid. Its styles for the
+    /// new states are applied.
+    fn add_state(&mut self, state: State) {
+        unsafe { lvgl_sys::lv_obj_add_state(self.raw().as_mut(), state.into()) }
+    }
+
+    /// Clears states from the widget.
+    fn clear_state(&mut self, state: State) {
+        unsafe { lvgl_sys::lv_obj_clear_state(self.raw().as_mut(), state.into()) }
+    }
+
+    /// Returns the widget's current states.
+    fn get_state(&self) -> State {
+        let state = unsafe { lvgl_sys::lv_obj_get_state(self.raw().as_ptr()) };
+        State::from_bits_truncate(state as u16)
+    }
+
+    /// Tests if the widget has all of the specified states.
+    fn has_state(&self, state: State) -> bool {
+        self.get_state().contains(state)
+    }
+
+    /// Attaches a value of any type to the widget, dropping the previously
+    /// attached one. The value is dropped when the widget is deleted.
+    ///
+    /// Each widget holds a single value; use a struct to attach several.
+    fn set_user_data<T: 'static>(&mut self, value: T) {
+        unsafe { user_data::set(self.raw().as_mut(), value) }
+    }
+
+    /// Returns the value attached to the widget, if it is a `T`.
+    fn get_user_data<T: 'static>(&self) -> Option<&T> {
+        unsafe { user_data::get::<T>(self.raw().as_ref()).map(|data| &*data) }
+    }
+
+    /// Returns the value attached to the widget mutably, if it is a `T`.
+    fn get_user_data_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        unsafe { user_data::get::<T>(self.raw().as_ref()).map(|data| &mut *data) }
+    }
+
+    /// Detaches the value attached to the widget and returns it, if it is a
+    /// `T`.
+    fn take_user_data<T: 'static>(&mut self) -> Option<T> {
+        unsafe { user_data::take(self.raw().as_mut()) }
+    }
+
+    /// Deletes the widget along with all of its children, freeing their
+    /// attached user data.
+    ///
+    /// This consumes the handle, but LVGL objects can have several handles
+    /// (e.g. those passed to event handlers, or handles of the children). Any
+    /// remaining handle to the widget or its children is dangling afterwards
+    /// and must not be used; `is_valid()` can check a handle whose fate is
+    /// unknown.
+    fn delete(self) {
+        unsafe { lvgl_sys::lv_obj_del(self.raw().as_ptr()) }
+    }
+
+    /// Deletes the widget along with all of its children on the next call to
+    /// `task_handler()`. Unlike `delete()`, this can be called from the
+    /// widget's own event handlers. Other handles to the widget stay usable
+    /// until then.
+    fn delete_async(self) {
+        unsafe { lvgl_sys::lv_obj_del_async(self.raw().as_ptr()) }
+    }
+
+    /// Deletes all children of the widget. Handles to them are dangling
+    /// afterwards, as with `delete()`.
+    fn clean(&mut self) {
+        unsafe { lvgl_sys::lv_obj_clean(self.raw().as_ptr()) }
+    }
+
+    /// Returns the number of children of the widget.
+    fn get_child_count(&self) -> u32 {
+        unsafe { lvgl_sys::lv_obj_get_child_cnt(self.raw().as_ptr()) }
+    }
+
+    /// Returns the child at `index`, in creation order. Negative indices count
+    /// from the last child, e.g. -1 is the last one.
+    fn get_child_by_index(&self, index: i32) -> Option<Obj<'a>> {
+        let child = unsafe { lvgl_sys::lv_obj_get_child(self.raw().as_ptr(), index) };
+        NonNull::new(child).and_then(|raw| unsafe { Obj::from_raw(raw) })
+    }
+
+    /// Moves the widget, with its children, to the end of `parent`'s
+    /// children, e.g. to move content between tabs or screens without
+    /// recreating it. As with `create()`, the new parent must outlive the
+    /// widget's borrows.
+    fn set_parent<'p: 'a>(&mut self, parent: &mut impl Widget<'p>) {
+        unsafe { lvgl_sys::lv_obj_set_parent(self.raw().as_ptr(), parent.raw().as_ptr()) }
+    }
+
+    /// Returns the widget's index among its parent's children, which is also
+    /// its drawing order: later children are drawn on top.
+    fn get_index(&self) -> u32 {
+        unsafe { lvgl_sys::lv_obj_get_index(self.raw().as_ptr()) }
+    }
+
+    /// Moves the widget to `index` among its parent's children, shifting the
+    /// others. Negative indices count from the last child. This changes both
+    /// the drawing order and the order in layouts, e.g. to reorder a list.
+    fn move_to_index(&mut self, index: i32) {
+        unsafe { lvgl_sys::lv_obj_move_to_index(self.raw().as_ptr(), index) }
+    }
+
+    /// Moves the widget in front of its siblings.
+    fn move_foreground(&mut self) {
+        self.move_to_index(-1);
+    }
+
+    /// Moves the widget behind its siblings.
+    fn move_background(&mut self) {
+        self.move_to_index(0);
+    }
+
+    /// Swaps the positions of two widgets in the widget tree, even if they
+    /// have different parents.
+    fn swap<'b>(&mut self, other: &mut impl Widget<'b>) {
+        unsafe { lvgl_sys::lv_obj_swap(self.raw().as_ptr(), other.raw().as_ptr()) }
+    }
+
+    /// Assigns an ID to the widget, replacing any previous one, so it can be
+    /// found with `find_by_id()` by code without a handle to it.
+    fn set_id(&mut self, id: &'static str) {
+        unsafe { widget_id::set(self.raw().as_mut(), id) }
+    }
+
+    /// Returns the ID assigned to the widget with `set_id()`, if any.
+    fn get_id(&self) -> Option<&'static str> {
+        unsafe { widget_id::get(self.raw().as_ptr()) }
+    }
+
+    /// Finds the first widget with the given ID among the widget's
+    /// descendants, depth first, e.g. `screen.find_by_id("speed_label")`.
+    fn find_by_id(&self, id: &str) -> Option<Obj<'a>> {
+        let raw = unsafe { widget_id::find(self.raw().as_ptr(), id) };
+        NonNull::new(raw).and_then(|raw| unsafe { Obj::from_raw(raw) })
+    }
+
+    /// Tests if the widget is a `W`, or a widget derived from it. Every widget
+    /// is an `Obj`.
+    fn is_widget<W: WidgetClass>(&self) -> bool {
+        let mut class = unsafe { lvgl_sys::lv_obj_get_class(self.raw().as_ptr()) };
+        while let Some(c) = unsafe { class.as_ref() } {
+            if ptr::eq(c, W::class()) {
+                return true;
+            }
+            class = c.base_class;
+        }
+        false
+    }
+
+    /// Converts the handle into a `W` if the widget is one, e.g. to get a
+    /// `Btn` back from an `Obj` found by walking the widget tree. Returns the
+    /// handle unchanged otherwise.
+    fn try_into_widget<W>(self) -> Result<W, Self>
+    where
+        W: Widget<'a> + WidgetClass,
+    {
+        if !self.is_widget::<W>() {
+            return Err(self);
+        }
+        unsafe { W::from_raw(self.raw()) }.ok_or(self)
+    }
+
+    /// Tests if the handle still refers to a live LVGL object, i.e. it was not
+    /// deleted. This walks all screens, so it is meant for debugging and
+    /// assertions.
+    fn is_valid(&self) -> bool {
+        unsafe { lvgl_sys::lv_obj_is_valid(self.raw().as_ptr()) }
+    }
+
+    /// Returns the widget's area on the screen, in absolute coordinates, after
+    /// resolving any pending layout. Use it to position custom drawing
+    /// relative to the widget.
+    fn get_coords(&self) -> Area {
+        unsafe { Area::from((*laid_out(self)).coords) }
+    }
+
+    /// Returns the widget's X position relative to its parent, after
+    /// resolving any pending layout.
+    fn get_x(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_x(laid_out(self)) }
+    }
+
+    /// Returns the widget's Y position relative to its parent, after
+    /// resolving any pending layout.
+    fn get_y(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_y(laid_out(self)) }
+    }
+
+    /// Returns the widget's width in pixels, after resolving any pending
+    /// layout.
+    fn get_width(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_width(laid_out(self)) }
+    }
+
+    /// Returns the widget's height in pixels, after resolving any pending
+    /// layout.
+    fn get_height(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_height(laid_out(self)) }
+    }
+
+    /// Returns the width of the widget's content area, i.e. without its
+    /// padding and border, after resolving any pending layout.
+    fn get_content_width(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_content_width(laid_out(self)) }
+    }
+
+    /// Returns the height of the widget's content area, after resolving any
+    /// pending layout.
+    fn get_content_height(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_content_height(laid_out(self)) }
+    }
+
+    /// Returns the width and height the widget's content needs, e.g. the size
+    /// of a label's text, after resolving any pending layout.
+    fn get_self_size(&self) -> (i16, i16) {
+        unsafe {
+            let obj = laid_out(self);
+            (
+                lvgl_sys::lv_obj_get_self_width(obj),
+                lvgl_sys::lv_obj_get_self_height(obj),
+            )
+        }
+    }
+
+    /// Sets when the widget's scrollbars are shown, e.g. `ScrollbarMode::Off`
+    /// to hide them while keeping the widget scrollable.
+    fn set_scrollbar_mode(&mut self, mode: ScrollbarMode) {
+        unsafe { lvgl_sys::lv_obj_set_scrollbar_mode(self.raw().as_ptr(), mode.into()) }
+    }
+
+    /// Returns when the widget's scrollbars are shown.
+    fn get_scrollbar_mode(&self) -> ScrollbarMode {
+        unsafe { lvgl_sys::lv_obj_get_scrollbar_mode(self.raw().as_ptr()) }.into()
+    }
+
+    /// Sets the directions the widget can be scrolled in, e.g. `Dir::VER` to
+    /// scroll a list only vertically.
+    fn set_scroll_dir(&mut self, dir: Dir) {
+        unsafe { lvgl_sys::lv_obj_set_scroll_dir(self.raw().as_ptr(), dir.into()) }
+    }
+
+    /// Returns the directions the widget can be scrolled in.
+    fn get_scroll_dir(&self) -> Dir {
+        let dir = unsafe { lvgl_sys::lv_obj_get_scroll_dir(self.raw().as_ptr()) };
+        Dir::from_bits_truncate(dir as u8)
+    }
+
+    /// Extends the area where the widget can be pressed by `pad` pixels on
+    /// each side, without changing how it is drawn. This keeps small buttons
+    /// in a tightly packed toolbar easy to tap with a finger.
+    fn set_ext_click_area(&mut self, pad: i16) {
+        unsafe { lvgl_sys::lv_obj_set_ext_click_area(self.raw().as_mut(), pad) }
+    }
+
+    /// Gets the extra space around the widget where it can be pressed.
+    fn get_ext_click_area(&self) -> i16 {
+        // LVGL stores it in the optional special attributes
+        unsafe {
+            let spec_attr = self.raw().as_ref().spec_attr;
+            spec_attr.as_ref().map_or(0, |attr| attr.ext_click_pad)
+        }
+    }
+
+    /// Tests if `point`, in absolute coordinates, presses the widget. This
+    /// accounts for the extended click area and the widget's own hit testing,
+    /// e.g. an arc's ring, and is false for widgets that are not clickable.
+    fn hit_test(&self, point: Point) -> bool {
+        let point = native_point(point);
+        unsafe { lvgl_sys::lv_obj_hit_test(laid_out(self), &point) }
+    }
+
+    /// Returns the topmost widget among the widget and its descendants that
+    /// `point`, in absolute coordinates, would press, the way input devices
+    /// find the widget under a finger or cursor. Use it on a screen to route
+    /// custom gestures, or in tests.
+    fn search_obj(&self, point: Point) -> Option<Obj<'a>> {
+        let mut point = native_point(point);
+        let obj = unsafe { lvgl_sys::lv_indev_search_obj(laid_out(self), &mut point) };
+        NonNull::new(obj).and_then(|raw| unsafe { Obj::from_raw(raw) })
+    }
+
+    /// Animates a numeric style property of a part of the widget from `from`
+    /// to `to` over `duration`, setting it locally as `set_style_*()` does:
+    ///
+    /// ```ignore
+    /// let time = Duration::from_millis(300);
+    /// // Fade in
+    /// btn.animate_style(Part::Main, StyleProp::OPA, 0, 255, time, AnimPath::EaseOut)?;
+    /// // Slide in from the left
+    /// btn.animate_style(Part::Main, StyleProp::TRANSLATE_X, -100, 0, time, AnimPath::Linear)?;
+    /// ```
+    ///
+    /// A running animation of the same property of the widget is cancelled,
+    /// and the animation is deleted with the widget. Fails with
+    /// `LvError::InvalidReference` for properties that are not numbers, such
+    /// as colors, fonts and images.
+    fn animate_style(
+        &mut self,
+        part: Self::Part,
+        prop: StyleProp,
+        from: i32,
+        to: i32,
+        duration: Duration,
+        path: AnimPath,
+    ) -> LvResult<()> {
+        anim::animate_style(self.raw(), part.into(), prop, from, to, duration, path)
+    }
+
+    /// Animates the properties of `transition` when the widget's part enters
+    /// `state`, e.g. `State::PRESSED`, or `State::DEFAULT` to animate going
+    /// back from any other state. See `StyleTransition`.
+    fn set_style_transition(
+        &mut self,
+        part: Self::Part,
+        state: State,
+        transition: &'static StyleTransition,
+    ) {
+        let selector =
+            part.into() | lvgl_sys::lv_state_t::from(state) as lvgl_sys::lv_style_selector_t;
+        unsafe {
+            lvgl_sys::lv_obj_set_style_transition(self.raw().as_mut(), transition.raw(), selector)
+        }
+    }
+
+    gen_lv_obj_style!(align, Align);
+    gen_lv_obj_style!(anim_speed, u32);
+    gen_lv_obj_style!(anim_time, u32);
+    gen_lv_obj_style!(arc_color, Color);
+    gen_lv_obj_style!(arc_opa, Opacity);
+    gen_lv_obj_style!(arc_rounded, bool);
+    gen_lv_obj_style!(arc_width, i16);
+    gen_lv_obj_style!(bg_color, Color);
+    gen_lv_obj_style!(bg_dither_mode, u8);
+    gen_lv_obj_style!(bg_grad_color, Color);
+    gen_lv_obj_style!(bg_grad_stop, i16);
+    gen_lv_obj_style!(bg_img_opa, Opacity);
+    gen_lv_obj_style!(bg_img_recolor, Color);
+    gen_lv_obj_style!(bg_img_recolor_opa, Opacity);
+    gen_lv_obj_style!(bg_img_tiled, bool);
+    gen_lv_obj_style!(bg_main_stop, i16);
+    gen_lv_obj_style!(bg_opa, Opacity);
+    gen_lv_obj_style!(blend_mode, u8);
+    gen_lv_obj_style!(border_color, Color);
+    gen_lv_obj_style!(border_opa, Opacity);
+    gen_lv_obj_style!(border_post, bool);
+    gen_lv_obj_style!(border_side, u8);
+    gen_lv_obj_style!(border_width, i16);
+    gen_lv_obj_style!(clip_corner, bool);
+    gen_lv_obj_style!(color_filter_opa, Opacity);
+    gen_lv_obj_style!(flex_flow, FlexFlow);
+    gen_lv_obj_style!(flex_grow, u8);
+    gen_lv_obj_style!(flex_main_place, FlexAlign);
+    gen_lv_obj_style!(flex_cross_place, FlexAlign);
+    gen_lv_obj_style!(flex_track_place, FlexAlign);
+    gen_lv_obj_style!(grid_cell_column_pos, i16);
+    gen_lv_obj_style!(grid_cell_column_span, i16);
+    gen_lv_obj_style!(grid_cell_row_pos, i16);
+    gen_lv_obj_style!(grid_cell_row_span, i16);
+    gen_lv_obj_style!(grid_cell_x_align, GridAlign);
+    gen_lv_obj_style!(grid_cell_y_align, GridAlign);
+    gen_lv_obj_style!(grid_column_align, GridAlign);
+    gen_lv_obj_style!(grid_row_align, GridAlign);
+    gen_lv_obj_style!(height, i16);
+    gen_lv_obj_style!(img_opa, Opacity);
+    gen_lv_obj_style!(img_recolor, Color);
+    gen_lv_obj_style!(img_recolor_opa, Opacity);
+    gen_lv_obj_style!(layout, Layout);
+    gen_lv_obj_style!(line_color, Color);
+    gen_lv_obj_style!(line_dash_gap, i16);
+    gen_lv_obj_style!(line_dash_width, i16);
+    gen_lv_obj_style!(line_opa, Opacity);
+    gen_lv_obj_style!(line_rounded, bool);
+    gen_lv_obj_style!(line_width, i16);
+    gen_lv_obj_style!(max_height, i16);
+    gen_lv_obj_style!(max_width, i16);
+    gen_lv_obj_style!(min_height, i16);
+    gen_lv_obj_style!(min_width, i16);
+    gen_lv_obj_style!(opa, Opacity);
+    gen_lv_obj_style!(outline_color, Color);
+    gen_lv_obj_style!(outline_opa, Opacity);
+    gen_lv_obj_style!(outline_pad, i16);
+    gen_lv_obj_style!(outline_width, i16);
+    gen_lv_obj_style!(pad_bottom, i16);
+    gen_lv_obj_style!(pad_column, i16);
+    gen_lv_obj_style!(pad_left, i16);
+    gen_lv_obj_style!(pad_right, i16);
+    gen_lv_obj_style!(pad_row, i16);
+    gen_lv_obj_style!(pad_top, i16);
+    gen_lv_obj_style!(radius, i16);
+    gen_lv_obj_style!(shadow_color, Color);
+    gen_lv_obj_style!(shadow_ofs_x, i16);
+    gen_lv_obj_style!(shadow_ofs_y, i16);
+    gen_lv_obj_style!(shadow_opa, Opacity);
+    gen_lv_obj_style!(shadow_spread, i16);
+    gen_lv_obj_style!(shadow_width, i16);
+    gen_lv_obj_style!(text_align, TextAlign);
+    gen_lv_obj_style!(text_color, Color);
+    gen_lv_obj_style!(text_decor, u8);
+    gen_lv_obj_style!(text_font, Font);
+    gen_lv_obj_style!(text_letter_space, i16);
+    gen_lv_obj_style!(text_line_space, i16);
+    gen_lv_obj_style!(text_opa, Opacity);
+    gen_lv_obj_style!(transform_angle, i16);
+    gen_lv_obj_style!(transform_height, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
+    gen_lv_obj_style!(transform_pivot_x, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
+    gen_lv_obj_style!(transform_pivot_y, i16);
+    gen_lv_obj_style!(transform_width, i16);
+    gen_lv_obj_style!(transform_zoom, i16);
+    gen_lv_obj_style!(translate_x, i16);
+    gen_lv_obj_style!(translate_y, i16);
+    gen_lv_obj_style!(width, i16);
+    gen_lv_obj_style!(x, i16);
+    gen_lv_obj_style!(y, i16);
+
+    /// Returns the `text_font` style property of a part of the widget in its
+    /// current state, as resolved from its local styles, added styles and the
+    /// theme, e.g. to measure text in custom draw code. The `Font` is a copy
+    /// of LVGL's, sharing its glyphs. Returns `None` if no font is set.
+    fn get_style_text_font(&self, part: Self::Part) -> Option<Font> {
+        unsafe {
+            let value = lvgl_sys::lv_obj_get_style_prop(
+                self.raw().as_ptr(),
+                part.into(),
+                lvgl_sys::lv_style_prop_t_LV_STYLE_TEXT_FONT,
+            );
+            let font = (value.ptr as *const lvgl_sys::lv_font_t).as_ref()?;
+            // Safety: the font was set up by LVGL or given to a style
+            Some(Font::new_raw(*font))
+        }
+    }
+
+    gen_lv_obj_style_get!(anim_speed, u32);
+    gen_lv_obj_style_get!(anim_time, u32);
+    gen_lv_obj_style_get!(arc_color, Color);
+    gen_lv_obj_style_get!(arc_opa, Opacity);
+    gen_lv_obj_style_get!(arc_rounded, bool);
+    gen_lv_obj_style_get!(arc_width, i16);
+    gen_lv_obj_style_get!(bg_color, Color);
+    gen_lv_obj_style_get!(bg_dither_mode, u8);
+    gen_lv_obj_style_get!(bg_grad_color, Color);
+    gen_lv_obj_style_get!(bg_grad_stop, i16);
+    gen_lv_obj_style_get!(bg_img_opa, Opacity);
+    gen_lv_obj_style_get!(bg_img_recolor, Color);
+    gen_lv_obj_style_get!(bg_img_recolor_opa, Opacity);
+    gen_lv_obj_style_get!(bg_img_tiled, bool);
+    gen_lv_obj_style_get!(bg_main_stop, i16);
+    gen_lv_obj_style_get!(bg_opa, Opacity);
+    gen_lv_obj_style_get!(blend_mode, u8);
+    gen_lv_obj_style_get!(border_color, Color);
+    gen_lv_obj_style_get!(border_opa, Opacity);
+    gen_lv_obj_style_get!(border_post, bool);
+    gen_lv_obj_style_get!(border_side, u8);
+    gen_lv_obj_style_get!(border_width, i16);
+    gen_lv_obj_style_get!(clip_corner, bool);
+    gen_lv_obj_style_get!(color_filter_opa, Opacity);
+    gen_lv_obj_style_get!(flex_grow, u8);
+    gen_lv_obj_style_get!(grid_cell_column_pos, i16);
+    gen_lv_obj_style_get!(grid_cell_column_span, i16);
+    gen_lv_obj_style_get!(grid_cell_row_pos, i16);
+    gen_lv_obj_style_get!(grid_cell_row_span, i16);
+    gen_lv_obj_style_get!(height, i16);
+    gen_lv_obj_style_get!(img_opa, Opacity);
+    gen_lv_obj_style_get!(img_recolor, Color);
+    gen_lv_obj_style_get!(img_recolor_opa, Opacity);
+    gen_lv_obj_style_get!(line_color, Color);
+    gen_lv_obj_style_get!(line_dash_gap, i16);
+    gen_lv_obj_style_get!(line_dash_width, i16);
+    gen_lv_obj_style_get!(line_opa, Opacity);
+    gen_lv_obj_style_get!(line_rounded, bool);
+    gen_lv_obj_style_get!(line_width, i16);
+    gen_lv_obj_style_get!(max_height, i16);
+    gen_lv_obj_style_get!(max_width, i16);
+    gen_lv_obj_style_get!(min_height, i16);
+    gen_lv_obj_style_get!(min_width, i16);
+    gen_lv_obj_style_get!(opa, Opacity);
+    gen_lv_obj_style_get!(outline_color, Color);
+    gen_lv_obj_style_get!(outline_opa, Opacity);
+    gen_lv_obj_style_get!(outline_pad, i16);
+    gen_lv_obj_style_get!(outline_width, i16);
+    gen_lv_obj_style_get!(pad_bottom, i16);
+    gen_lv_obj_style_get!(pad_column, i16);
+    gen_lv_obj_style_get!(pad_left, i16);
+    gen_lv_obj_style_get!(pad_right, i16);
+    gen_lv_obj_style_get!(pad_row, i16);
+    gen_lv_obj_style_get!(pad_top, i16);
+    gen_lv_obj_style_get!(radius, i16);
+    gen_lv_obj_style_get!(shadow_color, Color);
+    gen_lv_obj_style_get!(shadow_ofs_x, i16);
+    gen_lv_obj_style_get!(shadow_ofs_y, i16);
+    gen_lv_obj_style_get!(shadow_opa, Opacity);
+    gen_lv_obj_style_get!(shadow_spread, i16);
+    gen_lv_obj_style_get!(shadow_width, i16);
+    gen_lv_obj_style_get!(text_align, TextAlign);
+    gen_lv_obj_style_get!(text_color, Color);
+    gen_lv_obj_style_get!(text_decor, u8);
+    gen_lv_obj_style_get!(text_letter_space, i16);
+    gen_lv_obj_style_get!(text_line_space, i16);
+    gen_lv_obj_style_get!(text_opa, Opacity);
+    gen_lv_obj_style_get!(transform_angle, i16);
+    gen_lv_obj_style_get!(transform_height, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
+    gen_lv_obj_style_get!(transform_pivot_x, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
+    gen_lv_obj_style_get!(transform_pivot_y, i16);
+    gen_lv_obj_style_get!(transform_width, i16);
+    gen_lv_obj_style_get!(transform_zoom, i16);
+    gen_lv_obj_style_get!(translate_x, i16);
+    gen_lv_obj_style_get!(translate_y, i16);
+    gen_lv_obj_style_get!(width, i16);
+    gen_lv_obj_style_get!(x, i16);
+    gen_lv_obj_style_get!(y, i16);
+}
+
+/// Widget types with an LVGL class (e.g. `lv_btn_class`), which generic
+/// handles can be checked against before converting them to the type. See
+/// `Widget::try_into_widget()`.
+///
+/// # Safety
+///
+/// `class()` must return the class of the objects the type wraps.
+pub unsafe trait WidgetClass {
+    /// Returns the LVGL class of the widget type.
+    fn class() -> &'static lvgl_sys::lv_obj_class_t;
+}
+
+unsafe impl WidgetClass for Obj<'_> {
+    fn class() -> &'static lvgl_sys::lv_obj_class_t {
+        unsafe { &lvgl_sys::lv_obj_class }
+    }
 }
 
 impl<'a> Widget<'a> for Obj<'a> {
@@ -231,15 +883,13 @@ macro_rules! define_object {
             {
                 use $crate::NativeObject;
                 unsafe {
-                    let obj = self.raw().as_mut();
-                    obj.user_data = $crate::Box::into_raw($crate::Box::new(f)) as *mut _;
                     lvgl_sys::lv_obj_add_event_cb(
-                        obj,
+                        self.raw().as_mut(),
                         lvgl_sys::lv_event_cb_t::Some(
                             $crate::support::event_callback::<'a, Self, F>,
                         ),
                         lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
-                        obj.user_data,
+                        $crate::Box::into_raw($crate::Box::new(f)) as *mut _,
                     );
                 }
                 Ok(())
@@ -299,16 +949,45 @@ macro_rules! define_object {
 //     }
 // }
 
+/// Resolves the pending layout of an object's screen, so its geometry is up
+/// to date, and returns the object.
+fn laid_out(obj: &impl NativeObject) -> *mut lvgl_sys::lv_obj_t {
+    let obj = obj.raw().as_ptr();
+    unsafe { lvgl_sys::lv_obj_update_layout(obj) };
+    obj
+}
+
+fn native_point(point: Point) -> lvgl_sys::lv_point_t {
+    lvgl_sys::lv_point_t {
+        x: point.x as lvgl_sys::lv_coord_t,
+        y: point.y as lvgl_sys::lv_coord_t,
+    }
+}
+
+/// The parts of a widget that styles can be applied to. Which parts a widget
+/// has depends on its type; see each widget's LVGL documentation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Part {
+    /// The background of the widget.
     Main,
+    /// The scrollbars.
     Scrollbar,
+    /// The indicator of e.g. a slider, bar or arc.
     Indicator,
+    /// The handle to adjust a slider or arc's value.
     Knob,
+    /// The selected option or cell, e.g. of a roller or table.
     Selected,
+    /// The items of widgets with several similar elements, e.g. the buttons of
+    /// a button matrix.
     Items,
+    /// The ticks of e.g. a chart or meter.
     Ticks,
+    /// The cursor of e.g. a text area.
     Cursor,
+    /// The first part custom widgets can use.
     CustomFirst,
+    /// Every part of the widget.
     Any,
 }
 
@@ -334,3 +1013,321 @@ impl From<Part> for lvgl_sys::lv_part_t {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+    use crate::widgets::{Btn, Label};
+
+    #[test]
+    fn local_style_on_part() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut obj = Obj::builder().build(&mut screen).unwrap();
+        obj.set_style_pad_top(Part::Scrollbar, 7);
+        let pad_top = |part: Part| unsafe {
+            lvgl_sys::lv_obj_get_style_prop(
+                obj.raw().as_ref(),
+                part.into(),
+                lvgl_sys::lv_style_prop_t_LV_STYLE_PAD_TOP,
+            )
+            .num
+        };
+        assert_eq!(pad_top(Part::Scrollbar), 7);
+    }
+
+    #[test]
+    fn read_back_computed_styles() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut label = Label::create(&mut screen).unwrap();
+        label.set_style_pad_top(Part::Main, 5);
+        label.set_style_bg_color(Part::Main, Color::from_rgb((255, 0, 0)));
+        label.set_style_text_align(Part::Main, TextAlign::Right);
+        assert_eq!(label.get_style_pad_top(Part::Main), 5);
+        assert_eq!(label.get_style_bg_color(Part::Main).r(), 255);
+        assert!(matches!(
+            label.get_style_text_align(Part::Main),
+            TextAlign::Right
+        ));
+
+        // Unset properties resolve to the theme's or the defaults
+        assert_eq!(label.get_style_pad_top(Part::Scrollbar), 0);
+        assert_eq!(
+            label.get_style_opa(Part::Main).bits(),
+            lvgl_sys::LV_OPA_COVER
+        );
+        let font = label.get_style_text_font(Part::Main).unwrap();
+        assert!(font.line_height() > 0);
+    }
+
+    #[test]
+    fn apply_const_styles() {
+        use crate::style::{ConstStyleProp, StyleProp};
+        static PAD: ConstStyle = ConstStyle::new(&[
+            ConstStyleProp::num(StyleProp::PAD_TOP, 3),
+            ConstStyleProp::END,
+        ]);
+        static WIDE_PAD: ConstStyle = ConstStyle::new(&[
+            ConstStyleProp::num(StyleProp::PAD_TOP, 9),
+            ConstStyleProp::num(StyleProp::WIDTH, 120),
+            ConstStyleProp::END,
+        ]);
+
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut obj = Obj::builder().build(&mut screen).unwrap();
+        obj.apply_styles(&[(&PAD, Part::Main), (&WIDE_PAD, Part::Scrollbar)]);
+        let raw = obj.raw();
+        let pad_top = |part: Part| unsafe {
+            lvgl_sys::lv_obj_get_style_prop(
+                raw.as_ref(),
+                part.into(),
+                lvgl_sys::lv_style_prop_t_LV_STYLE_PAD_TOP,
+            )
+            .num
+        };
+        assert_eq!(pad_top(Part::Main), 3);
+        assert_eq!(pad_top(Part::Scrollbar), 9);
+
+        obj.add_const_style(Part::Main, &WIDE_PAD);
+        assert_eq!(pad_top(Part::Main), 9);
+        let width = unsafe {
+            lvgl_sys::lv_obj_update_layout(raw.as_ptr());
+            lvgl_sys::lv_obj_get_width(raw.as_ptr())
+        };
+        assert_eq!(width, 120);
+    }
+
+    #[test]
+    fn set_width_out_of_range() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut obj = Obj::create(&mut screen).unwrap();
+        obj.set_width(40u32).unwrap();
+        assert_eq!(obj.set_height(u32::MAX), Err(LvError::OutOfRange));
+        assert_eq!(obj.get_style_width(Part::Main), 40);
+    }
+
+    #[test]
+    fn siblings_share_parent() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut first = Obj::create(&mut screen).unwrap();
+        let second = Obj::create(&mut screen).unwrap();
+        let nested = Obj::create(&mut first).unwrap();
+        assert_eq!(screen.get_child_by_index(-1).unwrap().raw(), second.raw());
+        assert_eq!(first.get_child_by_index(0).unwrap().raw(), nested.raw());
+    }
+
+    #[test]
+    fn reorder_children() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut panel = Obj::builder().build(&mut screen).unwrap();
+        let mut first = Obj::create(&mut panel).unwrap();
+        let mut second = Obj::create(&mut panel).unwrap();
+        let mut third = Obj::create(&mut panel).unwrap();
+        let order = |panel: &Obj| [0, 1, 2].map(|i| panel.get_child_by_index(i).unwrap().raw());
+
+        first.move_foreground();
+        assert_eq!(order(&panel), [second.raw(), third.raw(), first.raw()]);
+        first.move_background();
+        assert_eq!(first.get_index(), 0);
+        third.move_to_index(1);
+        assert_eq!(order(&panel), [first.raw(), third.raw(), second.raw()]);
+        first.swap(&mut second);
+        assert_eq!(order(&panel), [second.raw(), third.raw(), first.raw()]);
+    }
+
+    #[test]
+    fn move_between_parents() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut from = Obj::builder().build(&mut screen).unwrap();
+        let mut to = Obj::builder().build(&mut screen).unwrap();
+        let mut card = Obj::create(&mut from).unwrap();
+        let content = Obj::create(&mut card).unwrap();
+
+        card.set_parent(&mut to);
+        assert_eq!(from.get_child_count(), 0);
+        assert_eq!(to.get_child_by_index(0).unwrap().raw(), card.raw());
+        assert_eq!(card.get_child_by_index(0).unwrap().raw(), content.raw());
+
+        // Deleting the old parent leaves the moved widget alone
+        from.delete();
+        assert!(card.is_valid());
+        assert!(content.is_valid());
+    }
+
+    #[test]
+    fn geometry_after_layout() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut panel = Obj::builder()
+            .size(100, 80)
+            .align(Align::TopLeft, 10, 20)
+            .build(&mut screen)
+            .unwrap();
+        panel.set_style_pad_left(Part::Main, 5);
+        panel.set_style_pad_right(Part::Main, 5);
+        panel.set_style_pad_top(Part::Main, 5);
+        panel.set_style_pad_bottom(Part::Main, 5);
+        panel.set_style_border_width(Part::Main, 0);
+        let mut child = Obj::create(&mut panel).unwrap();
+        child.set_size(Size::pct(50), 30);
+
+        // No `task_handler()` ran, so the layout is still pending here
+        assert_eq!(panel.get_x(), 10);
+        assert_eq!(panel.get_y(), 20);
+        assert_eq!((panel.get_width(), panel.get_height()), (100, 80));
+        assert_eq!(panel.get_content_width(), 90);
+        assert_eq!(panel.get_content_height(), 70);
+        assert_eq!(child.get_width(), 45);
+        let coords = child.get_coords();
+        assert_eq!((coords.x1, coords.y1), (15, 25));
+        assert_eq!((coords.x2, coords.y2), (59, 54));
+        let mut label = Label::create(&mut panel).unwrap();
+        label.set_text("Speed").unwrap();
+        let (width, height) = label.get_self_size();
+        assert!(width > 0 && height > 0);
+    }
+
+    #[test]
+    fn find_widget_at_point() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut btn = Btn::builder()
+            .size(50, 30)
+            .pos(20, 20)
+            .build(&mut screen)
+            .unwrap();
+        let label = Label::create(&mut btn).unwrap();
+
+        assert!(btn.hit_test(Point::new(30, 30)));
+        assert!(!btn.hit_test(Point::new(10, 10)));
+        assert!(!label.hit_test(Point::new(30, 30)));
+        // Labels are not clickable, so the button under it is found
+        let found = screen.search_obj(Point::new(30, 30)).unwrap();
+        assert_eq!(found.raw(), btn.raw());
+        let found = screen.search_obj(Point::new(200, 200)).unwrap();
+        assert_eq!(found.raw(), screen.raw());
+    }
+
+    #[test]
+    fn vertical_scrolling_without_scrollbars() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let list = Obj::builder()
+            .scrollbar_mode(ScrollbarMode::Off)
+            .scroll_dir(Dir::VER)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(list.get_scrollbar_mode(), ScrollbarMode::Off);
+        assert_eq!(list.get_scroll_dir(), Dir::VER);
+        assert!(list.get_scroll_dir().contains(Dir::TOP));
+    }
+
+    #[test]
+    fn tappable_toolbar_buttons() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut btn = Btn::builder()
+            .size(16, 16)
+            .pos(20, 20)
+            .ext_click_area(10)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(btn.get_ext_click_area(), 10);
+        assert_eq!(btn.get_width(), 16);
+        assert!(btn.hit_test(Point::new(12, 12)));
+        assert!(!btn.hit_test(Point::new(5, 5)));
+
+        btn.set_ext_click_area(0);
+        assert!(!btn.hit_test(Point::new(12, 12)));
+    }
+
+    #[test]
+    fn disable_until_valid() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut save = Btn::builder()
+            .state(State::DISABLED)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(save.get_state(), State::DISABLED);
+
+        save.clear_state(State::DISABLED);
+        save.add_state(State::CHECKED | State::FOCUSED);
+        assert!(save.has_state(State::CHECKED));
+        assert!(save.has_state(State::CHECKED | State::FOCUSED));
+        assert!(!save.has_state(State::CHECKED | State::DISABLED));
+        save.clear_state(State::FOCUSED);
+        assert_eq!(save.get_state(), State::CHECKED);
+    }
+
+    #[test]
+    fn delete_with_children() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let screen_raw = screen.raw().as_ptr();
+        let children = || unsafe { lvgl_sys::lv_obj_get_child_cnt(screen_raw) };
+        let before = children();
+        let mut parent = Obj::builder().build(&mut screen).unwrap();
+        let child = Obj::builder().build(&mut parent).unwrap();
+        assert_eq!(children(), before + 1);
+
+        parent.delete();
+        assert_eq!(children(), before);
+        assert!(!child.is_valid());
+    }
+
+    #[test]
+    fn event_closures_dropped_on_delete() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut parent = Obj::builder().build(&mut screen).unwrap();
+        let mut child = Btn::create(&mut parent).unwrap();
+        let state = std::rc::Rc::new(());
+        let captured = state.clone();
+        child.on_event(move |_, _| drop(captured.clone())).unwrap();
+        assert_eq!(std::rc::Rc::strong_count(&state), 2);
+
+        parent.delete();
+        assert_eq!(std::rc::Rc::strong_count(&state), 1);
+    }
+
+    #[test]
+    fn checked_downcast() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let btn = Btn::create(&mut screen).unwrap();
+        let obj = unsafe { Obj::from_raw(btn.raw()) }.unwrap();
+        assert!(obj.is_widget::<Obj>());
+        assert!(obj.is_widget::<Btn>());
+
+        let obj = obj.try_into_widget::<Label>().unwrap_err();
+        assert!(obj.try_into_widget::<Btn>().is_ok());
+    }
+
+    #[test]
+    fn find_children() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut panel = Obj::builder().build(&mut screen).unwrap();
+        let first = Obj::builder().build(&mut panel).unwrap();
+        let mut last = Obj::builder().build(&mut panel).unwrap();
+        last.set_id("speed_label");
+        last.set_id("speed");
+
+        assert_eq!(panel.get_child_count(), 2);
+        assert_eq!(panel.get_child_by_index(0).unwrap().raw(), first.raw());
+        assert_eq!(panel.get_child_by_index(-1).unwrap().raw(), last.raw());
+        assert!(panel.get_child_by_index(2).is_none());
+
+        assert_eq!(last.get_id(), Some("speed"));
+        assert_eq!(screen.find_by_id("speed").unwrap().raw(), last.raw());
+        assert!(screen.find_by_id("speed_label").is_none());
+    }
+}
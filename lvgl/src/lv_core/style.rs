@@ -15,11 +15,14 @@
 //! All methods on the `Style` type directly lower to their C LVGL
 //! counterparts.
 
+use crate::misc::anim::AnimPath;
 use crate::{font::Font, Align, Box, Color, TextAlign};
 use core::{
     ffi::c_uint,
     fmt::{self, Debug},
     mem::{self, MaybeUninit},
+    ptr,
+    time::Duration,
 };
 use paste::paste;
 
@@ -83,6 +86,59 @@ impl From<Opacity> for u8 {
     }
 }
 
+/// Conversion of a style property's resolved value, as read back by the
+/// `Widget::get_style_*()` methods.
+pub(crate) trait FromStyleValue {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self;
+}
+
+impl FromStyleValue for i16 {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        unsafe { value.num as i16 }
+    }
+}
+
+impl FromStyleValue for u8 {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        unsafe { value.num as u8 }
+    }
+}
+
+impl FromStyleValue for u32 {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        unsafe { value.num as u32 }
+    }
+}
+
+impl FromStyleValue for bool {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        unsafe { value.num != 0 }
+    }
+}
+
+impl FromStyleValue for Color {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        Color::from_raw(unsafe { value.color })
+    }
+}
+
+impl FromStyleValue for Opacity {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        Opacity::from_bits_retain(unsafe { value.num as u32 })
+    }
+}
+
+impl FromStyleValue for TextAlign {
+    fn from_style_value(value: lvgl_sys::lv_style_value_t) -> Self {
+        match unsafe { value.num as u32 } {
+            lvgl_sys::LV_TEXT_ALIGN_CENTER => TextAlign::Center,
+            lvgl_sys::LV_TEXT_ALIGN_LEFT => TextAlign::Left,
+            lvgl_sys::LV_TEXT_ALIGN_RIGHT => TextAlign::Right,
+            _ => TextAlign::Auto,
+        }
+    }
+}
+
 bitflags! {
     pub struct GridAlign: c_uint {
         const START = lvgl_sys::lv_grid_align_t_LV_GRID_ALIGN_START;
@@ -501,8 +557,8 @@ impl Style {
 
     gen_lv_style!(set_align, Align);
     //gen_lv_style!(set_anim, );
-    //gen_lv_style!(set_anim_speed, );
-    //gen_lv_style!(set_anim_time, );
+    gen_lv_style!(set_anim_speed, u32);
+    gen_lv_style!(set_anim_time, u32);
     gen_lv_style!(set_arc_color, Color);
     //gen_lv_style!(set_arc_img_src, );
     gen_lv_style!(set_arc_opa, Opacity);
@@ -590,14 +646,305 @@ impl Style {
     gen_lv_style!(set_text_opa, Opacity);
     gen_lv_style!(set_transform_angle, i16);
     gen_lv_style!(set_transform_height, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
     gen_lv_style!(set_transform_pivot_x, i16);
+    #[cfg(any(lvgl_v8_3, lvgl_v9))]
     gen_lv_style!(set_transform_pivot_y, i16);
     gen_lv_style!(set_transform_width, i16);
     gen_lv_style!(set_transform_zoom, i16);
-    //gen_lv_style!(set_transition, );
+
+    /// Animates changes of the properties of `transition` when an object
+    /// using the style enters a state the style applies to.
+    pub fn set_transition(&mut self, transition: &'static StyleTransition) {
+        unsafe { lvgl_sys::lv_style_set_transition(self.raw.as_mut(), transition.raw()) }
+    }
     gen_lv_style!(set_translate_x, i16);
     gen_lv_style!(set_translate_y, i16);
     gen_lv_style!(set_width, i16);
     gen_lv_style!(set_x, i16);
     gen_lv_style!(set_y, i16);
 }
+
+/// One property of a [`ConstStyle`], built in constant expressions with
+/// `num()`, `color()` or `opa()`. A property list must end with
+/// [`ConstStyleProp::END`].
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct ConstStyleProp(lvgl_sys::lv_style_const_prop_t);
+
+impl ConstStyleProp {
+    /// Terminates a property list, like `LV_STYLE_PROP_INV` in C.
+    pub const END: Self = Self(lvgl_sys::lv_style_const_prop_t {
+        prop: 0,
+        value: lvgl_sys::lv_style_value_t { num: 0 },
+    });
+
+    /// A numeric property, e.g. `ConstStyleProp::num(StyleProp::WIDTH, 100)`.
+    pub const fn num(prop: StyleProp, value: i32) -> Self {
+        Self(lvgl_sys::lv_style_const_prop_t {
+            prop: prop.bits() as _,
+            value: lvgl_sys::lv_style_value_t { num: value },
+        })
+    }
+
+    /// A color property, e.g.
+    /// `ConstStyleProp::color(StyleProp::BG_COLOR, Color::from_hex(0xFF0000))`.
+    pub const fn color(prop: StyleProp, value: Color) -> Self {
+        Self(lvgl_sys::lv_style_const_prop_t {
+            prop: prop.bits() as _,
+            value: lvgl_sys::lv_style_value_t { color: value.raw },
+        })
+    }
+
+    /// An opacity property, e.g.
+    /// `ConstStyleProp::opa(StyleProp::BG_OPA, Opacity::OPA_50)`.
+    pub const fn opa(prop: StyleProp, value: Opacity) -> Self {
+        Self::num(prop, value.bits() as i32)
+    }
+}
+
+/// The layout of an `lv_style_t` initialized with `LV_STYLE_CONST_INIT()`,
+/// whose `prop1` and `is_const` bitfields cannot be set in constant
+/// expressions through the bindings.
+#[repr(C)]
+struct RawConstStyle {
+    #[cfg(lvgl_style_sentinel)]
+    sentinel: u32,
+    const_props: *const lvgl_sys::lv_style_const_prop_t,
+    prop1_is_const: u16,
+    has_group: u8,
+    prop_cnt: u8,
+}
+
+/// `is_const` is the last bit of the `prop1 : 15, is_const : 1` bitfield.
+#[cfg(target_endian = "little")]
+const IS_CONST: u16 = 1 << 15;
+#[cfg(target_endian = "big")]
+const IS_CONST: u16 = 1;
+
+/// A style whose properties are fixed at compile time, the equivalent of
+/// `LV_STYLE_CONST_INIT()`. Declared as a `static`, it is stored in flash
+/// rather than RAM, and can be added to any number of widgets with
+/// `Widget::add_const_style()` or `Widget::apply_styles()`:
+///
+/// ```ignore
+/// use lvgl::style::{ConstStyle, ConstStyleProp, StyleProp};
+///
+/// static BUTTON: ConstStyle = ConstStyle::new(&[
+///     ConstStyleProp::color(StyleProp::BG_COLOR, Color::from_hex(0x2196F3)),
+///     ConstStyleProp::num(StyleProp::RADIUS, 8),
+///     ConstStyleProp::END,
+/// ]);
+///
+/// btn.apply_styles(&[(&BUTTON, Part::Main)]);
+/// ```
+#[repr(transparent)]
+pub struct ConstStyle(lvgl_sys::lv_style_t);
+
+// LVGL never writes to a style marked as constant
+unsafe impl Sync for ConstStyle {}
+
+impl ConstStyle {
+    /// Creates a style with the given properties, which must end with
+    /// [`ConstStyleProp::END`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant or static, if the
+    /// properties are not terminated.
+    pub const fn new(props: &'static [ConstStyleProp]) -> Self {
+        assert!(
+            !props.is_empty() && props[props.len() - 1].0.prop == 0,
+            "const style properties must end with `ConstStyleProp::END`"
+        );
+        let raw = RawConstStyle {
+            #[cfg(lvgl_style_sentinel)]
+            sentinel: lvgl_sys::LV_STYLE_SENTINEL_VALUE as u32,
+            const_props: props.as_ptr() as *const _,
+            prop1_is_const: IS_CONST,
+            has_group: 0xFF,
+            prop_cnt: 0,
+        };
+        // SAFETY: `RawConstStyle` mirrors the layout of `lv_style_t`, and
+        // `transmute` checks that their sizes match.
+        Self(unsafe { mem::transmute::<RawConstStyle, lvgl_sys::lv_style_t>(raw) })
+    }
+
+    /// Returns the value of a property, if the style sets it.
+    pub fn get_prop(&self, prop: StyleProp) -> Option<lvgl_sys::lv_style_value_t> {
+        let mut value = MaybeUninit::<lvgl_sys::lv_style_value_t>::uninit();
+        let result =
+            unsafe { lvgl_sys::lv_style_get_prop(&self.0, prop.bits() as _, value.as_mut_ptr()) };
+        if <u8 as Into<u32>>::into(result) == lvgl_sys::LV_RES_OK {
+            Some(unsafe { value.assume_init() })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn raw(&self) -> *mut lvgl_sys::lv_style_t {
+        &self.0 as *const _ as *mut _
+    }
+}
+
+impl Debug for ConstStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConstStyle").finish_non_exhaustive()
+    }
+}
+
+/// Which style properties to animate, and how, when an object changes state,
+/// the equivalent of `lv_style_transition_dsc_t`. Declared as a `static`
+/// with the [`style_transition!`](crate::style_transition!) macro, which
+/// stores the list of properties alongside:
+///
+/// ```ignore
+/// use lvgl::style::{StyleProp, StyleTransition};
+/// use lvgl::misc::anim::AnimPath;
+///
+/// static PRESS: StyleTransition = lvgl::style_transition!(
+///     [StyleProp::BG_COLOR, StyleProp::TRANSFORM_WIDTH],
+///     Duration::from_millis(200),
+///     path: AnimPath::EaseOut,
+/// );
+///
+/// btn.set_style_transition(Part::Main, State::PRESSED, &PRESS);
+/// ```
+#[repr(transparent)]
+pub struct StyleTransition(lvgl_sys::lv_style_transition_dsc_t);
+
+// LVGL only reads transition descriptions
+unsafe impl Sync for StyleTransition {}
+
+impl StyleTransition {
+    /// Creates a transition of the properties in `props`, which must end
+    /// with `0`, over `time`. Prefer the `style_transition!` macro, which
+    /// builds the list from `StyleProp`s.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant or static, if the
+    /// properties are not terminated.
+    pub const fn new(props: &'static [lvgl_sys::lv_style_prop_t], time: Duration) -> Self {
+        assert!(
+            !props.is_empty() && props[props.len() - 1] == 0,
+            "transition properties must end with 0"
+        );
+        Self(lvgl_sys::lv_style_transition_dsc_t {
+            props: props.as_ptr(),
+            user_data: ptr::null_mut(),
+            path_xcb: AnimPath::Linear.callback(),
+            time: millis(time),
+            delay: 0,
+        })
+    }
+
+    /// Sets how the properties progress from their old to their new values.
+    pub const fn path(mut self, path: AnimPath) -> Self {
+        self.0.path_xcb = path.callback();
+        self
+    }
+
+    /// Sets the delay before the properties start changing.
+    pub const fn delay(mut self, delay: Duration) -> Self {
+        self.0.delay = millis(delay);
+        self
+    }
+
+    pub(crate) fn raw(&'static self) -> *const lvgl_sys::lv_style_transition_dsc_t {
+        &self.0
+    }
+}
+
+impl Debug for StyleTransition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StyleTransition")
+            .field("time", &self.0.time)
+            .field("delay", &self.0.delay)
+            .finish_non_exhaustive()
+    }
+}
+
+const fn millis(duration: Duration) -> u32 {
+    let millis = duration.as_millis();
+    if millis > u32::MAX as u128 {
+        u32::MAX
+    } else {
+        millis as u32
+    }
+}
+
+/// Declares a [`StyleTransition`] of a list of `StyleProp`s over a
+/// `Duration`, optionally followed by `path: AnimPath` and `delay: Duration`
+/// settings. See `StyleTransition`.
+#[macro_export]
+macro_rules! style_transition {
+    ([$($prop:expr),+ $(,)?], $time:expr $(, $setting:ident: $value:expr)* $(,)?) => {{
+        const PROPS: &[$crate::sys::lv_style_prop_t] = &[
+            $($crate::style::StyleProp::bits(&$prop) as $crate::sys::lv_style_prop_t,)+
+            0,
+        ];
+        $crate::style::StyleTransition::new(PROPS, $time)$(.$setting($value))*
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static STYLE: ConstStyle = ConstStyle::new(&[
+        ConstStyleProp::num(StyleProp::WIDTH, 50),
+        ConstStyleProp::color(StyleProp::BG_COLOR, Color::from_hex(0xFF0000)),
+        ConstStyleProp::opa(StyleProp::BG_OPA, Opacity::OPA_50),
+        ConstStyleProp::END,
+    ]);
+
+    #[test]
+    fn const_style_props() {
+        let width = STYLE.get_prop(StyleProp::WIDTH).unwrap();
+        assert_eq!(unsafe { width.num }, 50);
+        let color = STYLE.get_prop(StyleProp::BG_COLOR).unwrap();
+        let color = Color::from_raw(unsafe { color.color });
+        assert_eq!(color.to_u32(), Color::from_hex(0xFF0000).to_u32());
+        let opa = STYLE.get_prop(StyleProp::BG_OPA).unwrap();
+        assert_eq!(unsafe { opa.num }, Opacity::OPA_50.bits() as i32);
+        assert!(STYLE.get_prop(StyleProp::HEIGHT).is_none());
+    }
+
+    static FADE: StyleTransition = crate::style_transition!(
+        [StyleProp::BG_COLOR, StyleProp::BG_OPA],
+        Duration::from_millis(150),
+        path: AnimPath::EaseOut,
+        delay: Duration::from_millis(20),
+    );
+
+    #[test]
+    fn style_transition() {
+        let props = unsafe { core::slice::from_raw_parts(FADE.0.props, 3) };
+        assert_eq!(
+            props,
+            [
+                StyleProp::BG_COLOR.bits() as lvgl_sys::lv_style_prop_t,
+                StyleProp::BG_OPA.bits() as _,
+                0
+            ]
+        );
+        assert_eq!(FADE.0.time, 150);
+        assert_eq!(FADE.0.delay, 20);
+        assert_eq!(FADE.0.path_xcb, AnimPath::EaseOut.callback());
+
+        crate::tests::initialize_test(false);
+        let mut style = Style::default();
+        style.set_transition(&FADE);
+        let mut value = MaybeUninit::<lvgl_sys::lv_style_value_t>::uninit();
+        let value = unsafe {
+            lvgl_sys::lv_style_get_prop(
+                style.raw.as_ref(),
+                StyleProp::TRANSITION.bits() as _,
+                value.as_mut_ptr(),
+            );
+            value.assume_init().ptr
+        };
+        assert_eq!(value, FADE.raw() as *const _);
+    }
+}
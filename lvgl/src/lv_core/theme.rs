@@ -0,0 +1,190 @@
+//! Themes, which style widgets as they are created
+//!
+//! LVGL applies a display's theme to every widget created on it. The helpers
+//! here extend the display's current theme rather than replacing it, except
+//! for `use_mono_theme()`, which switches to LVGL's monochrome theme if it is
+//! enabled.
+
+use crate::guard::guard;
+use crate::lv_core::style::{Opacity, Style};
+use crate::{Box, Color, Display};
+
+/// Outlines that make keyboard and encoder focus visible, so widgets do not
+/// each need a focused style:
+///
+/// ```ignore
+/// FocusRing::default().install(&display);
+/// let _group = Group::create_default()?;
+/// ```
+///
+/// The outlines are shown for focus from a keypad or encoder, not for
+/// widgets that were touched or clicked, and are added to every widget LVGL
+/// adds to the default group, e.g. buttons, sliders and text areas.
+#[derive(Clone, Copy)]
+pub struct FocusRing {
+    /// The color of the outline of a focused widget.
+    pub color: Color,
+    /// The color of the outline of a widget being edited with an encoder.
+    pub edited_color: Color,
+    /// The width of the outline, in pixels.
+    pub width: i16,
+    /// The gap between the widget and its outline, in pixels.
+    pub pad: i16,
+}
+
+impl Default for FocusRing {
+    fn default() -> Self {
+        Self {
+            color: Color::from_hex(0x2196F3),
+            edited_color: Color::from_hex(0xFF9800),
+            width: 2,
+            pad: 2,
+        }
+    }
+}
+
+/// A theme applying the focus ring after the theme it extends.
+#[repr(C)]
+struct FocusTheme {
+    theme: lvgl_sys::lv_theme_t,
+    focused: Style,
+    edited: Style,
+}
+
+impl FocusRing {
+    /// Adds the outlines to the theme of `display`. Only widgets created
+    /// afterwards get them, so install the focus ring before building the
+    /// UI. The theme stays in LVGL memory for as long as the display uses it.
+    pub fn install(&self, display: &Display) {
+        let focused = self.outline(self.color);
+        let edited = self.outline(self.edited_color);
+
+        unsafe {
+            let disp = display.disp.as_ptr();
+            let parent = lvgl_sys::lv_disp_get_theme(disp);
+            let mut theme: lvgl_sys::lv_theme_t = if parent.is_null() {
+                core::mem::zeroed()
+            } else {
+                *parent
+            };
+            theme.disp = disp;
+            let focus = Box::into_raw(Box::new(FocusTheme {
+                theme,
+                focused,
+                edited,
+            }));
+            (*focus).theme.user_data = focus as *mut _;
+            lvgl_sys::lv_theme_set_parent(&mut (*focus).theme, parent);
+            lvgl_sys::lv_theme_set_apply_cb(&mut (*focus).theme, Some(apply_focus_ring));
+            lvgl_sys::lv_disp_set_theme(disp, &mut (*focus).theme);
+        }
+    }
+
+    fn outline(&self, color: Color) -> Style {
+        let mut style = Style::default();
+        style.set_outline_color(color);
+        style.set_outline_opa(Opacity::OPA_COVER);
+        style.set_outline_width(self.width);
+        style.set_outline_pad(self.pad);
+        style
+    }
+}
+
+/// Replaces the theme of `display` with LVGL's monochrome theme, which draws
+/// widgets with black and white fills and borders only, so that they stay
+/// legible on 1-bit displays such as OLEDs and memory LCDs. With `dark`,
+/// widgets are light on a black background. The theme keeps the display's
+/// current default font. Only widgets created afterwards are styled by it.
+///
+/// Only available with `LV_USE_THEME_MONO` enabled in `lv_conf.h`.
+#[cfg(lvgl_theme_mono)]
+pub fn use_mono_theme(display: &Display, dark: bool) {
+    unsafe {
+        let disp = display.disp.as_ptr();
+        let font = lvgl_sys::lv_obj_get_style_prop(
+            lvgl_sys::lv_disp_get_scr_act(disp),
+            lvgl_sys::LV_PART_MAIN,
+            lvgl_sys::lv_style_prop_t_LV_STYLE_TEXT_FONT,
+        )
+        .ptr as *const lvgl_sys::lv_font_t;
+        let theme = lvgl_sys::lv_theme_mono_init(disp, dark, font);
+        lvgl_sys::lv_disp_set_theme(disp, theme);
+    }
+}
+
+unsafe extern "C" fn apply_focus_ring(
+    theme: *mut lvgl_sys::lv_theme_t,
+    obj: *mut lvgl_sys::lv_obj_t,
+) {
+    guard("focus ring theme", || {
+        let focus = (*theme).user_data as *mut FocusTheme;
+        if !lvgl_sys::lv_obj_is_group_def(obj) {
+            return;
+        }
+        lvgl_sys::lv_obj_add_style(
+            obj,
+            (*focus).focused.raw.as_mut(),
+            lvgl_sys::LV_STATE_FOCUS_KEY as _,
+        );
+        if lvgl_sys::lv_obj_is_editable(obj) {
+            lvgl_sys::lv_obj_add_style(
+                obj,
+                (*focus).edited.raw.as_mut(),
+                lvgl_sys::LV_STATE_EDITED as _,
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Label, Slider};
+    use crate::{NativeObject, Part, State, Widget};
+
+    #[test]
+    fn visible_keyboard_focus() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        FocusRing {
+            width: 5,
+            ..Default::default()
+        }
+        .install(&display);
+        let mut screen = display.get_scr_act().unwrap();
+        let mut slider = Slider::create(&mut screen).unwrap();
+        let mut label = Label::create(&mut screen).unwrap();
+        fn outline_width(obj: &impl NativeObject) -> i32 {
+            unsafe {
+                lvgl_sys::lv_obj_get_style_prop(
+                    obj.raw().as_ref(),
+                    Part::Main.into(),
+                    lvgl_sys::lv_style_prop_t_LV_STYLE_OUTLINE_WIDTH,
+                )
+                .num
+            }
+        }
+
+        slider.add_state(State::FOCUSED);
+        assert_ne!(outline_width(&slider), 5);
+        slider.add_state(State::FOCUS_KEY);
+        assert_eq!(outline_width(&slider), 5);
+        slider.add_state(State::EDITED);
+        assert_eq!(outline_width(&slider), 5);
+        // Labels are not focusable, so keep the theme's styles
+        label.add_state(State::FOCUS_KEY);
+        assert_ne!(outline_width(&label), 5);
+    }
+
+    #[cfg(lvgl_theme_mono)]
+    #[test]
+    fn monochrome_theme() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        use_mono_theme(&display, true);
+        let mut screen = display.get_scr_act().unwrap();
+        let card = crate::Obj::create(&mut screen).unwrap();
+        assert!(unsafe { lvgl_sys::lv_theme_mono_is_inited() });
+        assert_eq!(card.get_style_bg_color(Part::Main).brightness(), 0);
+    }
+}
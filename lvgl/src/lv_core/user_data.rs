@@ -0,0 +1,130 @@
+//! Typed application data attached to widgets
+//!
+//! A widget's `user_data` pointer holds a `UserData<T>`, which starts with a
+//! header recording the type of the value and how to drop it. The value is
+//! dropped when it is replaced, taken back, or when LVGL deletes the widget.
+
+use crate::guard::guard;
+use crate::Box;
+use core::any::TypeId;
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+#[repr(C)]
+struct Header {
+    type_id: TypeId,
+    drop: unsafe fn(*mut Header),
+}
+
+/// The header is the first field, so a `*mut UserData<T>` is also a valid
+/// `*mut Header` whatever `T` is. The value is dropped explicitly, so that
+/// `take()` can move it out instead.
+#[repr(C)]
+struct UserData<T> {
+    header: Header,
+    value: ManuallyDrop<T>,
+}
+
+unsafe fn drop_user_data<T>(data: *mut Header) {
+    let mut data = Box::from_raw(data as *mut UserData<T>);
+    ManuallyDrop::drop(&mut data.value);
+}
+
+unsafe extern "C" fn delete_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("user data delete", || {
+        clear(&mut *(*event).target);
+    })
+}
+
+/// Returns the header of the object's user data, if any.
+unsafe fn header(obj: &lvgl_sys::lv_obj_t) -> Option<&Header> {
+    (obj.user_data as *const Header).as_ref()
+}
+
+/// Returns the object's user data if it is a `T`.
+pub(crate) unsafe fn get<T: 'static>(obj: &lvgl_sys::lv_obj_t) -> Option<*mut T> {
+    match header(obj) {
+        Some(header) if header.type_id == TypeId::of::<T>() => {
+            let data = obj.user_data as *mut UserData<T>;
+            Some(ptr::addr_of_mut!((*data).value) as *mut T)
+        }
+        _ => None,
+    }
+}
+
+/// Stores `value` as the object's user data, dropping any previous value.
+pub(crate) unsafe fn set<T: 'static>(obj: &mut lvgl_sys::lv_obj_t, value: T) {
+    if header(obj).is_some() {
+        clear(obj);
+    } else {
+        lvgl_sys::lv_obj_add_event_cb(
+            obj,
+            Some(delete_callback),
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+            ptr::null_mut(),
+        );
+    }
+    let data = UserData {
+        header: Header {
+            type_id: TypeId::of::<T>(),
+            drop: drop_user_data::<T>,
+        },
+        value: ManuallyDrop::new(value),
+    };
+    obj.user_data = Box::into_raw(Box::new(data)) as *mut _;
+}
+
+/// Removes the object's user data if it is a `T`, and returns it.
+pub(crate) unsafe fn take<T: 'static>(obj: &mut lvgl_sys::lv_obj_t) -> Option<T> {
+    get::<T>(obj)?;
+    let mut data = Box::from_raw(obj.user_data as *mut UserData<T>);
+    obj.user_data = ptr::null_mut();
+    lvgl_sys::lv_obj_remove_event_cb(obj, Some(delete_callback));
+    Some(ManuallyDrop::take(&mut data.value))
+}
+
+/// Drops the object's user data, if any.
+unsafe fn clear(obj: &mut lvgl_sys::lv_obj_t) {
+    if let Some(header) = header(obj) {
+        let drop = header.drop;
+        let data = obj.user_data as *mut Header;
+        obj.user_data = ptr::null_mut();
+        drop(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::Btn;
+    use crate::{tests, NativeObject, Widget};
+    use std::rc::Rc;
+
+    #[test]
+    fn typed_user_data() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        assert_eq!(btn.get_user_data::<u32>(), None);
+
+        btn.set_user_data(42u32);
+        assert_eq!(btn.get_user_data::<u32>(), Some(&42));
+        assert_eq!(btn.get_user_data::<i32>(), None);
+        *btn.get_user_data_mut::<u32>().unwrap() += 1;
+        assert_eq!(btn.take_user_data::<u32>(), Some(43));
+        assert_eq!(btn.get_user_data::<u32>(), None);
+    }
+
+    #[test]
+    fn user_data_dropped_with_widget() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        let state = Rc::new(());
+        btn.set_user_data(state.clone());
+        btn.set_user_data(state.clone());
+        assert_eq!(Rc::strong_count(&state), 2);
+
+        unsafe { lvgl_sys::lv_obj_del(btn.raw().as_ptr()) };
+        assert_eq!(Rc::strong_count(&state), 1);
+    }
+}
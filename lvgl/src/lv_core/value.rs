@@ -0,0 +1,196 @@
+//! Typed values of the widgets the user changes
+//!
+//! Sliders, arcs, switches, checkboxes, dropdowns, rollers and spinboxes
+//! implement [`ValueWidget`], whose `on_value_changed()` handlers receive the
+//! new value along with the widget:
+//!
+//! ```ignore
+//! let mut brightness = Slider::builder()
+//!     .range(0, 100)
+//!     .on_value_changed(|_, percent| backlight.set(percent))
+//!     .build(&mut screen)?;
+//! wifi_switch.on_value_changed(|_, on| wifi.enable(on))?;
+//! ```
+
+use crate::widgets::{Arc, Checkbox, Dropdown, Roller, Slider, Spinbox, Switch};
+use crate::{event_callback, Box, Builder, Event, LvResult, NativeObject, Widget};
+
+/// A widget with a value the user changes. See the module documentation.
+pub trait ValueWidget<'a>: Widget<'a> {
+    /// The type of the value, e.g. `i32` for a slider's position, `bool` for
+    /// whether a switch is on, or `u16` for the index of a selected option.
+    type Value: Copy;
+
+    /// Returns the widget's current value.
+    fn value(&self) -> Self::Value;
+
+    /// Adds an event handler calling `f` with the widget and its new value
+    /// whenever the value changes.
+    fn on_value_changed<F>(&mut self, f: F) -> LvResult<()>
+    where
+        F: FnMut(Self, Self::Value),
+    {
+        add_event_handler(self, value_changed(f));
+        Ok(())
+    }
+}
+
+/// Wraps `f` into an event handler passing it the new value on
+/// `Event::ValueChanged`.
+fn value_changed<'a, W, F>(mut f: F) -> impl FnMut(W, Event<W::SpecialEvent>)
+where
+    W: ValueWidget<'a>,
+    F: FnMut(W, W::Value),
+{
+    move |widget, event| {
+        if let Event::ValueChanged = event {
+            let value = widget.value();
+            f(widget, value)
+        }
+    }
+}
+
+fn add_event_handler<'a, W, F>(widget: &mut W, f: F)
+where
+    W: Widget<'a>,
+    F: FnMut(W, Event<W::SpecialEvent>),
+{
+    unsafe {
+        lvgl_sys::lv_obj_add_event_cb(
+            widget.raw().as_mut(),
+            lvgl_sys::lv_event_cb_t::Some(event_callback::<'a, W, F>),
+            lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+            Box::into_raw(Box::new(f)) as *mut _,
+        );
+    }
+}
+
+impl<'a, W: ValueWidget<'a>, S: FnOnce(&mut W)> Builder<W, S> {
+    /// Adds an event handler calling `f` with the widget and its new value
+    /// whenever the value changes. See `ValueWidget::on_value_changed()`.
+    pub fn on_value_changed<F>(self, f: F) -> Builder<W, impl FnOnce(&mut W)>
+    where
+        F: FnMut(W, W::Value),
+    {
+        self.on_event(value_changed(f))
+    }
+}
+
+impl<'a> ValueWidget<'a> for Slider<'a> {
+    type Value = i32;
+
+    fn value(&self) -> i32 {
+        self.get_value()
+    }
+}
+
+impl<'a> ValueWidget<'a> for Arc<'a> {
+    type Value = i32;
+
+    fn value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_arc_get_value(self.raw().as_ptr()).into() }
+    }
+}
+
+impl<'a> ValueWidget<'a> for Spinbox<'a> {
+    type Value = i32;
+
+    fn value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_spinbox_get_value(self.raw().as_ptr()) }
+    }
+}
+
+impl<'a> ValueWidget<'a> for Switch<'a> {
+    type Value = bool;
+
+    /// Returns whether the switch is on.
+    fn value(&self) -> bool {
+        self.is_checked()
+    }
+}
+
+impl<'a> ValueWidget<'a> for Checkbox<'a> {
+    type Value = bool;
+
+    /// Returns whether the checkbox is checked.
+    fn value(&self) -> bool {
+        self.is_checked()
+    }
+}
+
+impl<'a> ValueWidget<'a> for Dropdown<'a> {
+    type Value = u16;
+
+    /// Returns the index of the selected option.
+    fn value(&self) -> u16 {
+        unsafe { lvgl_sys::lv_dropdown_get_selected(self.raw().as_ptr()) }
+    }
+}
+
+impl<'a> ValueWidget<'a> for Roller<'a> {
+    type Value = u16;
+
+    /// Returns the index of the selected option.
+    fn value(&self) -> u16 {
+        unsafe { lvgl_sys::lv_roller_get_selected(self.raw().as_ptr()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnimationState, State};
+    use core::ptr;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    fn send_value_changed(widget: &impl NativeObject) {
+        unsafe {
+            lvgl_sys::lv_event_send(
+                widget.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED,
+                ptr::null_mut(),
+            )
+        };
+    }
+
+    #[test]
+    fn handlers_receive_values() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let levels = Rc::new(RefCell::new(Vec::new()));
+        let seen = levels.clone();
+        let slider = Slider::builder()
+            .range(0, 10)
+            .on_value_changed(move |_, level| seen.borrow_mut().push(level))
+            .build(&mut screen)
+            .unwrap();
+        slider.set_value(7, AnimationState::OFF);
+        send_value_changed(&slider);
+        assert_eq!(*levels.borrow(), [7]);
+
+        let states = Rc::new(RefCell::new(Vec::new()));
+        let seen = states.clone();
+        let mut switch = Switch::create(&mut screen).unwrap();
+        switch
+            .on_value_changed(move |_, on| seen.borrow_mut().push(on))
+            .unwrap();
+        switch.add_state(State::CHECKED);
+        send_value_changed(&switch);
+        switch.clear_state(State::CHECKED);
+        send_value_changed(&switch);
+        assert_eq!(*states.borrow(), [true, false]);
+
+        let selected = Rc::new(RefCell::new(None));
+        let seen = selected.clone();
+        let mut dropdown = Dropdown::builder()
+            .options(["Low", "Medium", "High"])
+            .on_value_changed(move |_, index| *seen.borrow_mut() = Some(index))
+            .build(&mut screen)
+            .unwrap();
+        unsafe { lvgl_sys::lv_dropdown_set_selected(dropdown.raw().as_mut(), 2) }
+        send_value_changed(&dropdown);
+        assert_eq!(*selected.borrow(), Some(2));
+    }
+}
@@ -0,0 +1,48 @@
+//! IDs assigned to widgets for finding them in the widget tree
+//!
+//! An ID is stored as the user data of a marker event callback rather than in
+//! the object's `user_data`, which is kept for application data. The marker
+//! only runs when the object is deleted, to free the ID.
+
+use crate::Box;
+use core::ptr;
+
+unsafe extern "C" fn id_marker(event: *mut lvgl_sys::lv_event_t) {
+    drop(Box::from_raw((*event).user_data as *mut &'static str));
+}
+
+/// Assigns `id` to the object, replacing its previous ID.
+pub(crate) unsafe fn set(obj: *mut lvgl_sys::lv_obj_t, id: &'static str) {
+    let previous = lvgl_sys::lv_obj_get_event_user_data(obj, Some(id_marker));
+    if !previous.is_null() {
+        lvgl_sys::lv_obj_remove_event_cb(obj, Some(id_marker));
+        drop(Box::from_raw(previous as *mut &'static str));
+    }
+    lvgl_sys::lv_obj_add_event_cb(
+        obj,
+        Some(id_marker),
+        lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+        Box::into_raw(Box::new(id)) as *mut _,
+    );
+}
+
+/// Returns the ID assigned to the object, if any.
+pub(crate) unsafe fn get(obj: *mut lvgl_sys::lv_obj_t) -> Option<&'static str> {
+    let id = lvgl_sys::lv_obj_get_event_user_data(obj, Some(id_marker));
+    (id as *const &'static str).as_ref().copied()
+}
+
+/// Finds the first descendant of `obj` with the given ID, depth first.
+pub(crate) unsafe fn find(obj: *mut lvgl_sys::lv_obj_t, id: &str) -> *mut lvgl_sys::lv_obj_t {
+    for i in 0..lvgl_sys::lv_obj_get_child_cnt(obj) {
+        let child = lvgl_sys::lv_obj_get_child(obj, i as i32);
+        if get(child) == Some(id) {
+            return child;
+        }
+        let found = find(child, id);
+        if !found.is_null() {
+            return found;
+        }
+    }
+    ptr::null_mut()
+}
@@ -1,7 +1,7 @@
 use core::mem;
 use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
-use core::ptr::NonNull;
+use core::ptr::{self, NonNull};
 
 /// Places a sized `T` into LVGL memory.
 ///
@@ -15,7 +15,7 @@ impl<T> Box<T> {
     pub fn new(value: T) -> Self {
         let size = mem::size_of::<T>();
         let inner = unsafe {
-            let ptr = lvgl_sys::lv_mem_alloc(size as cty::size_t) as *mut T;
+            let ptr = crate::storage::alloc(size) as *mut T;
 
             // LVGL should align the memory address for us!
             assert_eq!(
@@ -30,6 +30,7 @@ impl<T> Box<T> {
                     p
                 })
                 .unwrap_or_else(|| {
+                    diag!(error, "LVGL could not allocate {} bytes", size);
                     panic!("Could not allocate memory {} bytes: {:?}", size, mem_info());
                 })
         };
@@ -70,7 +71,8 @@ impl<T> Box<T> {
 impl<T> Drop for Box<T> {
     fn drop(&mut self) {
         unsafe {
-            lvgl_sys::lv_mem_free(self.0.as_ptr() as *mut cty::c_void);
+            ptr::drop_in_place(self.0.as_ptr());
+            crate::storage::free(self.0.as_ptr() as *mut cty::c_void);
         }
     }
 }
@@ -1,4 +1,6 @@
-use crate::{Box, LvResult, Obj, Widget};
+use crate::guard::guard;
+use crate::style::StyleProp;
+use crate::{Box, LvError, LvResult, Obj, Widget};
 use core::{
     ffi::c_void,
     mem::{self, MaybeUninit},
@@ -6,6 +8,7 @@ use core::{
     ptr::NonNull,
     time::Duration,
 };
+use paste::paste;
 
 /// A repetition count for an animation, finite or infinite.
 #[repr(u16)]
@@ -14,6 +17,42 @@ pub enum AnimRepeatCount {
     Infinite,
 }
 
+/// How an animation's value progresses from start to end. Equivalent to
+/// LVGL's `lv_anim_path_*()` functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AnimPath {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Overshoot,
+    Bounce,
+    /// Jumps to the end value when the animation ends.
+    Step,
+}
+
+impl AnimPath {
+    /// Returns LVGL's path function, in constant expressions as well.
+    pub(crate) const fn callback(self) -> lvgl_sys::lv_anim_path_cb_t {
+        Some(match self {
+            AnimPath::Linear => lvgl_sys::lv_anim_path_linear,
+            AnimPath::EaseIn => lvgl_sys::lv_anim_path_ease_in,
+            AnimPath::EaseOut => lvgl_sys::lv_anim_path_ease_out,
+            AnimPath::EaseInOut => lvgl_sys::lv_anim_path_ease_in_out,
+            AnimPath::Overshoot => lvgl_sys::lv_anim_path_overshoot,
+            AnimPath::Bounce => lvgl_sys::lv_anim_path_bounce,
+            AnimPath::Step => lvgl_sys::lv_anim_path_step,
+        })
+    }
+}
+
+impl From<AnimPath> for lvgl_sys::lv_anim_path_cb_t {
+    fn from(value: AnimPath) -> Self {
+        value.callback()
+    }
+}
+
 /// An LVGL animation. Equivalent to an `lv_anim_t`.
 pub struct Animation {
     pub(crate) raw: Box<lvgl_sys::lv_anim_t>,
@@ -102,6 +141,135 @@ impl Animation {
     pub fn set_early_apply(&mut self, apply: bool) {
         (*self.raw).set_early_apply(apply as u8);
     }
+
+    /// Sets how the value progresses from start to end.
+    pub fn set_path(&mut self, path: AnimPath) {
+        self.raw.path_cb = path.into();
+    }
+}
+
+/// Animates a numeric style property of an object's parts from `from` to `to`.
+/// See `Widget::animate_style()`.
+pub(crate) fn animate_style(
+    obj: NonNull<lvgl_sys::lv_obj_t>,
+    selector: lvgl_sys::lv_style_selector_t,
+    prop: StyleProp,
+    from: i32,
+    to: i32,
+    duration: Duration,
+    path: AnimPath,
+) -> LvResult<()> {
+    let exec_cb = style_exec_cb(prop).ok_or(LvError::InvalidReference)?;
+    unsafe {
+        let obj = obj.as_ptr() as *mut c_void;
+        lvgl_sys::lv_anim_del(obj, exec_cb);
+
+        let mut anim = MaybeUninit::<lvgl_sys::lv_anim_t>::uninit();
+        lvgl_sys::lv_anim_init(anim.as_mut_ptr());
+        let mut anim = anim.assume_init();
+        anim.var = obj;
+        anim.exec_cb = exec_cb;
+        anim.path_cb = path.into();
+        anim.user_data = selector as usize as *mut _;
+        anim.start_value = from;
+        anim.current_value = from;
+        anim.end_value = to;
+        anim.time = duration.as_millis().try_into().unwrap_or(u32::MAX);
+        // LVGL copies the animation and deletes it with the object
+        if lvgl_sys::lv_anim_start(&anim).is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+    }
+    Ok(())
+}
+
+/// Sets the style property `PROP` of the animated object's parts. There is
+/// one callback per property, so that `lv_anim_del()` only cancels an
+/// animation of the same property.
+unsafe extern "C" fn exec_style<const PROP: u32>(obj: *mut c_void, value: i32) {
+    let anim = lvgl_sys::lv_anim_get(obj, Some(exec_style::<PROP>));
+    let selector = anim
+        .as_ref()
+        .map_or(lvgl_sys::LV_PART_MAIN, |anim| anim.user_data as usize as _);
+    lvgl_sys::lv_obj_set_local_style_prop(
+        obj as *mut _,
+        PROP,
+        lvgl_sys::lv_style_value_t { num: value },
+        selector,
+    );
+}
+
+macro_rules! style_exec_cbs {
+    ($prop:expr, $($name:ident),* $(,)?) => {
+        paste! {
+            match $prop {
+                $(
+                    lvgl_sys::[<lv_style_prop_t_LV_STYLE_ $name>] => {
+                        Some(Some(exec_style::<{ lvgl_sys::[<lv_style_prop_t_LV_STYLE_ $name>] }>))
+                    }
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
+/// Returns the animation callback of a numeric style property, or `None` for
+/// colors, fonts, images and the other properties that cannot be animated as
+/// a number.
+fn style_exec_cb(prop: StyleProp) -> Option<lvgl_sys::lv_anim_exec_xcb_t> {
+    style_exec_cbs!(
+        prop.bits(),
+        WIDTH,
+        MIN_WIDTH,
+        MAX_WIDTH,
+        HEIGHT,
+        MIN_HEIGHT,
+        MAX_HEIGHT,
+        X,
+        Y,
+        TRANSFORM_WIDTH,
+        TRANSFORM_HEIGHT,
+        TRANSLATE_X,
+        TRANSLATE_Y,
+        TRANSFORM_ZOOM,
+        TRANSFORM_ANGLE,
+        PAD_TOP,
+        PAD_BOTTOM,
+        PAD_LEFT,
+        PAD_RIGHT,
+        PAD_ROW,
+        PAD_COLUMN,
+        BG_OPA,
+        BG_MAIN_STOP,
+        BG_GRAD_STOP,
+        BG_IMG_OPA,
+        BG_IMG_RECOLOR_OPA,
+        BORDER_OPA,
+        BORDER_WIDTH,
+        OUTLINE_WIDTH,
+        OUTLINE_OPA,
+        OUTLINE_PAD,
+        SHADOW_WIDTH,
+        SHADOW_OFS_X,
+        SHADOW_OFS_Y,
+        SHADOW_SPREAD,
+        SHADOW_OPA,
+        IMG_OPA,
+        IMG_RECOLOR_OPA,
+        LINE_WIDTH,
+        LINE_DASH_WIDTH,
+        LINE_DASH_GAP,
+        LINE_OPA,
+        ARC_WIDTH,
+        ARC_OPA,
+        TEXT_OPA,
+        TEXT_LETTER_SPACE,
+        TEXT_LINE_SPACE,
+        RADIUS,
+        OPA,
+        COLOR_FILTER_OPA,
+    )
 }
 
 unsafe extern "C" fn animator_trampoline<'a, 'b, T, F>(obj: *mut c_void, val: i32)
@@ -109,25 +277,27 @@ where
     T: Widget<'b>,
     F: FnMut(&mut Obj, i32) + 'a,
 {
-    unsafe {
-        let anim =
-            NonNull::new(lvgl_sys::lv_anim_get(obj, None) as *mut lvgl_sys::lv_anim_t).unwrap();
-        // yes, we have to do it this way. Casting `obj` directly to `&mut Obj` segfaults
-        let obj = (*(obj as *mut T)).raw();
-        if !anim.as_ref().user_data.is_null() {
-            let callback = &mut *(obj.as_ref().user_data as *mut F);
-            let mut obj_nondrop = Obj::from_raw(obj).unwrap();
-            callback(&mut obj_nondrop, val);
-            mem::forget(obj_nondrop)
+    guard("animation", || {
+        unsafe {
+            let anim =
+                NonNull::new(lvgl_sys::lv_anim_get(obj, None) as *mut lvgl_sys::lv_anim_t).unwrap();
+            // yes, we have to do it this way. Casting `obj` directly to `&mut Obj` segfaults
+            let obj = (*(obj as *mut T)).raw();
+            if !anim.as_ref().user_data.is_null() {
+                let callback = &mut *(anim.as_ref().user_data as *mut F);
+                let mut obj_nondrop = Obj::from_raw(obj).unwrap();
+                callback(&mut obj_nondrop, val);
+                mem::forget(obj_nondrop)
+            }
         }
-    }
+    })
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::widgets::Btn;
-    use crate::Display;
+    use crate::{Display, Part};
 
     #[test]
     fn anim_test() {
@@ -139,4 +309,37 @@ mod test {
             Animation::new(&mut btn, Duration::from_millis(10), 0, 100, |_, _| {}).unwrap();
         anim.start();
     }
+
+    #[test]
+    fn animate_style_to_end() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.animate_style(
+            Part::Main,
+            StyleProp::TRANSLATE_X,
+            0,
+            40,
+            Duration::from_millis(20),
+            AnimPath::EaseOut,
+        )
+        .unwrap();
+        assert_eq!(btn.get_style_translate_x(Part::Main), 0);
+        for _ in 0..5 {
+            crate::tick_inc(Duration::from_millis(10));
+            crate::task_handler().unwrap();
+        }
+        assert_eq!(btn.get_style_translate_x(Part::Main), 40);
+
+        let not_numeric = btn.animate_style(
+            Part::Main,
+            StyleProp::BG_COLOR,
+            0,
+            1,
+            Duration::from_millis(20),
+            AnimPath::Linear,
+        );
+        assert_eq!(not_numeric, Err(LvError::InvalidReference));
+    }
 }
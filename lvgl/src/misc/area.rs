@@ -1,3 +1,5 @@
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
 use lvgl_sys::lv_coord_t;
 
 pub static LV_SIZE_CONTENT: u32 = 2001 | lvgl_sys::_LV_COORD_TYPE_SPEC;
@@ -33,3 +35,319 @@ pub fn coord_get_pct(pct: lv_coord_t) -> lv_coord_t {
         <u32 as TryInto<lv_coord_t>>::try_into(lvgl_sys::_LV_COORD_TYPE_MASK).unwrap_unchecked()
     }) % 1000
 }
+
+const COORD_TYPE_SPEC: lv_coord_t = lvgl_sys::_LV_COORD_TYPE_SPEC as lv_coord_t;
+const COORD_TYPE_MASK: lv_coord_t = lvgl_sys::_LV_COORD_TYPE_MASK as lv_coord_t;
+
+/// The largest pixel value a coordinate can hold. Larger values are reserved
+/// for LVGL's special (e.g. percentage) coordinates.
+pub const COORD_MAX: i16 = ((1 << lvgl_sys::_LV_COORD_TYPE_SHIFT) - 1) as i16;
+
+const SIZE_CONTENT: lv_coord_t = 2001 | COORD_TYPE_SPEC;
+
+/// The error returned when a value does not fit in a [`Coord`] or [`Size`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CoordOutOfRange;
+
+impl fmt::Display for CoordOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "coordinate out of range (max. {} px)", COORD_MAX)
+    }
+}
+
+/// The unit of a [`Coord`] or [`Size`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unit {
+    Px(i16),
+    Pct(i16),
+    Content,
+}
+
+impl Unit {
+    fn of(raw: lv_coord_t) -> Self {
+        if raw == SIZE_CONTENT {
+            Unit::Content
+        } else if coord_is_pct(raw) {
+            // Negative percentages are stored as 1000 + |pct|
+            let pct = coord_get_pct(raw) as i16;
+            if (raw & !COORD_TYPE_MASK) > 1000 {
+                Unit::Pct(-pct)
+            } else {
+                Unit::Pct(pct)
+            }
+        } else {
+            Unit::Px(raw as i16)
+        }
+    }
+
+    fn checked_op(self, rhs: Self, op: impl Fn(i16, i16) -> Option<i16>) -> Option<lv_coord_t> {
+        match (self, rhs) {
+            (Unit::Px(a), Unit::Px(b)) => op(a, b)
+                .filter(|v| v.unsigned_abs() <= COORD_MAX as u16)
+                .map(|v| v as lv_coord_t),
+            (Unit::Pct(a), Unit::Pct(b)) => op(a, b)
+                .filter(|v| v.unsigned_abs() <= 999)
+                .map(|v| pct(v as lv_coord_t)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! coord_type {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+        #[repr(transparent)]
+        pub struct $name(lv_coord_t);
+
+        impl $name {
+            /// A value in pixels.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `px` is beyond ±`COORD_MAX`.
+            pub const fn px(px: i16) -> Self {
+                assert!(px.unsigned_abs() <= COORD_MAX as u16, "coordinate out of range");
+                Self(px as lv_coord_t)
+            }
+
+            /// A percentage of the parent's content area.
+            pub const fn pct(pct: u8) -> Self {
+                Self(pct as lv_coord_t | COORD_TYPE_SPEC)
+            }
+
+            /// Wraps an encoded LVGL coordinate, e.g. one read from a widget.
+            pub const fn from_raw(raw: lv_coord_t) -> Self {
+                Self(raw)
+            }
+
+            /// Returns the encoded LVGL coordinate.
+            pub const fn raw(self) -> lv_coord_t {
+                self.0
+            }
+
+            /// Returns the value in pixels, if it is one.
+            pub fn as_px(self) -> Option<i16> {
+                match Unit::of(self.0) {
+                    Unit::Px(px) => Some(px),
+                    _ => None,
+                }
+            }
+
+            /// Returns the value as a percentage, if it is one.
+            pub fn as_pct(self) -> Option<i16> {
+                match Unit::of(self.0) {
+                    Unit::Pct(pct) => Some(pct),
+                    _ => None,
+                }
+            }
+
+            /// Adds two values of the same unit, returning `None` if the units
+            /// differ or the result is out of range.
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                Unit::of(self.0)
+                    .checked_op(Unit::of(rhs.0), i16::checked_add)
+                    .map(Self)
+            }
+
+            /// Subtracts two values of the same unit, returning `None` if the
+            /// units differ or the result is out of range.
+            pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Unit::of(self.0)
+                    .checked_op(Unit::of(rhs.0), i16::checked_sub)
+                    .map(Self)
+            }
+
+            /// Multiplies the value by `rhs`, keeping its unit, returning
+            /// `None` if the result is out of range.
+            pub fn checked_mul(self, rhs: i16) -> Option<Self> {
+                let unit = Unit::of(self.0);
+                unit.checked_op(unit, |a, _| a.checked_mul(rhs)).map(Self)
+            }
+
+            /// Divides the value by `rhs`, keeping its unit, returning `None`
+            /// if `rhs` is zero.
+            pub fn checked_div(self, rhs: i16) -> Option<Self> {
+                let unit = Unit::of(self.0);
+                unit.checked_op(unit, |a, _| a.checked_div(rhs)).map(Self)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match Unit::of(self.0) {
+                    Unit::Px(px) => write!(f, "{}px", px),
+                    Unit::Pct(pct) => write!(f, "{}%", pct),
+                    Unit::Content => write!(f, "content"),
+                }
+            }
+        }
+
+        impl From<i16> for $name {
+            /// A value in pixels. See `px()`.
+            fn from(px: i16) -> Self {
+                Self::px(px)
+            }
+        }
+
+        impl TryFrom<i32> for $name {
+            type Error = CoordOutOfRange;
+
+            /// A value in pixels, if it is within ±`COORD_MAX`.
+            fn try_from(px: i32) -> Result<Self, Self::Error> {
+                if px.unsigned_abs() <= COORD_MAX as u32 {
+                    Ok(Self(px as lv_coord_t))
+                } else {
+                    Err(CoordOutOfRange)
+                }
+            }
+        }
+
+        impl TryFrom<u32> for $name {
+            type Error = CoordOutOfRange;
+
+            /// A value in pixels, if it is at most `COORD_MAX`.
+            fn try_from(px: u32) -> Result<Self, Self::Error> {
+                if px <= COORD_MAX as u32 {
+                    Ok(Self(px as lv_coord_t))
+                } else {
+                    Err(CoordOutOfRange)
+                }
+            }
+        }
+
+        impl From<$name> for lv_coord_t {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the units differ or the result is out of range.
+            fn add(self, rhs: Self) -> Self {
+                self.checked_add(rhs)
+                    .expect("coordinate addition of different units or out of range")
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the units differ or the result is out of range.
+            fn sub(self, rhs: Self) -> Self {
+                self.checked_sub(rhs)
+                    .expect("coordinate subtraction of different units or out of range")
+            }
+        }
+
+        impl Mul<i16> for $name {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the value is not in pixels or percent, or the result
+            /// is out of range.
+            fn mul(self, rhs: i16) -> Self {
+                self.checked_mul(rhs)
+                    .expect("coordinate multiplication out of range")
+            }
+        }
+
+        impl Div<i16> for $name {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the value is not in pixels or percent, or `rhs` is
+            /// zero.
+            fn div(self, rhs: i16) -> Self {
+                self.checked_div(rhs).expect("coordinate division by zero")
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the value is not in pixels or percent.
+            fn neg(self) -> Self {
+                self * -1
+            }
+        }
+    };
+}
+
+coord_type! {
+    /// A position or offset: either pixels or a percentage of the parent's
+    /// content area.
+    ///
+    /// ```ignore
+    /// widget.set_pos(Coord::pct(10), 20);
+    /// ```
+    Coord
+}
+
+coord_type! {
+    /// A width or height: pixels, a percentage of the parent's content area,
+    /// or the size of the widget's content.
+    ///
+    /// ```ignore
+    /// widget.set_size(Size::pct(100), Size::content());
+    /// ```
+    Size
+}
+
+impl Size {
+    /// Sizes the widget to fit its content.
+    pub const fn content() -> Self {
+        Self(SIZE_CONTENT)
+    }
+
+    /// Tests if the size fits the widget's content.
+    pub fn is_content(self) -> bool {
+        self.0 == SIZE_CONTENT
+    }
+}
+
+impl From<Coord> for Size {
+    fn from(coord: Coord) -> Self {
+        Self(coord.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coord_arithmetic_keeps_units() {
+        assert_eq!(Coord::px(10) + 5.into(), Coord::px(15));
+        assert_eq!(Coord::pct(10) + Coord::pct(20), Coord::pct(30));
+        assert_eq!(Coord::pct(10) - Coord::pct(30), Coord::from_raw(pct(-20)));
+        assert_eq!(-Coord::px(10), Coord::px(-10));
+        assert_eq!(Size::px(10) * 3, Size::px(30));
+        assert_eq!(Coord::pct(10).checked_add(Coord::px(1)), None);
+        assert_eq!(Size::content().checked_add(Size::px(1)), None);
+    }
+
+    #[test]
+    fn coord_encoding() {
+        assert_eq!(Coord::pct(10).raw(), pct(10));
+        assert_eq!(Size::content().raw(), LV_SIZE_CONTENT as lv_coord_t);
+        assert_eq!(Coord::from_raw(pct(-20)).as_pct(), Some(-20));
+        assert_eq!(
+            Coord::try_from(-(COORD_MAX as i32)).unwrap().as_px(),
+            Some(-COORD_MAX)
+        );
+        assert_eq!(Coord::try_from(COORD_MAX as i32 + 1), Err(CoordOutOfRange));
+        assert_eq!(Size::try_from(240u32).unwrap().as_px(), Some(240));
+        assert_eq!(Size::try_from(u32::MAX), Err(CoordOutOfRange));
+    }
+}
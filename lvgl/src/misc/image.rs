@@ -0,0 +1,137 @@
+//! Images drawn from pixel data in memory
+//!
+//! An [`ImageDsc`] describes pixel data, e.g. included with `include_bytes!()`
+//! from a file converted with LVGL's image converter, so widgets can draw it.
+
+use crate::{LvError, LvResult};
+use core::marker::PhantomData;
+
+/// How the pixels of an image are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// Pixels in the display's color format.
+    TrueColor,
+    /// Pixels in the display's color format, each followed by an alpha byte.
+    TrueColorAlpha,
+    /// Pixels in the display's color format, where `LV_COLOR_CHROMA_KEY` is
+    /// transparent.
+    TrueColorChromaKeyed,
+    /// 1 bit palette indices, after a palette of 2 colors.
+    Indexed1Bit,
+    /// 2 bit palette indices, after a palette of 4 colors.
+    Indexed2Bit,
+    /// 4 bit palette indices, after a palette of 16 colors.
+    Indexed4Bit,
+    /// 8 bit palette indices, after a palette of 256 colors.
+    Indexed8Bit,
+    /// 1 bit alpha values, drawn in the `img_recolor` style color.
+    Alpha1Bit,
+    /// 2 bit alpha values, drawn in the `img_recolor` style color.
+    Alpha2Bit,
+    /// 4 bit alpha values, drawn in the `img_recolor` style color.
+    Alpha4Bit,
+    /// 8 bit alpha values, drawn in the `img_recolor` style color.
+    Alpha8Bit,
+}
+
+impl From<ColorFormat> for lvgl_sys::lv_img_cf_t {
+    fn from(cf: ColorFormat) -> Self {
+        let native = match cf {
+            ColorFormat::TrueColor => lvgl_sys::LV_IMG_CF_TRUE_COLOR,
+            ColorFormat::TrueColorAlpha => lvgl_sys::LV_IMG_CF_TRUE_COLOR_ALPHA,
+            ColorFormat::TrueColorChromaKeyed => lvgl_sys::LV_IMG_CF_TRUE_COLOR_CHROMA_KEYED,
+            ColorFormat::Indexed1Bit => lvgl_sys::LV_IMG_CF_INDEXED_1BIT,
+            ColorFormat::Indexed2Bit => lvgl_sys::LV_IMG_CF_INDEXED_2BIT,
+            ColorFormat::Indexed4Bit => lvgl_sys::LV_IMG_CF_INDEXED_4BIT,
+            ColorFormat::Indexed8Bit => lvgl_sys::LV_IMG_CF_INDEXED_8BIT,
+            ColorFormat::Alpha1Bit => lvgl_sys::LV_IMG_CF_ALPHA_1BIT,
+            ColorFormat::Alpha2Bit => lvgl_sys::LV_IMG_CF_ALPHA_2BIT,
+            ColorFormat::Alpha4Bit => lvgl_sys::LV_IMG_CF_ALPHA_4BIT,
+            ColorFormat::Alpha8Bit => lvgl_sys::LV_IMG_CF_ALPHA_8BIT,
+        };
+        native as _
+    }
+}
+
+/// An image in memory. Equivalent to an `lv_img_dsc_t`.
+pub struct ImageDsc<'a> {
+    pub(crate) raw: lvgl_sys::lv_img_dsc_t,
+    data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ImageDsc<'a> {
+    /// The largest width or height of an image.
+    pub const MAX_SIZE: u32 = 2047;
+
+    /// Describes a `width` by `height` image whose pixels are stored in
+    /// `data` in the given format. Fails if the image is larger than
+    /// `MAX_SIZE` or `data` is shorter than `data_size()`.
+    pub fn new(width: u32, height: u32, cf: ColorFormat, data: &'a [u8]) -> LvResult<Self> {
+        if width > Self::MAX_SIZE || height > Self::MAX_SIZE {
+            return Err(LvError::InvalidReference);
+        }
+        let size = Self::data_size(width, height, cf);
+        if data.len() < size {
+            return Err(LvError::InvalidReference);
+        }
+        let mut raw: lvgl_sys::lv_img_dsc_t = unsafe { core::mem::zeroed() };
+        raw.header.set_cf(lvgl_sys::lv_img_cf_t::from(cf) as _);
+        raw.header.set_w(width);
+        raw.header.set_h(height);
+        raw.data_size = size as u32;
+        raw.data = data.as_ptr();
+        Ok(Self {
+            raw,
+            data: PhantomData,
+        })
+    }
+
+    /// Returns the number of bytes of pixel data, including any palette, of
+    /// a `width` by `height` image in the given format.
+    pub fn data_size(width: u32, height: u32, cf: ColorFormat) -> usize {
+        unsafe { lvgl_sys::lv_img_buf_get_img_size(width as _, height as _, cf.into()) as usize }
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> u32 {
+        self.raw.header.w()
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> u32 {
+        self.raw.header.h()
+    }
+
+    /// Wraps a descriptor whose data lives for `'a`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must describe valid pixel data that outlives `'a`.
+    pub(crate) unsafe fn from_raw(raw: lvgl_sys::lv_img_dsc_t) -> Self {
+        Self {
+            raw,
+            data: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to the descriptor, for use as an image source.
+    pub(crate) fn as_src(&self) -> *const core::ffi::c_void {
+        &self.raw as *const lvgl_sys::lv_img_dsc_t as *const _
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_image_size() {
+        let size = ImageDsc::data_size(4, 2, ColorFormat::Alpha8Bit);
+        assert_eq!(size, 8);
+        let data = [0xFF; 8];
+        let image = ImageDsc::new(4, 2, ColorFormat::Alpha8Bit, &data).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert!(ImageDsc::new(4, 3, ColorFormat::Alpha8Bit, &data).is_err());
+        assert!(ImageDsc::new(4096, 0, ColorFormat::Alpha8Bit, &[]).is_err());
+    }
+}
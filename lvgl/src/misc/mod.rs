@@ -1,2 +1,3 @@
 pub mod anim;
 pub mod area;
+pub mod image;
@@ -0,0 +1,484 @@
+//! Screen navigation with a back stack
+//!
+//! A [`Navigator`] keeps the screens of a multi-screen app on a stack.
+//! Pushing a screen loads it with a transition, and popping it returns to the
+//! screen below and deletes it, so apps don't have to track which screens to
+//! load and delete themselves:
+//!
+//! ```ignore
+//! use lvgl::navigator::Navigator;
+//!
+//! let mut nav = Navigator::<4>::new(display.get_scr_act()?)?;
+//! nav.set_back_gesture(Some(Dir::RIGHT));
+//!
+//! let mut settings = Screen::blank()?;
+//! let mut back = Btn::create(&mut settings)?;
+//! nav.back_button(&mut back);
+//! if let Err(settings) = nav.push(settings) {
+//!     // The stack is full
+//! }
+//! ```
+//!
+//! The stack holds up to `N` screens, including the root. A popped screen can
+//! also be kept with [`Navigator::pop_cached()`], to push it again later
+//! without building it anew.
+
+use crate::guard::guard;
+use crate::{Box, Dir, LvError, LvResult, NativeObject, Screen, Widget};
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::time::Duration;
+
+/// How a screen is animated when it is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenAnim {
+    /// Switch screens at once.
+    None,
+    /// Slide the new screen over the old one, towards the left.
+    OverLeft,
+    /// Slide the new screen over the old one, towards the right.
+    OverRight,
+    /// Slide the new screen over the old one, towards the top.
+    OverTop,
+    /// Slide the new screen over the old one, towards the bottom.
+    OverBottom,
+    /// Slide both screens towards the left.
+    MoveLeft,
+    /// Slide both screens towards the right.
+    MoveRight,
+    /// Slide both screens towards the top.
+    MoveTop,
+    /// Slide both screens towards the bottom.
+    MoveBottom,
+    /// Fade the new screen in over the old one.
+    FadeIn,
+    /// Fade the old screen out, revealing the new one.
+    FadeOut,
+    /// Slide the old screen out towards the left, revealing the new one.
+    OutLeft,
+    /// Slide the old screen out towards the right, revealing the new one.
+    OutRight,
+    /// Slide the old screen out towards the top, revealing the new one.
+    OutTop,
+    /// Slide the old screen out towards the bottom, revealing the new one.
+    OutBottom,
+}
+
+impl From<ScreenAnim> for lvgl_sys::lv_scr_load_anim_t {
+    fn from(anim: ScreenAnim) -> Self {
+        match anim {
+            ScreenAnim::None => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_NONE,
+            ScreenAnim::OverLeft => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OVER_LEFT,
+            ScreenAnim::OverRight => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OVER_RIGHT,
+            ScreenAnim::OverTop => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OVER_TOP,
+            ScreenAnim::OverBottom => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OVER_BOTTOM,
+            ScreenAnim::MoveLeft => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_MOVE_LEFT,
+            ScreenAnim::MoveRight => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_MOVE_RIGHT,
+            ScreenAnim::MoveTop => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_MOVE_TOP,
+            ScreenAnim::MoveBottom => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_MOVE_BOTTOM,
+            ScreenAnim::FadeIn => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_FADE_IN,
+            ScreenAnim::FadeOut => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_FADE_OUT,
+            ScreenAnim::OutLeft => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OUT_LEFT,
+            ScreenAnim::OutRight => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OUT_RIGHT,
+            ScreenAnim::OutTop => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OUT_TOP,
+            ScreenAnim::OutBottom => lvgl_sys::lv_scr_load_anim_t_LV_SCR_LOAD_ANIM_OUT_BOTTOM,
+        }
+    }
+}
+
+/// The animations a `Navigator` loads screens with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// How a pushed screen, or one replacing the current screen, is loaded.
+    pub push: ScreenAnim,
+    /// How the screen below is loaded when a screen is popped.
+    pub pop: ScreenAnim,
+    /// How long the animations take.
+    pub time: Duration,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            push: ScreenAnim::MoveLeft,
+            pop: ScreenAnim::MoveRight,
+            time: Duration::from_millis(300),
+        }
+    }
+}
+
+/// The state of a navigator, kept in LVGL memory so the event callbacks of
+/// its screens and back buttons can reach it.
+struct Stack<const N: usize> {
+    screens: [*mut lvgl_sys::lv_obj_t; N],
+    len: usize,
+    transition: Transition,
+    back_gesture: Option<Dir>,
+    /// The navigator itself, and the callbacks and pending pops that point to
+    /// the stack. It is freed when the last of them is gone.
+    refs: usize,
+    detached: bool,
+}
+
+impl<const N: usize> Stack<N> {
+    fn top(&self) -> *mut lvgl_sys::lv_obj_t {
+        self.screens[self.len - 1]
+    }
+
+    /// Forgets a screen that is being deleted, so it is never loaded or
+    /// deleted again.
+    fn remove(&mut self, screen: *mut lvgl_sys::lv_obj_t) {
+        if let Some(i) = self.screens[..self.len].iter().position(|&s| s == screen) {
+            self.screens.copy_within(i + 1..self.len, i);
+            self.len -= 1;
+        }
+    }
+
+    /// Loads the screen on top, deleting the screen shown so far if `del`.
+    /// The stack is passed as a pointer, as deleting a screen updates it.
+    unsafe fn load(stack: *mut Self, anim: ScreenAnim, del: bool) {
+        let screen = (*stack).top();
+        let time = (*stack)
+            .transition
+            .time
+            .as_millis()
+            .try_into()
+            .unwrap_or(u32::MAX);
+        lvgl_sys::lv_scr_load_anim(screen, anim.into(), time, 0, del)
+    }
+
+    unsafe fn pop(stack: *mut Self, del: bool) -> Option<*mut lvgl_sys::lv_obj_t> {
+        if (*stack).len < 2 {
+            return None;
+        }
+        let popped = (*stack).top();
+        (*stack).len -= 1;
+        Self::load(stack, (*stack).transition.pop, del);
+        Some(popped)
+    }
+}
+
+/// A stack of screens, where the screen on top is shown. See the module
+/// documentation for details.
+///
+/// Dropping the navigator leaves its screens as they are.
+pub struct Navigator<'a, const N: usize> {
+    stack: NonNull<Stack<N>>,
+    _screens: PhantomData<Screen<'a>>,
+}
+
+impl<'a, const N: usize> Navigator<'a, N> {
+    /// Creates a navigator with `root` at the bottom of its stack, and loads
+    /// it. Fails if `N` is zero.
+    pub fn new(root: Screen<'a>) -> LvResult<Self> {
+        if N == 0 {
+            return Err(LvError::InvalidReference);
+        }
+        let mut screens = [ptr::null_mut(); N];
+        screens[0] = root.raw().as_ptr();
+        unsafe { lvgl_sys::lv_disp_load_scr(screens[0]) }
+        let stack = Box::new(Stack {
+            screens,
+            len: 1,
+            transition: Transition::default(),
+            back_gesture: None,
+            refs: 1,
+            detached: false,
+        });
+        let stack = Box::into_raw(stack);
+        unsafe { watch(stack, screens[0]) };
+        Ok(Self {
+            stack: unsafe { NonNull::new_unchecked(stack) },
+            _screens: PhantomData,
+        })
+    }
+
+    /// Sets the animations screens are loaded with.
+    pub fn set_transition(&mut self, transition: Transition) {
+        unsafe { self.stack.as_mut().transition = transition }
+    }
+
+    /// Sets the direction of a swipe that pops the current screen, e.g.
+    /// `Dir::RIGHT` to go back by swiping from left to right, or `None` to
+    /// ignore swipes.
+    pub fn set_back_gesture(&mut self, dir: Option<Dir>) {
+        unsafe { self.stack.as_mut().back_gesture = dir }
+    }
+
+    /// Makes clicking `btn` pop the current screen.
+    pub fn back_button(&mut self, btn: &mut impl Widget<'_>) {
+        unsafe {
+            self.stack.as_mut().refs += 1;
+            lvgl_sys::lv_obj_add_event_cb(
+                btn.raw().as_mut(),
+                Some(back_button_callback::<N>),
+                lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                self.stack.as_ptr() as *mut _,
+            );
+        }
+    }
+
+    /// Pushes `screen` on the stack and loads it, keeping the current screen
+    /// to return to. If the stack is full, the screen is handed back in the
+    /// error.
+    pub fn push(&mut self, screen: Screen<'a>) -> Result<(), Screen<'a>> {
+        let stack = self.stack.as_ptr();
+        unsafe {
+            let len = (*stack).len;
+            if len == N {
+                return Err(screen);
+            }
+            (*stack).screens[len] = screen.raw().as_ptr();
+            (*stack).len += 1;
+            watch(stack, screen.raw().as_ptr());
+            Stack::load(stack, (*stack).transition.push, false);
+        }
+        Ok(())
+    }
+
+    /// Replaces the current screen with `screen` and loads it, deleting the
+    /// current screen.
+    pub fn replace(&mut self, screen: Screen<'a>) {
+        let stack = self.stack.as_ptr();
+        unsafe {
+            // Every screen may have been deleted by the app
+            if (*stack).len == 0 {
+                (*stack).len = 1;
+            }
+            let top = (*stack).len - 1;
+            (*stack).screens[top] = screen.raw().as_ptr();
+            watch(stack, screen.raw().as_ptr());
+            Stack::load(stack, (*stack).transition.push, true);
+        }
+    }
+
+    /// Pops the current screen, deleting it, and loads the screen below.
+    /// Returns false, doing nothing, at the root.
+    pub fn pop(&mut self) -> bool {
+        unsafe { Stack::pop(self.stack.as_ptr(), true).is_some() }
+    }
+
+    /// Pops the current screen and loads the screen below, like `pop()`, but
+    /// keeps the popped screen and returns it, e.g. to push it again later.
+    pub fn pop_cached(&mut self) -> Option<Screen<'a>> {
+        unsafe {
+            let popped = Stack::pop(self.stack.as_ptr(), false)?;
+            Screen::from_raw(NonNull::new_unchecked(popped))
+        }
+    }
+
+    /// Pops every screen above the root, deleting them, and loads the root.
+    pub fn pop_to_root(&mut self) {
+        let stack = self.stack.as_ptr();
+        unsafe {
+            while (*stack).len > 2 {
+                let screen = (*stack).screens[(*stack).len - 2];
+                (*stack).remove(screen);
+                lvgl_sys::lv_obj_del(screen);
+            }
+            Stack::pop(stack, true);
+        }
+    }
+
+    /// Returns the number of screens on the stack, including the root.
+    pub fn depth(&self) -> usize {
+        unsafe { self.stack.as_ref().len }
+    }
+}
+
+impl<const N: usize> Drop for Navigator<'_, N> {
+    fn drop(&mut self) {
+        unsafe {
+            self.stack.as_mut().detached = true;
+            release(self.stack.as_ptr());
+        }
+    }
+}
+
+/// Watches a screen's gestures and deletion, unless a previous push already
+/// does.
+unsafe fn watch<const N: usize>(stack: *mut Stack<N>, screen: *mut lvgl_sys::lv_obj_t) {
+    let callback = Some(screen_callback::<N> as unsafe extern "C" fn(_));
+    if lvgl_sys::lv_obj_get_event_user_data(screen, callback) != stack as *mut _ {
+        (*stack).refs += 1;
+        lvgl_sys::lv_obj_add_event_cb(
+            screen,
+            callback,
+            lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+            stack as *mut _,
+        );
+    }
+}
+
+unsafe fn release<const N: usize>(stack: *mut Stack<N>) {
+    (*stack).refs -= 1;
+    if (*stack).refs == 0 {
+        drop(Box::from_raw(stack));
+    }
+}
+
+/// Pops the current screen once the current event has been handled, as the
+/// event may come from the screen that is deleted.
+unsafe fn go_back<const N: usize>(stack: *mut Stack<N>) {
+    (*stack).refs += 1;
+    let res = lvgl_sys::lv_async_call(Some(async_pop::<N>), stack as *mut _);
+    if u32::from(res) != lvgl_sys::LV_RES_OK {
+        release(stack);
+    }
+}
+
+unsafe extern "C" fn async_pop<const N: usize>(stack: *mut core::ffi::c_void) {
+    guard("navigator pop", || {
+        let stack = stack as *mut Stack<N>;
+        if !(*stack).detached {
+            Stack::pop(stack, true);
+        }
+        release(stack);
+    })
+}
+
+unsafe extern "C" fn screen_callback<const N: usize>(event: *mut lvgl_sys::lv_event_t) {
+    guard("navigator screen", || {
+        let stack = (*event).user_data as *mut Stack<N>;
+        match (*event).code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_GESTURE if !(*stack).detached => {
+                let dir = lvgl_sys::lv_indev_get_gesture_dir(lvgl_sys::lv_indev_get_act());
+                let back = (*stack).back_gesture;
+                if back.map_or(false, |back| back.bits() == dir as u8)
+                    && (*stack).len > 1
+                    && (*stack).top() == (*event).current_target
+                {
+                    go_back(stack);
+                }
+            }
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => {
+                (*stack).remove((*event).current_target);
+                release(stack);
+            }
+            _ => {}
+        }
+    })
+}
+
+unsafe extern "C" fn back_button_callback<const N: usize>(event: *mut lvgl_sys::lv_event_t) {
+    guard("navigator back button", || {
+        let stack = (*event).user_data as *mut Stack<N>;
+        match (*event).code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED if !(*stack).detached => go_back(stack),
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => release(stack),
+            _ => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Btn;
+
+    fn active() -> *mut lvgl_sys::lv_obj_t {
+        unsafe { lvgl_sys::lv_disp_get_scr_act(ptr::null_mut()) }
+    }
+
+    fn is_valid(screen: *mut lvgl_sys::lv_obj_t) -> bool {
+        unsafe { lvgl_sys::lv_obj_is_valid(screen) }
+    }
+
+    #[test]
+    fn back_stack() {
+        crate::tests::initialize_test(true);
+        let root = crate::display::get_scr_act().unwrap();
+        let root_raw = root.raw().as_ptr();
+        let mut nav = Navigator::<3>::new(root).unwrap();
+        nav.set_transition(Transition {
+            push: ScreenAnim::None,
+            pop: ScreenAnim::None,
+            time: Duration::ZERO,
+        });
+
+        let mut settings = Screen::blank().unwrap();
+        let mut back = Btn::create(&mut settings).unwrap();
+        nav.back_button(&mut back);
+        let settings_raw = settings.raw().as_ptr();
+        nav.push(settings).unwrap();
+        let wifi = Screen::blank().unwrap();
+        let wifi_raw = wifi.raw().as_ptr();
+        nav.push(wifi).unwrap();
+        let extra = Screen::blank().unwrap();
+        let raw = extra.raw();
+        assert_eq!(nav.push(extra).map_err(|screen| screen.raw()), Err(raw));
+        assert_eq!((nav.depth(), active()), (3, wifi_raw));
+
+        assert!(nav.pop());
+        assert!(!is_valid(wifi_raw));
+        let settings = nav.pop_cached().unwrap();
+        assert_eq!((nav.depth(), active()), (1, root_raw));
+        assert!(is_valid(settings_raw));
+        assert!(!nav.pop());
+
+        nav.push(settings).unwrap();
+        unsafe {
+            lvgl_sys::lv_event_send(
+                back.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
+                ptr::null_mut(),
+            );
+            // The back button pops its screen once the click is handled
+            assert_eq!(active(), settings_raw);
+            lvgl_sys::lv_timer_handler();
+        }
+        assert_eq!((nav.depth(), active()), (1, root_raw));
+        assert!(!is_valid(settings_raw));
+    }
+
+    #[test]
+    fn replace_and_pop_to_root() {
+        crate::tests::initialize_test(true);
+        let root = crate::display::get_scr_act().unwrap();
+        let root_raw = root.raw().as_ptr();
+        let mut nav = Navigator::<4>::new(root).unwrap();
+        nav.set_transition(Transition {
+            time: Duration::ZERO,
+            ..Default::default()
+        });
+        let login = Screen::blank().unwrap();
+        let login_raw = login.raw().as_ptr();
+        nav.push(login).unwrap();
+        let home = Screen::blank().unwrap();
+        let home_raw = home.raw().as_ptr();
+        nav.replace(home);
+        assert_eq!((nav.depth(), active()), (2, home_raw));
+        assert!(!is_valid(login_raw));
+
+        let details = Screen::blank().unwrap();
+        let details_raw = details.raw().as_ptr();
+        nav.push(details).unwrap();
+        nav.pop_to_root();
+        assert_eq!((nav.depth(), active()), (1, root_raw));
+        assert!(!is_valid(home_raw) && !is_valid(details_raw));
+    }
+
+    #[test]
+    fn screens_deleted_elsewhere_are_forgotten() {
+        crate::tests::initialize_test(true);
+        let root = crate::display::get_scr_act().unwrap();
+        let root_raw = root.raw().as_ptr();
+        let mut nav = Navigator::<4>::new(root).unwrap();
+        nav.set_transition(Transition {
+            time: Duration::ZERO,
+            ..Default::default()
+        });
+        let list = Screen::blank().unwrap();
+        let list_raw = list.raw().as_ptr();
+        nav.push(list).unwrap();
+        let item = Screen::blank().unwrap();
+        let item_raw = item.raw().as_ptr();
+        nav.push(item).unwrap();
+
+        unsafe { lvgl_sys::lv_obj_del(list_raw) };
+        assert_eq!(nav.depth(), 2);
+        assert!(nav.pop());
+        assert_eq!((nav.depth(), active()), (1, root_raw));
+        assert!(!is_valid(item_raw));
+    }
+}
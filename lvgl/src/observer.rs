@@ -0,0 +1,507 @@
+//! Values observed by the UI
+//!
+//! A [`Subject`] holds a value, e.g. a temperature or the selected theme,
+//! and notifies its observers whenever it is set. Observers are closures,
+//! either on their own or bound to a widget, in which case they are removed
+//! when the widget is deleted. The application logic thus sets the state
+//! without knowing which widgets show it:
+//!
+//! ```ignore
+//! use lvgl::observer::IntSubject;
+//!
+//! let temperature = IntSubject::new(20);
+//! let mut label = Label::create(&mut screen)?;
+//! temperature.observe(&mut label, |label, celsius| {
+//!     let _ = label.set_text_fmt(format_args!("{} °C", celsius));
+//! });
+//! let mut heating = Switch::create(&mut screen)?;
+//! temperature.bind_state_if_eq(&mut heating, State::DISABLED, 30);
+//!
+//! // Updates the label
+//! temperature.set(21);
+//! ```
+//!
+//! The subjects are typed: [`IntSubject`], [`ColorSubject`],
+//! [`StringSubject`] with the `alloc` feature, [`PointerSubject`], or a
+//! `Subject` of any other `Clone` type. They follow the `lv_subject` and
+//! `lv_observer` API of LVGL 9, to which they will map once it is supported.
+//! Observers are notified when they are added, and each time the value is
+//! set, even to an equal value. An observer setting its own subject is not
+//! called again for the nested update, while the other observers are.
+
+use crate::guard::guard;
+use crate::widgets::Slider;
+use crate::{AnimationState, Box, Color, NativeObject, ObjFlag, State, Widget};
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+/// A subject holding an integer, e.g. a value shown on a slider.
+pub type IntSubject = Subject<i32>;
+
+/// A subject holding a color, e.g. a theme's accent color.
+pub type ColorSubject = Subject<Color>;
+
+/// A subject holding a string, e.g. a status message.
+#[cfg(feature = "alloc")]
+pub type StringSubject = Subject<alloc::string::String>;
+
+/// A subject holding a reference to static data, e.g. the current entry of
+/// a table of settings.
+pub type PointerSubject<T> = Subject<Option<&'static T>>;
+
+/// A value that notifies its observers when it is set. See the module
+/// documentation.
+pub struct Subject<T> {
+    inner: NonNull<Inner<T>>,
+}
+
+struct Inner<T> {
+    value: T,
+    previous: T,
+    observers: *mut Header,
+    notifying: u32,
+}
+
+/// The part of an observer independent of its closure, in a list in LVGL
+/// memory.
+#[repr(C)]
+struct Header {
+    next: *mut Header,
+    subject: *mut c_void,
+    /// The widget the observer is bound to, or null.
+    obj: *mut lvgl_sys::lv_obj_t,
+    /// Set when the observer is removed while its subject notifies.
+    removed: bool,
+    /// Set while the observer's closure runs, which must not be entered
+    /// again by a nested notification.
+    running: bool,
+    notify: unsafe fn(*mut Header, *const c_void),
+    unlink: unsafe fn(*mut Header),
+    drop: unsafe fn(*mut Header),
+    /// Called when the bound widget's value changes, for two-way bindings.
+    changed: Option<unsafe fn(*mut Header)>,
+}
+
+#[repr(C)]
+struct Node<T, F> {
+    header: Header,
+    f: F,
+    _value: PhantomData<fn(&T)>,
+}
+
+/// An observer added to a `Subject`, to remove it with `unsubscribe()`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Observer(NonNull<c_void>);
+
+unsafe fn notify_node<T, F: FnMut(*mut lvgl_sys::lv_obj_t, &T)>(
+    node: *mut Header,
+    value: *const c_void,
+) {
+    let node = node as *mut Node<T, F>;
+    ((*node).f)((*node).header.obj, &*(value as *const T));
+}
+
+unsafe fn drop_node<T, F>(node: *mut Header) {
+    drop(Box::from_raw(node as *mut Node<T, F>));
+}
+
+unsafe fn unlink_node<T>(node: *mut Header) {
+    remove((*node).subject as *mut Inner<T>, node);
+}
+
+impl<T: Clone> Subject<T> {
+    /// Creates a subject holding `value`, without observers.
+    pub fn new(value: T) -> Self {
+        let inner = Box::into_raw(Box::new(Inner {
+            previous: value.clone(),
+            value,
+            observers: ptr::null_mut(),
+            notifying: 0,
+        }));
+        Self {
+            inner: NonNull::new(inner).unwrap(),
+        }
+    }
+
+    /// Returns the value.
+    pub fn get(&self) -> T {
+        unsafe { (*self.inner.as_ptr()).value.clone() }
+    }
+
+    /// Returns the value before it was last set.
+    pub fn get_previous(&self) -> T {
+        unsafe { (*self.inner.as_ptr()).previous.clone() }
+    }
+
+    /// Sets the value and notifies the observers.
+    pub fn set(&self, value: T) {
+        unsafe { set(self.inner.as_ptr(), value) }
+    }
+
+    /// Notifies the observers of the value again, e.g. after the data a
+    /// `PointerSubject` refers to changed.
+    pub fn notify(&self) {
+        unsafe { notify(self.inner.as_ptr()) }
+    }
+
+    /// Adds an observer called with the value now and whenever it is set,
+    /// until it is removed with `unsubscribe()` or the subject is dropped.
+    pub fn subscribe(&self, mut f: impl FnMut(&T) + 'static) -> Observer {
+        self.add(ptr::null_mut(), move |_, value: &T| f(value), None)
+    }
+
+    /// Adds an observer bound to `widget`, which `f` is called with along
+    /// with the value, now and whenever it is set. The observer is removed
+    /// when the widget is deleted.
+    pub fn observe<'a, W: Widget<'a>>(
+        &self,
+        widget: &mut W,
+        mut f: impl FnMut(&mut W, &T) + 'static,
+    ) -> Observer {
+        let obj = widget.raw().as_ptr();
+        self.add(
+            obj,
+            move |obj, value: &T| {
+                if let Some(mut widget) =
+                    NonNull::new(obj).and_then(|obj| unsafe { W::from_raw(obj) })
+                {
+                    f(&mut widget, value)
+                }
+            },
+            None,
+        )
+    }
+
+    /// Removes an observer. Returns `false` if it is not an observer of this
+    /// subject, e.g. because the widget it was bound to was deleted.
+    pub fn unsubscribe(&self, observer: Observer) -> bool {
+        let inner = self.inner.as_ptr();
+        let target = observer.0.as_ptr() as *mut Header;
+        unsafe {
+            let mut node = (*inner).observers;
+            while !node.is_null() {
+                if node == target && !(*node).removed {
+                    remove(inner, node);
+                    return true;
+                }
+                node = (*node).next;
+            }
+        }
+        false
+    }
+
+    fn add<F: FnMut(*mut lvgl_sys::lv_obj_t, &T)>(
+        &self,
+        obj: *mut lvgl_sys::lv_obj_t,
+        f: F,
+        changed: Option<unsafe fn(*mut Header)>,
+    ) -> Observer {
+        let inner = self.inner.as_ptr();
+        let node = Box::into_raw(Box::new(Node::<T, F> {
+            header: Header {
+                next: unsafe { (*inner).observers },
+                subject: inner as *mut c_void,
+                obj,
+                removed: false,
+                running: false,
+                notify: notify_node::<T, F>,
+                unlink: unlink_node::<T>,
+                drop: drop_node::<T, F>,
+                changed,
+            },
+            f,
+            _value: PhantomData,
+        })) as *mut Header;
+        unsafe {
+            (*inner).observers = node;
+            if !obj.is_null() {
+                lvgl_sys::lv_obj_add_event_cb(
+                    obj,
+                    Some(observer_callback),
+                    lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                    node as *mut c_void,
+                );
+                if changed.is_some() {
+                    lvgl_sys::lv_obj_add_event_cb(
+                        obj,
+                        Some(observer_callback),
+                        lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED,
+                        node as *mut c_void,
+                    );
+                }
+            }
+            let value = (*inner).value.clone();
+            (*inner).notifying += 1;
+            call(node, &value);
+            (*inner).notifying -= 1;
+            if (*inner).notifying == 0 {
+                sweep(inner);
+            }
+        }
+        Observer(NonNull::new(node as *mut c_void).unwrap())
+    }
+}
+
+impl<T: Clone + PartialEq> Subject<T> {
+    /// Adds `flag` to `widget` while the value equals `value`, and clears it
+    /// otherwise, e.g. to hide a warning while a level is normal.
+    pub fn bind_flag_if_eq<'a>(
+        &self,
+        widget: &mut impl Widget<'a>,
+        flag: ObjFlag,
+        value: T,
+    ) -> Observer {
+        self.add(
+            widget.raw().as_ptr(),
+            move |obj, current: &T| unsafe {
+                if *current == value {
+                    lvgl_sys::lv_obj_add_flag(obj, flag.into());
+                } else {
+                    lvgl_sys::lv_obj_clear_flag(obj, flag.into());
+                }
+            },
+            None,
+        )
+    }
+
+    /// Adds `state` to `widget` while the value equals `value`, and clears
+    /// it otherwise, e.g. to disable a button while a form is incomplete.
+    pub fn bind_state_if_eq<'a>(
+        &self,
+        widget: &mut impl Widget<'a>,
+        state: State,
+        value: T,
+    ) -> Observer {
+        self.add(
+            widget.raw().as_ptr(),
+            move |obj, current: &T| unsafe {
+                if *current == value {
+                    lvgl_sys::lv_obj_add_state(obj, state.into());
+                } else {
+                    lvgl_sys::lv_obj_clear_state(obj, state.into());
+                }
+            },
+            None,
+        )
+    }
+}
+
+impl IntSubject {
+    /// Binds the value of `slider` to the subject both ways: the slider
+    /// shows the value, and moving the slider sets it.
+    pub fn bind_slider_value(&self, slider: &mut Slider<'_>) -> Observer {
+        unsafe fn changed(node: *mut Header) {
+            let value = lvgl_sys::lv_slider_get_value((*node).obj);
+            set((*node).subject as *mut Inner<i32>, value);
+        }
+        self.add(
+            slider.raw().as_ptr(),
+            |obj, value: &i32| unsafe {
+                lvgl_sys::lv_slider_set_value(obj, *value, AnimationState::OFF.into())
+            },
+            Some(changed),
+        )
+    }
+}
+
+impl<T> Drop for Subject<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let inner = Box::from_raw(self.inner.as_ptr());
+            let mut node = inner.observers;
+            while !node.is_null() {
+                let next = (*node).next;
+                unbind(node);
+                ((*node).drop)(node);
+                node = next;
+            }
+        }
+    }
+}
+
+unsafe fn set<T: Clone>(inner: *mut Inner<T>, value: T) {
+    (*inner).previous = mem::replace(&mut (*inner).value, value);
+    notify(inner);
+}
+
+unsafe fn notify<T: Clone>(inner: *mut Inner<T>) {
+    // Observers get a copy, as they may set the subject again
+    let value = (*inner).value.clone();
+    (*inner).notifying += 1;
+    let mut node = (*inner).observers;
+    while !node.is_null() {
+        call(node, &value);
+        node = (*node).next;
+    }
+    (*inner).notifying -= 1;
+    if (*inner).notifying == 0 {
+        sweep(inner);
+    }
+}
+
+/// Calls an observer with `value`, unless it is removed or already running.
+unsafe fn call<T>(node: *mut Header, value: &T) {
+    if (*node).removed || (*node).running {
+        return;
+    }
+    (*node).running = true;
+    ((*node).notify)(node, value as *const T as *const c_void);
+    (*node).running = false;
+}
+
+/// Removes the event callbacks of an observer from its widget, if any.
+unsafe fn unbind(node: *mut Header) {
+    let obj = mem::replace(&mut (*node).obj, ptr::null_mut());
+    if !obj.is_null() {
+        while lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+            obj,
+            Some(observer_callback),
+            node as *mut c_void,
+        ) {}
+    }
+}
+
+/// Removes an observer, or marks it to be removed once the subject is done
+/// notifying.
+unsafe fn remove<T>(inner: *mut Inner<T>, node: *mut Header) {
+    unbind(node);
+    (*node).removed = true;
+    if (*inner).notifying == 0 {
+        sweep(inner);
+    }
+}
+
+/// Frees the removed observers.
+unsafe fn sweep<T>(inner: *mut Inner<T>) {
+    let mut link: *mut *mut Header = &mut (*inner).observers;
+    while !(*link).is_null() {
+        let node = *link;
+        if (*node).removed {
+            *link = (*node).next;
+            ((*node).drop)(node);
+        } else {
+            link = &mut (*node).next;
+        }
+    }
+}
+
+unsafe extern "C" fn observer_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("observer", || {
+        let node = lvgl_sys::lv_event_get_user_data(event) as *mut Header;
+        if lvgl_sys::lv_event_get_code(event) == lvgl_sys::lv_event_code_t_LV_EVENT_DELETE {
+            // LVGL is running the widget's callbacks, which must stay in place
+            (*node).obj = ptr::null_mut();
+            ((*node).unlink)(node);
+        } else if let Some(changed) = (*node).changed {
+            changed(node);
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Label;
+    use crate::Display;
+    use core::cell::Cell;
+    use cstr_core::CStr;
+    use std::rc::Rc;
+    use std::string::String;
+
+    #[test]
+    fn observe_subjects() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+
+        let level = IntSubject::new(1);
+        let mut label = Label::create(&mut screen).unwrap();
+        level.observe(&mut label, |label, level| {
+            let _ = label.set_text_fmt(format_args!("Level {}", level));
+        });
+        let text = |label: &Label| unsafe {
+            String::from(
+                CStr::from_ptr(lvgl_sys::lv_label_get_text(label.raw().as_ptr()))
+                    .to_str()
+                    .unwrap(),
+            )
+        };
+        assert_eq!(text(&label), "Level 1");
+        level.set(2);
+        assert_eq!(text(&label), "Level 2");
+        assert_eq!(level.get_previous(), 1);
+
+        let mut warning = Label::create(&mut screen).unwrap();
+        level.bind_flag_if_eq(&mut warning, ObjFlag::HIDDEN, 2);
+        assert!(warning.has_flag(ObjFlag::HIDDEN));
+        level.set(3);
+        assert!(!warning.has_flag(ObjFlag::HIDDEN));
+
+        let mut slider = Slider::create(&mut screen).unwrap();
+        level.bind_slider_value(&mut slider);
+        assert_eq!(slider.get_value(), 3);
+        unsafe {
+            lvgl_sys::lv_slider_set_value(
+                slider.raw().as_ptr(),
+                7,
+                lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+            );
+            lvgl_sys::lv_event_send(
+                slider.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED,
+                ptr::null_mut(),
+            );
+        }
+        assert_eq!(level.get(), 7);
+        assert_eq!(text(&label), "Level 7");
+
+        // Deleting a widget removes its observers
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let observer = level.subscribe(move |_| counter.set(counter.get() + 1));
+        unsafe { lvgl_sys::lv_obj_del(label.raw().as_ptr()) };
+        level.set(8);
+        assert_eq!(calls.get(), 2);
+        assert!(level.unsubscribe(observer));
+        level.set(9);
+        assert_eq!(calls.get(), 2);
+
+        let accent = ColorSubject::new(Color::from_hex(0xFF0000));
+        accent.observe(&mut slider, |slider, color| {
+            slider.set_style_bg_color(crate::Part::Main, *color)
+        });
+        accent.set(Color::from_hex(0x0000FF));
+        assert_eq!(accent.get_previous().to_u32(), 0xFFFF0000);
+        drop(accent);
+        level.set(10);
+        assert_eq!(slider.get_value(), 10);
+    }
+
+    #[test]
+    fn nested_set_skips_running_observer() {
+        crate::tests::initialize_test(false);
+        let subject = Rc::new(IntSubject::new(0));
+        let seen = Rc::new(Cell::new(0));
+
+        let inner = subject.clone();
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        subject.subscribe(move |value| {
+            counter.set(counter.get() + 1);
+            if *value == 1 {
+                inner.set(2);
+            }
+        });
+        let last = seen.clone();
+        subject.subscribe(move |value| last.set(*value));
+
+        subject.set(1);
+        assert_eq!(subject.get(), 2);
+        // Called when added and for 1, not for the nested 2, which the other
+        // observer gets
+        assert_eq!(calls.get(), 2);
+        assert_eq!(seen.get(), 2);
+    }
+}
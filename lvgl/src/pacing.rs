@@ -0,0 +1,185 @@
+//! Main loops that sleep until LVGL has work to do
+//!
+//! Calling `task_handler()` in a loop with a fixed sleep either burns CPU
+//! time, when the sleep is short, or delays animations and input, when it is
+//! long. [`timer_handler()`](crate::timer_handler) returns the time until
+//! LVGL's next timer is due instead, and [`run_loop()`] waits exactly that
+//! long between calls, with any [`Pacer`]:
+//!
+//! ```ignore
+//! use lvgl::pacing::{run_loop, ThreadSleep};
+//! use core::ops::ControlFlow;
+//!
+//! run_loop(&mut ThreadSleep::new(), || {
+//!     if window.closed() {
+//!         ControlFlow::Break(())
+//!     } else {
+//!         ControlFlow::Continue(())
+//!     }
+//! })?;
+//! ```
+//!
+//! A closure taking the timeout is a pacer too, e.g. one that waits for the
+//! display's vsync or tearing effect (TE) signal, so that frames are flushed
+//! while the panel is not scanning out:
+//!
+//! ```ignore
+//! run_loop(&mut |timeout: Duration| te_pin.wait_for_rising_edge(timeout), || {
+//!     ControlFlow::<()>::Continue(())
+//! })?;
+//! ```
+//!
+//! On async executors such as Embassy, [`run_loop_async()`] awaits a timer
+//! instead:
+//!
+//! ```ignore
+//! use embassy_time::Timer;
+//!
+//! run_loop_async(
+//!     |timeout| Timer::after_millis(timeout.as_millis() as u64),
+//!     || ControlFlow::<()>::Continue(()),
+//! )
+//! .await?;
+//! ```
+//!
+//! Pacers other than [`ThreadSleep`] only wait: LVGL's tick must still be
+//! advanced, e.g. with the [`tick`](crate::tick) module. Waits are capped at
+//! [`MAX_WAIT`], so that input is still read if LVGL has no timers, and with
+//! the `defer` feature, the loop does not wait while closures are queued.
+
+use crate::LvResult;
+use core::future::Future;
+use core::ops::ControlFlow;
+use core::time::Duration;
+
+/// The longest time `run_loop()` waits between two calls of the timer
+/// handler.
+pub const MAX_WAIT: Duration = Duration::from_millis(100);
+
+/// Waits between two calls of LVGL's timer handler.
+pub trait Pacer {
+    /// Waits until the next frame should be rendered, at most `timeout`,
+    /// the time until LVGL's next timer is due. Returning earlier, e.g. on
+    /// an input interrupt, is fine.
+    fn wait(&mut self, timeout: Duration);
+}
+
+impl<F: FnMut(Duration)> Pacer for F {
+    fn wait(&mut self, timeout: Duration) {
+        self(timeout)
+    }
+}
+
+/// Sleeps the current thread, and advances LVGL's tick by the time that
+/// passed, as measured by the system clock.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ThreadSleep {
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    last: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl ThreadSleep {
+    /// Creates a pacer counting ticks from now.
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+            last: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for ThreadSleep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Pacer for ThreadSleep {
+    fn wait(&mut self, timeout: Duration) {
+        std::thread::sleep(timeout);
+        // With these features, LVGL reads the clock itself
+        #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+        {
+            let now = std::time::Instant::now();
+            let elapsed = now - self.last;
+            crate::tick_inc(elapsed);
+            // Keep the sub-millisecond remainder for the next tick
+            self.last = now - Duration::from_nanos(elapsed.subsec_nanos() as u64 % 1_000_000);
+        }
+    }
+}
+
+/// Returns how long to wait after the timer handler returned `next`.
+fn wait_time(next: Duration) -> Duration {
+    #[cfg(feature = "defer")]
+    if crate::defer::is_pending() {
+        return Duration::ZERO;
+    }
+    next.min(MAX_WAIT)
+}
+
+/// Calls the timer handler, then `frame`, and waits with `pacer` until LVGL
+/// has work to do again, until `frame` breaks with a value, which is
+/// returned.
+pub fn run_loop<R>(
+    pacer: &mut impl Pacer,
+    mut frame: impl FnMut() -> ControlFlow<R>,
+) -> LvResult<R> {
+    loop {
+        let next = crate::timer_handler()?;
+        if let ControlFlow::Break(value) = frame() {
+            return Ok(value);
+        }
+        pacer.wait(wait_time(next));
+    }
+}
+
+/// Calls the timer handler, then `frame`, and awaits the future returned by
+/// `wait` for the time until LVGL has work to do again, until `frame`
+/// breaks with a value, which is returned.
+pub async fn run_loop_async<R, W: Future<Output = ()>>(
+    mut wait: impl FnMut(Duration) -> W,
+    mut frame: impl FnMut() -> ControlFlow<R>,
+) -> LvResult<R> {
+    loop {
+        let next = crate::timer_handler()?;
+        if let ControlFlow::Break(value) = frame() {
+            return Ok(value);
+        }
+        wait(wait_time(next)).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::ptr;
+    use std::vec::Vec;
+
+    unsafe extern "C" fn idle(_: *mut lvgl_sys::lv_timer_t) {}
+
+    #[test]
+    fn waits_until_next_timer() {
+        crate::tests::initialize_test(false);
+        let timer = unsafe { lvgl_sys::lv_timer_create(Some(idle), 20, ptr::null_mut()) };
+
+        let mut waits = Vec::new();
+        let mut frames = 0;
+        let result = run_loop(&mut |timeout: Duration| waits.push(timeout), || {
+            frames += 1;
+            if frames == 3 {
+                ControlFlow::Break(frames)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(result, Ok(3));
+        assert_eq!(waits.len(), 2);
+        assert!(waits.iter().all(|wait| *wait <= Duration::from_millis(20)));
+        unsafe { lvgl_sys::lv_timer_del(timer) };
+    }
+}
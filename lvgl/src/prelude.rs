@@ -0,0 +1,20 @@
+//! Commonly used types and traits
+//!
+//! ```ignore
+//! use lvgl::prelude::*;
+//! ```
+//!
+//! This brings in the widgets, the `Widget`, `ValueWidget` and `NativeObject`
+//! traits needed to call their methods, displays, colors, styles and the enums
+//! their methods take. `misc::area::{Coord, Size}` are left out, as they would
+//! clash with the `Size` of `embedded_graphics::prelude`.
+
+pub use crate::style::{
+    ConstStyle, ConstStyleProp, FlexAlign, FlexFlow, GridAlign, Layout, Opacity, Style,
+};
+pub use crate::widgets::*;
+pub use crate::{
+    Align, AnimationState, Builder, Color, Dir, Display, DrawBuffer, DynDrawBuffer, Event,
+    LabelLongMode, LvError, LvResult, NativeObject, Obj, ObjFlag, Part, Screen, ScrollbarMode,
+    State, TextAlign, ValueWidget, Widget, WidgetClass,
+};
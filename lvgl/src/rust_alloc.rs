@@ -0,0 +1,119 @@
+//! Rust-backed memory management for LVGL
+//!
+//! By default LVGL manages its own statically-sized memory pool. This feature
+//! instead routes every LVGL allocation through Rust's global allocator, so
+//! there is a single heap to size and tune on embedded targets.
+//!
+//! # Building
+//!
+//! Enable the `rust_alloc` feature on the `lvgl` crate. The build script
+//! applies the following on top of your `lv_conf.h`:
+//!
+//! ```c
+//! #define LV_MEM_CUSTOM 1
+//! #define LV_MEM_CUSTOM_INCLUDE <rs_alloc.h>
+//! #define LV_MEM_CUSTOM_ALLOC   rs_lv_malloc
+//! #define LV_MEM_CUSTOM_FREE    rs_lv_free
+//! #define LV_MEM_CUSTOM_REALLOC rs_lv_realloc
+//! ```
+//!
+//! A `#[global_allocator]` must be available. On `std` targets this is the
+//! system allocator; on baremetal targets, register one (e.g. from the
+//! `embedded-alloc` crate) before LVGL is initialized.
+//!
+//! This feature cannot be combined with `lvgl_alloc`, which does the opposite
+//! and would cause every allocation to recurse.
+
+use alloc::alloc::{alloc, dealloc, realloc, Layout};
+use core::ffi::{c_size_t, c_void};
+use core::mem::size_of;
+use core::ptr;
+
+/// Alignment of every block handed out to LVGL. This matches what `malloc()`
+/// guarantees on common targets.
+const ALIGN: usize = 2 * size_of::<usize>();
+
+/// Every block is prefixed by a header storing its total size, as LVGL does
+/// not pass the size back when freeing. The header is padded to `ALIGN` so the
+/// returned pointer keeps the block's alignment.
+const HEADER: usize = ALIGN;
+
+fn block_layout(size: c_size_t) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(HEADER)?, ALIGN).ok()
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_malloc(size: c_size_t) -> *mut c_void {
+    let Some(layout) = block_layout(size) else {
+        return ptr::null_mut();
+    };
+    let base = alloc(layout);
+    if base.is_null() {
+        return ptr::null_mut();
+    }
+    (base as *mut usize).write(layout.size());
+    base.add(HEADER) as *mut c_void
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let base = (ptr as *mut u8).sub(HEADER);
+    let size = (base as *const usize).read();
+    // Safety: the layout was validated when the block was allocated.
+    dealloc(base, Layout::from_size_align_unchecked(size, ALIGN));
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_realloc(ptr: *mut c_void, new_size: c_size_t) -> *mut c_void {
+    if ptr.is_null() {
+        return rs_lv_malloc(new_size);
+    }
+    if new_size == 0 {
+        rs_lv_free(ptr);
+        return ptr::null_mut();
+    }
+    let Some(new_layout) = block_layout(new_size) else {
+        return ptr::null_mut();
+    };
+    let base = (ptr as *mut u8).sub(HEADER);
+    let old_size = (base as *const usize).read();
+    let new_base = realloc(
+        base,
+        Layout::from_size_align_unchecked(old_size, ALIGN),
+        new_layout.size(),
+    );
+    if new_base.is_null() {
+        return ptr::null_mut();
+    }
+    (new_base as *mut usize).write(new_layout.size());
+    new_base.add(HEADER) as *mut c_void
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn alloc_realloc_free_roundtrip() {
+        unsafe {
+            let p = rs_lv_malloc(16) as *mut u8;
+            assert!(!p.is_null());
+            assert_eq!(p as usize % ALIGN, 0);
+            for i in 0..16 {
+                p.add(i).write(i as u8);
+            }
+
+            let p = rs_lv_realloc(p as *mut c_void, 64) as *mut u8;
+            assert!(!p.is_null());
+            for i in 0..16 {
+                assert_eq!(p.add(i).read(), i as u8);
+            }
+
+            rs_lv_free(p as *mut c_void);
+            rs_lv_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Captures of what LVGL renders
+//!
+//! A [`Snapshot`] renders a widget, or a display's active screen, into
+//! memory with LVGL's snapshot API, independently of the display's draw
+//! buffer and flush callback:
+//!
+//! ```ignore
+//! let snapshot = display.snapshot()?;
+//! let corner = snapshot.pixel(0, 0);
+//! ```
+//!
+//! With the `screenshot` feature, `Display::screenshot_png()` saves the
+//! active screen as a PNG file, e.g. for documentation images or bug reports
+//! from a simulator:
+//!
+//! ```ignore
+//! display.screenshot_png("settings.png")?;
+//! ```
+//!
+//! Widgets on the top and system layers, such as message boxes, are not on
+//! the screen and are not captured with it.
+//!
+//! # Building
+//!
+//! Enable the `snapshot` feature, which sets `LV_USE_SNAPSHOT` in the
+//! generated `lv_conf.h`, or the `screenshot` feature, which requires `std`.
+
+use crate::{Color, Display, LvError, LvResult, NativeObject};
+use core::ptr::NonNull;
+use core::slice;
+#[cfg(feature = "screenshot")]
+use std::{error::Error, fmt, fs::File, io, path::Path, vec::Vec};
+
+/// A rendering of a widget, allocated from LVGL's memory and freed when
+/// dropped.
+pub struct Snapshot {
+    raw: NonNull<lvgl_sys::lv_img_dsc_t>,
+}
+
+impl Snapshot {
+    /// Renders `obj` and its children. Fails with `LvError::LvOOMemory` if
+    /// LVGL cannot allocate the image.
+    pub fn take(obj: &impl NativeObject) -> LvResult<Self> {
+        Self::take_raw(obj.raw())
+    }
+
+    fn take_raw(obj: NonNull<lvgl_sys::lv_obj_t>) -> LvResult<Self> {
+        let raw = unsafe {
+            lvgl_sys::lv_snapshot_take(obj.as_ptr(), lvgl_sys::LV_IMG_CF_TRUE_COLOR as _)
+        };
+        NonNull::new(raw)
+            .map(|raw| Self { raw })
+            .ok_or(LvError::LvOOMemory)
+    }
+
+    /// Returns the width of the snapshot, in pixels.
+    pub fn width(&self) -> u32 {
+        unsafe { self.raw.as_ref().header.w() }
+    }
+
+    /// Returns the height of the snapshot, in pixels.
+    pub fn height(&self) -> u32 {
+        unsafe { self.raw.as_ref().header.h() }
+    }
+
+    /// Returns the rendered pixels, row by row.
+    pub fn pixels(&self) -> &[Color] {
+        let len = (self.width() * self.height()) as usize;
+        unsafe { slice::from_raw_parts(self.raw.as_ref().data as *const Color, len) }
+    }
+
+    /// Returns the color of a pixel, or `None` outside the snapshot.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        self.pixels()
+            .get(y as usize * self.width() as usize + x as usize)
+            .copied()
+    }
+
+    /// Encodes the snapshot as an 8-bit RGB PNG image into `writer`.
+    #[cfg(feature = "screenshot")]
+    pub fn write_png<W: io::Write>(&self, writer: W) -> Result<(), ScreenshotError> {
+        write_png(writer, self.width(), self.height(), self.pixels())
+    }
+}
+
+/// Encodes `pixels`, row by row, as an 8-bit RGB PNG image into `writer`.
+#[cfg(feature = "screenshot")]
+pub(crate) fn write_png<W: io::Write>(
+    writer: W,
+    width: u32,
+    height: u32,
+    pixels: &[Color],
+) -> Result<(), ScreenshotError> {
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let rgb: Vec<u8> = pixels
+        .iter()
+        .flat_map(|color| {
+            let [_, r, g, b] = color.to_u32().to_be_bytes();
+            [r, g, b]
+        })
+        .collect();
+    encoder.write_header()?.write_image_data(&rgb)?;
+    Ok(())
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_snapshot_free(self.raw.as_ptr()) }
+    }
+}
+
+impl Display {
+    /// Renders the display's active screen. See `Snapshot::take()`.
+    pub fn snapshot(&self) -> LvResult<Snapshot> {
+        let screen = unsafe { lvgl_sys::lv_disp_get_scr_act(self.disp.as_ptr()) };
+        let screen = NonNull::new(screen).ok_or(LvError::InvalidReference)?;
+        Snapshot::take_raw(screen)
+    }
+
+    /// Renders the display's active screen and saves it as a PNG file at
+    /// `path`.
+    #[cfg(feature = "screenshot")]
+    pub fn screenshot_png(&self, path: impl AsRef<Path>) -> Result<(), ScreenshotError> {
+        let snapshot = self.snapshot()?;
+        let file = File::create(path)?;
+        snapshot.write_png(io::BufWriter::new(file))
+    }
+}
+
+/// An error that stopped saving a screenshot.
+#[cfg(feature = "screenshot")]
+#[derive(Debug)]
+pub enum ScreenshotError {
+    /// The file could not be written.
+    Io(io::Error),
+    /// The image could not be encoded.
+    Png(png::EncodingError),
+    /// The snapshot could not be taken.
+    Lvgl(LvError),
+}
+
+#[cfg(feature = "screenshot")]
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenshotError::Io(e) => write!(f, "I/O error: {}", e),
+            ScreenshotError::Png(e) => write!(f, "PNG error: {}", e),
+            ScreenshotError::Lvgl(e) => write!(f, "LVGL error: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "screenshot")]
+impl Error for ScreenshotError {}
+
+#[cfg(feature = "screenshot")]
+impl From<io::Error> for ScreenshotError {
+    fn from(err: io::Error) -> Self {
+        ScreenshotError::Io(err)
+    }
+}
+
+#[cfg(feature = "screenshot")]
+impl From<png::EncodingError> for ScreenshotError {
+    fn from(err: png::EncodingError) -> Self {
+        ScreenshotError::Png(err)
+    }
+}
+
+#[cfg(feature = "screenshot")]
+impl From<LvError> for ScreenshotError {
+    fn from(err: LvError) -> Self {
+        ScreenshotError::Lvgl(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DrawBuffer, Part, Widget};
+
+    #[test]
+    fn snapshot_screen() {
+        crate::tests::initialize_test(false);
+        let display =
+            Display::register(DrawBuffer::<{ 40 * 4 }>::default(), 40, 30, |_| {}).unwrap();
+        let mut screen = display.get_scr_act().unwrap();
+        screen.set_style_bg_color(Part::Main, Color::from_hex(0x0000FF));
+        screen.set_style_bg_opa(Part::Main, crate::style::Opacity::OPA_COVER);
+
+        let snapshot = display.snapshot().unwrap();
+        assert_eq!((snapshot.width(), snapshot.height()), (40, 30));
+        assert_eq!(snapshot.pixels().len(), 40 * 30);
+        let blue = Color::from_hex(0x0000FF).to_u32();
+        assert_eq!(snapshot.pixel(39, 29).map(|c| c.to_u32()), Some(blue));
+        assert!(snapshot.pixel(40, 0).is_none());
+        assert!(snapshot.pixel(0, 30).is_none());
+        assert!(snapshot.pixel(0, u32::MAX).is_none());
+
+        #[cfg(feature = "screenshot")]
+        {
+            let mut png = Vec::new();
+            snapshot.write_png(&mut png).unwrap();
+            assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Screens designed in SquareLine Studio
+//!
+//! SquareLine Studio exports a UI as C sources: a `ui_init()` function that
+//! creates the screens, a global `lv_obj_t *` for each widget, e.g.
+//! `ui_Main` or `ui_SaveButton`, and declarations of the functions the
+//! designer attached to events. Point `LVGL_UI_DIR` at the export directory
+//! to compile it along with LVGL, then describe the parts used from Rust with
+//! the [`squareline!`](crate::squareline!) macro:
+//!
+//! ```ignore
+//! use lvgl::widgets::{Btn, Slider};
+//!
+//! lvgl::squareline! {
+//!     /// The screens of the settings panel.
+//!     pub struct Ui {
+//!         screens {
+//!             main: ui_Main,
+//!         }
+//!         widgets {
+//!             save: ui_SaveButton as Btn,
+//!             volume: ui_Volume as Slider,
+//!         }
+//!     }
+//!     events {
+//!         fn save_clicked(event) {
+//!             println!("{:?} on {:?}", event.event(), event.target::<Btn>());
+//!         }
+//!     }
+//! }
+//!
+//! let ui = Ui::init()?;
+//! let volume = ui.volume().unwrap().get_value();
+//! ```
+//!
+//! The generated struct has an `init()` function calling `ui_init()`, which
+//! loads the first screen on the default display, and a method for each
+//! listed screen and widget returning it, or `None` if it does not exist at
+//! the time, e.g. for a screen SquareLine deletes when it is unloaded.
+//! Widgets are checked to be of the given type. Each function under
+//! `events` implements the C function of that name, with an [`UiEvent`]
+//! argument.
+//!
+//! # Building
+//!
+//! Set `LVGL_UI_DIR` to the directory of the exported `ui.c` and `ui.h`; the
+//! C files in it and its subdirectories, such as the screens, images and
+//! fonts, are compiled with the `lv_conf.h` LVGL is built with. Delete the
+//! exported `ui_events.c`, if any, as the `events` it defines are implemented
+//! in Rust instead.
+
+use crate::{Event, LvError, LvResult, Obj, Widget, WidgetClass};
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// An event sent to a function the designer attached to a widget.
+pub struct UiEvent {
+    raw: NonNull<lvgl_sys::lv_event_t>,
+}
+
+impl UiEvent {
+    /// Wraps an event passed to an exported event function.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be null or point to the event being sent.
+    #[doc(hidden)]
+    pub unsafe fn from_raw(raw: *mut lvgl_sys::lv_event_t) -> Option<Self> {
+        NonNull::new(raw).map(|raw| Self { raw })
+    }
+
+    /// Returns the kind of event, or `None` for the ones without an `Event`
+    /// variant.
+    pub fn event(&self) -> Option<Event<u32>> {
+        let code = unsafe { lvgl_sys::lv_event_get_code(self.raw.as_ptr()) };
+        Event::try_from(code).ok()
+    }
+
+    /// Returns the widget the event was sent to, if it is a `W`.
+    pub fn target<'a, W: Widget<'a> + WidgetClass>(&self) -> Option<W> {
+        let target = unsafe { lvgl_sys::lv_event_get_target(self.raw.as_ptr()) };
+        unsafe { widget(target) }
+    }
+}
+
+/// Runs the body of the exported event function `name` on `raw`, aborting
+/// the program if it panics, as other LVGL callbacks do.
+///
+/// # Safety
+///
+/// `raw` must be null or point to the event being sent.
+#[doc(hidden)]
+pub unsafe fn event(name: &str, raw: *mut lvgl_sys::lv_event_t, f: impl FnOnce(UiEvent)) {
+    crate::guard::guard(name, || {
+        if let Some(event) = UiEvent::from_raw(raw) {
+            f(event)
+        }
+    })
+}
+
+/// Calls the exported `ui_init()`, once.
+///
+/// # Safety
+///
+/// `ui_init` must be the exported function, which creates the screens.
+#[doc(hidden)]
+pub unsafe fn init(initialized: &AtomicBool, ui_init: unsafe extern "C" fn()) -> LvResult<()> {
+    crate::check_init()?;
+    if lvgl_sys::lv_disp_get_default().is_null() {
+        return Err(LvError::InvalidReference);
+    }
+    if initialized.load(Ordering::Relaxed) {
+        return Err(LvError::AlreadyInUse);
+    }
+    initialized.store(true, Ordering::Relaxed);
+    ui_init();
+    Ok(())
+}
+
+/// Wraps the value of an exported screen variable.
+///
+/// # Safety
+///
+/// `raw` must be null or point to a live object.
+#[doc(hidden)]
+pub unsafe fn screen<'a>(raw: *mut lvgl_sys::lv_obj_t) -> Option<crate::Screen<'a>> {
+    crate::Screen::from_raw(NonNull::new(raw)?)
+}
+
+/// Wraps the value of an exported widget variable, if it is a `W`.
+///
+/// # Safety
+///
+/// `raw` must be null or point to a live object.
+#[doc(hidden)]
+pub unsafe fn widget<'a, W: Widget<'a> + WidgetClass>(raw: *mut lvgl_sys::lv_obj_t) -> Option<W> {
+    let obj: Obj<'a> = Obj::from_raw(NonNull::new(raw)?)?;
+    obj.try_into_widget().ok()
+}
+
+/// Describes the screens, widgets and event functions of a SquareLine
+/// Studio export. See the [`squareline`](crate::squareline) module.
+#[macro_export]
+macro_rules! squareline {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(screens {
+                $($screen:ident: $screen_var:ident),* $(,)?
+            })?
+            $(widgets {
+                $($widget:ident: $widget_var:ident as $ty:ident),* $(,)?
+            })?
+        }
+        $(events {
+            $(fn $event:ident($arg:ident) $body:block)*
+        })?
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            _private: (),
+        }
+
+        impl $name {
+            /// Creates the screens with the exported `ui_init()`, which loads
+            /// the first one. Fails with `LvError::InvalidReference` if no
+            /// display is registered, and with `LvError::AlreadyInUse` if the
+            /// screens were already created.
+            $vis fn init() -> $crate::LvResult<Self> {
+                extern "C" {
+                    fn ui_init();
+                }
+                static INITIALIZED: ::core::sync::atomic::AtomicBool =
+                    ::core::sync::atomic::AtomicBool::new(false);
+                unsafe { $crate::squareline::init(&INITIALIZED, ui_init)? };
+                Ok(Self { _private: () })
+            }
+
+            $($(
+                #[doc = concat!("Returns the `", stringify!($screen_var), "` screen, if it exists.")]
+                $vis fn $screen(&self) -> Option<$crate::Screen<'static>> {
+                    #[allow(non_upper_case_globals)]
+                    extern "C" {
+                        static mut $screen_var: *mut $crate::sys::lv_obj_t;
+                    }
+                    unsafe { $crate::squareline::screen($screen_var) }
+                }
+            )*)?
+
+            $($(
+                #[doc = concat!("Returns the `", stringify!($widget_var), "` widget, if it exists.")]
+                $vis fn $widget(&self) -> Option<$ty<'static>> {
+                    #[allow(non_upper_case_globals)]
+                    extern "C" {
+                        static mut $widget_var: *mut $crate::sys::lv_obj_t;
+                    }
+                    unsafe { $crate::squareline::widget($widget_var) }
+                }
+            )*)?
+        }
+
+        $($(
+            #[no_mangle]
+            extern "C" fn $event(event: *mut $crate::sys::lv_event_t) {
+                let f = |$arg: $crate::squareline::UiEvent| $body;
+                unsafe { $crate::squareline::event(stringify!($event), event, f) }
+            }
+        )*)?
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::{Btn, Label};
+    use crate::{Display, Event, NativeObject};
+    use core::ptr;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    // A stand-in for the C sources SquareLine exports
+    #[no_mangle]
+    #[allow(non_upper_case_globals)]
+    static mut ui_Main: *mut lvgl_sys::lv_obj_t = ptr::null_mut();
+    #[no_mangle]
+    #[allow(non_upper_case_globals)]
+    static mut ui_Save: *mut lvgl_sys::lv_obj_t = ptr::null_mut();
+
+    extern "C" {
+        fn save_clicked(e: *mut lvgl_sys::lv_event_t);
+    }
+
+    #[no_mangle]
+    extern "C" fn ui_init() {
+        unsafe {
+            ui_Main = lvgl_sys::lv_obj_create(ptr::null_mut());
+            ui_Save = lvgl_sys::lv_btn_create(ui_Main);
+            lvgl_sys::lv_obj_add_event_cb(
+                ui_Save,
+                Some(save_clicked),
+                lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
+                ptr::null_mut(),
+            );
+            lvgl_sys::lv_disp_load_scr(ui_Main);
+        }
+    }
+
+    static CLICKS: AtomicU32 = AtomicU32::new(0);
+
+    crate::squareline! {
+        struct Ui {
+            screens {
+                main: ui_Main,
+            }
+            widgets {
+                save: ui_Save as Btn,
+                save_label: ui_Save as Label,
+            }
+        }
+        events {
+            fn save_clicked(event) {
+                assert_eq!(event.event(), Some(Event::Clicked));
+                assert!(event.target::<Btn>().is_some());
+                CLICKS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[test]
+    fn squareline_export() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let ui = Ui::init().unwrap();
+        assert_eq!(Ui::init().err(), Some(crate::LvError::AlreadyInUse));
+
+        let main = ui.main().unwrap();
+        assert_eq!(display.get_scr_act().unwrap().raw(), main.raw());
+        let save = ui.save().unwrap();
+        assert!(ui.save_label().is_none());
+
+        unsafe {
+            lvgl_sys::lv_event_send(
+                save.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
+                ptr::null_mut(),
+            )
+        };
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,27 @@
+//! Memory for the crate's own bookkeeping: LVGL's memory, or the registered
+//! arena with the `static_arena` feature. See the `arena` module.
+
+#[cfg(feature = "static_arena")]
+pub(crate) use crate::arena::{alloc, free, realloc};
+
+#[cfg(not(feature = "static_arena"))]
+use core::ffi::c_void;
+
+/// Allocates `size` bytes, or returns null if the memory is full.
+#[cfg(not(feature = "static_arena"))]
+pub(crate) unsafe fn alloc(size: usize) -> *mut c_void {
+    lvgl_sys::lv_mem_alloc(size as _)
+}
+
+/// Resizes a block returned by `alloc()` or `realloc()`, or returns null,
+/// leaving the block as it is, if the memory is full.
+#[cfg(not(feature = "static_arena"))]
+pub(crate) unsafe fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    lvgl_sys::lv_mem_realloc(ptr, size as _)
+}
+
+/// Frees a block returned by `alloc()` or `realloc()`.
+#[cfg(not(feature = "static_arena"))]
+pub(crate) unsafe fn free(ptr: *mut c_void) {
+    lvgl_sys::lv_mem_free(ptr)
+}
@@ -1,12 +1,13 @@
 use crate::display::DisplayError;
-use crate::Widget;
+use crate::guard::guard;
+use crate::{Box, Widget};
 use core::convert::{TryFrom, TryInto};
 #[cfg(feature = "nightly")]
 use core::error::Error;
 use core::fmt;
 use core::ptr::NonNull;
 #[cfg(feature = "embedded_graphics")]
-use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use embedded_graphics::pixelcolor::{BinaryColor, Gray8, GrayColor, Rgb565, Rgb888, RgbColor};
 
 pub type LvResult<T> = Result<T, LvError>;
 
@@ -17,6 +18,7 @@ pub enum LvError {
     Uninitialized,
     LvOOMemory,
     AlreadyInUse,
+    OutOfRange,
 }
 
 impl fmt::Display for LvError {
@@ -29,6 +31,7 @@ impl fmt::Display for LvError {
                 LvError::Uninitialized => "LVGL uninitialized",
                 LvError::LvOOMemory => "LVGL out of memory",
                 LvError::AlreadyInUse => "Resource already in use",
+                LvError::OutOfRange => "Value out of range",
             }
         )
     }
@@ -56,6 +59,7 @@ impl From<LvError> for DisplayError {
             LvError::Uninitialized => NotAvailable,
             LvError::LvOOMemory => FailedToRegister,
             LvError::AlreadyInUse => FailedToRegister,
+            LvError::OutOfRange => FailedToRegister,
         }
     }
 }
@@ -96,34 +100,242 @@ impl Color {
     pub fn b(&self) -> u8 {
         unsafe { lvgl_sys::_LV_COLOR_GET_B(self.raw) as u8 }
     }
+    /// Returns the color as 8-bit channels packed into `0xAARRGGBB`,
+    /// regardless of the configured color depth.
+    pub fn to_u32(&self) -> u32 {
+        unsafe { lvgl_sys::_LV_COLOR_TO32(self.raw) }
+    }
+
+    /// Creates a `Color` from a `0xRRGGBB` value, like `lv_color_hex()`. Unlike
+    /// `from_rgb()`, this can be used in constants.
+    pub const fn from_hex(hex: u32) -> Self {
+        let (r, g, b) = ((hex >> 16) & 0xFF, (hex >> 8) & 0xFF, hex & 0xFF);
+        // The same packing as `lv_color_make()` for each color depth
+        let full = match lvgl_sys::LV_COLOR_DEPTH {
+            32 => 0xFF00_0000 | r << 16 | g << 8 | b,
+            16 => {
+                let rgb565 = ((r >> 3) << 11 | (g >> 2) << 5 | b >> 3) as u16;
+                if lvgl_sys::LV_COLOR_16_SWAP != 0 {
+                    rgb565.swap_bytes() as u32
+                } else {
+                    rgb565 as u32
+                }
+            }
+            8 => (r >> 5) << 5 | (g >> 5) << 2 | b >> 6,
+            _ => (r | g | b) >> 7,
+        };
+        Self {
+            raw: lvgl_sys::lv_color_t { full: full as _ },
+        }
+    }
+
+    /// Creates a `Color` from a hue (0-359°), saturation (0-100%) and value
+    /// (0-100%).
+    pub fn from_hsv((h, s, v): (u16, u8, u8)) -> Self {
+        let raw = unsafe { lvgl_sys::lv_color_hsv_to_rgb(h, s, v) };
+        Self { raw }
+    }
+
+    /// Returns the color's hue (0-359°), saturation (0-100%) and value
+    /// (0-100%).
+    pub fn to_hsv(&self) -> (u16, u8, u8) {
+        let hsv = unsafe { lvgl_sys::lv_color_to_hsv(self.raw) };
+        (hsv.h, hsv.s, hsv.v)
+    }
+
+    /// Mixes the color with `other`. A `ratio` of 255 gives this color, 0
+    /// gives `other`, and 128 an even mix.
+    pub fn mix(&self, other: Color, ratio: u8) -> Self {
+        let raw = unsafe { lvgl_sys::_LV_COLOR_MIX(self.raw, other.raw, ratio) };
+        Self { raw }
+    }
+
+    /// Mixes the color with white. A `level` of 0 leaves the color unchanged,
+    /// 255 makes it white.
+    pub fn lighten(&self, level: u8) -> Self {
+        let raw = unsafe { lvgl_sys::lv_color_lighten(self.raw, level) };
+        Self { raw }
+    }
+
+    /// Mixes the color with black. A `level` of 0 leaves the color unchanged,
+    /// 255 makes it black.
+    pub fn darken(&self, level: u8) -> Self {
+        let raw = unsafe { lvgl_sys::lv_color_darken(self.raw, level) };
+        Self { raw }
+    }
+
+    /// Returns the color's brightness (0-255) as LVGL computes it, weighting
+    /// green and red over blue.
+    pub fn brightness(&self) -> u8 {
+        unsafe { lvgl_sys::_LV_COLOR_BRIGHTNESS(self.raw) }
+    }
+
+    /// Returns the color's luma (0-255), using the Rec. 709 weights of the
+    /// red, green and blue channels. Useful for e.g. picking a readable text
+    /// color on top of it.
+    pub fn luminance(&self) -> u8 {
+        let argb = self.to_u32();
+        let (r, g, b) = ((argb >> 16) & 0xFF, (argb >> 8) & 0xFF, argb & 0xFF);
+        ((r * 2126 + g * 7152 + b * 722) / 10000) as u8
+    }
+
+    /// Black.
+    pub const BLACK: Color = Color::from_hex(0x000000);
+    /// White.
+    pub const WHITE: Color = Color::from_hex(0xFFFFFF);
+    /// The main red of LVGL's palette (`LV_PALETTE_RED`).
+    pub const RED: Color = Color::from_hex(0xF44336);
+    /// The main pink of LVGL's palette (`LV_PALETTE_PINK`).
+    pub const PINK: Color = Color::from_hex(0xE91E63);
+    /// The main purple of LVGL's palette (`LV_PALETTE_PURPLE`).
+    pub const PURPLE: Color = Color::from_hex(0x9C27B0);
+    /// The main deep purple of LVGL's palette (`LV_PALETTE_DEEP_PURPLE`).
+    pub const DEEP_PURPLE: Color = Color::from_hex(0x673AB7);
+    /// The main indigo of LVGL's palette (`LV_PALETTE_INDIGO`).
+    pub const INDIGO: Color = Color::from_hex(0x3F51B5);
+    /// The main blue of LVGL's palette (`LV_PALETTE_BLUE`).
+    pub const BLUE: Color = Color::from_hex(0x2196F3);
+    /// The main light blue of LVGL's palette (`LV_PALETTE_LIGHT_BLUE`).
+    pub const LIGHT_BLUE: Color = Color::from_hex(0x03A9F4);
+    /// The main cyan of LVGL's palette (`LV_PALETTE_CYAN`).
+    pub const CYAN: Color = Color::from_hex(0x00BCD4);
+    /// The main teal of LVGL's palette (`LV_PALETTE_TEAL`).
+    pub const TEAL: Color = Color::from_hex(0x009688);
+    /// The main green of LVGL's palette (`LV_PALETTE_GREEN`).
+    pub const GREEN: Color = Color::from_hex(0x4CAF50);
+    /// The main light green of LVGL's palette (`LV_PALETTE_LIGHT_GREEN`).
+    pub const LIGHT_GREEN: Color = Color::from_hex(0x8BC34A);
+    /// The main lime of LVGL's palette (`LV_PALETTE_LIME`).
+    pub const LIME: Color = Color::from_hex(0xCDDC39);
+    /// The main yellow of LVGL's palette (`LV_PALETTE_YELLOW`).
+    pub const YELLOW: Color = Color::from_hex(0xFFEB3B);
+    /// The main amber of LVGL's palette (`LV_PALETTE_AMBER`).
+    pub const AMBER: Color = Color::from_hex(0xFFC107);
+    /// The main orange of LVGL's palette (`LV_PALETTE_ORANGE`).
+    pub const ORANGE: Color = Color::from_hex(0xFF9800);
+    /// The main deep orange of LVGL's palette (`LV_PALETTE_DEEP_ORANGE`).
+    pub const DEEP_ORANGE: Color = Color::from_hex(0xFF5722);
+    /// The main brown of LVGL's palette (`LV_PALETTE_BROWN`).
+    pub const BROWN: Color = Color::from_hex(0x795548);
+    /// The main blue grey of LVGL's palette (`LV_PALETTE_BLUE_GREY`).
+    pub const BLUE_GREY: Color = Color::from_hex(0x607D8B);
+    /// The main grey of LVGL's palette (`LV_PALETTE_GREY`).
+    pub const GREY: Color = Color::from_hex(0x9E9E9E);
+}
+
+/// The error returned when parsing a `Color` from a string fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseColorError;
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hex color, expected \"#rrggbb\" or \"#rgb\"")
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl Error for ParseColorError {}
+
+impl TryFrom<&str> for Color {
+    type Error = ParseColorError;
+
+    /// Parses a CSS-style hex color, `"#rrggbb"` or `"#rgb"`. The leading `#`
+    /// is optional.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseColorError);
+        }
+        let hex = u32::from_str_radix(digits, 16).map_err(|_| ParseColorError)?;
+        match digits.len() {
+            6 => Ok(Color::from_hex(hex)),
+            // Each digit is repeated, e.g. "#f80" is "#ff8800"
+            3 => Ok(Color::from_hex(
+                (hex & 0xF00) * 0x1100 | (hex & 0x0F0) * 0x110 | (hex & 0x00F) * 0x11,
+            )),
+            _ => Err(ParseColorError),
+        }
+    }
 }
 
+/// Converts to 8 bits per channel whatever `LV_COLOR_DEPTH` is, e.g. for
+/// `DrawTarget<Color = Rgb888>` panels and 32-bit framebuffers.
 #[cfg(feature = "embedded_graphics")]
 impl From<Color> for Rgb888 {
     fn from(color: Color) -> Self {
-        unsafe {
-            Rgb888::new(
-                lvgl_sys::_LV_COLOR_GET_R(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_G(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_B(color.raw) as u8,
-            )
-        }
+        let [b, g, r, _] = color.to_u32().to_le_bytes();
+        Rgb888::new(r, g, b)
     }
 }
 
 #[cfg(feature = "embedded_graphics")]
 impl From<Color> for Rgb565 {
     fn from(color: Color) -> Self {
-        unsafe {
-            Rgb565::new(
-                lvgl_sys::_LV_COLOR_GET_R(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_G(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_B(color.raw) as u8,
-            )
+        if lvgl_sys::LV_COLOR_DEPTH == 16 {
+            // The channels already have the widths of RGB565
+            unsafe {
+                Rgb565::new(
+                    lvgl_sys::_LV_COLOR_GET_R(color.raw) as u8,
+                    lvgl_sys::_LV_COLOR_GET_G(color.raw) as u8,
+                    lvgl_sys::_LV_COLOR_GET_B(color.raw) as u8,
+                )
+            }
+        } else {
+            Rgb888::from(color).into()
         }
     }
 }
 
+#[cfg(feature = "embedded_graphics")]
+impl From<Rgb888> for Color {
+    fn from(color: Rgb888) -> Self {
+        Color::from_rgb((color.r(), color.g(), color.b()))
+    }
+}
+
+#[cfg(feature = "embedded_graphics")]
+impl From<Rgb565> for Color {
+    fn from(color: Rgb565) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
+/// Converts to `On` for light colors, with a brightness of at least 128, as
+/// for a lit OLED pixel. Use `BinaryColor::invert()` for panels where `On`
+/// is dark.
+#[cfg(feature = "embedded_graphics")]
+impl From<Color> for BinaryColor {
+    fn from(color: Color) -> Self {
+        BinaryColor::from(color.brightness() >= 128)
+    }
+}
+
+#[cfg(feature = "embedded_graphics")]
+impl From<Color> for Gray8 {
+    fn from(color: Color) -> Self {
+        Gray8::new(color.luminance())
+    }
+}
+
+#[cfg(feature = "embedded_graphics")]
+impl From<BinaryColor> for Color {
+    fn from(color: BinaryColor) -> Self {
+        if color.is_on() {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        }
+    }
+}
+
+#[cfg(feature = "embedded_graphics")]
+impl From<Gray8> for Color {
+    fn from(color: Gray8) -> Self {
+        let luma = color.luma();
+        Color::from_rgb((luma, luma, luma))
+    }
+}
+
 impl From<Color> for lvgl_sys::lv_color_t {
     fn from(val: Color) -> Self {
         val.raw
@@ -290,18 +502,25 @@ where
     T: Widget<'a> + Sized,
     F: FnMut(T, Event<<T as Widget<'a>>::SpecialEvent>),
 {
-    let code = (*event).code;
-    let obj = (*event).target;
-    // convert the lv_event_code_t to lvgl-rs Event type
-    if let Ok(code) = code.try_into() {
-        if let Some(obj_ptr) = NonNull::new(obj) {
-            let object = T::from_raw(obj_ptr).unwrap();
-            // get the pointer from the Rust callback closure FnMut provided by users
-            let user_closure = &mut *((*obj).user_data as *mut F);
-            // call user callback closure
-            user_closure(object, code);
+    guard("event", || {
+        let code = (*event).code;
+        // The object the callback was added to, which is not the target if the
+        // event bubbled up from a child
+        let obj = (*event).current_target;
+        // convert the lv_event_code_t to lvgl-rs Event type
+        if let Ok(code) = code.try_into() {
+            if let Some(object) = NonNull::new(obj).and_then(|raw| T::from_raw(raw)) {
+                // get the pointer from the Rust callback closure FnMut provided by users
+                let user_closure = &mut *((*event).user_data as *mut F);
+                // call user callback closure
+                user_closure(object, code);
+            }
         }
-    }
+        // The object is being deleted, so the closure will not be called again
+        if code == lvgl_sys::lv_event_code_t_LV_EVENT_DELETE {
+            drop(Box::from_raw((*event).user_data as *mut F));
+        }
+    })
 }
 
 /// Possible LVGL alignments for widgets.
@@ -392,6 +611,7 @@ impl From<AnimationState> for lvgl_sys::lv_anim_enable_t {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 pub enum LabelLongMode {
     Clip = lvgl_sys::LV_LABEL_LONG_CLIP,
@@ -407,161 +627,240 @@ impl From<LabelLongMode> for u8 {
     }
 }
 
-/// Possible LVGL object flags.
-///
-/// This enum contains all LV_OBJ_FLAGS defined in "lvgl-sys/bindings.rs".
-///
-/// The flags are conceived as bit fields of a 32-bit word, and can thus be
-/// logically combined via boolean operations.
-///
-/// For more detailed information on the flags, see:
-/// <https://docs.lvgl.io/master/details/common-widget-features/flags.html>
-///
-/// TODO: not all LVGL V9 flags are available in the old LVGL V8 used here.
-///       Those flags are left commented-out.
-///
-/// TODO: perhaps use the bitflags! crate instead? See lvgl/lv_core/style.rs
-///       for an example.
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-pub enum ObjFlag {
-    /// Make the widget hidden (as if it weren’t there at all).
-    Hidden,
+impl From<u8> for LabelLongMode {
+    fn from(value: u8) -> Self {
+        match value as u32 {
+            lvgl_sys::LV_LABEL_LONG_CLIP => Self::Clip,
+            lvgl_sys::LV_LABEL_LONG_DOT => Self::Dot,
+            lvgl_sys::LV_LABEL_LONG_SCROLL => Self::Scroll,
+            lvgl_sys::LV_LABEL_LONG_SCROLL_CIRCULAR => Self::ScrollCircular,
+            // LVGL's default
+            _ => Self::Wrap,
+        }
+    }
+}
 
-    /// Make the widget clickable by input devices.
-    Clickable,
+bitflags! {
+    /// Possible LVGL object flags.
+    ///
+    /// The flags are bit fields of a 32-bit word, and can be combined with `|`
+    /// to add, clear or test several of them at once:
+    ///
+    /// ```ignore
+    /// widget.add_flag(ObjFlag::CLICKABLE | ObjFlag::CHECKABLE);
+    /// ```
+    ///
+    /// For more detailed information on the flags, see:
+    /// <https://docs.lvgl.io/8.3/widgets/obj.html#flags>
+    #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+    pub struct ObjFlag: u32 {
+        /// Make the widget hidden (as if it weren’t there at all).
+        const HIDDEN = lvgl_sys::LV_OBJ_FLAG_HIDDEN as u32;
 
-    /// Add the focused state to the widget when clicked.
-    ClickFocusable,
+        /// Make the widget clickable by input devices.
+        const CLICKABLE = lvgl_sys::LV_OBJ_FLAG_CLICKABLE as u32;
 
-    /// Toggle the checked state when the widget is clicked.
-    Checkable,
+        /// Add the focused state to the widget when clicked.
+        const CLICK_FOCUSABLE = lvgl_sys::LV_OBJ_FLAG_CLICK_FOCUSABLE as u32;
 
-    /// Make the widget scrollable.
-    Scrollable,
+        /// Toggle the checked state when the widget is clicked.
+        const CHECKABLE = lvgl_sys::LV_OBJ_FLAG_CHECKABLE as u32;
 
-    /// Allow elastic scrolling with slower movement.
-    ScrollElastic,
+        /// Make the widget scrollable.
+        const SCROLLABLE = lvgl_sys::LV_OBJ_FLAG_SCROLLABLE as u32;
 
-    /// Enable momentum scrolling (continue scrolling when “thrown”).
-    ScrollMomentum,
+        /// Allow elastic scrolling with slower movement.
+        const SCROLL_ELASTIC = lvgl_sys::LV_OBJ_FLAG_SCROLL_ELASTIC as u32;
 
-    /// Allow scrolling only one snappable child.
-    ScrollOne,
+        /// Enable momentum scrolling (continue scrolling when “thrown”).
+        const SCROLL_MOMENTUM = lvgl_sys::LV_OBJ_FLAG_SCROLL_MOMENTUM as u32;
 
-    /// Propagate horizontal scrolling to the parent.
-    ScrollChainHor,
+        /// Allow scrolling only one snappable child.
+        const SCROLL_ONE = lvgl_sys::LV_OBJ_FLAG_SCROLL_ONE as u32;
 
-    /// Propagate vertical scrolling to the parent.
-    ScrollChainVer,
+        /// Propagate horizontal scrolling to the parent.
+        const SCROLL_CHAIN_HOR = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_HOR as u32;
 
-    /// Shorthand for (SCROLL_CHAIN_HOR | SCROLL_CHAIN_VER).
-    ScrollChain,
+        /// Propagate vertical scrolling to the parent.
+        const SCROLL_CHAIN_VER = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_VER as u32;
 
-    /// Automatically scroll to make the widget visible when focused.
-    ScrollOnFocus,
+        /// Shorthand for `SCROLL_CHAIN_HOR | SCROLL_CHAIN_VER`.
+        const SCROLL_CHAIN = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN as u32;
 
-    /// Allow scrolling the focused widget with arrow keys.
-    ScrollWithArrow,
+        /// Automatically scroll to make the widget visible when focused.
+        const SCROLL_ON_FOCUS = lvgl_sys::LV_OBJ_FLAG_SCROLL_ON_FOCUS as u32;
 
-    /// Allow the widget to be snapped if the parent has scroll snapping enabled.
-    Snappable,
+        /// Allow scrolling the focused widget with arrow keys.
+        const SCROLL_WITH_ARROW = lvgl_sys::LV_OBJ_FLAG_SCROLL_WITH_ARROW as u32;
 
-    /// Keep the widget in the pressed state even if the pointer moves outside it.
-    PressLock,
+        /// Allow the widget to be snapped if the parent has scroll snapping enabled.
+        const SNAPPABLE = lvgl_sys::LV_OBJ_FLAG_SNAPPABLE as u32;
 
-    /// Propagate events to the parent.
-    EventBubble,
+        /// Keep the widget in the pressed state even if the pointer moves outside it.
+        const PRESS_LOCK = lvgl_sys::LV_OBJ_FLAG_PRESS_LOCK as u32;
 
-    /// Propagate events to children.
-    //EventTrickle,
+        /// Propagate events to the parent.
+        const EVENT_BUBBLE = lvgl_sys::LV_OBJ_FLAG_EVENT_BUBBLE as u32;
 
-    /// Propagate state changes to children.
-    //StateTrickle,
+        /// Propagate gestures to the parent.
+        const GESTURE_BUBBLE = lvgl_sys::LV_OBJ_FLAG_GESTURE_BUBBLE as u32;
 
-    /// Propagate gestures to the parent.
-    GestureBubble,
+        /// Enable more accurate hit (click) testing (e.g., account for rounded corners).
+        const ADV_HITTEST = lvgl_sys::LV_OBJ_FLAG_ADV_HITTEST as u32;
 
-    /// Enable more accurate hit (click) testing (e.g., account for rounded corners).
-    AdvHitTest,
+        /// Exclude the widget from layout positioning.
+        const IGNORE_LAYOUT = lvgl_sys::LV_OBJ_FLAG_IGNORE_LAYOUT as u32;
 
-    /// Exclude the widget from layout positioning.
-    IgnoreLayout,
+        /// Do not scroll with the parent and ignore layout.
+        const FLOATING = lvgl_sys::LV_OBJ_FLAG_FLOATING as u32;
 
-    /// Do not scroll with the parent and ignore layout.
-    Floating,
+        /// Allow children to overflow outside the widget's bounds.
+        const OVERFLOW_VISIBLE = lvgl_sys::LV_OBJ_FLAG_OVERFLOW_VISIBLE as u32;
 
-    /// Enable sending LV_EVENT_DRAW_TASK_ADDED events.
-    //SendDrawTaskEvents,
+        /// Custom flag, free to use by layouts.
+        const LAYOUT_1 = lvgl_sys::LV_OBJ_FLAG_LAYOUT_1 as u32;
 
-    /// Allow children to overflow outside the widget's bounds.
-    OverflowVisible,
+        /// Custom flag, free to use by layouts.
+        const LAYOUT_2 = lvgl_sys::LV_OBJ_FLAG_LAYOUT_2 as u32;
 
-    /// Start a new flex track on this item.
-    //FlexInNewTrack,
+        /// Custom flag, free to use by widgets.
+        const WIDGET_1 = lvgl_sys::LV_OBJ_FLAG_WIDGET_1 as u32;
 
-    /// Custom flag, free to use by layouts.
-    Layout1,
+        /// Custom flag, free to use by widgets.
+        const WIDGET_2 = lvgl_sys::LV_OBJ_FLAG_WIDGET_2 as u32;
 
-    /// Custom flag, free to use by layouts.
-    Layout2,
+        /// Custom flag, free to use by the user.
+        const USER_1 = lvgl_sys::LV_OBJ_FLAG_USER_1 as u32;
 
-    /// Custom flag, free to use by widgets.
-    Widget1,
+        /// Custom flag, free to use by the user.
+        const USER_2 = lvgl_sys::LV_OBJ_FLAG_USER_2 as u32;
 
-    /// Custom flag, free to use by widgets.
-    Widget2,
+        /// Custom flag, free to use by the user.
+        const USER_3 = lvgl_sys::LV_OBJ_FLAG_USER_3 as u32;
 
-    /// Custom flag, free to use by the user.
-    User1,
+        /// Custom flag, free to use by the user.
+        const USER_4 = lvgl_sys::LV_OBJ_FLAG_USER_4 as u32;
+    }
+}
 
-    /// Custom flag, free to use by the user.
-    User2,
+impl From<ObjFlag> for lvgl_sys::lv_obj_flag_t {
+    fn from(obj_flag: ObjFlag) -> Self {
+        obj_flag.bits() as lvgl_sys::lv_obj_flag_t
+    }
+}
 
-    /// Custom flag, free to use by the user.
-    User3,
+bitflags! {
+    /// The states of a widget, e.g. pressed or checked. Input devices set
+    /// most of them, and styles can be applied to a part in a given state.
+    /// A widget with no state set is in its default state.
+    ///
+    /// For more detailed information on the states, see:
+    /// <https://docs.lvgl.io/8.3/overview/coords.html#states>
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct State: u16 {
+        /// Toggled or checked.
+        const CHECKED = lvgl_sys::LV_STATE_CHECKED as u16;
+
+        /// Focused by a keypad or encoder, or clicked by a touchpad or mouse.
+        const FOCUSED = lvgl_sys::LV_STATE_FOCUSED as u16;
+
+        /// Focused by a keypad or encoder, but not by a touchpad or mouse.
+        const FOCUS_KEY = lvgl_sys::LV_STATE_FOCUS_KEY as u16;
+
+        /// Edited by an encoder.
+        const EDITED = lvgl_sys::LV_STATE_EDITED as u16;
+
+        /// Hovered by a mouse.
+        const HOVERED = lvgl_sys::LV_STATE_HOVERED as u16;
+
+        /// Being pressed.
+        const PRESSED = lvgl_sys::LV_STATE_PRESSED as u16;
+
+        /// Being scrolled.
+        const SCROLLED = lvgl_sys::LV_STATE_SCROLLED as u16;
+
+        /// Disabled, so input devices cannot interact with it.
+        const DISABLED = lvgl_sys::LV_STATE_DISABLED as u16;
+
+        /// Custom state, free to use by the user.
+        const USER_1 = lvgl_sys::LV_STATE_USER_1 as u16;
 
-    /// Custom flag, free to use by the user.
-    User4,
+        /// Custom state, free to use by the user.
+        const USER_2 = lvgl_sys::LV_STATE_USER_2 as u16;
+
+        /// Custom state, free to use by the user.
+        const USER_3 = lvgl_sys::LV_STATE_USER_3 as u16;
+
+        /// Custom state, free to use by the user.
+        const USER_4 = lvgl_sys::LV_STATE_USER_4 as u16;
+    }
 }
 
-impl From<ObjFlag> for lvgl_sys::lv_obj_flag_t {
-    fn from(obj_flag: ObjFlag) -> Self {
-        let native_obj_flag = match obj_flag {
-            ObjFlag::Hidden => lvgl_sys::LV_OBJ_FLAG_HIDDEN,
-            ObjFlag::Clickable => lvgl_sys::LV_OBJ_FLAG_CLICKABLE,
-            ObjFlag::ClickFocusable => lvgl_sys::LV_OBJ_FLAG_CLICK_FOCUSABLE,
-            ObjFlag::Checkable => lvgl_sys::LV_OBJ_FLAG_CHECKABLE,
-            ObjFlag::Scrollable => lvgl_sys::LV_OBJ_FLAG_SCROLLABLE,
-            ObjFlag::ScrollElastic => lvgl_sys::LV_OBJ_FLAG_SCROLL_ELASTIC,
-            ObjFlag::ScrollMomentum => lvgl_sys::LV_OBJ_FLAG_SCROLL_MOMENTUM,
-            ObjFlag::ScrollOne => lvgl_sys::LV_OBJ_FLAG_SCROLL_ONE,
-            ObjFlag::ScrollChainHor => lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_HOR,
-            ObjFlag::ScrollChainVer => lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_VER,
-            ObjFlag::ScrollChain => lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN,
-            ObjFlag::ScrollOnFocus => lvgl_sys::LV_OBJ_FLAG_SCROLL_ON_FOCUS,
-            ObjFlag::ScrollWithArrow => lvgl_sys::LV_OBJ_FLAG_SCROLL_WITH_ARROW,
-            ObjFlag::Snappable => lvgl_sys::LV_OBJ_FLAG_SNAPPABLE,
-            ObjFlag::PressLock => lvgl_sys::LV_OBJ_FLAG_PRESS_LOCK,
-            ObjFlag::EventBubble => lvgl_sys::LV_OBJ_FLAG_EVENT_BUBBLE,
-            //ObjFlag::EventTrickle => lvgl_sys::LV_OBJ_FLAG_EVENT_TRICKLE,
-            //ObjFlag::StateTrickle => lvgl_sys::LV_OBJ_FLAG_STATE_TRICKLE,
-            ObjFlag::GestureBubble => lvgl_sys::LV_OBJ_FLAG_GESTURE_BUBBLE,
-            ObjFlag::AdvHitTest => lvgl_sys::LV_OBJ_FLAG_ADV_HITTEST,
-            ObjFlag::IgnoreLayout => lvgl_sys::LV_OBJ_FLAG_IGNORE_LAYOUT,
-            ObjFlag::Floating => lvgl_sys::LV_OBJ_FLAG_FLOATING,
-            //ObjFlag::SendDrawTaskEvents => lvgl_sys::LV_OBJ_FLAG_SEND_DRAW_TASK_EVENTS,
-            ObjFlag::OverflowVisible => lvgl_sys::LV_OBJ_FLAG_OVERFLOW_VISIBLE,
-            //ObjFlag::FlexInNewTrack => lvgl_sys::LV_OBJ_FLAG_FLEX_IN_NEW_TRACK,
-            ObjFlag::Layout1 => lvgl_sys::LV_OBJ_FLAG_LAYOUT_1,
-            ObjFlag::Layout2 => lvgl_sys::LV_OBJ_FLAG_LAYOUT_2,
-            ObjFlag::Widget1 => lvgl_sys::LV_OBJ_FLAG_WIDGET_1,
-            ObjFlag::Widget2 => lvgl_sys::LV_OBJ_FLAG_WIDGET_2,
-            ObjFlag::User1 => lvgl_sys::LV_OBJ_FLAG_USER_1,
-            ObjFlag::User2 => lvgl_sys::LV_OBJ_FLAG_USER_2,
-            ObjFlag::User3 => lvgl_sys::LV_OBJ_FLAG_USER_3,
-            ObjFlag::User4 => lvgl_sys::LV_OBJ_FLAG_USER_4,
+impl From<State> for lvgl_sys::lv_state_t {
+    fn from(state: State) -> Self {
+        state.bits() as lvgl_sys::lv_state_t
+    }
+}
+
+bitflags! {
+    /// Directions, e.g. those a widget can be scrolled in.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct Dir: u8 {
+        const LEFT = lvgl_sys::LV_DIR_LEFT as u8;
+        const RIGHT = lvgl_sys::LV_DIR_RIGHT as u8;
+        const TOP = lvgl_sys::LV_DIR_TOP as u8;
+        const BOTTOM = lvgl_sys::LV_DIR_BOTTOM as u8;
+
+        /// Shorthand for `LEFT | RIGHT`.
+        const HOR = lvgl_sys::LV_DIR_HOR as u8;
+
+        /// Shorthand for `TOP | BOTTOM`.
+        const VER = lvgl_sys::LV_DIR_VER as u8;
+
+        /// Shorthand for `HOR | VER`.
+        const ALL = lvgl_sys::LV_DIR_ALL as u8;
+    }
+}
+
+impl From<Dir> for lvgl_sys::lv_dir_t {
+    fn from(dir: Dir) -> Self {
+        dir.bits() as lvgl_sys::lv_dir_t
+    }
+}
+
+/// When the scrollbars of a scrollable widget are shown.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ScrollbarMode {
+    /// Never show the scrollbars.
+    Off,
+    /// Always show the scrollbars.
+    On,
+    /// Show the scrollbars while the widget is being scrolled.
+    Active,
+    /// Show the scrollbars when the content is large enough to be scrolled.
+    Auto,
+}
+
+impl From<ScrollbarMode> for lvgl_sys::lv_scrollbar_mode_t {
+    fn from(mode: ScrollbarMode) -> Self {
+        let native = match mode {
+            ScrollbarMode::Off => lvgl_sys::LV_SCROLLBAR_MODE_OFF,
+            ScrollbarMode::On => lvgl_sys::LV_SCROLLBAR_MODE_ON,
+            ScrollbarMode::Active => lvgl_sys::LV_SCROLLBAR_MODE_ACTIVE,
+            ScrollbarMode::Auto => lvgl_sys::LV_SCROLLBAR_MODE_AUTO,
         };
-        native_obj_flag as lvgl_sys::lv_obj_flag_t
+        native as _
+    }
+}
+
+impl From<lvgl_sys::lv_scrollbar_mode_t> for ScrollbarMode {
+    fn from(mode: lvgl_sys::lv_scrollbar_mode_t) -> Self {
+        match mode as u32 {
+            lvgl_sys::LV_SCROLLBAR_MODE_OFF => ScrollbarMode::Off,
+            lvgl_sys::LV_SCROLLBAR_MODE_ON => ScrollbarMode::On,
+            lvgl_sys::LV_SCROLLBAR_MODE_ACTIVE => ScrollbarMode::Active,
+            _ => ScrollbarMode::Auto,
+        }
     }
 }
 
@@ -569,6 +868,80 @@ impl From<ObjFlag> for lvgl_sys::lv_obj_flag_t {
 mod test {
     use super::*;
 
+    #[test]
+    fn obj_flags_combine() {
+        let flags = ObjFlag::SCROLL_CHAIN_HOR | ObjFlag::SCROLL_CHAIN_VER;
+        assert_eq!(flags, ObjFlag::SCROLL_CHAIN);
+        assert_eq!(
+            lvgl_sys::lv_obj_flag_t::from(ObjFlag::HIDDEN | ObjFlag::CLICKABLE),
+            (lvgl_sys::LV_OBJ_FLAG_HIDDEN | lvgl_sys::LV_OBJ_FLAG_CLICKABLE)
+                as lvgl_sys::lv_obj_flag_t
+        );
+    }
+
+    #[test]
+    fn color_from_hex() {
+        let hex = Color::from_hex(0xCE33FF);
+        let rgb = Color::from_rgb((206, 51, 255));
+        assert_eq!(hex.to_u32(), rgb.to_u32());
+        assert_eq!(Color::WHITE.to_u32(), 0xFFFF_FFFF);
+        assert_eq!(Color::BLACK.to_u32(), 0xFF00_0000);
+    }
+
+    #[test]
+    fn color_from_str() {
+        let color = Color::try_from("#ce33ff").unwrap();
+        assert_eq!(color.to_u32(), Color::from_hex(0xCE33FF).to_u32());
+        let short = Color::try_from("f80").unwrap();
+        assert_eq!(short.to_u32(), Color::from_hex(0xFF8800).to_u32());
+        assert!(Color::try_from("#12345").is_err());
+        assert!(Color::try_from("+12345").is_err());
+    }
+
+    #[test]
+    fn color_hsv_round_trip() {
+        let color = Color::from_hsv((0, 100, 100));
+        assert_eq!(color.to_u32(), Color::from_hex(0xFF0000).to_u32());
+        assert_eq!(color.to_hsv(), (0, 100, 100));
+    }
+
+    #[test]
+    fn color_shades() {
+        let blue = Color::BLUE;
+        assert_eq!(blue.mix(Color::WHITE, 255).to_u32(), blue.to_u32());
+        assert_eq!(blue.mix(Color::WHITE, 0).to_u32(), Color::WHITE.to_u32());
+        assert_eq!(blue.lighten(255).to_u32(), Color::WHITE.to_u32());
+        assert_eq!(blue.darken(255).to_u32(), Color::BLACK.to_u32());
+        assert!(blue.lighten(64).luminance() > blue.luminance());
+        assert!(blue.darken(64).brightness() < blue.brightness());
+        assert_eq!(Color::WHITE.luminance(), 255);
+        assert_eq!(Color::BLACK.luminance(), 0);
+    }
+
+    #[cfg(feature = "embedded_graphics")]
+    #[test]
+    fn color_to_embedded_graphics() {
+        assert_eq!(Rgb888::from(Color::WHITE), Rgb888::WHITE);
+        assert_eq!(Rgb888::from(Color::from_hex(0xFF0000)), Rgb888::RED);
+        assert_eq!(Rgb565::from(Color::WHITE), Rgb565::WHITE);
+        assert_eq!(Rgb565::from(Color::from_hex(0x0000FF)), Rgb565::BLUE);
+        let orange = Color::from(Rgb888::new(255, 136, 0));
+        assert_eq!(orange.to_u32(), Color::from_hex(0xFF8800).to_u32());
+        let green = Color::from(Rgb565::GREEN);
+        assert_eq!(green.to_u32(), Color::from_hex(0x00FF00).to_u32());
+
+        assert_eq!(BinaryColor::from(Color::WHITE), BinaryColor::On);
+        assert_eq!(
+            BinaryColor::from(Color::from_hex(0x202020)),
+            BinaryColor::Off
+        );
+        assert_eq!(Gray8::from(Color::BLACK), Gray8::BLACK);
+        assert_eq!(Gray8::from(Color::WHITE), Gray8::WHITE);
+        assert_eq!(Color::from(BinaryColor::On).to_u32(), Color::WHITE.to_u32());
+        let gray = Color::from(Gray8::new(0x80));
+        assert_eq!(gray.to_u32(), Color::from_hex(0x808080).to_u32());
+    }
+
     #[test]
     fn color_properties_accessible() {
         let color = Color::from_rgb((206, 51, 255));
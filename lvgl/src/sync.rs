@@ -0,0 +1,454 @@
+//! Shared access to LVGL from multiple threads or tasks
+//!
+//! LVGL is not thread-safe: all calls into it, including `task_handler()`,
+//! must happen one at a time. [`LvglMutex`] owns some UI state (typically the
+//! widgets that other threads update) and only hands it out through a guard
+//! holding a single global lock, which also covers LVGL's timer handler. This
+//! way, updating a label from a sensor thread and running the UI loop can't
+//! overlap.
+//!
+//! The lock is a spinlock built on atomics, so it works on `no_std` targets.
+//! It must not be taken from an interrupt handler, as it could spin forever
+//! on a single-core system; use `tick_inc()` from interrupts instead.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use lvgl::sync::{AssertLvglSend, LvglMutex};
+//! use lvgl::widgets::Label;
+//!
+//! static STATUS: LvglMutex<Option<AssertLvglSend<Label<'static>>>> = LvglMutex::new(None);
+//!
+//! // UI thread
+//! let label = Label::create(&mut screen)?;
+//! // Safety: the label is only used through `STATUS`
+//! *STATUS.lock() = Some(unsafe { AssertLvglSend::new(label) });
+//! loop {
+//!     STATUS.lock().task_handler()?;
+//!     sleep(Duration::from_millis(5));
+//! }
+//!
+//! // Sensor thread
+//! if let Some(label) = STATUS.lock().as_mut() {
+//!     label.set_text("Ready")?;
+//! }
+//! ```
+//!
+//! # Command queues
+//!
+//! Instead of sharing widgets, other threads can send commands to the UI
+//! thread through a [`UiChannel`], a fixed-size queue that doesn't allocate.
+//! The UI thread applies them with [`UiChannel::drain()`], typically right
+//! before calling `task_handler()`:
+//!
+//! ```ignore
+//! enum UiCommand {
+//!     SetTemperature(i32),
+//! }
+//!
+//! static UI: UiChannel<UiCommand, 8> = UiChannel::new();
+//!
+//! // Sensor thread
+//! let _ = UI.send(UiCommand::SetTemperature(read_sensor()));
+//!
+//! // UI thread
+//! loop {
+//!     UI.drain(|cmd| match cmd {
+//!         UiCommand::SetTemperature(t) => bar.set_value(t, AnimationState::ON),
+//!     });
+//!     lvgl::task_handler()?;
+//! }
+//! ```
+//!
+//! With the `alloc` feature, a channel of [`BoxedCommand`]s can carry
+//! arbitrary closures. After [`UiChannel::attach()`], the commands of a
+//! channel are run by LVGL itself during the next `task_handler()` call.
+
+use crate::{LvError, LvResult};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::hint;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Held while any `LvglMutex` is locked. There is only one LVGL instance, so
+/// every mutex shares this lock.
+static LVGL_LOCK: AtomicBool = AtomicBool::new(false);
+
+/// A mutex protecting UI state along with all access to LVGL. See the module
+/// documentation for details.
+///
+/// All instances share one global lock, so locking two of them on the same
+/// thread at once deadlocks. Use a single mutex holding a struct instead.
+///
+/// Like `std::sync::Mutex`, the protected value must be `Send`. Widgets are
+/// not, so wrap them in an [`AssertLvglSend`].
+pub struct LvglMutex<T> {
+    data: UnsafeCell<T>,
+}
+
+// Safety: the contents are only reachable through the global lock, which
+// also serializes every LVGL call made through a guard.
+unsafe impl<T: Send> Sync for LvglMutex<T> {}
+unsafe impl<T: Send> Send for LvglMutex<T> {}
+
+impl<T> LvglMutex<T> {
+    /// Creates a new mutex holding `data`. This can be used to initialize a
+    /// `static`.
+    pub const fn new(data: T) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the global LVGL lock, spinning until it is available.
+    pub fn lock(&self) -> LvglGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Acquires the global LVGL lock if it is not currently held.
+    pub fn try_lock(&self) -> Option<LvglGuard<'_, T>> {
+        LVGL_LOCK
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| LvglGuard {
+                mutex: self,
+                _not_send: PhantomData,
+            })
+    }
+
+    /// Returns the protected value. No locking is needed, as the mutable
+    /// borrow guarantees exclusive access.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consumes the mutex, returning the protected value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: Default> Default for LvglMutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> fmt::Debug for LvglMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LvglMutex")
+            .field("locked", &LVGL_LOCK.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Exclusive access to LVGL and the value of an `LvglMutex`. The lock is
+/// released when the guard is dropped.
+pub struct LvglGuard<'a, T> {
+    mutex: &'a LvglMutex<T>,
+    // The guard must be dropped on the thread that created it
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T> LvglGuard<'_, T> {
+    /// Calls the LVGL timer handler while holding the lock. See
+    /// `task_handler()`.
+    #[inline]
+    pub fn task_handler(&mut self) -> LvResult<()> {
+        crate::task_handler()
+    }
+}
+
+impl<T> Deref for LvglGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for LvglGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for LvglGuard<'_, T> {
+    fn drop(&mut self) {
+        LVGL_LOCK.store(false, Ordering::Release);
+    }
+}
+
+/// A value holding LVGL objects, such as widgets, asserted to be `Send` so
+/// it can be put in an `LvglMutex`.
+///
+/// LVGL objects are not tied to a thread, but every call into LVGL must be
+/// made with the global lock held.
+#[derive(Debug)]
+pub struct AssertLvglSend<T>(T);
+
+// Safety: upheld by the caller of `AssertLvglSend::new()`
+unsafe impl<T> Send for AssertLvglSend<T> {}
+
+impl<T> AssertLvglSend<T> {
+    /// Wraps `value`.
+    ///
+    /// # Safety
+    ///
+    /// Apart from LVGL objects, `value` must only hold data that is `Send`,
+    /// and once it is moved to another thread, it must only be used with the
+    /// lock of an `LvglMutex` held, e.g. by only reaching it through the
+    /// mutex.
+    pub unsafe fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for AssertLvglSend<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for AssertLvglSend<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// A command run on the UI thread by `UiChannel::run_pending()`.
+pub trait Command {
+    /// Executes the command.
+    fn execute(self);
+}
+
+/// A closure queued to run on the UI thread.
+#[cfg(feature = "alloc")]
+pub type BoxedCommand = alloc::boxed::Box<dyn FnOnce() + Send>;
+
+#[cfg(feature = "alloc")]
+impl Command for BoxedCommand {
+    fn execute(self) {
+        self()
+    }
+}
+
+/// A bounded queue of commands sent from any thread to the UI thread. See
+/// the module documentation for details.
+///
+/// The queue has its own lock, separate from that of `LvglMutex`, which is
+/// only held while a command is added or removed. Sending from an interrupt
+/// handler is not supported, as it could spin forever if the interrupt
+/// preempts the UI thread while it holds the lock.
+pub struct UiChannel<C, const N: usize> {
+    lock: AtomicBool,
+    queue: UnsafeCell<Queue<C, N>>,
+}
+
+struct Queue<C, const N: usize> {
+    items: [MaybeUninit<C>; N],
+    head: usize,
+    len: usize,
+}
+
+// Safety: commands are moved between threads, but only accessed while the
+// queue's lock is held.
+unsafe impl<C: Send, const N: usize> Sync for UiChannel<C, N> {}
+unsafe impl<C: Send, const N: usize> Send for UiChannel<C, N> {}
+
+impl<C, const N: usize> UiChannel<C, N> {
+    /// Creates an empty channel holding up to `N` commands. This can be used
+    /// to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            lock: AtomicBool::new(false),
+            queue: UnsafeCell::new(Queue {
+                items: [const { MaybeUninit::uninit() }; N],
+                head: 0,
+                len: 0,
+            }),
+        }
+    }
+
+    fn with_queue<R>(&self, f: impl FnOnce(&mut Queue<C, N>) -> R) -> R {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
+        }
+        let res = f(unsafe { &mut *self.queue.get() });
+        self.lock.store(false, Ordering::Release);
+        res
+    }
+
+    /// Queues a command. If the channel is full, the command is handed back
+    /// in the error.
+    pub fn send(&self, cmd: C) -> Result<(), C> {
+        self.with_queue(|q| {
+            if q.len == N {
+                return Err(cmd);
+            }
+            q.items[(q.head + q.len) % N].write(cmd);
+            q.len += 1;
+            Ok(())
+        })
+    }
+
+    /// Removes the oldest queued command, if any.
+    pub fn recv(&self) -> Option<C> {
+        self.with_queue(|q| {
+            if q.len == 0 {
+                return None;
+            }
+            let cmd = unsafe { q.items[q.head].assume_init_read() };
+            q.head = (q.head + 1) % N;
+            q.len -= 1;
+            Some(cmd)
+        })
+    }
+
+    /// Passes every queued command to `f` in the order they were sent. The
+    /// queue is not locked while `f` runs, so it may send further commands.
+    pub fn drain(&self, mut f: impl FnMut(C)) {
+        while let Some(cmd) = self.recv() {
+            f(cmd);
+        }
+    }
+
+    /// Returns the number of queued commands.
+    pub fn len(&self) -> usize {
+        self.with_queue(|q| q.len)
+    }
+
+    /// Returns `true` if no commands are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<C: Command, const N: usize> UiChannel<C, N> {
+    /// Executes every queued command in the order they were sent. Call this
+    /// on the UI thread.
+    pub fn run_pending(&self) {
+        self.drain(C::execute);
+    }
+}
+
+impl<C: Command + Send, const N: usize> UiChannel<C, N> {
+    /// Registers an LVGL timer that runs the queued commands during every
+    /// `task_handler()` call.
+    pub fn attach(&'static self) -> LvResult<()> {
+        unsafe extern "C" fn run<C: Command, const N: usize>(timer: *mut lvgl_sys::lv_timer_t) {
+            crate::guard::guard("UI channel", || {
+                let channel = (*timer).user_data as *const UiChannel<C, N>;
+                (*channel).run_pending();
+            })
+        }
+
+        let timer = unsafe {
+            lvgl_sys::lv_timer_create(Some(run::<C, N>), 0, self as *const Self as *mut _)
+        };
+        if timer.is_null() {
+            Err(LvError::LvOOMemory)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C, const N: usize> Default for UiChannel<C, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, const N: usize> Drop for UiChannel<C, N> {
+    fn drop(&mut self) {
+        while self.recv().is_some() {}
+    }
+}
+
+impl<C, const N: usize> fmt::Debug for UiChannel<C, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UiChannel")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lock_is_shared_and_released() {
+        static A: LvglMutex<u32> = LvglMutex::new(0);
+        static B: LvglMutex<u32> = LvglMutex::new(0);
+
+        {
+            let mut a = A.lock();
+            *a += 1;
+            assert!(B.try_lock().is_none());
+        }
+        assert_eq!(*B.try_lock().unwrap(), 0);
+        assert_eq!(*A.lock(), 1);
+
+        fn shareable<T: Sync>() {}
+        shareable::<LvglMutex<Option<AssertLvglSend<crate::Obj<'static>>>>>();
+    }
+    #[test]
+    fn channel_is_fifo_and_bounded() {
+        let channel: UiChannel<u32, 2> = UiChannel::new();
+        assert!(channel.send(1).is_ok());
+        assert!(channel.send(2).is_ok());
+        assert_eq!(channel.send(3), Err(3));
+
+        assert_eq!(channel.recv(), Some(1));
+        assert!(channel.send(3).is_ok());
+
+        let mut received = Vec::new();
+        channel.drain(|cmd| received.push(cmd));
+        assert_eq!(received, [2, 3]);
+        assert!(channel.is_empty());
+    }
+
+    #[test]
+    fn attached_channel_runs_commands() {
+        use core::sync::atomic::AtomicU32;
+
+        static SUM: AtomicU32 = AtomicU32::new(0);
+        struct Add(u32);
+        impl Command for Add {
+            fn execute(self) {
+                SUM.fetch_add(self.0, Ordering::Relaxed);
+            }
+        }
+        static CHANNEL: UiChannel<Add, 4> = UiChannel::new();
+
+        crate::tests::initialize_test(false);
+        CHANNEL.attach().unwrap();
+        assert!(CHANNEL.send(Add(2)).is_ok());
+        assert!(CHANNEL.send(Add(3)).is_ok());
+        crate::task_handler().unwrap();
+        assert_eq!(SUM.load(Ordering::Relaxed), 5);
+        assert!(CHANNEL.is_empty());
+    }
+}
@@ -0,0 +1,484 @@
+//! Headless display and input devices for testing UIs
+//!
+//! [`TestDisplay`] registers a display that renders into memory instead of a
+//! screen, along with a virtual pointer and keypad driven from code. Widget
+//! behavior and layouts can then be checked in unit tests and CI without
+//! hardware or SDL:
+//!
+//! ```ignore
+//! use lvgl::testing::TestDisplay;
+//! use lvgl::widgets::Btn;
+//!
+//! let mut ui = TestDisplay::new(DrawBuffer::<{ 240 * 24 }>::default(), 240, 240)?;
+//! let mut screen = ui.display.get_scr_act()?;
+//! let mut btn = Btn::create(&mut screen)?;
+//! btn.set_size(100, 40);
+//! btn.on_event(|_, event| println!("{:?}", event))?;
+//!
+//! ui.click(Point::new(50, 20))?;
+//! ui.render();
+//! assert_eq!(ui.pixel(239, 239).map(|c| c.to_u32()), Some(0xFFFFFFFF));
+//! ```
+//!
+//! Input is read by LVGL's timers, so the helpers that press the pointer or
+//! keys advance the tick and run `task_handler()` until LVGL has read it. They
+//! are not available with the `rust_timer` or `custom_timer` features, where
+//! the tick is not driven by `tick_inc()`.
+//!
+//! # Golden images
+//!
+//! Rendered frames can be compared with reference images to catch visual
+//! regressions in plain `cargo test`. `frame_hash()` gives a hash to compare
+//! with one recorded in the test, and `diff()` compares with reference
+//! pixels, allowing a tolerance per color channel and reporting the regions
+//! that differ. With the `screenshot` feature, `assert_golden()` compares
+//! with a PNG file that it creates on the first run:
+//!
+//! ```ignore
+//! ui.assert_golden("tests/golden/settings.png", 2);
+//! ```
+//!
+//! Run the tests with `LVGL_UPDATE_GOLDEN=1` to rewrite the reference images
+//! after an intended change.
+//!
+//! # Building
+//!
+//! Enable the `testing` feature, typically as a dev-dependency feature.
+
+use crate::input_device::keypad::{Keypad, KeypadInputData};
+use crate::input_device::pointer::{Pointer, PointerInputData};
+use crate::input_device::{InputDriver, InputState};
+use crate::{Area, Color, Display, DrawBuffer, LvError, LvResult, Point};
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, Ref, RefCell};
+use core::fmt;
+#[cfg(feature = "screenshot")]
+use std::{env, fs::File, io, path::Path};
+
+/// The tick advanced between runs of `task_handler()` while pumping LVGL.
+#[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+const STEP_MS: u32 = 5;
+
+/// An off-screen display with a virtual pointer and keypad.
+pub struct TestDisplay {
+    /// The display rendering into memory.
+    pub display: Display,
+    /// The virtual pointer, driven by `press()`, `release()` and `click()`.
+    pub pointer: Pointer,
+    /// The virtual keypad, driven by `type_key()`. Assign it to a group to
+    /// control widgets with it.
+    pub keypad: Keypad,
+    width: u32,
+    frame: Rc<RefCell<Vec<Color>>>,
+    pointer_state: Rc<Cell<InputState>>,
+    keys: Rc<RefCell<VecDeque<InputState>>>,
+}
+
+impl TestDisplay {
+    /// Registers a `width` by `height` display rendering into memory, with its
+    /// input devices.
+    pub fn new<const N: usize>(
+        draw_buffer: DrawBuffer<N>,
+        width: u32,
+        height: u32,
+    ) -> LvResult<Self> {
+        let frame = Rc::new(RefCell::new(vec![
+            Color::default();
+            (width * height) as usize
+        ]));
+        let flush_frame = frame.clone();
+        let display = Display::register(draw_buffer, width, height, move |refresh| {
+            let mut frame = flush_frame.borrow_mut();
+            let area = &refresh.area;
+            let row_len = (area.x2 - area.x1 + 1) as usize;
+            for (iy, y) in (area.y1..=area.y2).enumerate() {
+                for (ix, x) in (area.x1..=area.x2).enumerate() {
+                    let (x, y) = (x as u32, y as u32);
+                    if x < width && y < height {
+                        frame[(y * width + x) as usize] = refresh.colors[iy * row_len + ix];
+                    }
+                }
+            }
+        })
+        .map_err(LvError::from)?;
+
+        let pointer_state = Rc::new(Cell::new(
+            PointerInputData::Touch(Point::new(0, 0)).released(),
+        ));
+        let read_pointer = pointer_state.clone();
+        let pointer = Pointer::register(move || read_pointer.get().once(), &display)?;
+
+        let keys = Rc::new(RefCell::new(VecDeque::new()));
+        let read_keys = keys.clone();
+        let last_key = Cell::new(KeypadInputData(0).released());
+        let keypad = Keypad::register(
+            move || {
+                let mut keys = read_keys.borrow_mut();
+                if let Some(key) = keys.pop_front() {
+                    last_key.set(key);
+                }
+                if keys.is_empty() {
+                    last_key.get().once()
+                } else {
+                    last_key.get().and_continued()
+                }
+            },
+            &display,
+        )?;
+
+        Ok(Self {
+            display,
+            pointer,
+            keypad,
+            width,
+            frame,
+            pointer_state,
+            keys,
+        })
+    }
+
+    /// Redraws the whole active screen into memory immediately.
+    pub fn render(&mut self) {
+        unsafe {
+            let screen = lvgl_sys::lv_disp_get_scr_act(self.display.disp.as_ptr());
+            lvgl_sys::lv_obj_invalidate(screen);
+            lvgl_sys::lv_refr_now(self.display.disp.as_ptr());
+        }
+    }
+
+    /// Returns the rendered pixels, row by row. They are updated by LVGL's
+    /// refreshes, or by `render()`.
+    pub fn frame(&self) -> Ref<'_, [Color]> {
+        Ref::map(self.frame.borrow(), Vec::as_slice)
+    }
+
+    /// Returns the rendered color of a pixel, or `None` outside the display.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width {
+            return None;
+        }
+        self.frame().get((y * self.width + x) as usize).copied()
+    }
+
+    /// Returns a hash of the size and rendered colors of the frame, which
+    /// stays the same as long as the frame does.
+    pub fn frame_hash(&self) -> u64 {
+        // FNV-1a
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let width = self.width.to_le_bytes();
+        let colors = self.frame().iter().flat_map(|c| rgb(c).to_le_bytes());
+        for byte in width.into_iter().chain(colors) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+
+    /// Compares the rendered frame with `reference`, its expected pixels as
+    /// `0xRRGGBB`, row by row. Returns where they differ by more than
+    /// `tolerance` in a color channel, or `None` if they match. Fails with
+    /// `LvError::InvalidReference` if `reference` is not the size of the
+    /// frame.
+    pub fn diff(&self, reference: &[u32], tolerance: u8) -> LvResult<Option<FrameDiff>> {
+        let frame = self.frame();
+        if reference.len() != frame.len() {
+            return Err(LvError::InvalidReference);
+        }
+        let mut diff = FrameDiff::default();
+        // Rows with differences, and the leftmost and rightmost difference
+        let mut band: Option<Area> = None;
+        for (y, row) in frame.chunks(self.width as usize).enumerate() {
+            let expected = &reference[y * row.len()..(y + 1) * row.len()];
+            let mut columns: Option<(usize, usize)> = None;
+            for (x, (color, &expected)) in row.iter().zip(expected).enumerate() {
+                let delta = channel_delta(rgb(color), expected);
+                if delta > tolerance {
+                    diff.pixels += 1;
+                    diff.max_delta = diff.max_delta.max(delta);
+                    columns = Some(columns.map_or((x, x), |(x1, _)| (x1, x)));
+                }
+            }
+            match (columns, band.as_mut()) {
+                (Some((x1, x2)), Some(area)) => {
+                    area.x1 = area.x1.min(x1 as i16);
+                    area.x2 = area.x2.max(x2 as i16);
+                    area.y2 = y as i16;
+                }
+                (Some((x1, x2)), None) => {
+                    band = Some(Area {
+                        x1: x1 as i16,
+                        x2: x2 as i16,
+                        y1: y as i16,
+                        y2: y as i16,
+                    })
+                }
+                (None, Some(_)) => diff.regions.extend(band.take()),
+                (None, None) => {}
+            }
+        }
+        diff.regions.extend(band);
+        Ok((diff.pixels > 0).then_some(diff))
+    }
+
+    /// Renders the screen and compares it with the PNG image at `path`, as
+    /// `diff()` does, panicking with the regions that differ. The rendered
+    /// frame is then saved next to the reference, with an `.actual.png`
+    /// extension.
+    ///
+    /// If there is no image at `path` or the `LVGL_UPDATE_GOLDEN`
+    /// environment variable is set, the rendered frame is saved there
+    /// instead.
+    #[cfg(feature = "screenshot")]
+    #[track_caller]
+    pub fn assert_golden(&mut self, path: impl AsRef<Path>, tolerance: u8) {
+        let path = path.as_ref();
+        self.render();
+        if env::var_os("LVGL_UPDATE_GOLDEN").is_some() || !path.exists() {
+            self.save_png(path);
+            return;
+        }
+        let (width, reference) =
+            read_png(path).unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+        let height = self.frame().len() as u32 / self.width;
+        if (width, reference.len()) != (self.width, self.frame().len()) {
+            panic!(
+                "{} is {}x{}, but the display is {}x{}",
+                path.display(),
+                width,
+                reference.len() as u32 / width.max(1),
+                self.width,
+                height
+            );
+        }
+        if let Some(diff) = self.diff(&reference, tolerance).unwrap() {
+            let actual = path.with_extension("actual.png");
+            self.save_png(&actual);
+            panic!(
+                "{} does not match the rendered frame: {}; saved the frame to {}",
+                path.display(),
+                diff,
+                actual.display()
+            );
+        }
+    }
+
+    #[cfg(feature = "screenshot")]
+    fn save_png(&self, path: &Path) {
+        let height = self.frame().len() as u32 / self.width;
+        File::create(path)
+            .map_err(Into::into)
+            .and_then(|file| {
+                crate::snapshot::write_png(
+                    io::BufWriter::new(file),
+                    self.width,
+                    height,
+                    &self.frame(),
+                )
+            })
+            .unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+    }
+
+    /// Advances LVGL's tick by `ms` milliseconds, running `task_handler()`
+    /// every few milliseconds as a main loop would.
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    pub fn advance(&mut self, ms: u32) -> LvResult<()> {
+        let mut elapsed = 0;
+        while elapsed < ms {
+            let step = STEP_MS.min(ms - elapsed);
+            crate::tick_inc_ms(step);
+            crate::task_handler()?;
+            elapsed += step;
+        }
+        Ok(())
+    }
+
+    /// Presses the pointer at `point`, and lets LVGL read it.
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    pub fn press(&mut self, point: Point) -> LvResult<()> {
+        self.pointer_state
+            .set(PointerInputData::Touch(point).pressed());
+        self.advance(crate::config::INDEV_READ_PERIOD_MS)
+    }
+
+    /// Releases the pointer where it is, and lets LVGL read it.
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    pub fn release(&mut self) -> LvResult<()> {
+        if let InputState::Pressed(data) = self.pointer_state.get() {
+            self.pointer_state.set(InputState::Released(data));
+        }
+        self.advance(crate::config::INDEV_READ_PERIOD_MS)
+    }
+
+    /// Presses and releases the pointer at `point`.
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    pub fn click(&mut self, point: Point) -> LvResult<()> {
+        self.press(point)?;
+        self.release()
+    }
+
+    /// Presses and releases `key` on the keypad, which is either an ASCII
+    /// character or one of LVGL's control keys (`lvgl_sys::LV_KEY_*`), and
+    /// lets LVGL read it.
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    pub fn type_key(&mut self, key: u32) -> LvResult<()> {
+        let mut keys = self.keys.borrow_mut();
+        keys.push_back(KeypadInputData(key).pressed());
+        keys.push_back(KeypadInputData(key).released());
+        drop(keys);
+        self.advance(crate::config::INDEV_READ_PERIOD_MS)
+    }
+}
+
+/// Where a rendered frame differs from a reference. See
+/// `TestDisplay::diff()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameDiff {
+    /// The number of pixels that differ.
+    pub pixels: usize,
+    /// The largest difference in a color channel.
+    pub max_delta: u8,
+    /// The bounding boxes of the differing pixels, one per run of rows with
+    /// differences, from top to bottom.
+    pub regions: Vec<Area>,
+}
+
+impl fmt::Display for FrameDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pixels differ by up to {}, in",
+            self.pixels, self.max_delta
+        )?;
+        for (i, area) in self.regions.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(
+                f,
+                "{} ({}, {})-({}, {})",
+                sep, area.x1, area.y1, area.x2, area.y2
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a color as `0xRRGGBB`.
+fn rgb(color: &Color) -> u32 {
+    color.to_u32() & 0xFF_FFFF
+}
+
+/// Returns the largest difference between the channels of two `0xRRGGBB`
+/// colors.
+fn channel_delta(a: u32, b: u32) -> u8 {
+    let (a, b) = (a.to_be_bytes(), b.to_be_bytes());
+    (1..4).map(|i| a[i].abs_diff(b[i])).max().unwrap_or(0)
+}
+
+/// Reads a PNG image as its width and its pixels as `0xRRGGBB`.
+#[cfg(feature = "screenshot")]
+fn read_png(path: &Path) -> Result<(u32, Vec<u32>), png::DecodingError> {
+    let mut decoder = png::Decoder::new(File::open(path)?);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    let samples = info.color_type.samples();
+    let pixels = buf[..info.buffer_size()]
+        .chunks(samples)
+        .map(|px| match px {
+            [r, g, b, ..] => u32::from_be_bytes([0, *r, *g, *b]),
+            [l, ..] => u32::from_be_bytes([0, *l, *l, *l]),
+            [] => 0,
+        })
+        .collect();
+    Ok((info.width, pixels))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Btn;
+    use crate::{tests, Event, Widget};
+
+    #[test]
+    fn click_and_render() {
+        tests::initialize_test(false);
+        let buffer = DrawBuffer::<{ 240 * 24 }>::default();
+        let mut ui = TestDisplay::new(buffer, 240, 240).unwrap();
+        let mut screen = ui.display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.set_size(100, 40);
+        btn.set_style_bg_color(crate::Part::Main, Color::RED);
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        btn.on_event(move |_, event| {
+            if matches!(event, Event::Clicked) {
+                counter.set(counter.get() + 1)
+            }
+        })
+        .unwrap();
+
+        ui.render();
+        assert_eq!(ui.pixel(50, 20).unwrap().to_u32(), Color::RED.to_u32());
+        assert_eq!(ui.pixel(240, 0).map(|c| c.to_u32()), None);
+
+        ui.click(Point::new(50, 20)).unwrap();
+        assert_eq!(clicks.get(), 1);
+        ui.click(Point::new(200, 200)).unwrap();
+        assert_eq!(clicks.get(), 1);
+    }
+
+    #[test]
+    fn diff_against_reference() {
+        tests::initialize_test(false);
+        let buffer = DrawBuffer::<{ 32 * 8 }>::default();
+        let mut ui = TestDisplay::new(buffer, 32, 32).unwrap();
+        let mut screen = ui.display.get_scr_act().unwrap();
+        screen.set_style_bg_color(crate::Part::Main, Color::from_hex(0x406080));
+        ui.render();
+        let hash = ui.frame_hash();
+
+        let bg = rgb(&Color::from_hex(0x406080));
+        let mut reference = vec![bg; 32 * 32];
+        assert_eq!(ui.diff(&reference, 0), Ok(None));
+        // Within tolerance
+        reference[0] = bg + 0x010101;
+        assert_eq!(ui.diff(&reference, 1), Ok(None));
+        // Two regions, of rows 3-4 and row 10
+        reference[3 * 32 + 5] = 0xFFFFFF;
+        reference[4 * 32 + 2] = 0xFFFFFF;
+        reference[10 * 32 + 31] = 0x000000;
+        let diff = ui.diff(&reference, 1).unwrap().unwrap();
+        assert_eq!(diff.pixels, 3);
+        assert_eq!(
+            diff.max_delta,
+            channel_delta(bg, 0xFFFFFF).max(channel_delta(bg, 0))
+        );
+        assert_eq!(
+            diff.regions,
+            [
+                Area {
+                    x1: 2,
+                    x2: 5,
+                    y1: 3,
+                    y2: 4
+                },
+                Area {
+                    x1: 31,
+                    x2: 31,
+                    y1: 10,
+                    y2: 10
+                },
+            ]
+        );
+        assert_eq!(ui.diff(&reference[1..], 0), Err(LvError::InvalidReference));
+
+        ui.render();
+        assert_eq!(ui.frame_hash(), hash);
+        screen.set_style_bg_color(crate::Part::Main, Color::from_hex(0x204060));
+        ui.render();
+        assert_ne!(ui.frame_hash(), hash);
+    }
+}
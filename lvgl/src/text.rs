@@ -0,0 +1,142 @@
+use crate::{LvError, LvResult};
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+/// Capacity in bytes of the stack buffer a `&str` is copied into to pass it
+/// to LVGL, including the terminating NUL. Longer text is copied into LVGL
+/// memory instead.
+pub const STACK_TEXT_CAPACITY: usize = 64;
+
+/// Text which LVGL copies, given as a `&str` as well as a `&CStr`, so setters
+/// taking it need no `CString`:
+///
+/// ```ignore
+/// label.set_text("Ready")?;
+/// label.set_text(cstr!("Ready"))?;
+/// ```
+///
+/// A `&str` is made NUL-terminated on the stack, or in LVGL memory if it has
+/// `STACK_TEXT_CAPACITY` bytes or more. Text after an interior NUL is cut
+/// off, as C would end the string there.
+pub trait AsCStr {
+    /// Calls `f` with the text as a NUL-terminated string. Fails with
+    /// `LvError::LvOOMemory` if LVGL memory is needed but not available.
+    fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> LvResult<R>;
+}
+
+impl AsCStr for CStr {
+    fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> LvResult<R> {
+        Ok(f(self))
+    }
+}
+
+impl AsCStr for str {
+    fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> LvResult<R> {
+        let text = self.split('\0').next().unwrap_or_default();
+        if text.len() < STACK_TEXT_CAPACITY {
+            let mut buf = [0; STACK_TEXT_CAPACITY];
+            buf[..text.len()].copy_from_slice(text.as_bytes());
+            Ok(f(unsafe {
+                CStr::from_bytes_with_nul_unchecked(&buf[..=text.len()])
+            }))
+        } else {
+            let mut buf = TextBuf::with_capacity(text.len() + 1)?;
+            buf.push_str(text)?;
+            Ok(f(buf.as_c_str()))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsCStr for alloc::string::String {
+    fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> LvResult<R> {
+        self.as_str().with_c_str(f)
+    }
+}
+
+impl<T: AsCStr + ?Sized> AsCStr for &T {
+    fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> LvResult<R> {
+        (**self).with_c_str(f)
+    }
+}
+
+/// A NUL-terminated string built up in LVGL memory.
+pub(crate) struct TextBuf {
+    buf: NonNull<u8>,
+    len: usize,
+    cap: usize,
+}
+
+impl TextBuf {
+    /// Joins `items` with `\n`, e.g. into the options of a dropdown.
+    pub(crate) fn join(items: impl IntoIterator<Item = impl AsRef<str>>) -> LvResult<Self> {
+        let mut joined = Self::with_capacity(64)?;
+        for (i, item) in items.into_iter().enumerate() {
+            if i > 0 {
+                joined.push_str("\n")?;
+            }
+            joined.push_str(item.as_ref())?;
+        }
+        Ok(joined)
+    }
+
+    pub(crate) fn with_capacity(cap: usize) -> LvResult<Self> {
+        let buf = unsafe { crate::storage::alloc(cap) } as *mut u8;
+        let buf = NonNull::new(buf).ok_or(LvError::LvOOMemory)?;
+        unsafe { *buf.as_ptr() = 0 }
+        Ok(Self { buf, len: 0, cap })
+    }
+
+    /// Appends `s` up to its first NUL, if any.
+    pub(crate) fn push_str(&mut self, s: &str) -> LvResult<()> {
+        let bytes = s.split('\0').next().unwrap_or_default().as_bytes();
+        let len = self.len + bytes.len();
+        if len >= self.cap {
+            let cap = (len + 1).max(self.cap * 2);
+            let buf = unsafe { crate::storage::realloc(self.buf.as_ptr() as *mut _, cap) };
+            self.buf = NonNull::new(buf as *mut u8).ok_or(LvError::LvOOMemory)?;
+            self.cap = cap;
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), self.buf.as_ptr().add(self.len), bytes.len());
+            *self.buf.as_ptr().add(len) = 0;
+        }
+        self.len = len;
+        Ok(())
+    }
+
+    pub(crate) fn as_c_str(&self) -> &CStr {
+        unsafe {
+            CStr::from_bytes_with_nul_unchecked(core::slice::from_raw_parts(
+                self.buf.as_ptr(),
+                self.len + 1,
+            ))
+        }
+    }
+}
+
+impl Drop for TextBuf {
+    fn drop(&mut self) {
+        unsafe { crate::storage::free(self.buf.as_ptr() as *mut _) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn str_as_c_str() {
+        crate::tests::initialize_test(false);
+        let short = "Ready".with_c_str(|text| text.to_bytes().to_vec()).unwrap();
+        assert_eq!(short, b"Ready");
+        let cut = "On\0Off"
+            .with_c_str(|text| text.to_bytes().to_vec())
+            .unwrap();
+        assert_eq!(cut, b"On");
+
+        let long = "Lorem ipsum ".repeat(10);
+        let copied = long.with_c_str(|text| text.to_bytes().to_vec()).unwrap();
+        assert_eq!(copied, long.as_bytes());
+    }
+}
@@ -0,0 +1,128 @@
+//! Driving LVGL's tick from a hardware timer
+//!
+//! LVGL needs to know how much time has passed, which on embedded targets is
+//! usually done by calling `tick_inc()` from a periodic timer interrupt. This
+//! module wraps that pattern: start any [`PeriodicTimer`] with [`start()`],
+//! and call [`on_interrupt()`] from its interrupt handler.
+//!
+//! With the `cortex_m` feature, [`SysTick`] implements `PeriodicTimer` for
+//! the Cortex-M system timer.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use cortex_m_rt::exception;
+//!
+//! #[exception]
+//! fn SysTick() {
+//!     lvgl::tick::on_interrupt();
+//! }
+//!
+//! fn main() {
+//!     let cp = cortex_m::Peripherals::take().unwrap();
+//!     let mut systick = lvgl::tick::SysTick::new(cp.SYST, 64_000_000);
+//!     lvgl::tick::start(&mut systick, 5);
+//!     // Initialize displays, etc.
+//! }
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// The period of the running timer in milliseconds, or 0 if none is running.
+static PERIOD_MS: AtomicU32 = AtomicU32::new(0);
+
+/// A hardware timer that can raise an interrupt at a fixed period.
+pub trait PeriodicTimer {
+    /// Configures the timer to interrupt every `period_ms` milliseconds and
+    /// starts it.
+    fn start_periodic(&mut self, period_ms: u32);
+}
+
+/// Starts `timer` with the given period. Each call to `on_interrupt()` then
+/// advances LVGL's tick by that period.
+pub fn start<T: PeriodicTimer>(timer: &mut T, period_ms: u32) {
+    PERIOD_MS.store(period_ms, Ordering::Relaxed);
+    timer.start_periodic(period_ms);
+}
+
+/// Advances LVGL's tick by the period passed to `start()`. Call this from the
+/// timer's interrupt handler.
+#[inline]
+pub fn on_interrupt() {
+    crate::tick_inc_ms(PERIOD_MS.load(Ordering::Relaxed));
+}
+
+/// The Cortex-M system timer, clocked from the core clock.
+#[cfg(feature = "cortex_m")]
+pub struct SysTick {
+    syst: cortex_m::peripheral::SYST,
+    core_clock_hz: u32,
+}
+
+#[cfg(feature = "cortex_m")]
+impl SysTick {
+    /// Takes ownership of the system timer. `core_clock_hz` is the frequency
+    /// of the core clock.
+    pub fn new(syst: cortex_m::peripheral::SYST, core_clock_hz: u32) -> Self {
+        Self {
+            syst,
+            core_clock_hz,
+        }
+    }
+
+    /// Stops the timer and returns the system timer peripheral.
+    pub fn free(mut self) -> cortex_m::peripheral::SYST {
+        self.syst.disable_interrupt();
+        self.syst.disable_counter();
+        self.syst
+    }
+}
+
+#[cfg(feature = "cortex_m")]
+impl PeriodicTimer for SysTick {
+    /// # Panics
+    ///
+    /// Panics if the period does not fit in the 24-bit reload register at the
+    /// configured core clock.
+    fn start_periodic(&mut self, period_ms: u32) {
+        use cortex_m::peripheral::syst::SystClkSource;
+
+        let reload = (self.core_clock_hz as u64 / 1000) * period_ms as u64;
+        assert!(
+            (1..=0x0100_0000).contains(&reload),
+            "SysTick period out of range"
+        );
+        self.syst.set_clock_source(SystClkSource::Core);
+        self.syst.set_reload(reload as u32 - 1);
+        self.syst.clear_current();
+        self.syst.enable_interrupt();
+        self.syst.enable_counter();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+
+    struct MockTimer(u32);
+
+    impl PeriodicTimer for MockTimer {
+        fn start_periodic(&mut self, period_ms: u32) {
+            self.0 = period_ms;
+        }
+    }
+
+    #[test]
+    fn interrupt_advances_tick() {
+        tests::initialize_test(false);
+        let mut timer = MockTimer(0);
+        start(&mut timer, 10);
+        assert_eq!(timer.0, 10);
+
+        let before = unsafe { lvgl_sys::lv_tick_get() };
+        on_interrupt();
+        let elapsed = unsafe { lvgl_sys::lv_tick_elaps(before) };
+        assert!(elapsed >= 10);
+    }
+}
@@ -5,9 +5,9 @@
 //!
 //! # Building
 //!
-//! Set `LV_TICK_CUSTOM` to `1` and `LV_TICK_CUSTOM_INCLUDE` to `<rs_timer.h>`
-//! in `lv_conf.h`, and enable the `rust_timer` feature on the `lvgl` crate to
-//! enable this functionality.
+//! Enable the `rust_timer` feature on the `lvgl` crate to enable this
+//! functionality. The build script sets `LV_TICK_CUSTOM` and its hooks on top
+//! of your `lv_conf.h`.
 //!
 //! # Usage
 //!
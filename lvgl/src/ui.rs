@@ -0,0 +1,177 @@
+//! Declarative screen definitions
+//!
+//! The [`ui!`](crate::ui!) macro describes a tree of widgets, which it
+//! creates on a parent object along with their layout and event handlers:
+//!
+//! ```ignore
+//! use lvgl::widgets::Slider;
+//!
+//! let mut screen = display.get_scr_act()?;
+//! lvgl::ui! {
+//!     screen {
+//!         flex_column {
+//!             label("Hello"),
+//!             slider(0..=100, on_change: |slider: Slider| println!("{}", slider.get_value())),
+//!             flex_row(height: 60) {
+//!                 btn(on_clicked: |_| println!("Cancel")) { label("Cancel") },
+//!                 btn(on_clicked: |_| println!("OK")) { label("OK") },
+//!             },
+//!         },
+//!     }
+//! }?;
+//! ```
+//!
+//! Each widget is written as `kind(arguments) { children }`, where the
+//! children are optional. The supported kinds are:
+//!
+//! - `container`, `flex_column` and `flex_row`: a plain object, laid out
+//!   with the flex layout for the latter two. Flex containers span the width
+//!   of their parent and fit the height of their content.
+//! - `label(text)`: a label, with a `&str` or `&CStr` as text.
+//! - `btn()`, `switch()`: a button or switch.
+//! - `slider(range)`, `bar(range)`: a slider or bar with the bounds of the
+//!   given integer range, such as `0..=100`.
+//! - `widget(builder)`: any widget, created from its [`Builder`].
+//!
+//! The arguments after the ones listed above are `name: value` pairs, each
+//! calling the builder method of that name, e.g. `width: 100` or
+//! `on_clicked: |_| ...`. Methods with several arguments take them as a
+//! tuple, e.g. `size: (100, 40)` or `align: (Align::Center, 0, 0)`.
+//!
+//! The macro evaluates to an `LvResult<()>`, which is an error if creating
+//! any of the widgets failed.
+//!
+//! [`Builder`]: crate::Builder
+
+use core::ops::{Bound, RangeBounds};
+
+/// Returns the inclusive bounds of an integer range, defaulting to LVGL's
+/// default range of 0 to 100 for unbounded ends.
+#[doc(hidden)]
+pub fn range_bounds(range: impl RangeBounds<i32>) -> (i32, i32) {
+    let min = match range.start_bound() {
+        Bound::Included(&min) => min,
+        Bound::Excluded(&min) => min + 1,
+        Bound::Unbounded => 0,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(&max) => max,
+        Bound::Excluded(&max) => max - 1,
+        Bound::Unbounded => 100,
+    };
+    (min, max)
+}
+
+/// Creates a tree of widgets on a parent object. See the [`ui`](crate::ui)
+/// module.
+#[macro_export]
+macro_rules! ui {
+    ($parent:ident { $($nodes:tt)* }) => {
+        (|| -> $crate::LvResult<()> {
+            $crate::ui!(@nodes $parent; $($nodes)*);
+            Ok(())
+        })()
+    };
+
+    // A list of widgets, each with optional children
+    (@nodes $parent:ident;) => {};
+    (@nodes $parent:ident; $kind:ident $(($($args:tt)*))? $({ $($children:tt)* })? $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut widget = $crate::ui!(@make $kind($($($args)*)?)).build(&mut $parent)?;
+            $($crate::ui!(@nodes widget; $($children)*);)?
+        }
+        $($crate::ui!(@nodes $parent; $($rest)*);)?
+    };
+
+    // The builder for each kind of widget
+    (@make container($($props:tt)*)) => {
+        $crate::ui!(@props $crate::Obj::builder(); $($props)*)
+    };
+    (@make flex_column($($props:tt)*)) => {
+        $crate::ui!(@make flex($crate::style::FlexFlow::COLUMN; $($props)*))
+    };
+    (@make flex_row($($props:tt)*)) => {
+        $crate::ui!(@make flex($crate::style::FlexFlow::ROW; $($props)*))
+    };
+    (@make flex($flow:expr; $($props:tt)*)) => {
+        $crate::ui!(@props $crate::Obj::builder()
+            .size(
+                $crate::misc::area::Size::pct(100),
+                $crate::misc::area::Size::content(),
+            )
+            .flex_flow($flow);
+            $($props)*)
+    };
+    (@make label($text:expr $(, $($props:tt)*)?)) => {
+        $crate::ui!(@props $crate::widgets::Label::builder().text($text); $($($props)*)?)
+    };
+    (@make btn($($props:tt)*)) => {
+        $crate::ui!(@props $crate::widgets::Btn::builder(); $($props)*)
+    };
+    (@make switch($($props:tt)*)) => {
+        $crate::ui!(@props $crate::widgets::Switch::builder(); $($props)*)
+    };
+    (@make slider($range:expr $(, $($props:tt)*)?)) => {{
+        let (min, max) = $crate::ui::range_bounds($range);
+        $crate::ui!(@props $crate::widgets::Slider::builder().range(min, max); $($($props)*)?)
+    }};
+    (@make bar($range:expr $(, $($props:tt)*)?)) => {{
+        let (min, max) = $crate::ui::range_bounds($range);
+        $crate::ui!(@props $crate::widgets::Bar::builder().range(min, max); $($($props)*)?)
+    }};
+    (@make widget($builder:expr $(, $($props:tt)*)?)) => {
+        $crate::ui!(@props $builder; $($($props)*)?)
+    };
+
+    // `name: value` arguments, calling builder methods
+    (@props $builder:expr;) => {
+        $builder
+    };
+    (@props $builder:expr; $name:ident: ($($arg:expr),* $(,)?) $(, $($rest:tt)*)?) => {
+        $crate::ui!(@props $builder.$name($($arg),*); $($($rest)*)?)
+    };
+    (@props $builder:expr; $name:ident: $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::ui!(@props $builder.$name($arg); $($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Slider;
+    use crate::{tests, Align};
+
+    #[test]
+    fn range_bounds_are_inclusive() {
+        assert_eq!(range_bounds(0..=100), (0, 100));
+        assert_eq!(range_bounds(0..100), (0, 99));
+        assert_eq!(range_bounds(..), (0, 100));
+    }
+
+    #[test]
+    fn creates_widget_tree() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        crate::ui! {
+            screen {
+                flex_column {
+                    label("Hello"),
+                    slider(0..=10, on_change: |_: Slider| {}),
+                    flex_row(height: 60) {
+                        btn(size: (80, 40), align: (Align::Center, 0, 0)) { label("OK") },
+                    },
+                },
+                widget(Slider::builder(), width: 100),
+            }
+        }
+        .unwrap();
+
+        unsafe {
+            let screen = crate::NativeObject::raw(&screen).as_ptr();
+            assert_eq!(lvgl_sys::lv_obj_get_child_cnt(screen), 2);
+            let column = lvgl_sys::lv_obj_get_child(screen, 0);
+            assert_eq!(lvgl_sys::lv_obj_get_child_cnt(column), 3);
+        }
+    }
+}
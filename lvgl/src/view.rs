@@ -0,0 +1,302 @@
+//! Reusable UI components
+//!
+//! A [`View`] is a panel of widgets along with its state, e.g. a status bar
+//! or a settings form, which can be created inside any parent. A [`ViewSlot`]
+//! mounts a view in a container of its own, calls the view's hooks as it is
+//! shown, hidden and unmounted, and deletes its widgets when it is unmounted.
+//! The same view can thus be reused in slots on several screens:
+//!
+//! ```ignore
+//! use lvgl::view::{View, ViewSlot};
+//!
+//! struct Clock<'a> {
+//!     time: Label<'a>,
+//! }
+//!
+//! impl<'a> View<'a> for Clock<'a> {
+//!     fn create(parent: &mut Obj<'a>) -> LvResult<Self> {
+//!         Ok(Self { time: Label::create(parent)? })
+//!     }
+//!
+//!     fn on_show(&mut self) {
+//!         let _ = self.time.set_text(&now());
+//!     }
+//! }
+//!
+//! let mut header = ViewSlot::<Clock>::new(&mut screen)?;
+//! header.container().set_height(30)?;
+//! header.mount()?;
+//! ```
+//!
+//! Structs deriving [`Screen`](crate::Screen) can implement `create()` by
+//! calling their generated constructor, `Self::create(parent)`.
+//!
+//! Views are independent of screen navigation: a [`Navigator`] switches
+//! between screens, which may each hold any number of slots.
+//!
+//! [`Navigator`]: crate::navigator::Navigator
+
+use crate::guard::guard;
+use crate::misc::area::Size;
+use crate::{Box, LvResult, NativeObject, Obj, ObjFlag, Widget};
+use core::ffi::c_void;
+use core::ptr::NonNull;
+
+/// A reusable panel of widgets. See the module documentation for details.
+pub trait View<'a>: Sized {
+    /// Creates the view's widgets inside `parent`.
+    fn create(parent: &mut Obj<'a>) -> LvResult<Self>;
+
+    /// Called when the view is mounted, and when it is shown again after
+    /// being hidden.
+    fn on_show(&mut self) {}
+
+    /// Called when the view is hidden, and before it is unmounted while
+    /// shown.
+    fn on_hide(&mut self) {}
+
+    /// Called right before the view's widgets are deleted, e.g. to stop
+    /// timers updating them.
+    fn on_destroy(&mut self) {}
+}
+
+/// A container that mounts and unmounts a `V`. See the module documentation
+/// for details.
+///
+/// Dropping the slot unmounts its view, but leaves the container. If the
+/// container is deleted first, e.g. along with its screen, the view is
+/// hidden and destroyed right before its widgets are deleted.
+pub struct ViewSlot<'a, V: View<'a>> {
+    container: Obj<'a>,
+    mounted: NonNull<Mounted<V>>,
+}
+
+/// The mounted view, in LVGL memory so that the container's delete callback
+/// can reach it wherever the slot is moved.
+struct Mounted<V> {
+    view: Option<V>,
+    /// Set when the container is deleted.
+    deleted: bool,
+}
+
+impl<'a, V: View<'a>> ViewSlot<'a, V> {
+    /// Creates an empty slot filling `parent`, with a container that has no
+    /// styles of its own. Use `container()` to size or place it otherwise.
+    pub fn new<'p: 'a>(parent: &mut impl Widget<'p>) -> LvResult<Self> {
+        let mut container = Obj::create(parent)?;
+        unsafe { lvgl_sys::lv_obj_remove_style_all(container.raw().as_mut()) }
+        container.set_size(Size::pct(100), Size::pct(100));
+        let mounted = Box::into_raw(Box::new(Mounted {
+            view: None,
+            deleted: false,
+        }));
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                container.raw().as_ptr(),
+                Some(container_deleted::<V>),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                mounted as *mut c_void,
+            );
+        }
+        Ok(Self {
+            container,
+            mounted: NonNull::new(mounted).unwrap(),
+        })
+    }
+
+    /// Returns the container views are mounted in.
+    pub fn container(&mut self) -> &mut Obj<'a> {
+        &mut self.container
+    }
+
+    /// Creates a `V` in the slot and shows it, unmounting the view mounted
+    /// so far. If creating the view fails, the widgets it created are
+    /// deleted.
+    pub fn mount(&mut self) -> LvResult<&mut V> {
+        self.unmount();
+        let mut view = match V::create(&mut self.container) {
+            Ok(view) => view,
+            Err(err) => {
+                unsafe { lvgl_sys::lv_obj_clean(self.container.raw().as_mut()) }
+                return Err(err);
+            }
+        };
+        self.container.clear_flag(ObjFlag::HIDDEN);
+        view.on_show();
+        Ok(self.mounted().view.insert(view))
+    }
+
+    /// Hides and destroys the mounted view, if any, and deletes its widgets.
+    pub fn unmount(&mut self) {
+        if let Some(view) = self.mounted().view.take() {
+            destroy(self.container.raw().as_ptr(), view);
+            unsafe { lvgl_sys::lv_obj_clean(self.container.raw().as_mut()) }
+        }
+    }
+
+    /// Shows the slot, and the mounted view if it was hidden.
+    pub fn show(&mut self) {
+        if self.container.has_flag(ObjFlag::HIDDEN) {
+            self.container.clear_flag(ObjFlag::HIDDEN);
+            if let Some(view) = &mut self.mounted().view {
+                view.on_show();
+            }
+        }
+    }
+
+    /// Hides the slot, keeping the mounted view to show again later.
+    pub fn hide(&mut self) {
+        if !self.container.has_flag(ObjFlag::HIDDEN) {
+            self.container.add_flag(ObjFlag::HIDDEN);
+            if let Some(view) = &mut self.mounted().view {
+                view.on_hide();
+            }
+        }
+    }
+
+    /// Returns the mounted view, if any.
+    pub fn view(&self) -> Option<&V> {
+        unsafe { (*self.mounted.as_ptr()).view.as_ref() }
+    }
+
+    /// Returns the mounted view, if any, e.g. to update its widgets.
+    pub fn view_mut(&mut self) -> Option<&mut V> {
+        self.mounted().view.as_mut()
+    }
+
+    fn mounted(&mut self) -> &mut Mounted<V> {
+        unsafe { self.mounted.as_mut() }
+    }
+}
+
+impl<'a, V: View<'a>> Drop for ViewSlot<'a, V> {
+    fn drop(&mut self) {
+        // The container is gone if its screen was deleted, e.g. popped, in
+        // which case its delete callback already destroyed the view
+        if !self.mounted().deleted {
+            unsafe {
+                lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    self.container.raw().as_ptr(),
+                    Some(container_deleted::<V>),
+                    self.mounted.as_ptr() as *mut c_void,
+                );
+            }
+            self.unmount();
+        }
+        drop(unsafe { Box::from_raw(self.mounted.as_ptr()) });
+    }
+}
+
+/// Runs the hooks of a view being unmounted from `container`, and drops it.
+fn destroy<'a, V: View<'a>>(container: *mut lvgl_sys::lv_obj_t, mut view: V) {
+    let hidden = unsafe { lvgl_sys::lv_obj_has_flag(container, lvgl_sys::LV_OBJ_FLAG_HIDDEN as _) };
+    if !hidden {
+        view.on_hide();
+    }
+    view.on_destroy();
+}
+
+/// Destroys the mounted view when the container is deleted, before its
+/// children, i.e. the view's widgets, are.
+unsafe extern "C" fn container_deleted<'a, V: View<'a>>(event: *mut lvgl_sys::lv_event_t) {
+    guard("view slot", || {
+        let mounted = lvgl_sys::lv_event_get_user_data(event) as *mut Mounted<V>;
+        (*mounted).deleted = true;
+        if let Some(view) = (*mounted).view.take() {
+            destroy((*event).current_target, view);
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Label;
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static HOOKS: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+    }
+
+    fn hook(name: &'static str) {
+        HOOKS.with(|hooks| hooks.borrow_mut().push(name));
+    }
+
+    fn take_hooks() -> Vec<&'static str> {
+        HOOKS.with(|hooks| hooks.take())
+    }
+
+    struct Status<'a> {
+        _text: Label<'a>,
+    }
+
+    impl<'a> View<'a> for Status<'a> {
+        fn create(parent: &mut Obj<'a>) -> LvResult<Self> {
+            hook("create");
+            Ok(Self {
+                _text: Label::create(parent)?,
+            })
+        }
+
+        fn on_show(&mut self) {
+            hook("show");
+        }
+
+        fn on_hide(&mut self) {
+            hook("hide");
+        }
+
+        fn on_destroy(&mut self) {
+            hook("destroy");
+        }
+    }
+
+    #[test]
+    fn mount_and_unmount() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut slot = ViewSlot::<Status>::new(&mut screen).unwrap();
+        let container = slot.container().raw().as_ptr();
+        let children = || unsafe { lvgl_sys::lv_obj_get_child_cnt(container) };
+        take_hooks();
+
+        slot.mount().unwrap();
+        assert_eq!(take_hooks(), ["create", "show"]);
+        assert_eq!(children(), 1);
+        slot.hide();
+        slot.hide();
+        slot.show();
+        assert_eq!(take_hooks(), ["hide", "show"]);
+
+        slot.mount().unwrap();
+        assert_eq!(take_hooks(), ["hide", "destroy", "create", "show"]);
+        assert_eq!(children(), 1);
+        slot.hide();
+        slot.unmount();
+        assert_eq!(take_hooks(), ["hide", "destroy"]);
+        assert_eq!(children(), 0);
+        assert!(slot.view().is_none());
+
+        slot.mount().unwrap();
+        drop(slot);
+        assert_eq!(take_hooks(), ["create", "show", "hide", "destroy"]);
+        assert_eq!(children(), 0);
+    }
+
+    #[test]
+    fn container_deleted_first() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut parent = Obj::create(&mut screen).unwrap();
+        let mut slot = ViewSlot::<Status>::new(&mut parent).unwrap();
+        slot.mount().unwrap();
+        take_hooks();
+
+        unsafe { lvgl_sys::lv_obj_del(parent.raw().as_ptr()) };
+        assert_eq!(take_hooks(), ["hide", "destroy"]);
+        assert!(slot.view().is_none());
+        drop(slot);
+        assert!(take_hooks().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+//! An HTML canvas backend for running LVGL in a browser
+//!
+//! [`Canvas`] registers an HTML `<canvas>` element as an LVGL display, and
+//! feeds mouse and touch input on it back as a pointer device. With
+//! [`Canvas::run()`], LVGL is then driven from the browser's animation
+//! frames:
+//!
+//! ```ignore
+//! use lvgl::web::Canvas;
+//! use lvgl::DrawBuffer;
+//! use wasm_bindgen::prelude::*;
+//! use wasm_bindgen::JsCast;
+//!
+//! #[wasm_bindgen(start)]
+//! pub fn start() -> Result<(), JsValue> {
+//!     let canvas = web_sys::window()
+//!         .and_then(|w| w.document())
+//!         .and_then(|d| d.get_element_by_id("lvgl"))
+//!         .ok_or("no canvas")?
+//!         .dyn_into::<web_sys::HtmlCanvasElement>()?;
+//!     let canvas = Canvas::attach(canvas, DrawBuffer::<{ 320 * 24 }>::default())?;
+//!     let mut screen = canvas.display.get_scr_act().unwrap();
+//!     // Create widgets on `screen`...
+//!     canvas.run();
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The display resolution is the `width` and `height` of the canvas element;
+//! scaling it with CSS is supported.
+
+use crate::input_device::pointer::{Pointer, PointerInputData};
+use crate::input_device::{InputDriver, InputState};
+use crate::{Display, DrawBuffer, Point};
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::time::Duration;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData, PointerEvent};
+
+/// An HTML canvas registered as an LVGL display with pointer input.
+pub struct Canvas {
+    /// The display drawing to the canvas.
+    pub display: Display,
+    canvas: HtmlCanvasElement,
+    _pointer: Pointer,
+    listeners: Vec<(&'static str, Closure<dyn FnMut(PointerEvent)>)>,
+}
+
+impl Canvas {
+    /// Registers `canvas` as a display, at the resolution given by its
+    /// `width` and `height` attributes, and listens to pointer events on it.
+    pub fn attach<const N: usize>(
+        canvas: HtmlCanvasElement,
+        draw_buffer: DrawBuffer<N>,
+    ) -> Result<Self, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or("Canvas has no 2D context")?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+
+        let mut rgba = Vec::new();
+        let display = Display::register(draw_buffer, canvas.width(), canvas.height(), {
+            move |refresh| {
+                let area = &refresh.area;
+                let width = (area.x2 - area.x1 + 1) as u32;
+                let height = (area.y2 - area.y1 + 1) as u32;
+                rgba.clear();
+                for color in &refresh.colors[..(width * height) as usize] {
+                    let argb = color.to_u32();
+                    rgba.extend_from_slice(&[
+                        (argb >> 16) as u8,
+                        (argb >> 8) as u8,
+                        argb as u8,
+                        0xFF,
+                    ]);
+                }
+                if let Ok(image) =
+                    ImageData::new_with_u8_clamped_array_and_sh(Clamped(&rgba), width, height)
+                {
+                    let _ = context.put_image_data(&image, area.x1 as f64, area.y1 as f64);
+                }
+            }
+        })
+        .map_err(|e| JsValue::from_str(&format!("{}", e)))?;
+
+        let state = Rc::new(Cell::new(
+            PointerInputData::Touch(Point::new(0, 0)).released(),
+        ));
+        let read_state = state.clone();
+        let pointer = Pointer::register(move || read_state.get().once(), &display)
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))?;
+
+        let mut listeners = Vec::new();
+        for (event, pressed) in [
+            ("pointerdown", Some(true)),
+            ("pointermove", None),
+            ("pointerup", Some(false)),
+            ("pointercancel", Some(false)),
+        ] {
+            let state = state.clone();
+            let target = canvas.clone();
+            let listener = Closure::<dyn FnMut(PointerEvent)>::new(move |e: PointerEvent| {
+                let point = canvas_point(&target, &e);
+                let pressed = pressed.unwrap_or(matches!(state.get(), InputState::Pressed(_)));
+                state.set(if pressed {
+                    PointerInputData::Touch(point).pressed()
+                } else {
+                    PointerInputData::Touch(point).released()
+                });
+                e.prevent_default();
+            });
+            canvas.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())?;
+            listeners.push((event, listener));
+        }
+
+        Ok(Self {
+            display,
+            canvas,
+            _pointer: pointer,
+            listeners,
+        })
+    }
+
+    /// Drives LVGL from the browser's animation frames for as long as the
+    /// page is open. This consumes the canvas, which is kept alive by the
+    /// animation loop.
+    pub fn run(self) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let performance = window.performance();
+        let now = move || performance.as_ref().map_or(0.0, |p| p.now());
+
+        let frame: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+        let next_frame = frame.clone();
+        let last = Cell::new(now());
+        *frame.borrow_mut() = Some(Closure::new(move || {
+            let _canvas = &self;
+            let t = now();
+            #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+            crate::tick_inc(Duration::from_secs_f64((t - last.get()).max(0.0) / 1000.0));
+            last.set(t);
+            // Nothing else runs LVGL in the browser, so this cannot be re-entered
+            crate::task_handler().unwrap();
+            if let Some(callback) = next_frame.borrow().as_ref() {
+                request_frame(callback);
+            }
+        }));
+        if let Some(callback) = frame.borrow().as_ref() {
+            request_frame(callback);
+        }
+    }
+}
+
+fn request_frame(callback: &Closure<dyn FnMut()>) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+    }
+}
+
+/// Converts the position of a pointer event to canvas pixels, accounting for
+/// CSS scaling of the canvas.
+fn canvas_point(canvas: &HtmlCanvasElement, e: &PointerEvent) -> Point {
+    let scale_x = canvas.width() as f64 / canvas.client_width().max(1) as f64;
+    let scale_y = canvas.height() as f64 / canvas.client_height().max(1) as f64;
+    Point::new(
+        (e.offset_x() as f64 * scale_x) as i32,
+        (e.offset_y() as f64 * scale_y) as i32,
+    )
+}
+
+impl Drop for Canvas {
+    fn drop(&mut self) {
+        for (event, listener) in &self.listeners {
+            let _ = self
+                .canvas
+                .remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+        }
+    }
+}
@@ -1,7 +1,68 @@
+use super::bar::{anim_time, millis};
 use crate::lv_core::obj::NativeObject;
 use crate::widgets::Arc;
+use crate::{Builder, ObjFlag, Part, Widget};
+use core::mem::MaybeUninit;
+use core::time::Duration;
 
 impl Arc<'_> {
+    /// Sets whether the arc can only be pressed on its ring, extended by
+    /// `Widget::set_ext_click_area()`, rather than anywhere in its bounding
+    /// box. This lets widgets placed inside the arc receive the presses
+    /// instead.
+    pub fn set_ring_hit_test(&mut self, enabled: bool) {
+        if enabled {
+            self.add_flag(ObjFlag::ADV_HITTEST)
+        } else {
+            self.clear_flag(ObjFlag::ADV_HITTEST)
+        }
+    }
+
+    /// Sets the arc's value, clamped to its range.
+    pub fn set_value(&mut self, value: i16) {
+        unsafe { lvgl_sys::lv_arc_set_value(self.raw().as_mut(), value) }
+    }
+
+    /// Sets how long `animate_value()` takes to sweep to a new value. This is
+    /// the `anim_time` style property of the arc's main part.
+    pub fn set_anim_time(&mut self, time: Duration) {
+        self.set_style_anim_time(Part::Main, millis(time));
+    }
+
+    /// Gets how long `animate_value()` takes to sweep to a new value.
+    pub fn get_anim_time(&self) -> Duration {
+        anim_time(self)
+    }
+
+    /// Sets the arc's value, sweeping to it over the arc's animation time
+    /// rather than jumping as `set_value()` does. Any previous sweep is
+    /// cancelled.
+    pub fn animate_value(&mut self, value: i16) {
+        unsafe {
+            let obj = self.raw().as_ptr();
+            lvgl_sys::lv_anim_del(obj as *mut _, Some(arc_value_anim));
+            let time = millis(self.get_anim_time());
+            // A new arc's value is unset, below its minimum
+            let current = lvgl_sys::lv_arc_get_value(obj).max(lvgl_sys::lv_arc_get_min_value(obj));
+            if time == 0 || current == value {
+                lvgl_sys::lv_arc_set_value(obj, value);
+                return;
+            }
+
+            let mut anim = MaybeUninit::<lvgl_sys::lv_anim_t>::uninit();
+            lvgl_sys::lv_anim_init(anim.as_mut_ptr());
+            let mut anim = anim.assume_init();
+            anim.var = obj as *mut _;
+            anim.exec_cb = Some(arc_value_anim);
+            anim.start_value = current.into();
+            anim.current_value = current.into();
+            anim.end_value = value.into();
+            anim.time = time;
+            // LVGL copies the animation and deletes it with the arc
+            lvgl_sys::lv_anim_start(&anim);
+        }
+    }
+
     // /// Set the start angle, for the given arc part.
     // /// 0 degrees for the right, 90 degrees for the bottom, etc.
     // pub fn set_start_angle(&mut self, angle: u16, part: ArcPart) -> LvResult<()> {
@@ -38,6 +99,78 @@ impl Arc<'_> {
     //     Ok(())
     // }
 }
+
+unsafe extern "C" fn arc_value_anim(obj: *mut core::ffi::c_void, value: i32) {
+    lvgl_sys::lv_arc_set_value(obj as *mut _, value as i16);
+}
+
+impl<'a, S: FnOnce(&mut Arc<'a>)> Builder<Arc<'a>, S> {
+    /// Sets how long `animate_value()` takes. See `Arc::set_anim_time()`.
+    pub fn anim_time(self, time: Duration) -> Builder<Arc<'a>, impl FnOnce(&mut Arc<'a>)> {
+        self.with(move |arc| arc.set_anim_time(time))
+    }
+
+    /// Limits presses to the arc's ring. See `Arc::set_ring_hit_test()`.
+    pub fn ring_hit_test(self, enabled: bool) -> Builder<Arc<'a>, impl FnOnce(&mut Arc<'a>)> {
+        self.with(move |arc| arc.set_ring_hit_test(enabled))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::Arc;
+    use crate::{tests, NativeObject, Widget};
+    use core::time::Duration;
+
+    #[test]
+    fn ring_click_area() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut arc = Arc::builder()
+            .size(100, 100)
+            .pos(0, 0)
+            .ext_click_area(8)
+            .ring_hit_test(true)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(arc.get_ext_click_area(), 8);
+
+        let raw = arc.raw().as_ptr();
+        let hit = |x, y| unsafe {
+            lvgl_sys::lv_obj_update_layout(raw);
+            lvgl_sys::lv_obj_hit_test(raw, &lvgl_sys::lv_point_t { x, y })
+        };
+        assert!(hit(50, 2));
+        assert!(hit(50, -4));
+        assert!(!hit(50, 50));
+
+        arc.set_ring_hit_test(false);
+        assert!(hit(50, 50));
+    }
+
+    #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+    #[test]
+    fn animated_value() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut arc = Arc::builder()
+            .anim_time(Duration::from_millis(100))
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(arc.get_anim_time(), Duration::from_millis(100));
+        let value = |arc: &Arc| unsafe { lvgl_sys::lv_arc_get_value(arc.raw().as_ptr()) };
+
+        arc.animate_value(80);
+        crate::task_handler().unwrap();
+        assert_eq!(value(&arc), 0);
+        crate::tick_inc_ms(50);
+        crate::task_handler().unwrap();
+        assert!((1..80).contains(&value(&arc)));
+        crate::tick_inc_ms(60);
+        crate::task_handler().unwrap();
+        assert_eq!(value(&arc), 80);
+    }
+}
 /*
 /// The different parts, of an arc object.
 #[derive(Debug, Copy, Clone, PartialEq)]
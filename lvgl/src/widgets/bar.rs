@@ -1,6 +1,7 @@
 use crate::support::AnimationState;
 use crate::widgets::Bar;
-use crate::NativeObject;
+use crate::{Builder, NativeObject, Part, Widget};
+use core::time::Duration;
 
 impl Bar<'_> {
     /// Set minimum and the maximum values of the bar
@@ -17,6 +18,49 @@ impl Bar<'_> {
             lvgl_sys::lv_bar_set_value(self.core.raw().as_mut(), value, anim.into());
         }
     }
+
+    /// Sets how long the bar takes to sweep to a new value set with
+    /// `AnimationState::ON`, instead of the theme's default. This is the
+    /// `anim_time` style property of the bar's main part.
+    pub fn set_anim_time(&mut self, time: Duration) {
+        self.set_style_anim_time(Part::Main, millis(time));
+    }
+
+    /// Gets how long the bar takes to sweep to a new value.
+    pub fn get_anim_time(&self) -> Duration {
+        anim_time(self)
+    }
+}
+
+/// Converts a duration to LVGL's milliseconds, saturating.
+pub(crate) fn millis(time: Duration) -> u32 {
+    time.as_millis().try_into().unwrap_or(u32::MAX)
+}
+
+/// Returns the `anim_time` style property of an object's main part.
+pub(crate) fn anim_time(obj: &impl NativeObject) -> Duration {
+    let time = unsafe {
+        lvgl_sys::lv_obj_get_style_prop(
+            obj.raw().as_ref(),
+            Part::Main.into(),
+            lvgl_sys::lv_style_prop_t_LV_STYLE_ANIM_TIME,
+        )
+        .num
+    };
+    Duration::from_millis(time as u64)
+}
+
+impl<'a, S: FnOnce(&mut Bar<'a>)> Builder<Bar<'a>, S> {
+    /// Sets the bar's value, without animation.
+    pub fn value(self, value: i32) -> Builder<Bar<'a>, impl FnOnce(&mut Bar<'a>)> {
+        self.with(move |bar| bar.set_value(value, AnimationState::OFF))
+    }
+
+    /// Sets how long value changes take to animate. See
+    /// `Bar::set_anim_time()`.
+    pub fn anim_time(self, time: Duration) -> Builder<Bar<'a>, impl FnOnce(&mut Bar<'a>)> {
+        self.with(move |bar| bar.set_anim_time(time))
+    }
 }
 /*
 /// The different parts, of a bar object.
@@ -0,0 +1,125 @@
+use crate::guard::guard;
+use crate::widgets::Calendar;
+use crate::{Box, LvError, LvResult, NativeObject};
+use core::{mem, ptr};
+
+/// A day of a calendar. Equivalent to an `lv_calendar_date_t`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CalendarDate {
+    /// The year, e.g. 2023.
+    pub year: u16,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1 to 31.
+    pub day: u8,
+}
+
+impl CalendarDate {
+    /// Creates a date from its year, month and day.
+    pub const fn new(year: u16, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+}
+
+impl Calendar<'_> {
+    /// Highlights `dates` on the calendar, e.g. to mark events, replacing any
+    /// previously highlighted dates. The dates are copied, so they can be
+    /// computed at runtime and dropped afterwards.
+    pub fn set_highlighted_dates(&mut self, dates: &[CalendarDate]) -> LvResult<()> {
+        let count = u16::try_from(dates.len()).map_err(|_| LvError::InvalidReference)?;
+        unsafe {
+            let calendar = self.raw().as_ptr();
+            let copy = if dates.is_empty() {
+                ptr::null_mut()
+            } else {
+                let copy = crate::storage::alloc(mem::size_of_val(dates)) as *mut CalendarDate;
+                if copy.is_null() {
+                    return Err(LvError::LvOOMemory);
+                }
+                ptr::copy_nonoverlapping(dates.as_ptr(), copy, dates.len());
+                copy
+            };
+            lvgl_sys::lv_calendar_set_highlighted_dates(calendar, copy as *mut _, count);
+
+            let owned =
+                lvgl_sys::lv_obj_get_event_user_data(calendar, Some(highlighted_dates_callback))
+                    as *mut HighlightedDates;
+            if owned.is_null() {
+                lvgl_sys::lv_obj_add_event_cb(
+                    calendar,
+                    Some(highlighted_dates_callback),
+                    lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                    Box::into_raw(Box::new(HighlightedDates(copy))) as *mut _,
+                );
+            } else {
+                crate::storage::free((*owned).0 as *mut _);
+                (*owned).0 = copy;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the highlighted dates.
+    pub fn get_highlighted_dates(&self) -> &[CalendarDate] {
+        unsafe {
+            let calendar = self.raw().as_ptr();
+            let dates = lvgl_sys::lv_calendar_get_highlighted_dates(calendar);
+            if dates.is_null() {
+                return &[];
+            }
+            let count = lvgl_sys::lv_calendar_get_highlighted_dates_num(calendar);
+            core::slice::from_raw_parts(dates as *const CalendarDate, count.into())
+        }
+    }
+}
+
+/// The copy of the highlighted dates a calendar owns, freed with it.
+struct HighlightedDates(*mut CalendarDate);
+
+unsafe extern "C" fn highlighted_dates_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("calendar highlighted dates", || {
+        let owned = Box::from_raw((*event).user_data as *mut HighlightedDates);
+        crate::storage::free(owned.0 as *mut _);
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+
+    #[test]
+    fn highlight_computed_dates() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut calendar = Calendar::create(&mut screen).unwrap();
+        assert!(calendar.get_highlighted_dates().is_empty());
+
+        let events: Vec<_> = [3, 14, 28]
+            .iter()
+            .map(|&day| CalendarDate::new(2023, 6, day))
+            .collect();
+        calendar.set_highlighted_dates(&events).unwrap();
+        drop(events);
+        assert_eq!(
+            calendar.get_highlighted_dates(),
+            [
+                CalendarDate::new(2023, 6, 3),
+                CalendarDate::new(2023, 6, 14),
+                CalendarDate::new(2023, 6, 28)
+            ]
+        );
+
+        calendar
+            .set_highlighted_dates(&[CalendarDate::new(2023, 7, 1)])
+            .unwrap();
+        assert_eq!(
+            calendar.get_highlighted_dates(),
+            [CalendarDate::new(2023, 7, 1)]
+        );
+        calendar.set_highlighted_dates(&[]).unwrap();
+        assert!(calendar.get_highlighted_dates().is_empty());
+        unsafe { lvgl_sys::lv_obj_del(calendar.raw().as_ptr()) };
+    }
+}
@@ -0,0 +1,283 @@
+use crate::misc::image::{ColorFormat, ImageDsc};
+use crate::style::Opacity;
+use crate::widgets::Canvas;
+use crate::{Area, Color, LvError, LvResult, NativeObject};
+use core::ptr;
+
+/// How an image is rotated, zoomed and placed by `Canvas::transform()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanvasTransform {
+    /// The rotation, in tenths of a degree clockwise.
+    pub angle: i16,
+    /// The zoom factor, where 256 is the original size and 512 twice as big.
+    pub zoom: u16,
+    /// The X position of the image's top left corner on the canvas.
+    pub offset_x: i16,
+    /// The Y position of the image's top left corner on the canvas.
+    pub offset_y: i16,
+    /// The X coordinate in the image it is rotated and zoomed around.
+    pub pivot_x: i32,
+    /// The Y coordinate in the image it is rotated and zoomed around.
+    pub pivot_y: i32,
+    /// Whether to smooth the edges of the rotated or zoomed image.
+    pub antialias: bool,
+}
+
+impl Default for CanvasTransform {
+    fn default() -> Self {
+        Self {
+            angle: 0,
+            zoom: 256,
+            offset_x: 0,
+            offset_y: 0,
+            pivot_x: 0,
+            pivot_y: 0,
+            antialias: true,
+        }
+    }
+}
+
+impl<'a> Canvas<'a> {
+    /// Sets the buffer the canvas draws into and shows, `width` by `height`
+    /// pixels in the display's color format. LVGL keeps drawing from it for as
+    /// long as the canvas exists, so it must be `'static`, e.g. a `static` or
+    /// a leaked allocation.
+    pub fn set_buffer(
+        &mut self,
+        buf: &'static mut [Color],
+        width: u16,
+        height: u16,
+    ) -> LvResult<()> {
+        if buf.len() < width as usize * height as usize {
+            return Err(LvError::InvalidReference);
+        }
+        unsafe {
+            lvgl_sys::lv_canvas_set_buffer(
+                self.raw().as_mut(),
+                buf.as_mut_ptr() as *mut _,
+                width as _,
+                height as _,
+                lvgl_sys::LV_IMG_CF_TRUE_COLOR as _,
+            )
+        }
+        Ok(())
+    }
+
+    /// Sets the buffer the canvas draws into and shows, `width` by `height`
+    /// pixels in the given format. Its size must be at least
+    /// `ImageDsc::data_size()`, and it must be `'static`, as for
+    /// `set_buffer()`.
+    ///
+    /// Indexed and alpha formats take far less memory than the display's
+    /// color format, e.g. 1 bit per pixel for a two-color drawing surface:
+    ///
+    /// ```ignore
+    /// static mut BUF: [u8; 2 * 4 + 240 * 240 / 8] = [0; 2 * 4 + 240 * 240 / 8];
+    ///
+    /// let buf = unsafe { &mut *ptr::addr_of_mut!(BUF) };
+    /// canvas.set_buffer_cf(buf, 240, 240, ColorFormat::Indexed1Bit)?;
+    /// canvas.set_palette(0, Color::BLACK)?;
+    /// canvas.set_palette(1, Color::WHITE)?;
+    /// ```
+    pub fn set_buffer_cf(
+        &mut self,
+        buf: &'static mut [u8],
+        width: u16,
+        height: u16,
+        cf: ColorFormat,
+    ) -> LvResult<()> {
+        if buf.len() < ImageDsc::data_size(width.into(), height.into(), cf) {
+            return Err(LvError::InvalidReference);
+        }
+        unsafe {
+            lvgl_sys::lv_canvas_set_buffer(
+                self.raw().as_mut(),
+                buf.as_mut_ptr() as *mut _,
+                width as _,
+                height as _,
+                cf.into(),
+            )
+        }
+        Ok(())
+    }
+
+    /// Sets the color of palette entry `id` of a canvas with an indexed
+    /// format. Fails if the canvas is not indexed, or `id` is beyond its
+    /// palette.
+    pub fn set_palette(&mut self, id: u8, color: Color) -> LvResult<()> {
+        let colors = match self.get_img().raw.header.cf() {
+            lvgl_sys::LV_IMG_CF_INDEXED_1BIT => 2,
+            lvgl_sys::LV_IMG_CF_INDEXED_2BIT => 4,
+            lvgl_sys::LV_IMG_CF_INDEXED_4BIT => 16,
+            lvgl_sys::LV_IMG_CF_INDEXED_8BIT => 256,
+            _ => 0,
+        };
+        if u32::from(id) >= colors {
+            return Err(LvError::InvalidReference);
+        }
+        unsafe { lvgl_sys::lv_canvas_set_palette(self.raw().as_mut(), id, color.raw) }
+        Ok(())
+    }
+
+    /// Sets a pixel of a canvas with an indexed format to palette entry
+    /// `index`.
+    pub fn set_px_index(&mut self, x: i16, y: i16, index: u8) {
+        let color = lvgl_sys::lv_color_t { full: index as _ };
+        unsafe { lvgl_sys::lv_canvas_set_px_color(self.raw().as_mut(), x, y, color) }
+    }
+
+    /// Returns the palette entry of a pixel of a canvas with an indexed
+    /// format.
+    pub fn get_px_index(&self, x: i16, y: i16) -> u8 {
+        // LVGL returns the index itself for indexed formats
+        unsafe { lvgl_sys::lv_canvas_get_px(self.raw().as_ptr(), x, y).full as u8 }
+    }
+
+    /// Sets the opacity of a pixel of a canvas with an alpha format, or a
+    /// true color format with alpha.
+    pub fn set_px_opa(&mut self, x: i16, y: i16, opa: u8) {
+        unsafe { lvgl_sys::lv_canvas_set_px_opa(self.raw().as_mut(), x, y, opa) }
+    }
+
+    /// Returns the canvas's buffer as an image, e.g. to transform it onto
+    /// another canvas.
+    pub fn get_img(&self) -> ImageDsc<'_> {
+        unsafe { ImageDsc::from_raw(*lvgl_sys::lv_canvas_get_img(self.raw().as_ptr())) }
+    }
+
+    /// Fills the whole canvas with a color.
+    pub fn fill_bg(&mut self, color: Color, opa: Opacity) {
+        unsafe { lvgl_sys::lv_canvas_fill_bg(self.raw().as_mut(), color.raw, opa.into()) }
+    }
+
+    /// Returns the color of a pixel of the canvas.
+    pub fn get_px(&self, x: i16, y: i16) -> Color {
+        Color::from_raw(unsafe { lvgl_sys::lv_canvas_get_px(self.raw().as_ptr(), x, y) })
+    }
+
+    /// Draws `image` rotated and zoomed onto the canvas, e.g. to stamp a
+    /// rotated icon. The image must be in a true color format, and cannot be
+    /// the canvas's own buffer.
+    pub fn transform(&mut self, image: &ImageDsc<'_>, transform: CanvasTransform) {
+        unsafe {
+            lvgl_sys::lv_canvas_transform(
+                self.raw().as_mut(),
+                &image.raw as *const _ as *mut _,
+                transform.angle,
+                transform.zoom,
+                transform.offset_x,
+                transform.offset_y,
+                transform.pivot_x,
+                transform.pivot_y,
+                transform.antialias,
+            )
+        }
+    }
+
+    /// Blurs an area of the canvas, or all of it, horizontally by `radius`
+    /// pixels.
+    pub fn blur_hor(&mut self, area: Option<Area>, radius: u16) {
+        let area = area.map(lvgl_sys::lv_area_t::from);
+        unsafe {
+            lvgl_sys::lv_canvas_blur_hor(
+                self.raw().as_mut(),
+                area.as_ref().map_or(ptr::null(), |area| area as *const _),
+                radius,
+            )
+        }
+    }
+
+    /// Blurs an area of the canvas, or all of it, vertically by `radius`
+    /// pixels. Blur both ways for a blurred background:
+    ///
+    /// ```ignore
+    /// canvas.blur_hor(None, 8);
+    /// canvas.blur_ver(None, 8);
+    /// ```
+    pub fn blur_ver(&mut self, area: Option<Area>, radius: u16) {
+        let area = area.map(lvgl_sys::lv_area_t::from);
+        unsafe {
+            lvgl_sys::lv_canvas_blur_ver(
+                self.raw().as_mut(),
+                area.as_ref().map_or(ptr::null(), |area| area as *const _),
+                radius,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+
+    #[test]
+    fn stamp_and_blur() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let stamp_buf = vec![Color::default(); 4 * 4].leak();
+        let mut stamp = Canvas::create(&mut screen).unwrap();
+        stamp.set_buffer(stamp_buf, 4, 4).unwrap();
+        stamp.fill_bg(Color::WHITE, Opacity::OPA_COVER);
+
+        let small = vec![Color::default(); 8].leak();
+        let buf = vec![Color::default(); 20 * 20].leak();
+        let mut canvas = Canvas::create(&mut screen).unwrap();
+        assert!(canvas.set_buffer(small, 20, 20).is_err());
+        canvas.set_buffer(buf, 20, 20).unwrap();
+        canvas.fill_bg(Color::BLACK, Opacity::OPA_COVER);
+        let transform = CanvasTransform {
+            offset_x: 8,
+            offset_y: 8,
+            antialias: false,
+            ..Default::default()
+        };
+        canvas.transform(&stamp.get_img(), transform);
+        assert_eq!(canvas.get_px(9, 9).to_u32(), Color::WHITE.to_u32());
+        assert_eq!(canvas.get_px(2, 2).to_u32(), Color::BLACK.to_u32());
+
+        let area = Area {
+            x1: 0,
+            y1: 0,
+            x2: 19,
+            y2: 19,
+        };
+        canvas.blur_hor(Some(area), 4);
+        canvas.blur_ver(None, 4);
+        let edge = canvas.get_px(8, 9);
+        assert!(edge.r() > 0 && edge.to_u32() != Color::WHITE.to_u32());
+    }
+
+    #[test]
+    fn low_memory_formats() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let buf = vec![0; 2 * 4 + 16 * 16 / 8].leak();
+        let mut canvas = Canvas::create(&mut screen).unwrap();
+        canvas
+            .set_buffer_cf(buf, 16, 16, ColorFormat::Indexed1Bit)
+            .unwrap();
+        canvas.set_palette(0, Color::BLACK).unwrap();
+        canvas.set_palette(1, Color::WHITE).unwrap();
+        assert!(canvas.set_palette(2, Color::RED).is_err());
+        canvas.set_px_index(3, 3, 1);
+        assert_eq!(canvas.get_px_index(3, 3), 1);
+        assert_eq!(canvas.get_px_index(4, 3), 0);
+
+        let small = vec![0; 16 * 16 - 1].leak();
+        let alpha_buf = vec![0; 16 * 16].leak();
+        let mut mask = Canvas::create(&mut screen).unwrap();
+        assert!(mask
+            .set_buffer_cf(small, 16, 16, ColorFormat::Alpha8Bit)
+            .is_err());
+        mask.set_buffer_cf(alpha_buf, 16, 16, ColorFormat::Alpha8Bit)
+            .unwrap();
+        assert!(mask.set_palette(0, Color::BLACK).is_err());
+        mask.set_px_opa(1, 1, 128);
+        let img = mask.get_img();
+        assert_eq!(
+            unsafe { lvgl_sys::lv_img_buf_get_px_alpha(&img.raw as *const _ as *mut _, 1, 1) },
+            128
+        );
+    }
+}
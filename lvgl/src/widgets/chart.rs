@@ -0,0 +1,163 @@
+use crate::widgets::Chart;
+use crate::{Builder, NativeObject};
+
+/// An axis of a chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartAxis {
+    /// The Y axis on the left.
+    PrimaryY,
+    /// The Y axis on the right.
+    SecondaryY,
+    /// The X axis at the bottom.
+    PrimaryX,
+    /// The X axis at the top.
+    SecondaryX,
+}
+
+impl From<ChartAxis> for lvgl_sys::lv_chart_axis_t {
+    fn from(axis: ChartAxis) -> Self {
+        let native = match axis {
+            ChartAxis::PrimaryY => lvgl_sys::LV_CHART_AXIS_PRIMARY_Y,
+            ChartAxis::SecondaryY => lvgl_sys::LV_CHART_AXIS_SECONDARY_Y,
+            ChartAxis::PrimaryX => lvgl_sys::LV_CHART_AXIS_PRIMARY_X,
+            ChartAxis::SecondaryX => lvgl_sys::LV_CHART_AXIS_SECONDARY_X,
+        };
+        native as _
+    }
+}
+
+/// How new points are added to a chart that is already full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartUpdateMode {
+    /// The old points shift left, so the chart scrolls.
+    Shift,
+    /// New points overwrite the oldest ones from the left, like an
+    /// oscilloscope sweep.
+    Circular,
+}
+
+impl From<ChartUpdateMode> for lvgl_sys::lv_chart_update_mode_t {
+    fn from(mode: ChartUpdateMode) -> Self {
+        let native = match mode {
+            ChartUpdateMode::Shift => lvgl_sys::LV_CHART_UPDATE_MODE_SHIFT,
+            ChartUpdateMode::Circular => lvgl_sys::LV_CHART_UPDATE_MODE_CIRCULAR,
+        };
+        native as _
+    }
+}
+
+/// The ticks and labels drawn along a chart axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AxisTicks {
+    /// The length of the major ticks, in pixels.
+    pub major_len: i16,
+    /// The length of the minor ticks, in pixels.
+    pub minor_len: i16,
+    /// The number of major ticks.
+    pub major_cnt: i16,
+    /// The number of minor ticks between two major ticks.
+    pub minor_cnt: i16,
+    /// Whether to label the major ticks with their values.
+    pub labels: bool,
+    /// The space reserved for the ticks and labels, in pixels.
+    pub draw_size: i16,
+}
+
+impl Default for AxisTicks {
+    fn default() -> Self {
+        Self {
+            major_len: 10,
+            minor_len: 5,
+            major_cnt: 5,
+            minor_cnt: 2,
+            labels: true,
+            draw_size: 50,
+        }
+    }
+}
+
+impl Chart<'_> {
+    /// Sets the range of values of an axis. By default, both Y axes range
+    /// from 0 to 100.
+    pub fn set_range(&mut self, axis: ChartAxis, min: i16, max: i16) {
+        unsafe { lvgl_sys::lv_chart_set_range(self.raw().as_mut(), axis.into(), min, max) }
+    }
+
+    /// Sets the ticks and labels drawn along an axis. The chart's parent must
+    /// leave room for them, as they are drawn outside the chart.
+    pub fn set_axis_tick(&mut self, axis: ChartAxis, ticks: AxisTicks) {
+        unsafe {
+            lvgl_sys::lv_chart_set_axis_tick(
+                self.raw().as_mut(),
+                axis.into(),
+                ticks.major_len,
+                ticks.minor_len,
+                ticks.major_cnt,
+                ticks.minor_cnt,
+                ticks.labels,
+                ticks.draw_size,
+            )
+        }
+    }
+
+    /// Sets how new points are added once the chart is full.
+    pub fn set_update_mode(&mut self, mode: ChartUpdateMode) {
+        unsafe { lvgl_sys::lv_chart_set_update_mode(self.raw().as_mut(), mode.into()) }
+    }
+}
+
+impl<'a, S: FnOnce(&mut Chart<'a>)> Builder<Chart<'a>, S> {
+    /// Sets the range of an axis. See `Chart::set_range()`.
+    pub fn range(
+        self,
+        axis: ChartAxis,
+        min: i16,
+        max: i16,
+    ) -> Builder<Chart<'a>, impl FnOnce(&mut Chart<'a>)> {
+        self.with(move |chart| chart.set_range(axis, min, max))
+    }
+
+    /// Sets the ticks of an axis. See `Chart::set_axis_tick()`.
+    pub fn axis_tick(
+        self,
+        axis: ChartAxis,
+        ticks: AxisTicks,
+    ) -> Builder<Chart<'a>, impl FnOnce(&mut Chart<'a>)> {
+        self.with(move |chart| chart.set_axis_tick(axis, ticks))
+    }
+
+    /// Sets how new points are added. See `Chart::set_update_mode()`.
+    pub fn update_mode(
+        self,
+        mode: ChartUpdateMode,
+    ) -> Builder<Chart<'a>, impl FnOnce(&mut Chart<'a>)> {
+        self.with(move |chart| chart.set_update_mode(mode))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+
+    #[test]
+    fn oscilloscope_axes() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let chart = Chart::builder()
+            .range(ChartAxis::PrimaryY, -50, 50)
+            .axis_tick(ChartAxis::PrimaryY, AxisTicks::default())
+            .update_mode(ChartUpdateMode::Circular)
+            .div_line_count(5, 8)
+            .build(&mut screen)
+            .unwrap();
+
+        let raw = unsafe { &*(chart.raw().as_ptr() as *const lvgl_sys::lv_chart_t) };
+        assert_eq!((raw.ymin[0], raw.ymax[0]), (-50, 50));
+        assert_eq!((raw.hdiv_cnt, raw.vdiv_cnt), (5, 8));
+        assert_eq!(
+            raw.update_mode() as u32,
+            lvgl_sys::LV_CHART_UPDATE_MODE_CIRCULAR
+        );
+    }
+}
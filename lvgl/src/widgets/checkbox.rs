@@ -0,0 +1,32 @@
+use crate::widgets::Checkbox;
+use crate::{State, Widget};
+
+impl Checkbox<'_> {
+    /// Returns whether the checkbox is checked.
+    pub fn is_checked(&self) -> bool {
+        self.has_state(State::CHECKED)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Switch;
+
+    #[test]
+    fn read_back_checked() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut checkbox = Checkbox::create(&mut screen).unwrap();
+        let mut switch = Switch::builder()
+            .state(State::CHECKED)
+            .build(&mut screen)
+            .unwrap();
+        assert!(!checkbox.is_checked());
+        assert!(switch.is_checked());
+        checkbox.add_state(State::CHECKED);
+        switch.clear_state(State::CHECKED);
+        assert!(checkbox.is_checked());
+        assert!(!switch.is_checked());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::text::TextBuf;
+use crate::widgets::Dropdown;
+use crate::{Builder, LvResult, NativeObject};
+use core::ffi::c_char;
+use cstr_core::CStr;
+
+impl<'a> Dropdown<'a> {
+    /// Sets the dropdown's options, e.g. from an array, a `Vec` or an
+    /// iterator over an enum's names:
+    ///
+    /// ```ignore
+    /// dropdown.set_options(["Celsius", "Fahrenheit", "Kelvin"])?;
+    /// dropdown.set_options(units.iter().map(Unit::name))?;
+    /// ```
+    ///
+    /// The options are joined with `\n` in a buffer in LVGL memory, which
+    /// fails with `LvError::LvOOMemory` if it cannot be allocated.
+    pub fn set_options(
+        &mut self,
+        options: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> LvResult<()> {
+        let options = TextBuf::join(options)?;
+        unsafe {
+            lvgl_sys::lv_dropdown_set_options(self.raw().as_mut(), options.as_c_str().as_ptr())
+        }
+        Ok(())
+    }
+
+    /// Copies the text of the selected option into `buf`, truncated to fit,
+    /// and returns it. This does not allocate, so the text can be shown
+    /// elsewhere, e.g. in a label, on `no_std` targets:
+    ///
+    /// ```ignore
+    /// let mut buf = [0; 32];
+    /// label.set_text(dropdown.get_selected_str(&mut buf))?;
+    /// ```
+    pub fn get_selected_str<'b>(&self, buf: &'b mut [u8]) -> &'b CStr {
+        copy_str(buf, |ptr, size| unsafe {
+            lvgl_sys::lv_dropdown_get_selected_str(self.raw().as_ptr(), ptr, size)
+        })
+    }
+
+    /// Returns the text of the selected option.
+    #[cfg(feature = "alloc")]
+    pub fn get_selected_string(&self) -> alloc::string::String {
+        unsafe {
+            option_string(
+                lvgl_sys::lv_dropdown_get_options(self.raw().as_ptr()),
+                lvgl_sys::lv_dropdown_get_selected(self.raw().as_ptr()),
+            )
+        }
+    }
+}
+
+impl<'a, S: FnOnce(&mut Dropdown<'a>)> Builder<Dropdown<'a>, S> {
+    /// Sets the dropdown's options. See `Dropdown::set_options()`. If they
+    /// cannot be allocated, the dropdown keeps LVGL's placeholder options.
+    pub fn options(
+        self,
+        options: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Builder<Dropdown<'a>, impl FnOnce(&mut Dropdown<'a>)> {
+        self.with(move |dropdown| {
+            let _ = dropdown.set_options(options);
+        })
+    }
+}
+
+/// Lets `copy` write a NUL-terminated string of at most `buf.len()` bytes
+/// into `buf`, and returns it.
+pub(crate) fn copy_str(buf: &mut [u8], copy: impl FnOnce(*mut c_char, u32)) -> &CStr {
+    if buf.is_empty() {
+        // LVGL treats a size of 0 as unlimited
+        return unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") };
+    }
+    copy(
+        buf.as_mut_ptr() as *mut _,
+        buf.len().min(u32::MAX as usize) as u32,
+    );
+    unsafe { CStr::from_ptr(buf.as_ptr() as *const _) }
+}
+
+/// Returns the option at `index` of newline-separated `options`.
+///
+/// # Safety
+///
+/// `options` must point to a NUL-terminated string.
+#[cfg(feature = "alloc")]
+pub(crate) unsafe fn option_string(options: *const c_char, index: u16) -> alloc::string::String {
+    let option = CStr::from_ptr(options)
+        .to_bytes()
+        .split(|&b| b == b'\n')
+        .nth(index.into())
+        .unwrap_or_default();
+    alloc::string::String::from_utf8_lossy(option).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::Dropdown;
+    use crate::{tests, NativeObject};
+    use cstr_core::CStr;
+
+    #[test]
+    fn selected_option_text() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut dropdown = Dropdown::create(&mut screen).unwrap();
+        dropdown
+            .set_options(["Celsius", "Fahrenheit", "Kelvin"])
+            .unwrap();
+        unsafe { lvgl_sys::lv_dropdown_set_selected(dropdown.raw().as_ptr(), 1) }
+
+        let mut buf = [0; 32];
+        assert_eq!(
+            dropdown.get_selected_str(&mut buf).to_bytes(),
+            b"Fahrenheit"
+        );
+        let mut buf = [0; 5];
+        assert_eq!(dropdown.get_selected_str(&mut buf).to_bytes(), b"Fahr");
+        assert_eq!(dropdown.get_selected_str(&mut []).to_bytes(), b"");
+        #[cfg(feature = "alloc")]
+        assert_eq!(dropdown.get_selected_string(), "Fahrenheit");
+    }
+
+    #[test]
+    fn options_from_iterators() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let options = |dropdown: &Dropdown| unsafe {
+            CStr::from_ptr(lvgl_sys::lv_dropdown_get_options(dropdown.raw().as_ptr()))
+                .to_bytes()
+                .to_vec()
+        };
+
+        let names: Vec<String> = (1..=20).map(|i| format!("Channel {}", i)).collect();
+        let mut dropdown = Dropdown::builder()
+            .options(&names)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(options(&dropdown), names.join("\n").as_bytes());
+        assert_eq!(
+            unsafe { lvgl_sys::lv_dropdown_get_option_cnt(dropdown.raw().as_ptr()) },
+            20
+        );
+
+        dropdown.set_options(["On", "Of\0f"]).unwrap();
+        assert_eq!(options(&dropdown), b"On\nOf");
+        dropdown.set_options(Vec::<&str>::new()).unwrap();
+        assert_eq!(options(&dropdown), b"");
+    }
+}
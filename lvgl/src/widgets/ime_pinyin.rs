@@ -0,0 +1,249 @@
+use crate::widgets::Keyboard;
+use crate::{LvError, LvResult, NativeObject, Obj, Widget};
+use core::ptr::{self, NonNull};
+
+define_object!(ImePinyin);
+
+unsafe impl crate::WidgetClass for ImePinyin<'_> {
+    fn class() -> &'static lvgl_sys::lv_obj_class_t {
+        unsafe { &lvgl_sys::lv_ime_pinyin_class }
+    }
+}
+
+/// The layout of the keyboard used to type pinyin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PinyinMode {
+    /// A full keyboard, one key per letter.
+    #[default]
+    K26,
+    /// A phone keypad, with three or four letters per key.
+    K9,
+    /// The phone keypad's numbers.
+    K9Number,
+}
+
+impl From<PinyinMode> for lvgl_sys::lv_ime_pinyin_mode_t {
+    fn from(value: PinyinMode) -> Self {
+        match value {
+            PinyinMode::K26 => lvgl_sys::lv_ime_pinyin_mode_t_LV_IME_PINYIN_MODE_K26,
+            PinyinMode::K9 => lvgl_sys::lv_ime_pinyin_mode_t_LV_IME_PINYIN_MODE_K9,
+            PinyinMode::K9Number => lvgl_sys::lv_ime_pinyin_mode_t_LV_IME_PINYIN_MODE_K9_NUMBER,
+        }
+    }
+}
+
+/// A syllable of a `PinyinDict` and the characters it is typed as.
+#[derive(Debug, Copy, Clone)]
+#[repr(transparent)]
+pub struct PinyinEntry(lvgl_sys::lv_pinyin_dict_t);
+
+impl PinyinEntry {
+    /// Ends a dictionary.
+    pub const END: Self = Self(lvgl_sys::lv_pinyin_dict_t {
+        py: ptr::null(),
+        py_mb: ptr::null(),
+    });
+
+    /// Creates an entry typing `candidates`, the characters offered in
+    /// order, from the lowercase syllable `pinyin`. Both end with `\0`; the
+    /// `pinyin_dict!` macro adds it.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant or static, if a
+    /// string does not end with `\0` or the syllable does not start with a
+    /// lowercase letter.
+    pub const fn new(pinyin: &'static str, candidates: &'static str) -> Self {
+        let py = pinyin.as_bytes();
+        let py_mb = candidates.as_bytes();
+        assert!(
+            py.len() > 1 && py[0].is_ascii_lowercase() && py[py.len() - 1] == 0,
+            "pinyin must start with a lowercase letter and end with \\0"
+        );
+        assert!(
+            !py_mb.is_empty() && py_mb[py_mb.len() - 1] == 0,
+            "candidates must end with \\0"
+        );
+        Self(lvgl_sys::lv_pinyin_dict_t {
+            py: py.as_ptr() as *const _,
+            py_mb: py_mb.as_ptr() as *const _,
+        })
+    }
+
+    const fn initial(&self) -> Option<u8> {
+        if self.0.py.is_null() {
+            None
+        } else {
+            // SAFETY: `new()` checked that the syllable has a first letter
+            Some(unsafe { *(self.0.py as *const u8) })
+        }
+    }
+}
+
+/// A dictionary of the syllables an `ImePinyin` offers characters for, in
+/// place of LVGL's default one. Declared as a `static` with the
+/// [`pinyin_dict!`](crate::pinyin_dict!) macro:
+///
+/// ```ignore
+/// static DICT: PinyinDict = lvgl::pinyin_dict! {
+///     "hao" => "好号浩",
+///     "ni" => "你呢尼",
+/// };
+/// ```
+pub struct PinyinDict(&'static [PinyinEntry]);
+
+// LVGL only reads the dictionary
+unsafe impl Sync for PinyinDict {}
+
+impl PinyinDict {
+    /// Creates a dictionary from its entries, ending with
+    /// `PinyinEntry::END`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant or static, if the
+    /// entries are not terminated or not sorted by their first letter, which
+    /// LVGL indexes them by.
+    pub const fn new(entries: &'static [PinyinEntry]) -> Self {
+        assert!(
+            entries.len() > 1 && entries[entries.len() - 1].initial().is_none(),
+            "a pinyin dictionary has entries and ends with PinyinEntry::END"
+        );
+        let mut i = 1;
+        while i < entries.len() - 1 {
+            let (Some(previous), Some(initial)) = (entries[i - 1].initial(), entries[i].initial())
+            else {
+                panic!("PinyinEntry::END must be last");
+            };
+            assert!(
+                previous <= initial,
+                "pinyin entries must be sorted by their first letter"
+            );
+            i += 1;
+        }
+        Self(entries)
+    }
+
+    fn raw(&'static self) -> *mut lvgl_sys::lv_pinyin_dict_t {
+        self.0.as_ptr() as *mut _
+    }
+}
+
+/// Declares a [`PinyinDict`] of `"pinyin" => "candidates"` entries, sorted
+/// by their first letter. See `PinyinDict`.
+#[macro_export]
+macro_rules! pinyin_dict {
+    ($($pinyin:literal => $candidates:literal),+ $(,)?) => {
+        $crate::widgets::PinyinDict::new(&[
+            $($crate::widgets::PinyinEntry::new(
+                concat!($pinyin, "\0"),
+                concat!($candidates, "\0"),
+            ),)+
+            $crate::widgets::PinyinEntry::END,
+        ])
+    };
+}
+
+impl<'a> ImePinyin<'a> {
+    /// Creates a pinyin input method on `parent`, using LVGL's default
+    /// dictionary. It is invisible until attached to a keyboard with
+    /// `set_keyboard()`, and then shows the characters matching the letters
+    /// typed in a candidate panel above the keyboard. Requires the
+    /// `ime_pinyin` feature.
+    ///
+    /// ```ignore
+    /// let mut textarea = Textarea::create(&mut screen)?;
+    /// let mut keyboard = Keyboard::create(&mut screen)?;
+    /// keyboard.set_textarea(&mut textarea);
+    /// let mut ime = ImePinyin::create(&mut screen)?;
+    /// ime.set_keyboard(&mut keyboard);
+    /// ```
+    ///
+    /// The candidates are shown in the 16 px CJK font LVGL comes with, which
+    /// the feature enables; style the IME's text font to use another one.
+    pub fn create<'p: 'a>(parent: &mut impl Widget<'p>) -> LvResult<Self> {
+        crate::check_init()?;
+        unsafe {
+            let ptr = lvgl_sys::lv_ime_pinyin_create(parent.raw().as_mut());
+            let raw = NonNull::new(ptr).ok_or(LvError::InvalidReference)?;
+            lvgl_sys::lv_obj_set_style_text_font(
+                ptr,
+                &lvgl_sys::lv_font_simsun_16_cjk,
+                lvgl_sys::LV_PART_MAIN,
+            );
+            Ok(Self {
+                core: Obj::from_raw(raw).unwrap(),
+            })
+        }
+    }
+
+    /// Attaches the input method to `keyboard`, whose text area it then
+    /// types characters in.
+    pub fn set_keyboard(&mut self, keyboard: &mut Keyboard) {
+        unsafe {
+            lvgl_sys::lv_ime_pinyin_set_keyboard(self.raw().as_mut(), keyboard.raw().as_mut())
+        }
+    }
+
+    /// Replaces the dictionary of syllables and their characters.
+    pub fn set_dict(&mut self, dict: &'static PinyinDict) {
+        unsafe { lvgl_sys::lv_ime_pinyin_set_dict(self.raw().as_mut(), dict.raw()) }
+    }
+
+    /// Switches the keyboard to the layout of `mode`. The keyboard must be
+    /// attached with `set_keyboard()` first; fails with
+    /// `LvError::InvalidReference` otherwise.
+    pub fn set_mode(&mut self, mode: PinyinMode) -> LvResult<()> {
+        if self.get_keyboard().is_none() {
+            return Err(LvError::InvalidReference);
+        }
+        unsafe { lvgl_sys::lv_ime_pinyin_set_mode(self.raw().as_mut(), mode.into()) }
+        Ok(())
+    }
+
+    /// Returns the keyboard the input method is attached to, if any.
+    pub fn get_keyboard(&self) -> Option<Obj<'a>> {
+        let keyboard = unsafe { lvgl_sys::lv_ime_pinyin_get_kb(self.raw().as_ptr()) };
+        NonNull::new(keyboard).and_then(|raw| unsafe { Obj::from_raw(raw) })
+    }
+
+    /// Returns the panel showing the candidate characters, e.g. to style it.
+    pub fn get_cand_panel(&self) -> Option<Obj<'a>> {
+        let panel = unsafe { lvgl_sys::lv_ime_pinyin_get_cand_panel(self.raw().as_ptr()) };
+        NonNull::new(panel).and_then(|raw| unsafe { Obj::from_raw(raw) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    static DICT: PinyinDict = crate::pinyin_dict! {
+        "hao" => "好号",
+        "ni" => "你呢",
+        "nihao" => "你好",
+    };
+
+    #[test]
+    fn pinyin_with_custom_dict() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut keyboard = Keyboard::create(&mut screen).unwrap();
+        let mut ime = ImePinyin::create(&mut screen).unwrap();
+        assert!(ime.get_keyboard().is_none());
+        assert_eq!(ime.set_mode(PinyinMode::K9), Err(LvError::InvalidReference));
+
+        ime.set_keyboard(&mut keyboard);
+        assert_eq!(ime.get_keyboard().unwrap().raw(), keyboard.raw());
+        assert!(ime.get_cand_panel().is_some());
+        ime.set_mode(PinyinMode::K9).unwrap();
+
+        ime.set_dict(&DICT);
+        let dict = unsafe { lvgl_sys::lv_ime_pinyin_get_dict(ime.raw().as_ptr()) };
+        assert_eq!(dict, DICT.raw());
+        assert_eq!(DICT.0.len(), 4);
+        assert_eq!(DICT.0[1].initial(), Some(b'n'));
+    }
+}
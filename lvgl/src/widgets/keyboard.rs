@@ -1,5 +1,6 @@
+use crate::guard::guard;
 use crate::widgets::{Keyboard, Textarea};
-use crate::NativeObject;
+use crate::{Box, NativeObject, ObjFlag, Widget};
 
 impl Keyboard<'_> {
     /// Associates a given `Textarea` to the keyboard.
@@ -11,4 +12,118 @@ impl Keyboard<'_> {
             )
         }
     }
+
+    /// Shows the keyboard to edit `textarea`, scrolling the text area into
+    /// view. When the user presses the keyboard's OK or close key, the
+    /// keyboard is hidden, the text area loses focus, and the text area's
+    /// parent scrolls back to where it was before. The text area then
+    /// receives the `Ready` or `Cancel` event as usual.
+    ///
+    /// This replaces any previous attachment, so one keyboard can be attached
+    /// to each text area of a form as it is focused.
+    pub fn attach(&mut self, textarea: &mut Textarea) {
+        unsafe {
+            let keyboard = self.raw().as_ptr();
+            let textarea = textarea.raw().as_ptr();
+            let parent = lvgl_sys::lv_obj_get_parent(textarea);
+            let attachment = Attachment {
+                textarea,
+                parent,
+                scroll: if parent.is_null() {
+                    (0, 0)
+                } else {
+                    (
+                        lvgl_sys::lv_obj_get_scroll_x(parent),
+                        lvgl_sys::lv_obj_get_scroll_y(parent),
+                    )
+                },
+            };
+
+            let data = lvgl_sys::lv_obj_get_event_user_data(keyboard, Some(attachment_callback))
+                as *mut Attachment;
+            if data.is_null() {
+                lvgl_sys::lv_obj_add_event_cb(
+                    keyboard,
+                    Some(attachment_callback),
+                    lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                    Box::into_raw(Box::new(attachment)) as *mut _,
+                );
+            } else {
+                *data = attachment;
+            }
+
+            lvgl_sys::lv_keyboard_set_textarea(keyboard, textarea);
+            self.clear_flag(ObjFlag::HIDDEN);
+            lvgl_sys::lv_obj_scroll_to_view_recursive(
+                textarea,
+                lvgl_sys::lv_anim_enable_t_LV_ANIM_ON,
+            );
+        }
+    }
+}
+
+/// The text area a keyboard was attached to, and how to restore its parent.
+struct Attachment {
+    textarea: *mut lvgl_sys::lv_obj_t,
+    parent: *mut lvgl_sys::lv_obj_t,
+    scroll: (lvgl_sys::lv_coord_t, lvgl_sys::lv_coord_t),
+}
+
+unsafe extern "C" fn attachment_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("keyboard attachment", || {
+        let keyboard = (*event).target;
+        let attachment = (*event).user_data as *mut Attachment;
+        match (*event).code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_READY
+            | lvgl_sys::lv_event_code_t_LV_EVENT_CANCEL => {
+                let Attachment {
+                    textarea,
+                    parent,
+                    scroll: (x, y),
+                } = *attachment;
+                // The text area stays assigned, as LVGL sends it the event
+                // after the keyboard
+                lvgl_sys::lv_obj_add_flag(keyboard, ObjFlag::HIDDEN.into());
+                // The text area may have been deleted while the keyboard was open
+                if lvgl_sys::lv_obj_is_valid(textarea) {
+                    lvgl_sys::lv_obj_clear_state(textarea, lvgl_sys::LV_STATE_FOCUSED as _);
+                }
+                if lvgl_sys::lv_obj_is_valid(parent) {
+                    lvgl_sys::lv_obj_scroll_to(parent, x, y, lvgl_sys::lv_anim_enable_t_LV_ANIM_ON);
+                }
+            }
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => drop(Box::from_raw(attachment)),
+            _ => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::{Keyboard, Textarea};
+    use crate::{tests, NativeObject, ObjFlag, Widget};
+
+    #[test]
+    fn attach_and_dismiss() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut textarea = Textarea::create(&mut screen).unwrap();
+        let mut keyboard = Keyboard::create(&mut screen).unwrap();
+        keyboard.add_flag(ObjFlag::HIDDEN);
+
+        keyboard.attach(&mut textarea);
+        keyboard.attach(&mut textarea);
+        assert!(!keyboard.has_flag(ObjFlag::HIDDEN));
+        let target = unsafe { lvgl_sys::lv_keyboard_get_textarea(keyboard.raw().as_ptr()) };
+        assert_eq!(target, textarea.raw().as_ptr());
+
+        unsafe {
+            lvgl_sys::lv_event_send(
+                keyboard.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_READY,
+                core::ptr::null_mut(),
+            )
+        };
+        assert!(keyboard.has_flag(ObjFlag::HIDDEN));
+    }
 }
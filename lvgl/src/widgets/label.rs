@@ -1,35 +1,49 @@
 use crate::widgets::Label;
-use crate::{LabelLongMode, NativeObject};
+use crate::{AsCStr, Builder, LabelLongMode, LvResult, NativeObject, Part, Widget};
+use core::fmt;
 use cstr_core::CStr;
 
-#[cfg(feature = "alloc")]
-mod alloc_imp {
-    use crate::widgets::Label;
-    //use crate::LvError;
-    use cstr_core::CString;
-    //use core::convert::TryFrom;
-
-    impl<S: AsRef<str>> From<S> for Label<'_> {
-        fn from(text: S) -> Self {
-            // text.try_into().unwrap()
-            let text_cstr = CString::new(text.as_ref()).unwrap();
-            let mut label = Label::new().unwrap();
-            label.set_text(text_cstr.as_c_str());
-            label
+/// Capacity in bytes of the stack buffer `Label::set_text_fmt()` formats into,
+/// including the terminating NUL.
+pub const TEXT_FMT_CAPACITY: usize = 128;
+
+/// A NUL-terminated string formatted on the stack, truncated to fit.
+pub(super) struct FmtBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FmtBuffer<N> {
+    pub(super) fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
         }
     }
 
-    // Issue link: https://github.com/rust-lang/rust/issues/50133
-    //
-    // impl<S: AsRef<str>> TryFrom<S> for Label {
-    //     type Error = LvError;
-    //     fn try_from(text: S) -> Result<Self, Self::Error> {
-    //         let text_cstr = CString::new(text.as_ref())?;
-    //         let mut label = Label::new()?;
-    //         label.set_text(text_cstr.as_c_str())?;
-    //         Ok(label)
-    //     }
-    // }
+    pub(super) fn as_c_str(&self) -> &CStr {
+        // The buffer starts zeroed and its last byte is never written
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.buf[..=self.len]) }
+    }
+}
+
+impl<const N: usize> fmt::Write for FmtBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // An interior NUL would end the string early in C
+        let text = s.split('\0').next().unwrap_or_default();
+        let mut end = text.len().min(N - 1 - self.len);
+        // Never cut a UTF-8 character in half
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf[self.len..self.len + end].copy_from_slice(&text.as_bytes()[..end]);
+        self.len += end;
+        if end < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl Label<'_> {
@@ -39,13 +53,177 @@ impl Label<'_> {
         }
     }
 
-    pub fn get_long_mode(&self) -> u8 {
-        unsafe { lvgl_sys::lv_label_get_long_mode(self.raw().as_ref()) }
+    pub fn get_long_mode(&self) -> LabelLongMode {
+        unsafe { lvgl_sys::lv_label_get_long_mode(self.raw().as_ref()) }.into()
+    }
+
+    /// Sets how fast text scrolls in the `Scroll` and `ScrollCircular` long
+    /// modes, in pixels per second. This is the `anim_speed` style property
+    /// of the label's main part.
+    pub fn set_scroll_speed(&mut self, px_per_sec: u32) {
+        self.set_style_anim_speed(Part::Main, px_per_sec);
     }
 
-    pub fn set_text(&mut self, text: &CStr) {
+    /// Gets how fast text scrolls, in pixels per second.
+    pub fn get_scroll_speed(&self) -> u32 {
         unsafe {
-            lvgl_sys::lv_label_set_text(self.raw().as_mut(), text.as_ptr());
+            lvgl_sys::lv_obj_get_style_prop(
+                self.raw().as_ref(),
+                Part::Main.into(),
+                lvgl_sys::lv_style_prop_t_LV_STYLE_ANIM_SPEED,
+            )
+            .num as u32
         }
     }
+
+    /// Stops scrolling the text where it is, until `resume_scroll()` is
+    /// called or the text or long mode changes. As LVGL's scroll animations
+    /// cannot be told apart from others, this stops every animation of the
+    /// label itself; animations of its style properties are not resumed.
+    pub fn pause_scroll(&mut self) {
+        unsafe { lvgl_sys::lv_anim_del(self.raw().as_ptr() as *mut _, None) };
+    }
+
+    /// Restarts scrolling the text from its start after `pause_scroll()`.
+    pub fn resume_scroll(&mut self) {
+        let long_mode = self.get_long_mode();
+        self.set_long_mode(long_mode);
+    }
+
+    /// Sets the label's text, which LVGL copies, e.g. `label.set_text("Ready")`.
+    /// See `AsCStr` for the types of text accepted.
+    pub fn set_text(&mut self, text: impl AsCStr) -> LvResult<()> {
+        text.with_c_str(|text| unsafe {
+            lvgl_sys::lv_label_set_text(self.raw().as_mut(), text.as_ptr())
+        })
+    }
+
+    /// Sets the label's text from format arguments, without allocating:
+    ///
+    /// ```ignore
+    /// label.set_text_fmt(format_args!("{:.1} °C", temperature))?;
+    /// ```
+    ///
+    /// The text is formatted on the stack, and LVGL copies it into the label.
+    /// Text longer than `TEXT_FMT_CAPACITY - 1` bytes is truncated, in which
+    /// case the truncated text is set and an error is returned.
+    pub fn set_text_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        let mut text = FmtBuffer::<TEXT_FMT_CAPACITY>::new();
+        let result = fmt::write(&mut text, args);
+        unsafe { lvgl_sys::lv_label_set_text(self.raw().as_mut(), text.as_c_str().as_ptr()) }
+        result
+    }
+}
+
+/// Creates a label with `text` on the active screen, e.g.
+/// `Label::from("20:46")`. Panics if there is no active screen or LVGL memory
+/// is exhausted; use `Label::create()` and `set_text()` to handle errors.
+impl<S: AsRef<str>> From<S> for Label<'_> {
+    fn from(text: S) -> Self {
+        let mut label = Label::new().unwrap();
+        label.set_text(text.as_ref()).unwrap();
+        label
+    }
+}
+
+impl<'a, S: FnOnce(&mut Label<'a>)> Builder<Label<'a>, S> {
+    /// Sets the label's text. See `Label::set_text()`. If the text needs
+    /// LVGL memory that is not available, the label is left empty.
+    pub fn text(self, text: impl AsCStr) -> Builder<Label<'a>, impl FnOnce(&mut Label<'a>)> {
+        self.with(move |label| {
+            let _ = label.set_text(text);
+        })
+    }
+
+    /// Sets the label's long mode. See `Label::set_long_mode()`.
+    pub fn long_mode(
+        self,
+        long_mode: LabelLongMode,
+    ) -> Builder<Label<'a>, impl FnOnce(&mut Label<'a>)> {
+        self.with(move |label| label.set_long_mode(long_mode))
+    }
+
+    /// Sets how fast the text scrolls. See `Label::set_scroll_speed()`.
+    pub fn scroll_speed(self, px_per_sec: u32) -> Builder<Label<'a>, impl FnOnce(&mut Label<'a>)> {
+        self.with(move |label| label.set_scroll_speed(px_per_sec))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn fmt_buffer_truncates_at_char_boundary() {
+        let mut text = FmtBuffer::<8>::new();
+        write!(text, "{:.1} C", 21.0).unwrap();
+        assert_eq!(text.as_c_str().to_bytes(), b"21.0 C");
+        assert!(write!(text, "°").is_err());
+        assert_eq!(text.as_c_str().to_bytes(), b"21.0 C");
+    }
+
+    #[test]
+    fn set_text_fmt() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut label = Label::create(&mut screen).unwrap();
+        label.set_text_fmt(format_args!("{} items", 3)).unwrap();
+        let text = unsafe { CStr::from_ptr(lvgl_sys::lv_label_get_text(label.raw().as_ref())) };
+        assert_eq!(text.to_bytes(), b"3 items");
+    }
+
+    #[test]
+    fn set_text_from_str() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut label = Label::create(&mut screen).unwrap();
+        let raw = label.raw().as_ptr();
+        let text = || unsafe { CStr::from_ptr(lvgl_sys::lv_label_get_text(raw)) };
+
+        label.set_text("Ready").unwrap();
+        assert_eq!(text().to_bytes(), b"Ready");
+        let long = "A notification too long for the stack buffer. ".repeat(3);
+        label.set_text(&long).unwrap();
+        assert_eq!(text().to_bytes(), long.as_bytes());
+        label
+            .set_text(CStr::from_bytes_with_nul(b"Done\0").unwrap())
+            .unwrap();
+        assert_eq!(text().to_bytes(), b"Done");
+    }
+
+    #[test]
+    fn from_str() {
+        crate::tests::initialize_test(true);
+        let label: Label = "20:46".into();
+        let text = unsafe { CStr::from_ptr(lvgl_sys::lv_label_get_text(label.raw().as_ref())) };
+        assert_eq!(text.to_bytes(), b"20:46");
+    }
+
+    #[test]
+    fn pause_circular_scroll() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut label = Label::builder()
+            .text("A status line longer than its label")
+            .width(40)
+            .long_mode(LabelLongMode::ScrollCircular)
+            .scroll_speed(20)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(label.get_long_mode(), LabelLongMode::ScrollCircular);
+        assert_eq!(label.get_scroll_speed(), 20);
+
+        let raw = label.raw().as_ptr();
+        unsafe { lvgl_sys::lv_obj_update_layout(raw) };
+        let scroll = unsafe { lvgl_sys::lv_anim_get(raw as *mut _, None).as_ref() };
+        let scroll_cb = scroll.and_then(|anim| anim.exec_cb);
+        assert!(scroll_cb.is_some());
+        let running = |exec_cb| unsafe { !lvgl_sys::lv_anim_get(raw as *mut _, exec_cb).is_null() };
+
+        label.pause_scroll();
+        assert!(!running(None));
+        label.resume_scroll();
+        assert!(running(scroll_cb));
+    }
 }
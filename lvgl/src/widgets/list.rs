@@ -0,0 +1,154 @@
+use crate::guard::guard;
+use crate::widgets::{Btn, Label, List};
+use crate::{AsCStr, Box, LvError, LvResult, NativeObject, ObjFlag, Widget};
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+impl<'a> List<'a> {
+    /// Adds a button with an optional icon, e.g. one of LVGL's symbols:
+    ///
+    /// ```ignore
+    /// let wifi = CStr::from_bytes_with_nul(lvgl_sys::LV_SYMBOL_WIFI)?;
+    /// list.add_btn(Some(wifi), "Wi-Fi")?;
+    /// ```
+    ///
+    /// Clicks on the button bubble up to the list, where `on_btn_clicked()`
+    /// handles them.
+    pub fn add_btn(&mut self, icon: Option<&CStr>, text: impl AsCStr) -> LvResult<Btn<'a>> {
+        unsafe {
+            let btn = text.with_c_str(|text| {
+                lvgl_sys::lv_list_add_btn(
+                    self.raw().as_mut(),
+                    icon.map_or(ptr::null(), |icon| icon.as_ptr() as *const _),
+                    text.as_ptr(),
+                )
+            })?;
+            let mut btn = NonNull::new(btn)
+                .and_then(|raw| Btn::from_raw(raw))
+                .ok_or(LvError::InvalidReference)?;
+            btn.add_flag(ObjFlag::EVENT_BUBBLE);
+            Ok(btn)
+        }
+    }
+
+    /// Adds a text item, e.g. as a section header.
+    pub fn add_text(&mut self, text: impl AsCStr) -> LvResult<Label<'a>> {
+        unsafe {
+            let label = text.with_c_str(|text| {
+                lvgl_sys::lv_list_add_text(self.raw().as_mut(), text.as_ptr())
+            })?;
+            NonNull::new(label)
+                .and_then(|raw| Label::from_raw(raw))
+                .ok_or(LvError::InvalidReference)
+        }
+    }
+
+    /// Returns the text of a button of the list.
+    pub fn get_btn_text(&self, btn: &Btn) -> Option<&CStr> {
+        unsafe {
+            let text = lvgl_sys::lv_list_get_btn_text(self.raw().as_ptr(), btn.raw().as_ptr());
+            (!text.is_null()).then(|| CStr::from_ptr(text))
+        }
+    }
+
+    /// Calls `f` with the index of a clicked button among the list's buttons,
+    /// not counting text items, and its text. This routes the taps of a menu
+    /// without keeping a handle to every button:
+    ///
+    /// ```ignore
+    /// list.on_btn_clicked(|index, _text| match index {
+    ///     0 => show_network(),
+    ///     1 => show_display(),
+    ///     _ => {}
+    /// });
+    /// ```
+    pub fn on_btn_clicked<F>(&mut self, f: F)
+    where
+        F: FnMut(u32, &CStr),
+    {
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                self.raw().as_mut(),
+                Some(btn_clicked_callback::<F>),
+                lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                Box::into_raw(Box::new(f)) as *mut _,
+            );
+        }
+    }
+}
+
+/// Returns the index of `btn` among the buttons of `list`.
+unsafe fn btn_index(list: *mut lvgl_sys::lv_obj_t, btn: *mut lvgl_sys::lv_obj_t) -> u32 {
+    let mut index = 0;
+    for i in 0..lvgl_sys::lv_obj_get_child_cnt(list) {
+        let child = lvgl_sys::lv_obj_get_child(list, i as i32);
+        if child == btn {
+            break;
+        }
+        if lvgl_sys::lv_obj_check_type(child, &lvgl_sys::lv_list_btn_class) {
+            index += 1;
+        }
+    }
+    index
+}
+
+unsafe extern "C" fn btn_clicked_callback<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(u32, &CStr),
+{
+    guard("list button clicked", || {
+        let user_closure = (*event).user_data as *mut F;
+        let list = (*event).current_target;
+        let btn = (*event).target;
+        match (*event).code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED
+                if lvgl_sys::lv_obj_get_parent(btn) == list
+                    && lvgl_sys::lv_obj_check_type(btn, &lvgl_sys::lv_list_btn_class) =>
+            {
+                let text = lvgl_sys::lv_list_get_btn_text(list, btn);
+                let text = if text.is_null() {
+                    CStr::from_bytes_with_nul_unchecked(b"\0")
+                } else {
+                    CStr::from_ptr(text)
+                };
+                (*user_closure)(btn_index(list, btn), text);
+            }
+            // The list is being deleted, so the closure will not be called again
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => drop(Box::from_raw(user_closure)),
+            _ => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn route_menu_clicks() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut list = List::create(&mut screen).unwrap();
+        list.add_text("Settings").unwrap();
+        let icon = CStr::from_bytes_with_nul(lvgl_sys::LV_SYMBOL_SETTINGS).unwrap();
+        list.add_btn(Some(icon), "General").unwrap();
+        let wifi = CStr::from_bytes_with_nul(b"Wi-Fi\0").unwrap();
+        let btn = list.add_btn(None, wifi).unwrap();
+        assert_eq!(list.get_btn_text(&btn), Some(wifi));
+
+        let clicked = Rc::new(RefCell::new(None));
+        let seen = clicked.clone();
+        list.on_btn_clicked(move |index, text| *seen.borrow_mut() = Some((index, text.to_owned())));
+        unsafe {
+            lvgl_sys::lv_event_send(
+                btn.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
+                ptr::null_mut(),
+            )
+        };
+        assert_eq!(*clicked.borrow(), Some((1, wifi.to_owned())));
+    }
+}
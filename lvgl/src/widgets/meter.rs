@@ -1,3 +1,11 @@
+use super::label::FmtBuffer;
+use crate::guard::guard;
+use crate::misc::image::ImageDsc;
+use crate::widgets::Meter;
+use crate::{Box, Color, LvError, LvResult, NativeObject};
+use core::fmt;
+use core::ptr::NonNull;
+
 pub enum MeterPart {
     Arc,
     Needle,
@@ -15,3 +23,299 @@ impl From<MeterPart> for u8 {
         }
     }
 }
+
+/// A scale of a meter, created by `Meter::add_scale()`. It can only be used
+/// with the meter that created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeterScale {
+    meter: NonNull<lvgl_sys::lv_obj_t>,
+    raw: NonNull<lvgl_sys::lv_meter_scale_t>,
+}
+
+/// An indicator of a meter, e.g. a needle, showing a value on a scale. It can
+/// only be used with the meter that created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeterIndicator {
+    meter: NonNull<lvgl_sys::lv_obj_t>,
+    raw: NonNull<lvgl_sys::lv_meter_indicator_t>,
+}
+
+/// The size of the buffer LVGL draws a tick's label from, including the
+/// terminating NUL.
+const TICK_LABEL_CAPACITY: usize = 16;
+
+/// The text of a major tick's label, formatted with `write!()`. Text beyond
+/// the label's capacity of 15 bytes is cut off.
+pub struct TickLabel(FmtBuffer<TICK_LABEL_CAPACITY>);
+
+impl fmt::Write for TickLabel {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+}
+
+impl<'a> Meter<'a> {
+    /// Adds a scale to the meter. Set it up with `set_scale_ticks()`,
+    /// `set_scale_major_ticks()` and `set_scale_range()`.
+    pub fn add_scale(&mut self) -> LvResult<MeterScale> {
+        let raw = unsafe { lvgl_sys::lv_meter_add_scale(self.raw().as_mut()) };
+        Ok(MeterScale {
+            meter: self.raw(),
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+        })
+    }
+
+    /// Sets the number of ticks of a scale, and the width and length of its
+    /// minor ticks.
+    pub fn set_scale_ticks(
+        &mut self,
+        scale: MeterScale,
+        count: u16,
+        width: u16,
+        len: u16,
+        color: Color,
+    ) -> LvResult<()> {
+        let scale = self.check_scale(scale)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_ticks(
+                self.raw().as_mut(),
+                scale,
+                count,
+                width,
+                len,
+                color.raw,
+            )
+        }
+        Ok(())
+    }
+
+    /// Makes every `nth` tick of a scale a major tick, labelled with its
+    /// value `label_gap` pixels from the tick. The labels can be customized
+    /// with `on_tick_label()`.
+    pub fn set_scale_major_ticks(
+        &mut self,
+        scale: MeterScale,
+        nth: u16,
+        width: u16,
+        len: u16,
+        color: Color,
+        label_gap: i16,
+    ) -> LvResult<()> {
+        let scale = self.check_scale(scale)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_major_ticks(
+                self.raw().as_mut(),
+                scale,
+                nth,
+                width,
+                len,
+                color.raw,
+                label_gap,
+            )
+        }
+        Ok(())
+    }
+
+    /// Sets the range of values of a scale, the angle it spans in degrees,
+    /// and its rotation in degrees clockwise from 3 o'clock.
+    pub fn set_scale_range(
+        &mut self,
+        scale: MeterScale,
+        min: i32,
+        max: i32,
+        angle_range: u32,
+        rotation: u32,
+    ) -> LvResult<()> {
+        let scale = self.check_scale(scale)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_range(
+                self.raw().as_mut(),
+                scale,
+                min,
+                max,
+                angle_range,
+                rotation,
+            )
+        }
+        Ok(())
+    }
+
+    /// Adds a needle drawn as a line to a scale. `r_mod` adjusts its length
+    /// relative to the scale's radius.
+    pub fn add_needle_line(
+        &mut self,
+        scale: MeterScale,
+        width: u16,
+        color: Color,
+        r_mod: i16,
+    ) -> LvResult<MeterIndicator> {
+        let scale = self.check_scale(scale)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_needle_line(self.raw().as_mut(), scale, width, color.raw, r_mod)
+        };
+        self.indicator(raw)
+    }
+
+    /// Adds a needle drawn as an image to a scale. The image should point to
+    /// the right, and is rotated around the pixel at `pivot_x`, `pivot_y`,
+    /// which is placed at the center of the meter. LVGL draws the image for
+    /// as long as the meter exists, so it must be `'static`.
+    pub fn add_needle_img(
+        &mut self,
+        scale: MeterScale,
+        image: &'static ImageDsc<'static>,
+        pivot_x: i16,
+        pivot_y: i16,
+    ) -> LvResult<MeterIndicator> {
+        let scale = self.check_scale(scale)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_needle_img(
+                self.raw().as_mut(),
+                scale,
+                image.as_src(),
+                pivot_x,
+                pivot_y,
+            )
+        };
+        self.indicator(raw)
+    }
+
+    /// Sets the value an indicator shows.
+    pub fn set_indicator_value(&mut self, indicator: MeterIndicator, value: i32) -> LvResult<()> {
+        if indicator.meter != self.raw() {
+            return Err(LvError::InvalidReference);
+        }
+        unsafe {
+            lvgl_sys::lv_meter_set_indicator_value(
+                self.raw().as_mut(),
+                indicator.raw.as_ptr(),
+                value,
+            )
+        }
+        Ok(())
+    }
+
+    /// Formats the labels of the major ticks with `f`, which is called with
+    /// each tick's value while the meter is drawn, e.g. to add units or map
+    /// the values:
+    ///
+    /// ```ignore
+    /// meter.on_tick_label(|label, value| {
+    ///     let _ = write!(label, "{}k", value / 1000);
+    /// });
+    /// ```
+    pub fn on_tick_label<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut TickLabel, i32),
+    {
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                self.raw().as_mut(),
+                Some(tick_label_callback::<F>),
+                lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                Box::into_raw(Box::new(f)) as *mut _,
+            );
+        }
+    }
+
+    fn check_scale(&self, scale: MeterScale) -> LvResult<*mut lvgl_sys::lv_meter_scale_t> {
+        if scale.meter == self.raw() {
+            Ok(scale.raw.as_ptr())
+        } else {
+            Err(LvError::InvalidReference)
+        }
+    }
+
+    fn indicator(&self, raw: *mut lvgl_sys::lv_meter_indicator_t) -> LvResult<MeterIndicator> {
+        Ok(MeterIndicator {
+            meter: self.raw(),
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+        })
+    }
+}
+
+unsafe extern "C" fn tick_label_callback<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(&mut TickLabel, i32),
+{
+    guard("meter tick label", || {
+        let user_closure = (*event).user_data as *mut F;
+        match (*event).code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_PART_BEGIN => {
+                let dsc = &*lvgl_sys::lv_event_get_draw_part_dsc(event);
+                // The meter's background is drawn by the base class, and the
+                // ticks themselves have no text
+                if dsc.class_p != core::ptr::addr_of!(lvgl_sys::lv_meter_class)
+                    || dsc.type_ != lvgl_sys::lv_meter_draw_part_type_t_LV_METER_DRAW_PART_TICK
+                    || dsc.text.is_null()
+                    || dsc.text_length == 0
+                {
+                    return;
+                }
+                let mut label = TickLabel(FmtBuffer::new());
+                (*user_closure)(&mut label, dsc.value);
+                let text = label.0.as_c_str().to_bytes_with_nul();
+                let len = text.len().min(dsc.text_length as usize);
+                let buf = core::slice::from_raw_parts_mut(dsc.text as *mut u8, len);
+                buf.copy_from_slice(&text[..len]);
+                buf[len - 1] = 0;
+            }
+            // The meter is being deleted, so the closure will not be called again
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => drop(Box::from_raw(user_closure)),
+            _ => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::misc::image::ColorFormat;
+    use crate::tests;
+    use core::fmt::Write;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn gauge_with_needle_image() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let data = vec![0xFF; 40 * 4].leak();
+        let needle = ImageDsc::new(40, 4, ColorFormat::Alpha8Bit, data).unwrap();
+        let needle = std::boxed::Box::leak(std::boxed::Box::new(needle));
+        let mut meter = Meter::create(&mut screen).unwrap();
+        let scale = meter.add_scale().unwrap();
+        meter
+            .set_scale_ticks(scale, 21, 2, 10, Color::BLACK)
+            .unwrap();
+        meter
+            .set_scale_major_ticks(scale, 5, 4, 15, Color::BLACK, 10)
+            .unwrap();
+        meter.set_scale_range(scale, 0, 8000, 270, 135).unwrap();
+        let indicator = meter.add_needle_img(scale, needle, 2, 2).unwrap();
+        meter.set_indicator_value(indicator, 3000).unwrap();
+
+        let labels = Rc::new(RefCell::new(Vec::new()));
+        let seen = labels.clone();
+        meter.on_tick_label(move |label, value| {
+            let _ = write!(label, "{}k rpm", value / 1000);
+            let text = label.0.as_c_str();
+            seen.borrow_mut().push(text.to_str().unwrap().to_owned());
+        });
+        unsafe { lvgl_sys::lv_refr_now(core::ptr::null_mut()) };
+
+        assert_eq!(
+            *labels.borrow(),
+            ["0k rpm", "2k rpm", "4k rpm", "6k rpm", "8k rpm"]
+        );
+        let raw = unsafe { indicator.raw.as_ref() };
+        assert_eq!(
+            raw.type_,
+            lvgl_sys::lv_meter_indicator_type_t_LV_METER_INDICATOR_TYPE_NEEDLE_IMG as _
+        );
+        assert_eq!(raw.end_value, 3000);
+
+        let mut other = Meter::create(&mut screen).unwrap();
+        assert!(other.set_indicator_value(indicator, 0).is_err());
+    }
+}
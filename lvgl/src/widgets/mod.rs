@@ -10,19 +10,45 @@
 
 mod arc;
 mod bar;
+mod calendar;
+mod canvas;
+mod chart;
+mod checkbox;
+mod dropdown;
+#[cfg(feature = "ime_pinyin")]
+mod ime_pinyin;
 mod keyboard;
 mod label;
+mod list;
 mod meter;
+mod msgbox;
+mod roller;
 mod slider;
+mod spangroup;
+mod switch;
 mod table;
+mod tabview;
 
 include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 
 use crate::NativeObject;
 pub use arc::*;
 pub use bar::*;
+pub use calendar::*;
+pub use canvas::*;
+pub use chart::*;
+pub use checkbox::*;
+pub use dropdown::*;
+#[cfg(feature = "ime_pinyin")]
+pub use ime_pinyin::*;
 pub use keyboard::*;
 pub use label::*;
+pub use list::*;
 pub use meter::*;
+pub use msgbox::*;
+pub use roller::*;
 pub use slider::*;
+pub use spangroup::*;
+pub use switch::*;
 pub use table::*;
+pub use tabview::*;
@@ -0,0 +1,196 @@
+use crate::guard::guard;
+use crate::{LvError, LvResult, NativeObject, Widget};
+use core::ffi::c_char;
+use core::{mem, ptr};
+use cstr_core::CStr;
+
+define_object!(Msgbox);
+
+unsafe impl crate::WidgetClass for Msgbox<'_> {
+    fn class() -> &'static lvgl_sys::lv_obj_class_t {
+        unsafe { &lvgl_sys::lv_msgbox_class }
+    }
+}
+
+impl<'a> Msgbox<'a> {
+    /// Creates a message box on `parent`, with a row of `buttons` below the
+    /// text, and a close button next to the title if `add_close_btn` is set.
+    /// The button texts are copied into LVGL memory, as LVGL keeps pointers
+    /// to them.
+    pub fn create<'p: 'a>(
+        parent: &mut impl Widget<'p>,
+        title: &CStr,
+        text: &CStr,
+        buttons: &[&CStr],
+        add_close_btn: bool,
+    ) -> LvResult<Self> {
+        crate::check_init()?;
+        unsafe {
+            let map = if buttons.is_empty() {
+                ptr::null_mut()
+            } else {
+                // The button matrix keeps the map, terminated by an empty
+                // string, so it is stored in one block with the texts
+                let pointers = (buttons.len() + 1) * mem::size_of::<*const c_char>();
+                let texts: usize = buttons.iter().map(|b| b.to_bytes_with_nul().len()).sum();
+                let map = crate::storage::alloc(pointers + texts + 1) as *mut *const c_char;
+                if map.is_null() {
+                    return Err(LvError::LvOOMemory);
+                }
+                let mut text = (map as *mut u8).add(pointers);
+                for (i, button) in buttons.iter().enumerate() {
+                    let bytes = button.to_bytes_with_nul();
+                    ptr::copy_nonoverlapping(bytes.as_ptr(), text, bytes.len());
+                    *map.add(i) = text as *const c_char;
+                    text = text.add(bytes.len());
+                }
+                *text = 0;
+                *map.add(buttons.len()) = text as *const c_char;
+                map
+            };
+            let ptr = lvgl_sys::lv_msgbox_create(
+                parent.raw().as_mut(),
+                title.as_ptr(),
+                text.as_ptr(),
+                map,
+                add_close_btn,
+            );
+            match ptr::NonNull::new(ptr) {
+                Some(raw) => {
+                    if !map.is_null() {
+                        lvgl_sys::lv_obj_add_event_cb(
+                            ptr,
+                            Some(button_map_callback),
+                            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                            map as *mut _,
+                        );
+                    }
+                    Ok(Self {
+                        core: crate::Obj::from_raw(raw).unwrap(),
+                    })
+                }
+                None => {
+                    crate::storage::free(map as *mut _);
+                    Err(LvError::InvalidReference)
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the button last pressed, if any.
+    pub fn get_active_btn(&self) -> Option<u16> {
+        if !self.has_btns() {
+            return None;
+        }
+        let btn = unsafe { lvgl_sys::lv_msgbox_get_active_btn(self.raw().as_ptr()) };
+        (btn != lvgl_sys::LV_BTNMATRIX_BTN_NONE as u16).then_some(btn)
+    }
+
+    /// Returns the text of the button last pressed, if any. In an event
+    /// handler, this tells which button closed the dialog:
+    ///
+    /// ```ignore
+    /// msgbox.on_event(|msgbox, event| {
+    ///     if let Event::ValueChanged = event {
+    ///         if msgbox.get_active_btn_text() == Some(cstr!("Apply")) {
+    ///             apply();
+    ///         }
+    ///         msgbox.close_async();
+    ///     }
+    /// })?;
+    /// ```
+    pub fn get_active_btn_text(&self) -> Option<&CStr> {
+        if !self.has_btns() {
+            return None;
+        }
+        unsafe {
+            let text = lvgl_sys::lv_msgbox_get_active_btn_text(self.raw().as_ptr());
+            (!text.is_null()).then(|| CStr::from_ptr(text))
+        }
+    }
+
+    /// Closes the message box, deleting it and its children.
+    pub fn close(self) {
+        unsafe { lvgl_sys::lv_msgbox_close(self.raw().as_ptr()) }
+    }
+
+    /// Closes the message box once the current event has been handled. Use
+    /// this to close it from its own event handlers.
+    pub fn close_async(self) {
+        unsafe { lvgl_sys::lv_msgbox_close_async(self.raw().as_ptr()) }
+    }
+
+    // LVGL expects the button matrix to exist when reading the active button
+    fn has_btns(&self) -> bool {
+        unsafe { !lvgl_sys::lv_msgbox_get_btns(self.raw().as_ptr()).is_null() }
+    }
+}
+
+unsafe extern "C" fn button_map_callback(event: *mut lvgl_sys::lv_event_t) {
+    guard("msgbox button map", || {
+        crate::storage::free((*event).user_data);
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{tests, Event};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn branch_on_pressed_button() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let title = CStr::from_bytes_with_nul(b"Unsaved changes\0").unwrap();
+        let text = CStr::from_bytes_with_nul(b"Apply them?\0").unwrap();
+        let mut apply = *b"Apply\0";
+        let mut discard = *b"Discard\0";
+        let mut msgbox = Msgbox::create(
+            &mut screen,
+            title,
+            text,
+            &[
+                CStr::from_bytes_with_nul(&apply).unwrap(),
+                CStr::from_bytes_with_nul(&discard).unwrap(),
+            ],
+            true,
+        )
+        .unwrap();
+        // The texts were copied
+        apply.fill(b'?');
+        discard.fill(b'?');
+        assert_eq!(msgbox.get_active_btn(), None);
+        assert_eq!(msgbox.get_active_btn_text(), None);
+
+        let pressed = Rc::new(RefCell::new(None));
+        let seen = pressed.clone();
+        msgbox
+            .on_event(move |msgbox, event| {
+                if let Event::ValueChanged = event {
+                    *seen.borrow_mut() = msgbox.get_active_btn_text().map(CStr::to_owned);
+                }
+            })
+            .unwrap();
+        let raw = msgbox.raw().as_ptr();
+        unsafe {
+            let btns = lvgl_sys::lv_msgbox_get_btns(raw);
+            lvgl_sys::lv_btnmatrix_set_selected_btn(btns, 1);
+            // The buttons' events bubble up to the message box
+            lvgl_sys::lv_event_send(
+                btns,
+                lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED,
+                ptr::null_mut(),
+            );
+        }
+        assert_eq!(msgbox.get_active_btn(), Some(1));
+        assert_eq!(
+            pressed.borrow().as_deref().map(CStr::to_bytes),
+            Some(&b"Discard"[..])
+        );
+
+        msgbox.close();
+        assert!(!unsafe { lvgl_sys::lv_obj_is_valid(raw) });
+    }
+}
@@ -0,0 +1,116 @@
+use super::dropdown::copy_str;
+use crate::text::TextBuf;
+use crate::widgets::Roller;
+use crate::{Builder, LvResult, NativeObject, Part, TextAlign, Widget};
+use cstr_core::CStr;
+
+/// Whether a roller stops at its first and last options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollerMode {
+    /// The roller stops at its first and last options.
+    Normal,
+    /// The options repeat, so the roller can be spun endlessly.
+    Infinite,
+}
+
+impl From<RollerMode> for lvgl_sys::lv_roller_mode_t {
+    fn from(mode: RollerMode) -> Self {
+        let native = match mode {
+            RollerMode::Normal => lvgl_sys::LV_ROLLER_MODE_NORMAL,
+            RollerMode::Infinite => lvgl_sys::LV_ROLLER_MODE_INFINITE,
+        };
+        native as _
+    }
+}
+
+impl Roller<'_> {
+    /// Sets the roller's options, e.g. `["January", "February", "March"]`.
+    /// See `Dropdown::set_options()`.
+    pub fn set_options(
+        &mut self,
+        options: impl IntoIterator<Item = impl AsRef<str>>,
+        mode: RollerMode,
+    ) -> LvResult<()> {
+        let options = TextBuf::join(options)?;
+        unsafe {
+            lvgl_sys::lv_roller_set_options(
+                self.raw().as_mut(),
+                options.as_c_str().as_ptr(),
+                mode.into(),
+            )
+        }
+        Ok(())
+    }
+
+    /// Aligns the options horizontally within the roller.
+    pub fn set_option_align(&mut self, align: TextAlign) {
+        self.set_style_text_align(Part::Main, align);
+    }
+
+    /// Copies the text of the selected option into `buf`, truncated to fit,
+    /// and returns it, e.g. to mirror the roller's value in a label. See
+    /// `Dropdown::get_selected_str()`.
+    pub fn get_selected_str<'b>(&self, buf: &'b mut [u8]) -> &'b CStr {
+        copy_str(buf, |ptr, size| unsafe {
+            lvgl_sys::lv_roller_get_selected_str(self.raw().as_ptr(), ptr, size)
+        })
+    }
+
+    /// Returns the text of the selected option.
+    #[cfg(feature = "alloc")]
+    pub fn get_selected_string(&self) -> alloc::string::String {
+        unsafe {
+            super::dropdown::option_string(
+                lvgl_sys::lv_roller_get_options(self.raw().as_ptr()),
+                lvgl_sys::lv_roller_get_selected(self.raw().as_ptr()),
+            )
+        }
+    }
+}
+
+impl<'a, S: FnOnce(&mut Roller<'a>)> Builder<Roller<'a>, S> {
+    /// Sets the roller's options. See `Roller::set_options()`. If they
+    /// cannot be allocated, the roller keeps LVGL's placeholder options.
+    pub fn options(
+        self,
+        options: impl IntoIterator<Item = impl AsRef<str>>,
+        mode: RollerMode,
+    ) -> Builder<Roller<'a>, impl FnOnce(&mut Roller<'a>)> {
+        self.with(move |roller| {
+            let _ = roller.set_options(options, mode);
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+
+    #[test]
+    fn compact_picker() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut roller = Roller::builder()
+            .options(
+                (0..60).step_by(15).map(|m| format!("{:02}", m)),
+                RollerMode::Infinite,
+            )
+            .visible_row_count(2)
+            .build(&mut screen)
+            .unwrap();
+        roller.set_option_align(TextAlign::Right);
+        unsafe {
+            lvgl_sys::lv_roller_set_selected(
+                roller.raw().as_ptr(),
+                2,
+                lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+            )
+        };
+
+        let mut buf = [0; 8];
+        assert_eq!(roller.get_selected_str(&mut buf).to_bytes(), b"30");
+        #[cfg(feature = "alloc")]
+        assert_eq!(roller.get_selected_string(), "30");
+    }
+}
@@ -1,15 +1,99 @@
 use crate::lv_core::obj::NativeObject;
 use crate::widgets::Slider;
-use crate::AnimationState;
+use crate::{AnimationState, Area, Builder, Part};
 
 impl Slider<'_> {
+    /// The slider's background, to pass to `add_style()` or `set_style_*()`.
+    pub const MAIN: Part = Part::Main;
+    /// The filled part of the slider, up to its value.
+    pub const INDICATOR: Part = Part::Indicator;
+    /// The handle the user drags.
+    pub const KNOB: Part = Part::Knob;
+
     /// Set a new value on the slider
     pub fn set_value(&self, value: i32, anim: AnimationState) {
         unsafe { lvgl_sys::lv_bar_set_value(self.core.raw().as_ptr(), value, anim.into()) }
     }
 
+    /// Sets the minimum and maximum values of the slider
+    pub fn set_range(&mut self, min: i32, max: i32) {
+        unsafe { lvgl_sys::lv_bar_set_range(self.core.raw().as_mut(), min, max) }
+    }
+
     /// Gets the current value of the slider
     pub fn get_value(&self) -> i32 {
         unsafe { lvgl_sys::lv_bar_get_value(self.core.raw().as_ptr()) }
     }
+
+    /// Returns the value of the left knob of a slider in range mode.
+    pub fn get_left_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_start_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Returns the slider's minimum value.
+    pub fn get_min_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_min_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Returns the slider's maximum value.
+    pub fn get_max_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_max_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Gets the area of the knob in screen coordinates, as it was last drawn,
+    /// e.g. to place a label showing the value above it.
+    pub fn get_knob_area(&self) -> Area {
+        let slider = self.core.raw().as_ptr() as *const lvgl_sys::lv_slider_t;
+        Area::from(unsafe { (*slider).right_knob_area })
+    }
+}
+
+impl<'a, S: FnOnce(&mut Slider<'a>)> Builder<Slider<'a>, S> {
+    /// Sets the slider's minimum and maximum values.
+    pub fn range(self, min: i32, max: i32) -> Builder<Slider<'a>, impl FnOnce(&mut Slider<'a>)> {
+        self.with(move |slider| slider.set_range(min, max))
+    }
+
+    /// Sets the slider's value, without animation.
+    pub fn value(self, value: i32) -> Builder<Slider<'a>, impl FnOnce(&mut Slider<'a>)> {
+        self.with(move |slider| slider.set_value(value, AnimationState::OFF))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::widgets::Slider;
+    use crate::{tests, Color, Widget};
+
+    #[test]
+    fn knob_follows_value() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut slider = Slider::builder()
+            .size(200, 10)
+            .pos(20, 100)
+            .value(50)
+            .build(&mut screen)
+            .unwrap();
+        slider.set_style_bg_color(Slider::KNOB, Color::from_hex(0xFF0000));
+        unsafe { lvgl_sys::lv_refr_now(core::ptr::null_mut()) };
+
+        let knob = slider.get_knob_area();
+        assert!((110..=130).contains(&((knob.x1 + knob.x2) / 2)));
+        assert!(!slider.is_dragged());
+    }
+
+    #[test]
+    fn read_back_range() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let slider = Slider::builder()
+            .range(-20, 40)
+            .value(25)
+            .build(&mut screen)
+            .unwrap();
+        assert_eq!(slider.get_value(), 25);
+        assert_eq!((slider.get_min_value(), slider.get_max_value()), (-20, 40));
+        assert_eq!(slider.get_left_value(), -20);
+    }
 }
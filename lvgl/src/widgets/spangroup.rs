@@ -0,0 +1,215 @@
+use super::label::{FmtBuffer, TEXT_FMT_CAPACITY};
+use crate::font::Font;
+use crate::widgets::Spangroup;
+use crate::{Color, LvError, LvResult, NativeObject};
+use core::fmt::{self, Write};
+use core::ptr::NonNull;
+
+/// A run of text with its own style in a `Spangroup`. Made with `span()`.
+pub struct Span<T> {
+    text: T,
+    color: Option<Color>,
+    font: Option<&'static Font>,
+    bold: bool,
+    decor: lvgl_sys::lv_text_decor_t,
+}
+
+/// Starts a span showing `text`, which is anything implementing `Display`,
+/// e.g. a `&str` or a number. The span has the spangroup's style unless
+/// changed with the methods of `Span`.
+pub fn span<T: fmt::Display>(text: T) -> Span<T> {
+    Span {
+        text,
+        color: None,
+        font: None,
+        bold: false,
+        decor: lvgl_sys::LV_TEXT_DECOR_NONE as _,
+    }
+}
+
+impl<T> Span<T> {
+    /// Sets the color of the text.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the font of the text. LVGL keeps pointing to it, so it must be
+    /// `'static`, e.g. made with `Font::leak()`.
+    pub fn font(mut self, font: &'static Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Shows the text in the bold font given to `RichText::bold_font()`.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Underlines the text.
+    pub fn underline(mut self) -> Self {
+        self.decor |= lvgl_sys::LV_TEXT_DECOR_UNDERLINE as lvgl_sys::lv_text_decor_t;
+        self
+    }
+
+    /// Strikes the text through.
+    pub fn strikethrough(mut self) -> Self {
+        self.decor |= lvgl_sys::LV_TEXT_DECOR_STRIKETHROUGH as lvgl_sys::lv_text_decor_t;
+        self
+    }
+}
+
+/// A span of `RichText`. Implemented by `Span`.
+pub trait RichSpan {
+    /// Sets the text and style of `span`. The text is formatted on the stack
+    /// and truncated to `TEXT_FMT_CAPACITY - 1` bytes.
+    fn apply(&self, span: NonNull<lvgl_sys::lv_span_t>, bold_font: Option<&'static Font>);
+}
+
+impl<T: fmt::Display> RichSpan for Span<T> {
+    fn apply(&self, span: NonNull<lvgl_sys::lv_span_t>, bold_font: Option<&'static Font>) {
+        let mut text = FmtBuffer::<TEXT_FMT_CAPACITY>::new();
+        let _ = write!(text, "{}", self.text);
+        unsafe {
+            let span = span.as_ptr();
+            lvgl_sys::lv_span_set_text(span, text.as_c_str().as_ptr());
+            let style = &mut (*span).style;
+            if let Some(color) = self.color {
+                lvgl_sys::lv_style_set_text_color(style, color.raw);
+            }
+            let font = if self.bold {
+                bold_font.or(self.font)
+            } else {
+                self.font
+            };
+            if let Some(font) = font {
+                lvgl_sys::lv_style_set_text_font(style, font.raw());
+            }
+            if self.decor != lvgl_sys::LV_TEXT_DECOR_NONE as lvgl_sys::lv_text_decor_t {
+                lvgl_sys::lv_style_set_text_decor(style, self.decor);
+            }
+        }
+    }
+}
+
+/// Spans of text with different styles, shown together by a `Spangroup`.
+/// Made with the [`rich_text!`](crate::rich_text!) macro:
+///
+/// ```ignore
+/// use lvgl::rich_text;
+/// use lvgl::widgets::span;
+///
+/// let bold_20 = Font::montserrat_20().leak();
+/// spans.set_rich_text(&rich_text![
+///     span("Temp: "),
+///     span(temperature).color(Color::RED).bold(),
+///     span(" °C"),
+/// ].bold_font(bold_20))?;
+/// ```
+///
+/// The spans borrow the values they show, without allocating, until the
+/// end of the statement, so the macro is used in the call setting them.
+pub struct RichText<'a> {
+    spans: &'a [&'a dyn RichSpan],
+    bold_font: Option<&'static Font>,
+}
+
+impl<'a> RichText<'a> {
+    /// Makes `RichText` from `spans`, shown in order. Used by the
+    /// [`rich_text!`](crate::rich_text!) macro, which builds the slice.
+    pub fn new(spans: &'a [&'a dyn RichSpan]) -> Self {
+        Self {
+            spans,
+            bold_font: None,
+        }
+    }
+
+    /// Sets the font of the spans made bold with `Span::bold()`. Without
+    /// it, they keep their font.
+    pub fn bold_font(mut self, font: &'static Font) -> Self {
+        self.bold_font = Some(font);
+        self
+    }
+}
+
+/// Makes `RichText` from a list of spans. See `RichText`.
+#[macro_export]
+macro_rules! rich_text {
+    ($($span:expr),* $(,)?) => {
+        $crate::widgets::RichText::new(&[$(&$span as &dyn $crate::widgets::RichSpan),*])
+    };
+}
+
+impl Spangroup<'_> {
+    /// Replaces the spans of the spangroup with `text`. LVGL copies the text
+    /// of each span. Fails with `LvError::LvOOMemory` if LVGL is out of
+    /// memory, leaving the spans added until then.
+    pub fn set_rich_text(&mut self, text: &RichText) -> LvResult<()> {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            while lvgl_sys::lv_spangroup_get_child_cnt(obj) > 0 {
+                lvgl_sys::lv_spangroup_del_span(obj, lvgl_sys::lv_spangroup_get_child(obj, 0));
+            }
+        }
+        let result = text.spans.iter().try_for_each(|rich_span| {
+            let span = NonNull::new(unsafe { lvgl_sys::lv_spangroup_new_span(obj) })
+                .ok_or(LvError::LvOOMemory)?;
+            rich_span.apply(span, text.bold_font);
+            Ok(())
+        });
+        unsafe { lvgl_sys::lv_spangroup_refr_mode(obj) };
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cstr_core::CStr;
+
+    #[test]
+    fn set_rich_text() {
+        crate::tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut spans = Spangroup::create(&mut screen).unwrap();
+        let red = Color::from_hex(0xFF0000);
+        let bold = unsafe { Font::new_raw(lvgl_sys::lv_font_montserrat_14) }.leak();
+        let temperature = 21.5;
+        spans
+            .set_rich_text(
+                &crate::rich_text![
+                    span("Temp: "),
+                    span(temperature).color(red).bold().underline(),
+                ]
+                .bold_font(bold),
+            )
+            .unwrap();
+
+        let obj = spans.raw().as_ptr();
+        unsafe {
+            assert_eq!(lvgl_sys::lv_spangroup_get_child_cnt(obj), 2);
+            let value = lvgl_sys::lv_spangroup_get_child(obj, 1);
+            assert_eq!(CStr::from_ptr((*value).txt).to_bytes(), b"21.5");
+            let mut color = core::mem::zeroed();
+            lvgl_sys::lv_style_get_prop(
+                &(*value).style,
+                lvgl_sys::lv_style_prop_t_LV_STYLE_TEXT_COLOR,
+                &mut color,
+            );
+            assert_eq!(Color::from_raw(color.color).to_u32(), red.to_u32());
+            let mut font = core::mem::zeroed();
+            lvgl_sys::lv_style_get_prop(
+                &(*value).style,
+                lvgl_sys::lv_style_prop_t_LV_STYLE_TEXT_FONT,
+                &mut font,
+            );
+            assert_eq!(font.ptr, bold.raw() as *const _);
+        }
+
+        spans
+            .set_rich_text(&crate::rich_text![span("Off")])
+            .unwrap();
+        assert_eq!(unsafe { lvgl_sys::lv_spangroup_get_child_cnt(obj) }, 1);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::widgets::Switch;
+use crate::{State, Widget};
+
+impl Switch<'_> {
+    /// Returns whether the switch is on.
+    pub fn is_checked(&self) -> bool {
+        self.has_state(State::CHECKED)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::widgets::Btnmatrix;
+use crate::{AnimationState, AsCStr, LvError, LvResult, NativeObject, Obj, Widget};
+
+define_object!(Tabview);
+
+unsafe impl crate::WidgetClass for Tabview<'_> {
+    fn class() -> &'static lvgl_sys::lv_obj_class_t {
+        unsafe { &lvgl_sys::lv_tabview_class }
+    }
+}
+
+/// Where the tab buttons of a tabview are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabPosition {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl From<TabPosition> for lvgl_sys::lv_dir_t {
+    fn from(position: TabPosition) -> Self {
+        let native = match position {
+            TabPosition::Top => lvgl_sys::LV_DIR_TOP,
+            TabPosition::Bottom => lvgl_sys::LV_DIR_BOTTOM,
+            TabPosition::Left => lvgl_sys::LV_DIR_LEFT,
+            TabPosition::Right => lvgl_sys::LV_DIR_RIGHT,
+        };
+        native as _
+    }
+}
+
+impl<'a> Tabview<'a> {
+    /// Creates a tabview on `parent`, with its tab buttons at `position` in a
+    /// bar `tab_size` pixels high (or wide, on the left or right).
+    pub fn create<'p: 'a>(
+        parent: &mut impl Widget<'p>,
+        position: TabPosition,
+        tab_size: i16,
+    ) -> LvResult<Self> {
+        crate::check_init()?;
+        unsafe {
+            let ptr = lvgl_sys::lv_tabview_create(parent.raw().as_mut(), position.into(), tab_size);
+            if let Some(raw) = core::ptr::NonNull::new(ptr) {
+                Ok(Self {
+                    core: Obj::from_raw(raw).unwrap(),
+                })
+            } else {
+                Err(LvError::InvalidReference)
+            }
+        }
+    }
+
+    /// Adds a tab named `name`, and returns its page to add the tab's content
+    /// to.
+    pub fn add_tab(&mut self, name: impl AsCStr) -> LvResult<Obj<'a>> {
+        unsafe {
+            let page = name.with_c_str(|name| {
+                lvgl_sys::lv_tabview_add_tab(self.raw().as_mut(), name.as_ptr())
+            })?;
+            core::ptr::NonNull::new(page)
+                .and_then(|raw| Obj::from_raw(raw))
+                .ok_or(LvError::InvalidReference)
+        }
+    }
+
+    /// Renames the tab at index `id`.
+    pub fn rename_tab(&mut self, id: u32, name: impl AsCStr) -> LvResult<()> {
+        name.with_c_str(|name| unsafe {
+            lvgl_sys::lv_tabview_rename_tab(self.raw().as_mut(), id, name.as_ptr())
+        })
+    }
+
+    /// Shows the tab at index `id`.
+    pub fn set_act(&mut self, id: u32, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_tabview_set_act(self.raw().as_mut(), id, anim.into()) }
+    }
+
+    /// Returns the index of the tab shown.
+    pub fn get_tab_act(&self) -> u16 {
+        unsafe { lvgl_sys::lv_tabview_get_tab_act(self.raw().as_ptr()) }
+    }
+
+    /// Returns where the tab buttons are placed.
+    pub fn get_tab_position(&self) -> TabPosition {
+        let tabview = unsafe { &*(self.raw().as_ptr() as *const lvgl_sys::lv_tabview_t) };
+        match tabview.tab_pos as u32 {
+            lvgl_sys::LV_DIR_BOTTOM => TabPosition::Bottom,
+            lvgl_sys::LV_DIR_LEFT => TabPosition::Left,
+            lvgl_sys::LV_DIR_RIGHT => TabPosition::Right,
+            _ => TabPosition::Top,
+        }
+    }
+
+    /// Sets the height of the tab bar, or its width if the tab buttons are on
+    /// the left or right. Fails with `LvError::OutOfRange` if `size` is beyond
+    /// `COORD_MAX`.
+    pub fn set_tab_bar_size(&mut self, size: i16) -> LvResult<()> {
+        let mut btns = self.get_tab_btns();
+        match self.get_tab_position() {
+            TabPosition::Top | TabPosition::Bottom => btns.set_height(size),
+            TabPosition::Left | TabPosition::Right => btns.set_width(size),
+        }
+    }
+
+    /// Returns the button matrix of the tab buttons, e.g. to style them.
+    pub fn get_tab_btns(&self) -> Btnmatrix<'a> {
+        unsafe {
+            let btns = lvgl_sys::lv_tabview_get_tab_btns(self.raw().as_ptr());
+            Btnmatrix::from_raw(core::ptr::NonNull::new_unchecked(btns)).unwrap()
+        }
+    }
+
+    /// Returns the container of the tabs' pages.
+    pub fn get_content(&self) -> Obj<'a> {
+        unsafe {
+            let content = lvgl_sys::lv_tabview_get_content(self.raw().as_ptr());
+            Obj::from_raw(core::ptr::NonNull::new_unchecked(content)).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests;
+    use cstr_core::CStr;
+
+    #[test]
+    fn side_tabs() {
+        tests::initialize_test(true);
+        let mut screen = crate::display::get_scr_act().unwrap();
+        let mut tabview = Tabview::create(&mut screen, TabPosition::Left, 60).unwrap();
+        assert_eq!(tabview.get_tab_position(), TabPosition::Left);
+        tabview.add_tab("Wi-Fi").unwrap();
+        tabview.add_tab("Sound").unwrap();
+        tabview.rename_tab(1, "Audio").unwrap();
+        tabview.set_act(1, AnimationState::OFF);
+        assert_eq!(tabview.get_tab_act(), 1);
+
+        tabview.set_tab_bar_size(80).unwrap();
+        let btns = tabview.get_tab_btns();
+        unsafe {
+            lvgl_sys::lv_obj_update_layout(tabview.raw().as_ptr());
+            assert_eq!(lvgl_sys::lv_obj_get_width(btns.raw().as_ptr()), 80);
+            let text = lvgl_sys::lv_btnmatrix_get_btn_text(btns.raw().as_ptr(), 1);
+            assert_eq!(CStr::from_ptr(text).to_bytes(), b"Audio");
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! A pure-Rust desktop window backend using `winit` and `softbuffer`
+//!
+//! This is an alternative to the SDL2 simulator that needs no C libraries.
+//! [`run()`] opens a window, registers it as a display along with the mouse
+//! as a pointer, and drives LVGL until the window is closed:
+//!
+//! ```no_run
+//! use lvgl::widgets::Label;
+//! use lvgl::DrawBuffer;
+//!
+//! let buffer = DrawBuffer::<{ 320 * 240 }>::default();
+//! lvgl::window::run("Demo", 320, 240, buffer, |display| {
+//!     let mut screen = display.get_scr_act()?;
+//!     let mut label = Label::create(&mut screen)?;
+//!     label.set_text("Hello!")?;
+//!     Ok(())
+//! })
+//! .unwrap();
+//! ```
+//!
+//! The window size is given in logical pixels, which is also the resolution
+//! LVGL renders at. On high-DPI screens, each LVGL pixel is scaled up to the
+//! window's scale factor. Resizing the window changes the display resolution
+//! accordingly.
+
+use crate::input_device::pointer::{Pointer, PointerInputData};
+use crate::input_device::{InputDriver, InputState};
+use crate::{Display, DrawBuffer, LvError, LvResult, Point};
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::num::NonZeroU32;
+use core::time::Duration;
+use std::error::Error;
+use std::time::Instant;
+use winit::application::ApplicationHandler;
+use winit::dpi::{LogicalSize, PhysicalSize};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::window::{Window, WindowId};
+
+/// How often LVGL's timers are run while the window is open.
+const FRAME_PERIOD: Duration = Duration::from_millis(5);
+
+/// How far a notch of the mouse wheel scrolls, in logical pixels.
+const LINE_HEIGHT: f32 = 20.0;
+
+/// An error that stopped the window.
+#[derive(Debug)]
+pub enum WindowError {
+    /// The event loop or window could not be created.
+    Winit(String),
+    /// The window contents could not be presented.
+    Present(softbuffer::SoftBufferError),
+    /// Registering the display or input device, or the setup function,
+    /// failed.
+    Lvgl(LvError),
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::Winit(e) => write!(f, "Window error: {}", e),
+            WindowError::Present(e) => write!(f, "Presentation error: {}", e),
+            WindowError::Lvgl(e) => write!(f, "LVGL error: {:?}", e),
+        }
+    }
+}
+
+impl Error for WindowError {}
+
+impl From<LvError> for WindowError {
+    fn from(err: LvError) -> Self {
+        WindowError::Lvgl(err)
+    }
+}
+
+/// Opens a window of `width` by `height` logical pixels and runs LVGL in it
+/// until it is closed. `setup` is called once the display and pointer have
+/// been registered, and should create the UI.
+pub fn run<F, const N: usize>(
+    title: &str,
+    width: u32,
+    height: u32,
+    draw_buffer: DrawBuffer<N>,
+    setup: F,
+) -> Result<(), WindowError>
+where
+    F: FnOnce(&Display) -> LvResult<()>,
+{
+    let event_loop = EventLoop::new().map_err(|e| WindowError::Winit(e.to_string()))?;
+    let mut app = App {
+        title,
+        size: LogicalSize::new(width, height),
+        draw_buffer: Some(draw_buffer),
+        setup: Some(setup),
+        state: None,
+        error: None,
+    };
+    event_loop
+        .run_app(&mut app)
+        .map_err(|e| WindowError::Winit(e.to_string()))?;
+    app.error.map_or(Ok(()), Err)
+}
+
+/// The pixels rendered by LVGL, as `0x00RRGGBB`.
+struct Frame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u32>,
+    dirty: bool,
+}
+
+impl Frame {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height) as usize],
+            dirty: true,
+        }
+    }
+}
+
+struct Running {
+    window: Rc<Window>,
+    surface: softbuffer::Surface<Rc<Window>, Rc<Window>>,
+    display: Display,
+    pointer: Pointer,
+    frame: Rc<RefCell<Frame>>,
+    pointer_state: Rc<Cell<InputState>>,
+    last_tick: Instant,
+}
+
+struct App<'t, F, const N: usize> {
+    title: &'t str,
+    size: LogicalSize<u32>,
+    draw_buffer: Option<DrawBuffer<N>>,
+    setup: Option<F>,
+    state: Option<Running>,
+    error: Option<WindowError>,
+}
+
+impl<F, const N: usize> App<'_, F, N>
+where
+    F: FnOnce(&Display) -> LvResult<()>,
+{
+    fn start(&mut self, event_loop: &ActiveEventLoop) -> Result<(), WindowError> {
+        let (Some(draw_buffer), Some(setup)) = (self.draw_buffer.take(), self.setup.take()) else {
+            return Ok(());
+        };
+        let attributes = Window::default_attributes()
+            .with_title(self.title)
+            .with_inner_size(self.size);
+        let window = Rc::new(
+            event_loop
+                .create_window(attributes)
+                .map_err(|e| WindowError::Winit(e.to_string()))?,
+        );
+        let context = softbuffer::Context::new(window.clone()).map_err(WindowError::Present)?;
+        let surface =
+            softbuffer::Surface::new(&context, window.clone()).map_err(WindowError::Present)?;
+
+        let size = logical_size(window.inner_size(), window.scale_factor());
+        let frame = Rc::new(RefCell::new(Frame::new(size.width, size.height)));
+        let flush_frame = frame.clone();
+        let display = Display::register(draw_buffer, size.width, size.height, move |refresh| {
+            let mut frame = flush_frame.borrow_mut();
+            let area = &refresh.area;
+            let row_len = (area.x2 - area.x1 + 1) as usize;
+            for (iy, y) in (area.y1..=area.y2).enumerate() {
+                for (ix, x) in (area.x1..=area.x2).enumerate() {
+                    let (x, y) = (x as u32, y as u32);
+                    if x < frame.width && y < frame.height {
+                        let idx = (y * frame.width + x) as usize;
+                        frame.pixels[idx] =
+                            refresh.colors[iy * row_len + ix].to_u32() & 0x00FF_FFFF;
+                    }
+                }
+            }
+            frame.dirty = true;
+        })
+        .map_err(LvError::from)?;
+
+        let pointer_state = Rc::new(Cell::new(
+            PointerInputData::Touch(Point::new(0, 0)).released(),
+        ));
+        let read_state = pointer_state.clone();
+        let pointer = Pointer::register(move || read_state.get().once(), &display)?;
+
+        setup(&display)?;
+        self.state = Some(Running {
+            window,
+            surface,
+            display,
+            pointer,
+            frame,
+            pointer_state,
+            last_tick: Instant::now(),
+        });
+        Ok(())
+    }
+
+    fn handle(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        event: WindowEvent,
+    ) -> Result<(), WindowError> {
+        let Some(state) = self.state.as_mut() else {
+            return Ok(());
+        };
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(physical) => {
+                let size = logical_size(physical, state.window.scale_factor());
+                let mut frame = state.frame.borrow_mut();
+                if (size.width, size.height) != (frame.width, frame.height) {
+                    *frame = Frame::new(size.width, size.height);
+                    drop(frame);
+                    state.display.set_resolution(size.width, size.height);
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = position.to_logical::<i32>(state.window.scale_factor());
+                let point = Point::new(position.x, position.y);
+                state.pointer_state.set(match state.pointer_state.get() {
+                    InputState::Pressed(_) => PointerInputData::Touch(point).pressed(),
+                    InputState::Released(_) => PointerInputData::Touch(point).released(),
+                });
+            }
+            WindowEvent::MouseInput {
+                state: button_state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let data = match state.pointer_state.get() {
+                    InputState::Pressed(d) | InputState::Released(d) => d,
+                };
+                state.pointer_state.set(match button_state {
+                    ElementState::Pressed => InputState::Pressed(data),
+                    ElementState::Released => InputState::Released(data),
+                });
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x * LINE_HEIGHT, y * LINE_HEIGHT),
+                    MouseScrollDelta::PixelDelta(position) => {
+                        let position = position.to_logical::<f32>(state.window.scale_factor());
+                        (position.x, position.y)
+                    }
+                };
+                state.pointer.scroll_wheel(Point::new(x as i32, y as i32));
+            }
+            WindowEvent::RedrawRequested => state.present()?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Running {
+    /// Copies the LVGL frame to the window, scaling it to the physical size.
+    fn present(&mut self) -> Result<(), WindowError> {
+        let physical = self.window.inner_size();
+        let (Some(width), Some(height)) = (
+            NonZeroU32::new(physical.width),
+            NonZeroU32::new(physical.height),
+        ) else {
+            return Ok(());
+        };
+        self.surface
+            .resize(width, height)
+            .map_err(WindowError::Present)?;
+        let mut buffer = self.surface.buffer_mut().map_err(WindowError::Present)?;
+        let mut frame = self.frame.borrow_mut();
+        for py in 0..physical.height {
+            let fy = (py as u64 * frame.height as u64 / physical.height as u64) as u32;
+            for px in 0..physical.width {
+                let fx = (px as u64 * frame.width as u64 / physical.width as u64) as u32;
+                buffer[(py * physical.width + px) as usize] =
+                    frame.pixels[(fy * frame.width + fx) as usize];
+            }
+        }
+        frame.dirty = false;
+        buffer.present().map_err(WindowError::Present)
+    }
+}
+
+impl<F, const N: usize> ApplicationHandler for App<'_, F, N>
+where
+    F: FnOnce(&Display) -> LvResult<()>,
+{
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if let Err(e) = self.start(event_loop) {
+            self.error = Some(e);
+            event_loop.exit();
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        if let Err(e) = self.handle(event_loop, event) {
+            self.error = Some(e);
+            event_loop.exit();
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        #[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+        crate::tick_inc(now.duration_since(state.last_tick));
+        state.last_tick = now;
+        if let Err(e) = crate::task_handler() {
+            self.error = Some(e.into());
+            event_loop.exit();
+            return;
+        }
+        if state.frame.borrow().dirty {
+            state.window.request_redraw();
+        }
+        event_loop.set_control_flow(ControlFlow::WaitUntil(now + FRAME_PERIOD));
+    }
+}
+
+/// Converts a physical window size to the LVGL resolution, which is never
+/// zero.
+fn logical_size(physical: PhysicalSize<u32>, scale_factor: f64) -> LogicalSize<u32> {
+    let size = physical.to_logical::<u32>(scale_factor);
+    LogicalSize::new(size.width.max(1), size.height.max(1))
+}